@@ -0,0 +1,11 @@
+-- Remove as tabelas e tipos criados em 0001_create_table.up.sql
+DROP TABLE IF EXISTS pagamento;
+DROP TABLE IF EXISTS pedido;
+DROP TABLE IF EXISTS cliente;
+DROP TABLE IF EXISTS produto;
+DROP TABLE IF EXISTS usuario;
+
+DROP TYPE IF EXISTS STATUS_PEDIDO_ENUM;
+DROP TYPE IF EXISTS CATEGORIA_ENUM;
+DROP TYPE IF EXISTS TIPO_USUARIO_ENUM;
+DROP TYPE IF EXISTS STATUS_USUARIO_ENUM;
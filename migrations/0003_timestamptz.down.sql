@@ -0,0 +1,19 @@
+-- Volta as colunas de data para TIMESTAMP (sem fuso), mantendo os valores em UTC
+ALTER TABLE usuario
+    ALTER COLUMN data_criacao TYPE TIMESTAMP USING data_criacao AT TIME ZONE 'UTC',
+    ALTER COLUMN data_atualizacao TYPE TIMESTAMP USING data_atualizacao AT TIME ZONE 'UTC';
+
+ALTER TABLE cliente
+    ALTER COLUMN data_criacao TYPE TIMESTAMP USING data_criacao AT TIME ZONE 'UTC',
+    ALTER COLUMN data_atualizacao TYPE TIMESTAMP USING data_atualizacao AT TIME ZONE 'UTC';
+
+ALTER TABLE produto
+    ALTER COLUMN data_criacao TYPE TIMESTAMP USING data_criacao AT TIME ZONE 'UTC',
+    ALTER COLUMN data_atualizacao TYPE TIMESTAMP USING data_atualizacao AT TIME ZONE 'UTC';
+
+ALTER TABLE pedido
+    ALTER COLUMN data_criacao TYPE TIMESTAMP USING data_criacao AT TIME ZONE 'UTC',
+    ALTER COLUMN data_atualizacao TYPE TIMESTAMP USING data_atualizacao AT TIME ZONE 'UTC';
+
+ALTER TABLE pagamento
+    ALTER COLUMN data_criacao TYPE TIMESTAMP USING data_criacao AT TIME ZONE 'UTC';
@@ -0,0 +1,19 @@
+-- Converte as colunas de data para TIMESTAMPTZ (valores existentes estão em UTC)
+ALTER TABLE usuario
+    ALTER COLUMN data_criacao TYPE TIMESTAMPTZ USING data_criacao AT TIME ZONE 'UTC',
+    ALTER COLUMN data_atualizacao TYPE TIMESTAMPTZ USING data_atualizacao AT TIME ZONE 'UTC';
+
+ALTER TABLE cliente
+    ALTER COLUMN data_criacao TYPE TIMESTAMPTZ USING data_criacao AT TIME ZONE 'UTC',
+    ALTER COLUMN data_atualizacao TYPE TIMESTAMPTZ USING data_atualizacao AT TIME ZONE 'UTC';
+
+ALTER TABLE produto
+    ALTER COLUMN data_criacao TYPE TIMESTAMPTZ USING data_criacao AT TIME ZONE 'UTC',
+    ALTER COLUMN data_atualizacao TYPE TIMESTAMPTZ USING data_atualizacao AT TIME ZONE 'UTC';
+
+ALTER TABLE pedido
+    ALTER COLUMN data_criacao TYPE TIMESTAMPTZ USING data_criacao AT TIME ZONE 'UTC',
+    ALTER COLUMN data_atualizacao TYPE TIMESTAMPTZ USING data_atualizacao AT TIME ZONE 'UTC';
+
+ALTER TABLE pagamento
+    ALTER COLUMN data_criacao TYPE TIMESTAMPTZ USING data_criacao AT TIME ZONE 'UTC';
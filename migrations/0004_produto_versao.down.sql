@@ -0,0 +1 @@
+ALTER TABLE produto DROP COLUMN IF EXISTS versao;
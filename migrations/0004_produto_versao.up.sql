@@ -0,0 +1,2 @@
+-- Versão do produto para controle de concorrência otimista (ETag/If-Match)
+ALTER TABLE produto ADD COLUMN IF NOT EXISTS versao INTEGER NOT NULL DEFAULT 1;
@@ -0,0 +1,4 @@
+DROP TABLE IF EXISTS produto_preco;
+ALTER TABLE produto
+    DROP COLUMN IF EXISTS data_arquivamento,
+    DROP COLUMN IF EXISTS arquivado;
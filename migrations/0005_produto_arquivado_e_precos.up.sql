@@ -0,0 +1,19 @@
+-- Exclusão lógica: pedidos antigos continuam apontando para o produto arquivado
+ALTER TABLE produto
+    ADD COLUMN IF NOT EXISTS arquivado BOOLEAN NOT NULL DEFAULT FALSE,
+    ADD COLUMN IF NOT EXISTS data_arquivamento TIMESTAMPTZ;
+
+-- Histórico de preços, usado para mostrar o valor cobrado em pedidos antigos
+CREATE TABLE IF NOT EXISTS produto_preco (
+    id SERIAL PRIMARY KEY,
+    produto_id INT NOT NULL,
+    preco FLOAT NOT NULL,
+    vigente_desde TIMESTAMPTZ NOT NULL,
+    CONSTRAINT fk_produto FOREIGN KEY (produto_id) REFERENCES produto(id)
+);
+
+CREATE INDEX IF NOT EXISTS idx_produto_preco_produto ON produto_preco (produto_id, vigente_desde);
+
+-- O preço atual passa a ser o primeiro registro de cada produto
+INSERT INTO produto_preco (produto_id, preco, vigente_desde)
+SELECT id, preco, COALESCE(data_criacao, CURRENT_TIMESTAMP) FROM produto;
@@ -0,0 +1,3 @@
+ALTER TABLE produto
+    DROP COLUMN IF EXISTS informacao_nutricional,
+    DROP COLUMN IF EXISTS alergenos;
@@ -0,0 +1,4 @@
+-- Alérgenos por ingrediente ({"queijo cheddar": ["Lactose"]}) e informação nutricional por porção
+ALTER TABLE produto
+    ADD COLUMN IF NOT EXISTS alergenos JSONB NOT NULL DEFAULT '{}',
+    ADD COLUMN IF NOT EXISTS informacao_nutricional JSONB;
@@ -0,0 +1,2 @@
+ALTER TABLE produto
+    DROP COLUMN IF EXISTS janelas_de_venda;
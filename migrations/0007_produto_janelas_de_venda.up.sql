@@ -0,0 +1,4 @@
+-- Janelas de venda no horário da loja, ex.: [{"dias": ["Sabado", "Domingo"], "inicio": "07:00:00", "fim": "11:00:00"}].
+-- Lista vazia: produto vendido a qualquer momento
+ALTER TABLE produto
+    ADD COLUMN IF NOT EXISTS janelas_de_venda JSONB NOT NULL DEFAULT '[]';
@@ -0,0 +1,14 @@
+DROP INDEX IF EXISTS idx_pagamento_loja;
+DROP INDEX IF EXISTS idx_pedido_loja;
+DROP INDEX IF EXISTS idx_produto_loja;
+
+ALTER TABLE usuario
+    DROP COLUMN IF EXISTS lojas;
+ALTER TABLE pagamento
+    DROP COLUMN IF EXISTS loja_id;
+ALTER TABLE pedido
+    DROP COLUMN IF EXISTS loja_id;
+ALTER TABLE produto
+    DROP COLUMN IF EXISTS loja_id;
+
+DROP TABLE IF EXISTS loja;
@@ -0,0 +1,26 @@
+-- Lojas (tenants). A loja 1 recebe todos os dados de antes do suporte a várias lojas
+CREATE TABLE IF NOT EXISTS loja (
+    id SERIAL PRIMARY KEY,
+    nome TEXT NOT NULL,
+    data_criacao TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    data_atualizacao TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+INSERT INTO loja (id, nome) VALUES (1, 'Loja padrão') ON CONFLICT (id) DO NOTHING;
+SELECT setval(pg_get_serial_sequence('loja', 'id'), (SELECT MAX(id) FROM loja));
+
+ALTER TABLE produto
+    ADD COLUMN IF NOT EXISTS loja_id INT NOT NULL DEFAULT 1 REFERENCES loja(id);
+ALTER TABLE pedido
+    ADD COLUMN IF NOT EXISTS loja_id INT NOT NULL DEFAULT 1 REFERENCES loja(id);
+ALTER TABLE pagamento
+    ADD COLUMN IF NOT EXISTS loja_id INT NOT NULL DEFAULT 1 REFERENCES loja(id);
+
+-- Lojas em que o usuário trabalha; o login vale para todas elas
+ALTER TABLE usuario
+    ADD COLUMN IF NOT EXISTS lojas INT [] NOT NULL DEFAULT '{1}';
+
+-- Toda consulta de produtos, pedidos e pagamentos filtra pela loja
+CREATE INDEX IF NOT EXISTS idx_produto_loja ON produto (loja_id);
+CREATE INDEX IF NOT EXISTS idx_pedido_loja ON pedido (loja_id);
+CREATE INDEX IF NOT EXISTS idx_pagamento_loja ON pagamento (loja_id);
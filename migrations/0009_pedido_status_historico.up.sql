@@ -0,0 +1,16 @@
+-- Momento de cada mudança de status dos pedidos, usado nos tempos de espera e de preparo
+CREATE TABLE IF NOT EXISTS pedido_status (
+    id SERIAL PRIMARY KEY,
+    pedido_id INT NOT NULL,
+    status STATUS_PEDIDO_ENUM NOT NULL,
+    data TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    CONSTRAINT fk_pedido FOREIGN KEY (pedido_id) REFERENCES pedido(id) ON DELETE CASCADE
+);
+
+CREATE INDEX IF NOT EXISTS idx_pedido_status_pedido ON pedido_status (pedido_id, data);
+
+-- Dos pedidos antigos só se sabe a criação e o status atual
+INSERT INTO pedido_status (pedido_id, status, data)
+SELECT id, 'Pendente', COALESCE(data_criacao, CURRENT_TIMESTAMP) FROM pedido;
+INSERT INTO pedido_status (pedido_id, status, data)
+SELECT id, status, COALESCE(data_atualizacao, CURRENT_TIMESTAMP) FROM pedido WHERE status <> 'Pendente';
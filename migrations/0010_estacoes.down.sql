@@ -0,0 +1,6 @@
+DROP TABLE IF EXISTS pedido_estacao;
+
+ALTER TABLE produto
+    DROP COLUMN IF EXISTS estacao_id;
+
+DROP TABLE IF EXISTS estacao;
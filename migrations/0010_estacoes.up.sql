@@ -0,0 +1,22 @@
+-- Estações da cozinha de cada loja; os produtos indicam em qual delas são preparados
+CREATE TABLE IF NOT EXISTS estacao (
+    id SERIAL PRIMARY KEY,
+    nome TEXT NOT NULL,
+    loja_id INT NOT NULL DEFAULT 1 REFERENCES loja(id),
+    data_criacao TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    data_atualizacao TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_estacao_loja ON estacao (loja_id);
+
+ALTER TABLE produto
+    ADD COLUMN IF NOT EXISTS estacao_id INT REFERENCES estacao(id) ON DELETE SET NULL;
+
+-- Os tickets vêm dos itens do pedido; aqui fica só o status dos que já saíram de Recebido
+CREATE TABLE IF NOT EXISTS pedido_estacao (
+    pedido_id INT NOT NULL REFERENCES pedido(id) ON DELETE CASCADE,
+    estacao_id INT NOT NULL REFERENCES estacao(id) ON DELETE CASCADE,
+    status VARCHAR(20) NOT NULL CHECK (status IN ('Recebido', 'EmPreparacao', 'Pronto')),
+    data_atualizacao TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (pedido_id, estacao_id)
+);
@@ -18,6 +18,7 @@ INSERT INTO produto (
         categoria,
         preco,
         ingredientes,
+        alergenos,
         data_criacao,
         data_atualizacao
     )
@@ -28,6 +29,7 @@ VALUES (
         'Lanche',
         10.99,
         '{"carne bovina", "queijo cheddar", "alface" }',
+        '{"queijo cheddar": ["Lactose"]}',
         CURRENT_TIMESTAMP,
         CURRENT_TIMESTAMP
     );
@@ -40,6 +42,7 @@ INSERT INTO produto (
         categoria,
         preco,
         ingredientes,
+        alergenos,
         data_criacao,
         data_atualizacao
     )
@@ -50,6 +53,7 @@ VALUES (
         'Lanche',
         12.99,
         '{"carne bovina", "queijo cheddar", "cebola" }',
+        '{"queijo cheddar": ["Lactose"]}',
         CURRENT_TIMESTAMP,
         CURRENT_TIMESTAMP
     );
@@ -62,6 +66,7 @@ INSERT INTO produto (
         categoria,
         preco,
         ingredientes,
+        alergenos,
         data_criacao,
         data_atualizacao
     )
@@ -72,6 +77,7 @@ VALUES (
         'Lanche',
         11.99,
         '{"carne frango", "queijo prato", "alface" }',
+        '{"queijo prato": ["Lactose"]}',
         CURRENT_TIMESTAMP,
         CURRENT_TIMESTAMP
     );
@@ -202,3 +208,9 @@ VALUES (
         'abc1234567890',
         CURRENT_TIMESTAMP
     );
+
+-- Histórico de status dos pedidos acima, como se tivessem passado pela API
+INSERT INTO pedido_status (pedido_id, status, data)
+SELECT id, 'Pendente', data_criacao FROM pedido;
+INSERT INTO pedido_status (pedido_id, status, data)
+SELECT id, status, data_atualizacao FROM pedido WHERE status <> 'Pendente';
@@ -1,2 +1,5 @@
+pub mod http_health_check_adapter;
 pub mod jwt_authentication_adapter;
 pub mod mercadopago_pagamento_webhook_adapter;
+pub mod postgres_health_check_adapter;
+pub mod simulado_pagamento_webhook_adapter;
@@ -0,0 +1,51 @@
+use std::time::Duration;
+
+use crate::traits::health_check_adapter::HealthCheckAdapter;
+
+// Considera a dependência disponível se ela responde HTTP, qualquer que seja o status:
+// o objetivo é saber se o serviço está no ar, não validar uma rota específica
+pub struct HttpHealthCheckAdapter {
+    nome: String,
+    url: Option<String>,
+    critico: bool,
+    client: reqwest::Client,
+}
+
+impl HttpHealthCheckAdapter {
+    pub fn new(nome: &str, url: Option<String>, critico: bool) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(2))
+            .build()
+            .unwrap_or_default();
+        HttpHealthCheckAdapter {
+            nome: nome.to_string(),
+            url,
+            critico,
+            client,
+        }
+    }
+}
+
+#[async_trait]
+impl HealthCheckAdapter for HttpHealthCheckAdapter {
+    fn nome(&self) -> String {
+        self.nome.clone()
+    }
+
+    fn critico(&self) -> bool {
+        self.critico
+    }
+
+    async fn verificar(&self) -> Result<(), String> {
+        let url = match &self.url {
+            Some(url) => url,
+            None => return Err("URL não configurada".to_string()),
+        };
+        self.client
+            .get(url)
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
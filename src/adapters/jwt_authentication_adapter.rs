@@ -1,19 +1,27 @@
 use crate::{
     base::domain_error::DomainError,
+    entities::loja::LOJA_PADRAO,
     entities::usuario::{Tipo, Usuario},
-    traits::authentication_adapter::AuthenticationAdapter,
+    traits::authentication_adapter::{AuthenticationAdapter, DadosDoToken},
 };
 use jsonwebtoken::{decode, encode, Algorithm, DecodingKey, EncodingKey, Header, Validation};
 use serde::{Deserialize, Serialize};
+use tracing::warn;
 
 #[derive(Debug, Serialize, Deserialize)]
 struct Claims {
     sub: String,
     role: Tipo,
-    company: String,
+    // Tokens emitidos antes do suporte a várias lojas valem só para a loja padrão
+    #[serde(default = "lojas_padrao")]
+    lojas: Vec<usize>,
     exp: usize,
 }
 
+fn lojas_padrao() -> Vec<usize> {
+    vec![LOJA_PADRAO]
+}
+
 #[derive(Clone)]
 pub struct JWTAuthenticationAdapter {
     secret: String,
@@ -31,7 +39,7 @@ impl AuthenticationAdapter for JWTAuthenticationAdapter {
         let my_claims = Claims {
             sub: user.id().to_string(),
             role: user.tipo().clone(),
-            company: "wdrops".to_string(),
+            lojas: user.lojas().clone(),
             exp: 10000000000,
         };
 
@@ -43,28 +51,29 @@ impl AuthenticationAdapter for JWTAuthenticationAdapter {
         );
         match token {
             Ok(t) => Ok(t),
-            Err(_) => Err(DomainError::Invalid("Erro ao gerar token".to_string())),
+            Err(err) => Err(DomainError::unavailable("Erro ao gerar token").com_causa(err)),
         }
     }
 
-    async fn validate_token(&self, token: String, role: Option<Tipo>) -> Result<String, DomainError> {
+    async fn validate_token(&self, token: String, role: Option<Tipo>) -> Result<DadosDoToken, DomainError> {
         let token_data = decode::<Claims>(
             &token,
             &DecodingKey::from_secret(self.secret.as_ref()),
             &Validation::new(Algorithm::HS512),
         );
         match token_data {
-            Ok(t) => match role {
-                Some(r) => {
-                    if t.claims.role != r {
-                        return Err(DomainError::Unauthorized);
-                    }
-                    Ok(t.claims.sub)
+            Ok(t) => {
+                if role.is_some_and(|r| t.claims.role != r) {
+                    return Err(DomainError::forbidden("Usuário sem permissão para esta operação"));
                 }
-                None => Ok(t.claims.sub),
-            },
+                Ok(DadosDoToken {
+                    usuario_id: t.claims.sub,
+                    tipo: t.claims.role,
+                    lojas: t.claims.lojas,
+                })
+            }
             Err(err) => {
-                eprintln!("Invalid Token: {}", err);
+                warn!(error = %err, "Token inválido");
                 Err(DomainError::Unauthorized)
             }
         }
@@ -86,7 +95,7 @@ mod tests {
     #[tokio::test]
     async fn should_generate_token() {
         let cpf = Cpf::new("123.456.789-09".to_string()).unwrap();
-        let now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let now = Utc::now();
         let user = Usuario::new(
             1,
             "Teste".to_string(),
@@ -95,7 +104,7 @@ mod tests {
             "senha_segura".to_string(),
             Tipo::Admin,
             Status::Ativo,
-            now.clone(),
+            now,
             now,
         );
         let jwt_authentication_adapter = jwt_authentication_adapter::JWTAuthenticationAdapter::new("secret".to_string());
@@ -106,7 +115,7 @@ mod tests {
     #[tokio::test]
     async fn should_validate_token_for_any_user() {
         let cpf = Cpf::new("123.456.789-09".to_string()).unwrap();
-        let now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let now = Utc::now();
         let user = Usuario::new(
             1,
             "Teste".to_string(),
@@ -115,7 +124,7 @@ mod tests {
             "senha_segura".to_string(),
             Tipo::Cozinha,
             Status::Ativo,
-            now.clone(),
+            now,
             now,
         );
         let jwt_authentication_adapter = jwt_authentication_adapter::JWTAuthenticationAdapter::new("secret".to_string());
@@ -124,13 +133,13 @@ mod tests {
         let token = token.unwrap();
         let user_id = jwt_authentication_adapter.validate_token(token, None).await;
         assert!(user_id.is_ok());
-        assert_eq!(user_id.unwrap(), user.id().to_string());
+        assert_eq!(user_id.unwrap().usuario_id, user.id().to_string());
     }
 
     #[tokio::test]
     async fn should_validate_token_for_admin_user() {
         let cpf = Cpf::new("123.456.789-09".to_string()).unwrap();
-        let now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let now = Utc::now();
         let user = Usuario::new(
             1,
             "Teste".to_string(),
@@ -139,7 +148,7 @@ mod tests {
             "senha_segura".to_string(),
             Tipo::Admin,
             Status::Ativo,
-            now.clone(),
+            now,
             now,
         );
         let jwt_authentication_adapter = jwt_authentication_adapter::JWTAuthenticationAdapter::new("secret".to_string());
@@ -148,13 +157,13 @@ mod tests {
         let token = token.unwrap();
         let user_id = jwt_authentication_adapter.validate_token(token, Some(Tipo::Admin)).await;
         assert!(user_id.is_ok());
-        assert_eq!(user_id.unwrap(), user.id().to_string());
+        assert_eq!(user_id.unwrap().usuario_id, user.id().to_string());
     }
 
     #[tokio::test]
     async fn should_block_token_for_non_admin_user() {
         let cpf = Cpf::new("123.456.789-09".to_string()).unwrap();
-        let now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let now = Utc::now();
         let user = Usuario::new(
             1,
             "Teste".to_string(),
@@ -163,7 +172,7 @@ mod tests {
             "senha_segura".to_string(),
             Tipo::Cozinha,
             Status::Ativo,
-            now.clone(),
+            now,
             now,
         );
         let jwt_authentication_adapter = jwt_authentication_adapter::JWTAuthenticationAdapter::new("secret".to_string());
@@ -173,4 +182,42 @@ mod tests {
         let user_id = jwt_authentication_adapter.validate_token(token, Some(Tipo::Admin)).await;
         assert!(user_id.is_err());
     }
+
+    #[tokio::test]
+    async fn should_carry_lojas_in_token() {
+        let cpf = Cpf::new("123.456.789-09".to_string()).unwrap();
+        let now = Utc::now();
+        let mut user = Usuario::new(
+            1,
+            "Teste".to_string(),
+            "teste@email.com".to_string(),
+            cpf.clone(),
+            "senha_segura".to_string(),
+            Tipo::Admin,
+            Status::Ativo,
+            now,
+            now,
+        );
+        user.set_lojas(vec![1, 3]);
+        let jwt_authentication_adapter = jwt_authentication_adapter::JWTAuthenticationAdapter::new("secret".to_string());
+        let token = jwt_authentication_adapter.get_token(user).await.unwrap();
+        let dados = jwt_authentication_adapter.validate_token(token, None).await.unwrap();
+        assert_eq!(dados.tipo, Tipo::Admin);
+        assert!(dados.acessa_loja(3));
+        assert!(!dados.acessa_loja(2));
+    }
+
+    #[tokio::test]
+    async fn should_accept_token_without_lojas_for_loja_padrao() {
+        let claims = serde_json::json!({ "sub": "1", "role": "Cozinha", "company": "wdrops", "exp": 10000000000u64 });
+        let token = encode(
+            &Header::new(Algorithm::HS512),
+            &claims,
+            &EncodingKey::from_secret("secret".as_ref()),
+        )
+        .unwrap();
+        let jwt_authentication_adapter = jwt_authentication_adapter::JWTAuthenticationAdapter::new("secret".to_string());
+        let dados = jwt_authentication_adapter.validate_token(token, None).await.unwrap();
+        assert_eq!(dados.lojas, vec![LOJA_PADRAO]);
+    }
 }
\ No newline at end of file
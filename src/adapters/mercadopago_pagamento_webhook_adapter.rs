@@ -3,14 +3,14 @@ use crate::entities::pagamento::Pagamento;
 use crate::traits::pagamento_webhook_adapter::PagamentoWebhookAdapter;
 use reqwest::Error as ReqwestError;
 use std::collections::HashMap;
-use std::env;
 use serde::Serialize;
 
 use serde_json::Value;
+use tracing::{debug, error};
 
 impl From<ReqwestError> for DomainError {
     fn from(error: ReqwestError) -> Self {
-        DomainError::Invalid(format!("Reqwest error: {}", error))
+        DomainError::external_service("Mercado Pago", "Erro na requisição").com_causa(error)
     }
 }
 
@@ -21,11 +21,17 @@ struct Data {
 }
 
 #[derive(Clone)]
-pub struct MercadoPagoPagamentoWebhookAdapter {}
+pub struct MercadoPagoPagamentoWebhookAdapter {
+    pagamentos_url: Option<String>,
+    api_host: Option<String>,
+}
 
 impl MercadoPagoPagamentoWebhookAdapter {
-    pub fn new() -> Self {
-        MercadoPagoPagamentoWebhookAdapter {}
+    pub fn new(pagamentos_url: Option<String>, api_host: Option<String>) -> Self {
+        MercadoPagoPagamentoWebhookAdapter {
+            pagamentos_url,
+            api_host,
+        }
     }
 }
 
@@ -54,33 +60,28 @@ impl PagamentoWebhookAdapter for MercadoPagoPagamentoWebhookAdapter {
         &self,
         mut pagamento: Pagamento,
     ) -> Result<Pagamento, DomainError> {
-        let post_url = match env::var("MOCK_PAGAMENTOS_URL") {
-            Ok(val) => val,
-            Err(_) => {
-                eprintln!("MOCK_PAGAMENTOS_URL environment variable not set");
-                return Err(DomainError::Invalid(
-                    "MOCK_PAGAMENTOS_URL environment variable not set".to_string(),
-                ));
+        let post_url = match &self.pagamentos_url {
+            Some(url) => url.clone(),
+            None => {
+                error!("mock_pagamentos_url não configurado");
+                return Err(DomainError::unavailable("mock_pagamentos_url não configurado"));
             }
         };
 
-        let API_HOST = match env::var("API_HOST") {
-            Ok(val) => val,
-            Err(_) => {
-                eprintln!("API_HOST environment variable not set");
-                return Err(DomainError::Invalid(
-                    "API_HOST environment variable not set".to_string(),
-                ));
+        let api_host = match &self.api_host {
+            Some(host) => host.clone(),
+            None => {
+                error!("api_host não configurado");
+                return Err(DomainError::unavailable("api_host não configurado"));
             }
         };
 
-        // let post_url = "http://localhost:9000/payment/";
-        // let API_HOST = "localhost:3000";
-
+        // O serviço de pagamentos não repassa cabeçalhos, então a loja vai na URL
         let webhook_url = format!(
-            "http://{}/pedidos/{}/webhook/pagamento",
-            API_HOST,
-            pagamento.id_pedido().clone()
+            "http://{}/pedidos/{}/webhook/pagamento?loja={}",
+            api_host,
+            pagamento.id_pedido().clone(),
+            pagamento.loja_id()
         );
 
         let client = reqwest::Client::new();
@@ -100,27 +101,30 @@ impl PagamentoWebhookAdapter for MercadoPagoPagamentoWebhookAdapter {
                         Ok(json_body) => {
                             if let Some(payment_code) = json_body["payment_code"].as_str() {
                                 pagamento.set_referencia(payment_code.to_string());
-                                println!("webhook set successful");
+                                debug!(referencia = payment_code, "Webhook registrado no Mercado Pago");
                                 Ok(pagamento)
                             } else {
-                                eprintln!("Payment code not found in the response");
-                                Err(DomainError::Invalid("Internal Server Error".to_string()))?
+                                error!("Payment code not found in the response");
+                                Err(DomainError::external_service("Mercado Pago", "Resposta sem payment_code"))
                             }
                         }
                         Err(e) => {
-                            println!("Error parsing JSON body: {:?}", e);
-                            Err(DomainError::Invalid("Internal Server Error".to_string()))?
+                            error!(error = %e, "Error parsing JSON body");
+                            Err(e.into())
                         }
                     }
                 } else {
-                    println!("webhook set failed with status code");
-                    Err(DomainError::Invalid("Internal Server Error".to_string()))
+                    error!(status = resp.status().as_u16(), "Webhook set failed");
+                    Err(DomainError::external_service(
+                        "Mercado Pago",
+                        format!("Status {} ao registrar webhook", resp.status().as_u16()),
+                    ))
                 }
             }
             Err(e) => {
                 // Handle errors here
-                println!("An error occurred: {:?}", e);
-                Err(DomainError::Invalid("Internal Server Error".to_string()))
+                error!(error = %e, "Erro na requisição ao Mercado Pago");
+                Err(e.into())
             }
         }
     }
@@ -0,0 +1,33 @@
+use deadpool_postgres::Pool;
+
+use crate::traits::health_check_adapter::HealthCheckAdapter;
+
+pub struct PostgresHealthCheckAdapter {
+    pool: Pool,
+}
+
+impl PostgresHealthCheckAdapter {
+    pub fn new(pool: Pool) -> Self {
+        PostgresHealthCheckAdapter { pool }
+    }
+}
+
+#[async_trait]
+impl HealthCheckAdapter for PostgresHealthCheckAdapter {
+    fn nome(&self) -> String {
+        "postgres".to_string()
+    }
+
+    fn critico(&self) -> bool {
+        true
+    }
+
+    async fn verificar(&self) -> Result<(), String> {
+        let client = self.pool.get().await.map_err(|err| err.to_string())?;
+        client
+            .simple_query("SELECT 1")
+            .await
+            .map_err(|err| err.to_string())?;
+        Ok(())
+    }
+}
@@ -0,0 +1,39 @@
+use serde_json::Value;
+use tracing::debug;
+
+use crate::adapters::mercadopago_pagamento_webhook_adapter::MercadoPagoPagamentoWebhookAdapter;
+use crate::base::domain_error::DomainError;
+use crate::entities::pagamento::Pagamento;
+use crate::traits::pagamento_webhook_adapter::PagamentoWebhookAdapter;
+
+// Usado quando não há serviço de pagamentos configurado: o pagamento fica pendente
+// com uma referência local e o webhook pode ser chamado manualmente com o mesmo
+// payload do Mercado Pago
+pub struct SimuladoPagamentoWebhookAdapter {
+    mercado_pago: MercadoPagoPagamentoWebhookAdapter,
+}
+
+impl SimuladoPagamentoWebhookAdapter {
+    pub fn new() -> Self {
+        SimuladoPagamentoWebhookAdapter {
+            mercado_pago: MercadoPagoPagamentoWebhookAdapter::new(None, None),
+        }
+    }
+}
+
+#[async_trait]
+impl PagamentoWebhookAdapter for SimuladoPagamentoWebhookAdapter {
+    fn processa_webhook(&self, data: Value, pagamento: Pagamento) -> Pagamento {
+        self.mercado_pago.processa_webhook(data, pagamento)
+    }
+
+    async fn set_webhook_pagamento(
+        &self,
+        mut pagamento: Pagamento,
+    ) -> Result<Pagamento, DomainError> {
+        let referencia = format!("simulado-{}", pagamento.id_pedido());
+        debug!(referencia = referencia.as_str(), "Pagamento simulado registrado");
+        pagamento.set_referencia(referencia);
+        Ok(pagamento)
+    }
+}
@@ -2,5 +2,10 @@ mod routes;
 mod error_handling;
 mod helpers;
 mod request_guards;
-mod config;
+pub mod config;
+mod fairings;
+mod telemetry;
+mod app_state;
+pub mod dependencies;
 pub mod server;
+pub mod migrate;
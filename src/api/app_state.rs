@@ -0,0 +1,90 @@
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+
+use chrono_tz::Tz;
+
+use super::dependencies::AppDependencies;
+use crate::controllers::cozinha_controller::CozinhaController;
+use crate::controllers::pedido_controller::PedidoController;
+use crate::entities::fila_da_cozinha::PoliticaDaFila;
+use crate::traits::{
+    cliente_gateway::ClienteGateway, estacao_gateway::EstacaoGateway, pagamento_gateway::PagamentoGateway,
+    pagamento_webhook_adapter::PagamentoWebhookAdapter, pedido_gateway::PedidoGateway,
+    produto_gateway::ProdutoGateway, unit_of_work::UnitOfWork,
+};
+use crate::use_cases::{
+    gerenciamento_de_estacoes_use_case::EstacaoUseCase,
+    pedidos_e_pagamentos_use_case::PedidosEPagamentosUseCase,
+    preparacao_e_entrega_use_case::PreparacaoeEntregaUseCase,
+};
+
+// Dependências dos casos de uso guardadas uma única vez no build. Os casos de uso
+// são montados a cada requisição sobre cópias dos repositórios restritas à loja
+// atual; os handlers recebem os controllers prontos pelos request guards, então
+// uma nova dependência só muda este arquivo
+pub struct AppState {
+    pedido_repository: Arc<dyn PedidoGateway + Sync + Send>,
+    cliente_repository: Arc<dyn ClienteGateway + Sync + Send>,
+    produto_repository: Arc<dyn ProdutoGateway + Sync + Send>,
+    estacao_repository: Arc<dyn EstacaoGateway + Sync + Send>,
+    pagamento_repository: Arc<dyn PagamentoGateway + Sync + Send>,
+    metodos_pagamento: HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>>,
+    unit_of_work: Arc<dyn UnitOfWork + Sync + Send>,
+    fuso_horario: Tz,
+    sla_cozinha: Duration,
+    politica_da_fila: PoliticaDaFila,
+}
+
+impl AppState {
+    pub fn new(dependencies: &AppDependencies) -> AppState {
+        AppState {
+            pedido_repository: dependencies.pedido_repository.clone(),
+            cliente_repository: dependencies.cliente_repository.clone(),
+            produto_repository: dependencies.produto_repository.clone(),
+            estacao_repository: dependencies.estacao_repository.clone(),
+            pagamento_repository: dependencies.pagamento_repository.clone(),
+            metodos_pagamento: dependencies.metodos_pagamento.clone(),
+            unit_of_work: dependencies.unit_of_work.clone(),
+            fuso_horario: dependencies.fuso_horario,
+            sla_cozinha: dependencies.sla_cozinha,
+            politica_da_fila: dependencies.politica_da_fila,
+        }
+    }
+
+    pub fn pedido_controller(&self, loja_id: usize) -> PedidoController {
+        let pedidos_e_pagamentos_use_case = PedidosEPagamentosUseCase::new(
+            self.pedido_repository.na_loja(loja_id),
+            self.cliente_repository.clone(),
+            self.produto_repository.na_loja(loja_id),
+            self.pagamento_repository.na_loja(loja_id),
+            self.metodos_pagamento.clone(),
+            self.unit_of_work.na_loja(loja_id),
+        )
+        .com_fuso_horario(self.fuso_horario);
+
+        PedidoController::new(
+            Arc::new(pedidos_e_pagamentos_use_case),
+            Arc::new(self.preparacao_e_entrega_use_case(loja_id)),
+        )
+    }
+
+    pub fn cozinha_controller(&self, loja_id: usize) -> CozinhaController {
+        let estacao_use_case = EstacaoUseCase::new(self.estacao_repository.na_loja(loja_id));
+
+        CozinhaController::new(
+            Arc::new(estacao_use_case),
+            Arc::new(self.preparacao_e_entrega_use_case(loja_id)),
+        )
+    }
+
+    fn preparacao_e_entrega_use_case(&self, loja_id: usize) -> PreparacaoeEntregaUseCase {
+        PreparacaoeEntregaUseCase::new(
+            self.pedido_repository.na_loja(loja_id),
+            self.estacao_repository.na_loja(loja_id),
+            self.unit_of_work.na_loja(loja_id),
+        )
+        .com_sla(self.sla_cozinha)
+        .com_politica_da_fila(self.politica_da_fila)
+    }
+}
@@ -0,0 +1,278 @@
+use chrono_tz::Tz;
+use deadpool_postgres::Pool;
+use std::collections::HashMap;
+use std::sync::Arc;
+use std::time::Duration;
+use tracing::info;
+
+use super::telemetry;
+use crate::adapters::http_health_check_adapter::HttpHealthCheckAdapter;
+use crate::adapters::jwt_authentication_adapter::JWTAuthenticationAdapter;
+use crate::adapters::mercadopago_pagamento_webhook_adapter::MercadoPagoPagamentoWebhookAdapter;
+use crate::adapters::postgres_health_check_adapter::PostgresHealthCheckAdapter;
+use crate::adapters::simulado_pagamento_webhook_adapter::SimuladoPagamentoWebhookAdapter;
+use crate::api::config::{Backend, Config, StorageBackend};
+use crate::entities::fila_da_cozinha::PoliticaDaFila;
+use crate::external::postgres;
+use crate::external::postgres::migrations::Migrator;
+use crate::gateways::aws_cognito_gateway::AwsCognitoRepository;
+use crate::gateways::{
+    in_memory_cliente_gateway::InMemoryClienteRepository,
+    in_memory_estacao_gateway::InMemoryEstacaoRepository,
+    in_memory_loja_gateway::InMemoryLojaRepository,
+    in_memory_pagamento_gateway::InMemoryPagamentoRepository,
+    in_memory_pedido_gateway::InMemoryPedidoRepository,
+    in_memory_produto_gateway::InMemoryProdutoRepository,
+    in_memory_storage_gateway::InMemoryStorageGateway,
+    in_memory_unit_of_work::InMemoryUnitOfWork,
+    in_memory_usuario_gateway::InMemoryUsuarioRepository,
+    local_storage_gateway::LocalStorageGateway,
+    postgres_cliente_gateway::PostgresClienteRepository,
+    postgres_estacao_gateway::PostgresEstacaoRepository,
+    postgres_loja_gateway::PostgresLojaRepository,
+    postgres_pagamento_gateway::PostgresPagamentoRepository,
+    postgres_pedido_gateway::PostgresPedidoRepository,
+    postgres_produto_gateway::PostgresProdutoRepository,
+    postgres_unit_of_work::PostgresUnitOfWork,
+    postgres_usuario_gateway::PostgresUsuarioGateway,
+    s3_storage_gateway::S3StorageGateway,
+    sem_transacao_unit_of_work::SemTransacaoUnitOfWork,
+};
+use crate::traits::authentication_adapter::AuthenticationAdapter;
+use crate::traits::health_check_adapter::HealthCheckAdapter;
+use crate::traits::pagamento_webhook_adapter::PagamentoWebhookAdapter;
+use crate::traits::{
+    cliente_gateway::ClienteGateway, estacao_gateway::EstacaoGateway, loja_gateway::LojaGateway,
+    pagamento_gateway::PagamentoGateway, pedido_gateway::PedidoGateway, produto_gateway::ProdutoGateway,
+    storage_gateway::StorageGateway, unit_of_work::UnitOfWork, usuario_gateway::UsuarioGateway,
+};
+
+// Tudo o que a aplicação precisa para subir. `from_config` monta as implementações
+// padrão; quem embute a API pode trocar qualquer campo antes de chamar `build_rocket`
+pub struct AppDependencies {
+    pub authentication_adapter: Arc<dyn AuthenticationAdapter + Sync + Send>,
+    pub usuario_repository: Arc<dyn UsuarioGateway + Sync + Send>,
+    pub loja_repository: Arc<dyn LojaGateway + Sync + Send>,
+    pub cliente_repository: Arc<dyn ClienteGateway + Sync + Send>,
+    pub produto_repository: Arc<dyn ProdutoGateway + Sync + Send>,
+    pub estacao_repository: Arc<dyn EstacaoGateway + Sync + Send>,
+    // O unit of work precisa operar sobre os mesmos dados dos repositórios de
+    // pedido e pagamento; use `com_pedidos_e_pagamentos` para trocar os três juntos
+    pub pedido_repository: Arc<dyn PedidoGateway + Sync + Send>,
+    pub pagamento_repository: Arc<dyn PagamentoGateway + Sync + Send>,
+    pub unit_of_work: Arc<dyn UnitOfWork + Sync + Send>,
+    pub storage: Arc<dyn StorageGateway + Sync + Send>,
+    // Chave é o método de pagamento gravado no pedido, ex.: "Mercado Pago"
+    pub metodos_pagamento: HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>>,
+    pub health_checks: Vec<Arc<dyn HealthCheckAdapter + Sync + Send>>,
+    // Só usado para expor as métricas do pool em /metrics
+    pub pool: Option<Pool>,
+    // Horário da loja, usado nas janelas de venda dos produtos
+    pub fuso_horario: Tz,
+    // Pedidos há mais tempo que isso na cozinha aparecem como atrasados
+    pub sla_cozinha: Duration,
+    pub politica_da_fila: PoliticaDaFila,
+}
+
+impl AppDependencies {
+    pub async fn from_config(config: &Config) -> Result<AppDependencies, String> {
+        // Sem nenhum gateway em postgres a aplicação sobe sem banco
+        let pool = if config.usa_postgres() {
+            info!(db_url = %telemetry::redact_url(&config.db_url), "Connecting to database");
+            let postgres_connection_manager =
+                postgres::PgConnectionManager::new(config.db_url.clone(), config.db_pool_size)?;
+            Some(postgres_connection_manager.pool)
+        } else {
+            info!("Nenhum gateway usa postgres, banco de dados não será conectado");
+            None
+        };
+
+        if let (Some(pool), true) = (&pool, config.auto_migrate) {
+            info!("Applying database migrations...");
+            let mut client = pool.get().await.map_err(|err| err.to_string())?;
+            let aplicadas = Migrator::new()
+                .up(&mut client)
+                .await
+                .map_err(|err| format!("Failed to apply migrations: {}", err))?;
+            for migration in aplicadas {
+                info!(migration = migration.name, "Migration aplicada");
+            }
+        }
+        if let (Some(pool), true) = (&pool, config.seed_demo) {
+            let mut client = pool.get().await.map_err(|err| err.to_string())?;
+            let inseridos = Migrator::new()
+                .seed(&mut client)
+                .await
+                .map_err(|err| format!("Failed to seed demo data: {}", err))?;
+            if inseridos {
+                info!("Dados de demonstração inseridos");
+            }
+        }
+        // Só é chamado nos backends postgres, e nesse caso o pool sempre existe
+        let postgres_pool = || pool.clone().expect("pool do postgres não inicializado");
+
+        let authentication_adapter: Arc<dyn AuthenticationAdapter + Sync + Send> =
+            Arc::new(JWTAuthenticationAdapter::new(config.secret.clone()));
+
+        let mut health_checks: Vec<Arc<dyn HealthCheckAdapter + Sync + Send>> = Vec::new();
+        if let Some(pool) = &pool {
+            health_checks.push(Arc::new(PostgresHealthCheckAdapter::new(pool.clone())));
+        }
+        if config.mock_pagamentos_url.is_some() {
+            health_checks.push(Arc::new(HttpHealthCheckAdapter::new(
+                "pagamentos",
+                config.mock_pagamentos_url.clone(),
+                false,
+            )));
+        }
+
+        // Config::load já recusa combinações inválidas, como cognito fora de clientes
+        info!(backend = %config.backend_usuario(), "Gateway de usuários");
+        let usuario_repository: Arc<dyn UsuarioGateway + Sync + Send> = match config.backend_usuario() {
+            Backend::Memory => Arc::new(InMemoryUsuarioRepository::new()),
+            _ => Arc::new(PostgresUsuarioGateway::new(postgres_pool()).await),
+        };
+
+        // Lojas ficam no mesmo backend dos usuários, que guardam a lista de lojas
+        // em que trabalham; em memória só existe a loja padrão até criarem outras
+        let loja_repository: Arc<dyn LojaGateway + Sync + Send> = match config.backend_usuario() {
+            Backend::Memory => Arc::new(InMemoryLojaRepository::new()),
+            _ => Arc::new(PostgresLojaRepository::new(postgres_pool()).await),
+        };
+
+        info!(backend = %config.backend_cliente(), "Gateway de clientes");
+        let cliente_repository: Arc<dyn ClienteGateway + Sync + Send> = match config.backend_cliente() {
+            Backend::Memory => Arc::new(InMemoryClienteRepository::new()),
+            Backend::Postgres => Arc::new(PostgresClienteRepository::new(postgres_pool()).await),
+            Backend::Cognito => {
+                let user_pool_id = config.aws_cognito_user_pool_id.clone().unwrap_or_default();
+                let cognito_repository = Arc::new(AwsCognitoRepository::new(user_pool_id).await);
+                health_checks.push(cognito_repository.clone());
+                cognito_repository
+            }
+        };
+
+        info!(backend = %config.backend_produto(), "Gateway de produtos");
+        let produto_repository: Arc<dyn ProdutoGateway + Sync + Send> = match config.backend_produto() {
+            Backend::Memory => Arc::new(InMemoryProdutoRepository::new()),
+            _ => Arc::new(PostgresProdutoRepository::new(postgres_pool()).await),
+        };
+
+        // Estações ficam com os produtos, que apontam para elas
+        let estacao_repository: Arc<dyn EstacaoGateway + Sync + Send> = match config.backend_produto() {
+            Backend::Memory => Arc::new(InMemoryEstacaoRepository::new()),
+            _ => Arc::new(PostgresEstacaoRepository::new(postgres_pool()).await),
+        };
+
+        // Os repositórios concretos são mantidos para que o unit of work consiga
+        // criar cópias que participam da mesma transação
+        info!(backend = %config.backend_pedido(), "Gateway de pedidos e pagamentos");
+        let (pedido_repository, pagamento_repository, unit_of_work): (
+            Arc<dyn PedidoGateway + Sync + Send>,
+            Arc<dyn PagamentoGateway + Sync + Send>,
+            Arc<dyn UnitOfWork + Sync + Send>,
+        ) = match config.backend_pedido() {
+            Backend::Memory => {
+                let pedido_repository = Arc::new(InMemoryPedidoRepository::new());
+                let pagamento_repository = Arc::new(InMemoryPagamentoRepository::new());
+                let unit_of_work = Arc::new(InMemoryUnitOfWork::new(
+                    pedido_repository.clone(),
+                    pagamento_repository.clone(),
+                ));
+                (pedido_repository, pagamento_repository, unit_of_work)
+            }
+            _ => {
+                let mut pedido_repository = PostgresPedidoRepository::new(
+                    postgres_pool(),
+                    cliente_repository.clone(),
+                    produto_repository.clone(),
+                )
+                .await;
+                if config.backend_cliente() == Backend::Postgres {
+                    pedido_repository = pedido_repository
+                        .com_clientes_no_postgres(PostgresClienteRepository::new(postgres_pool()).await);
+                }
+                if config.backend_produto() != Backend::Memory {
+                    pedido_repository = pedido_repository
+                        .com_produtos_no_postgres(PostgresProdutoRepository::new(postgres_pool()).await);
+                }
+                let pedido_repository = Arc::new(pedido_repository);
+                let pagamento_repository = Arc::new(
+                    PostgresPagamentoRepository::new(
+                        postgres_pool(),
+                        pedido_repository.clone(),
+                    )
+                    .await,
+                );
+                let unit_of_work = Arc::new(PostgresUnitOfWork::new(
+                    postgres_pool(),
+                    pedido_repository.clone(),
+                    pagamento_repository.clone(),
+                ));
+                (pedido_repository, pagamento_repository, unit_of_work)
+            }
+        };
+
+        info!(backend = %config.backend_storage(), "Storage de arquivos");
+        let storage: Arc<dyn StorageGateway + Sync + Send> = match config.backend_storage() {
+            StorageBackend::Memory => Arc::new(InMemoryStorageGateway::new()),
+            StorageBackend::Local => Arc::new(LocalStorageGateway::new(config.storage_dir.clone())),
+            StorageBackend::S3 => {
+                let bucket = config.s3_bucket.clone().unwrap_or_default();
+                let s3_storage = Arc::new(S3StorageGateway::new(bucket, config.s3_endpoint.clone()).await);
+                health_checks.push(s3_storage.clone());
+                s3_storage
+            }
+        };
+
+        // Sem serviço de pagamentos configurado o pagamento é simulado localmente
+        let mercado_pago_adapter: Arc<dyn PagamentoWebhookAdapter + Sync + Send> =
+            if config.mock_pagamentos_url.is_some() {
+                Arc::new(MercadoPagoPagamentoWebhookAdapter::new(
+                    config.mock_pagamentos_url.clone(),
+                    config.api_host.clone(),
+                ))
+            } else {
+                info!("mock_pagamentos_url não configurado, usando pagamentos simulados");
+                Arc::new(SimuladoPagamentoWebhookAdapter::new())
+            };
+
+        let mut metodos_pagamento: HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>> = HashMap::new();
+        metodos_pagamento.insert("Mercado Pago".to_string(), mercado_pago_adapter);
+
+        Ok(AppDependencies {
+            authentication_adapter,
+            usuario_repository,
+            loja_repository,
+            cliente_repository,
+            produto_repository,
+            estacao_repository,
+            pedido_repository,
+            pagamento_repository,
+            unit_of_work,
+            storage,
+            metodos_pagamento,
+            health_checks,
+            pool,
+            fuso_horario: config.fuso_horario_da_loja()?,
+            sla_cozinha: config.sla_cozinha(),
+            politica_da_fila: config.politica_da_fila(),
+        })
+    }
+
+    // Troca pedidos e pagamentos por implementações próprias. Sem um UnitOfWork
+    // que conheça esses repositórios as escritas passam a ser feitas sem transação
+    pub fn com_pedidos_e_pagamentos(
+        mut self,
+        pedido_repository: Arc<dyn PedidoGateway + Sync + Send>,
+        pagamento_repository: Arc<dyn PagamentoGateway + Sync + Send>,
+    ) -> Self {
+        self.unit_of_work = Arc::new(SemTransacaoUnitOfWork::new(
+            pedido_repository.clone(),
+            pagamento_repository.clone(),
+        ));
+        self.pedido_repository = pedido_repository;
+        self.pagamento_repository = pagamento_repository;
+        self
+    }
+}
@@ -1,54 +1,215 @@
 use crate::base::domain_error::DomainError;
 use rocket::http::Status;
+use rocket::request::Request;
+use rocket::response::{self, Responder};
 use rocket::serde::json::Json;
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::okapi::openapi3::Responses;
+use rocket_okapi::response::OpenApiResponderInner;
+use rocket_okapi::util::add_schema_response;
 use schemars::JsonSchema;
 use serde::Serialize;
+use std::error::Error;
+use tracing::error;
 
-impl From<DomainError> for Status {
+// Formato único de erro da API. `codigo` é estável e pode ser usado pelos clientes;
+// `msg` é só para leitura humana e pode mudar
+#[derive(Debug, Serialize, JsonSchema)]
+pub struct ErrorResponse {
+    pub codigo: String,
+    pub msg: String,
+    pub status: usize,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub detalhes: Vec<ErroDeCampo>,
+}
+
+#[derive(Debug, Clone, Serialize, JsonSchema)]
+pub struct ErroDeCampo {
+    pub campo: String,
+    pub msg: String,
+}
+
+#[derive(Debug)]
+pub struct ApiError {
+    status: Status,
+    body: ErrorResponse,
+}
+
+impl ApiError {
+    pub fn new(status: Status, codigo: &str, msg: impl Into<String>) -> Self {
+        ApiError {
+            status,
+            body: ErrorResponse {
+                codigo: codigo.to_string(),
+                msg: msg.into(),
+                status: status.code as usize,
+                detalhes: vec![],
+            },
+        }
+    }
+
+    pub fn com_detalhes(mut self, detalhes: Vec<ErroDeCampo>) -> Self {
+        self.body.detalhes = detalhes;
+        self
+    }
+}
+
+impl From<DomainError> for ApiError {
     fn from(error: DomainError) -> Self {
+        // Detalhes de falhas de infraestrutura ficam só no log, nunca na resposta
+        if let Some(causa) = error.source() {
+            error!(error = %error, causa = %cadeia_de_causas(causa), "Erro com causa");
+        }
+        let msg = error.to_string();
         match error {
-            DomainError::AlreadyExists => Status::Conflict,
-            DomainError::NotFound => Status::NotFound,
-            DomainError::Empty => Status::BadRequest,
-            DomainError::Invalid(_) => Status::BadRequest,
-            _ => Status::InternalServerError,
+            DomainError::Validation { campo, msg: motivo, .. } => {
+                let erro = ApiError::new(Status::BadRequest, "invalido", msg);
+                if campo.is_empty() {
+                    erro
+                } else {
+                    erro.com_detalhes(vec![ErroDeCampo { campo, msg: motivo }])
+                }
+            }
+            DomainError::Conflict { .. } => ApiError::new(Status::Conflict, "conflito", msg),
+            DomainError::NotFound { .. } => ApiError::new(Status::NotFound, "nao_encontrado", msg),
+            DomainError::Unauthorized => ApiError::new(Status::Unauthorized, "nao_autorizado", msg),
+            DomainError::Forbidden(_) => ApiError::new(Status::Forbidden, "proibido", msg),
+            DomainError::ExternalService { .. } => ApiError::new(Status::BadGateway, "servico_externo", msg),
+            DomainError::Persistence { .. } => {
+                ApiError::new(Status::InternalServerError, "erro_persistencia", "Erro ao acessar os dados")
+            }
+            DomainError::Unavailable { .. } => ApiError::new(Status::ServiceUnavailable, "indisponivel", msg),
         }
     }
 }
 
-#[derive(Debug, Serialize, JsonSchema)]
-pub struct ErrorResponse {
-    pub msg: String,
-    pub status: usize,
+fn cadeia_de_causas(causa: &(dyn Error + 'static)) -> String {
+    let mut cadeia = causa.to_string();
+    let mut atual = causa.source();
+    while let Some(causa) = atual {
+        cadeia.push_str(": ");
+        cadeia.push_str(&causa.to_string());
+        atual = causa.source();
+    }
+    cadeia
+}
+
+impl<'r> Responder<'r, 'static> for ApiError {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        (self.status, Json(self.body)).respond_to(req)
+    }
+}
+
+impl OpenApiResponderInner for ApiError {
+    fn responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let schema = gen.json_schema::<ErrorResponse>();
+        let mut responses = Responses::default();
+        for status in [400, 401, 403, 404, 409, 422, 500, 502, 503] {
+            add_schema_response(&mut responses, status, "application/json", schema.clone())?;
+        }
+        Ok(responses)
+    }
+}
+
+// Erros de desserialização do corpo guardados pelo JsonBody para o catcher de 422
+#[derive(Default)]
+pub struct ErrosDoCorpo(pub Vec<ErroDeCampo>);
+
+pub fn resposta_de_erro(status: Status, codigo: &str, msg: &str) -> (Status, Json<ErrorResponse>) {
+    let erro = ApiError::new(status, codigo, msg);
+    (erro.status, Json(erro.body))
 }
 
 #[catch(400)]
-fn bad_request() -> Json<ErrorResponse> {
-    let error = ErrorResponse {
-        msg: "Input inválido".to_string(),
-        status: 401,
-    };
-    Json(error)
+fn bad_request() -> (Status, Json<ErrorResponse>) {
+    resposta_de_erro(Status::BadRequest, "requisicao_invalida", "Input inválido")
 }
 
 #[catch(401)]
-fn unauthorized() -> Json<ErrorResponse> {
-    let error = ErrorResponse {
-        msg: "Credenciais invalidas".to_string(),
-        status: 401,
-    };
-    Json(error)
+fn unauthorized() -> (Status, Json<ErrorResponse>) {
+    resposta_de_erro(Status::Unauthorized, "nao_autorizado", "Credenciais invalidas")
+}
+
+#[catch(403)]
+fn forbidden() -> (Status, Json<ErrorResponse>) {
+    resposta_de_erro(Status::Forbidden, "proibido", "Usuário sem permissão para esta operação")
+}
+
+#[catch(404)]
+fn not_found() -> (Status, Json<ErrorResponse>) {
+    resposta_de_erro(Status::NotFound, "nao_encontrado", "Recurso não encontrado")
+}
+
+#[catch(422)]
+fn unprocessable_entity(req: &Request) -> (Status, Json<ErrorResponse>) {
+    let detalhes = req.local_cache(ErrosDoCorpo::default).0.clone();
+    let erro = ApiError::new(Status::UnprocessableEntity, "entrada_invalida", "Corpo da requisição inválido")
+        .com_detalhes(detalhes);
+    (erro.status, Json(erro.body))
 }
 
 #[catch(500)]
-fn internal() -> Json<ErrorResponse> {
-    let error = ErrorResponse {
-        msg: "Erro inesperado. Tente novamente mais tarde".to_string(),
-        status: 500,
-    };
-    Json(error)
+fn internal() -> (Status, Json<ErrorResponse>) {
+    resposta_de_erro(Status::InternalServerError, "erro_interno", "Erro inesperado. Tente novamente mais tarde")
+}
+
+#[catch(503)]
+fn unavailable() -> (Status, Json<ErrorResponse>) {
+    resposta_de_erro(Status::ServiceUnavailable, "indisponivel", "Serviço indisponível")
+}
+
+#[catch(default)]
+fn default_catcher(status: Status, _req: &Request) -> (Status, Json<ErrorResponse>) {
+    resposta_de_erro(status, "erro", status.reason().unwrap_or("Erro"))
 }
 
 pub fn generic_catchers() -> Vec<rocket::Catcher> {
-    catchers![bad_request, unauthorized, internal]
+    catchers![bad_request, unauthorized, forbidden, not_found, unprocessable_entity, internal, unavailable, default_catcher]
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_domain_error_vira_status_e_codigo() {
+        let casos = [
+            (DomainError::conflict("Usuário", "CPF já cadastrado"), Status::Conflict, "conflito"),
+            (DomainError::not_found("Pedido", 3), Status::NotFound, "nao_encontrado"),
+            (DomainError::Unauthorized, Status::Unauthorized, "nao_autorizado"),
+            (DomainError::forbidden("Somente admin"), Status::Forbidden, "proibido"),
+            (DomainError::external_service("Cognito", "timeout"), Status::BadGateway, "servico_externo"),
+            (DomainError::persistence("Erro no banco"), Status::InternalServerError, "erro_persistencia"),
+            (DomainError::unavailable("Pool esgotado"), Status::ServiceUnavailable, "indisponivel"),
+        ];
+        for (domain_error, status, codigo) in casos {
+            let erro = ApiError::from(domain_error);
+            assert_eq!(erro.status, status);
+            assert_eq!(erro.body.codigo, codigo);
+            assert_eq!(erro.body.status, status.code as usize);
+        }
+    }
+
+    #[test]
+    fn test_validacao_aponta_o_campo() {
+        let erro = ApiError::from(DomainError::empty("nome"));
+        assert_eq!(erro.status, Status::BadRequest);
+        assert_eq!(erro.body.codigo, "invalido");
+        assert_eq!(erro.body.detalhes[0].campo, "nome");
+    }
+
+    #[test]
+    fn test_not_found_identifica_o_registro() {
+        let erro = ApiError::from(DomainError::not_found("Pedido", 3));
+        assert_eq!(erro.body.msg, "Pedido 3 não encontrado");
+    }
+
+    #[test]
+    fn test_persistencia_nao_expoe_a_causa() {
+        let erro = ApiError::from(
+            DomainError::persistence("Erro ao acessar o banco de dados").com_causa("senha inválida para o usuário app"),
+        );
+        assert_eq!(erro.body.msg, "Erro ao acessar os dados");
+    }
 }
@@ -0,0 +1,42 @@
+use std::time::Instant;
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::{Data, Request, Response};
+
+use crate::external::metrics;
+
+struct InicioRequisicao(Option<Instant>);
+
+pub struct MetricsFairing;
+
+#[rocket::async_trait]
+impl Fairing for MetricsFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Prometheus Metrics",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        request.local_cache(|| InicioRequisicao(Some(Instant::now())));
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let inicio = match request.local_cache(|| InicioRequisicao(None)).0 {
+            Some(inicio) => inicio,
+            None => return,
+        };
+        // Usa o template da rota ("/pedidos/<id>") para não criar uma série por id
+        let rota = request
+            .route()
+            .map(|route| route.uri.as_str().to_string())
+            .unwrap_or_else(|| "nao_encontrada".to_string());
+        metrics::registrar_requisicao(
+            request.method().as_str(),
+            &rota,
+            response.status().code,
+            inicio.elapsed(),
+        );
+    }
+}
@@ -0,0 +1,101 @@
+use std::time::Instant;
+
+use rocket::fairing::{Fairing, Info, Kind};
+use rocket::http::Header;
+use rocket::route::{Handler, Outcome};
+use rocket::{Data, Request, Response, Route};
+use tracing::{info, info_span, Instrument};
+use uuid::Uuid;
+
+pub const REQUEST_ID_HEADER: &str = "X-Request-Id";
+
+#[derive(Clone, Debug)]
+pub struct RequestId(pub String);
+
+struct InicioRequisicao(Option<Instant>);
+
+impl RequestId {
+    // Reaproveita o id enviado pelo cliente (ou pelo load balancer) quando ele é seguro para logar
+    pub fn of<'r>(request: &'r Request<'_>) -> &'r RequestId {
+        request.local_cache(|| {
+            let recebido = request
+                .headers()
+                .get_one(REQUEST_ID_HEADER)
+                .filter(|id| id_valido(id));
+            match recebido {
+                Some(id) => RequestId(id.to_string()),
+                None => RequestId(Uuid::new_v4().to_string()),
+            }
+        })
+    }
+}
+
+fn id_valido(id: &str) -> bool {
+    !id.is_empty()
+        && id.len() <= 128
+        && id
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+pub struct RequestIdFairing;
+
+#[rocket::async_trait]
+impl Fairing for RequestIdFairing {
+    fn info(&self) -> Info {
+        Info {
+            name: "Request Id",
+            kind: Kind::Request | Kind::Response,
+        }
+    }
+
+    async fn on_request(&self, request: &mut Request<'_>, _: &mut Data<'_>) {
+        request.local_cache(|| InicioRequisicao(Some(Instant::now())));
+        RequestId::of(request);
+    }
+
+    async fn on_response<'r>(&self, request: &'r Request<'_>, response: &mut Response<'r>) {
+        let request_id = RequestId::of(request);
+        let duracao_ms = request
+            .local_cache(|| InicioRequisicao(None))
+            .0
+            .map(|inicio| inicio.elapsed().as_millis() as u64);
+        info!(
+            request_id = %request_id.0,
+            method = %request.method(),
+            uri = %request.uri(),
+            status = response.status().code,
+            duracao_ms,
+            "Requisição finalizada"
+        );
+        response.set_header(Header::new(REQUEST_ID_HEADER, request_id.0.clone()));
+    }
+}
+
+// Executa o handler (incluindo os request guards) dentro de um span com o request id,
+// assim todo log emitido por controllers, use cases e gateways carrega o id da requisição
+#[derive(Clone)]
+struct HandlerRastreado(Box<dyn Handler>);
+
+#[rocket::async_trait]
+impl Handler for HandlerRastreado {
+    async fn handle<'r>(&self, request: &'r Request<'_>, data: Data<'r>) -> Outcome<'r> {
+        let span = info_span!(
+            "request",
+            request_id = %RequestId::of(request).0,
+            method = %request.method(),
+            uri = %request.uri(),
+        );
+        self.0.handle(request, data).instrument(span).await
+    }
+}
+
+pub fn rastrear(routes: Vec<Route>) -> Vec<Route> {
+    routes
+        .into_iter()
+        .map(|mut route| {
+            route.handler = Box::new(HandlerRastreado(route.handler));
+            route
+        })
+        .collect()
+}
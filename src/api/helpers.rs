@@ -1 +1,5 @@
-pub mod param_helper;
\ No newline at end of file
+pub mod param_helper;
+pub mod json_body;
+pub mod etag;
+pub mod foto_upload;
+pub mod imagem;
\ No newline at end of file
@@ -0,0 +1,129 @@
+use rocket::http::Status;
+use rocket::request::Request;
+use rocket::response::{self, Responder, Response};
+use rocket::serde::json::Json;
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::okapi::openapi3::{Header, Object, ParameterValue, RefOr, Responses};
+use rocket_okapi::response::OpenApiResponderInner;
+use rocket_okapi::util::ensure_status_code_exists;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+// Resposta JSON com o cabeçalho ETag da versão do recurso, usada depois no If-Match
+pub struct ComEtag<T>(pub T, pub usize);
+
+// Resposta JSON pública que o cliente pode guardar e revalidar pelo If-None-Match,
+// recebendo 304 sem corpo enquanto o ETag não mudar
+pub struct Cacheavel<T> {
+    pub corpo: T,
+    pub etag: String,
+}
+
+const CACHE_CONTROL: &str = "public, no-cache";
+// O mesmo caminho responde por lojas diferentes conforme o cabeçalho X-Loja
+const VARY: &str = "X-Loja";
+
+// Se o If-None-Match enviado já contém o ETag (entre aspas) ou `*`
+pub fn etag_em_cache(req: &Request<'_>, etag: &str) -> bool {
+    req.headers()
+        .get("If-None-Match")
+        .flat_map(|valor| valor.split(','))
+        .map(|valor| valor.trim())
+        .any(|valor| valor == "*" || valor.strip_prefix("W/").unwrap_or(valor) == etag)
+}
+
+pub fn etag(versao: usize) -> String {
+    format!("\"{}\"", versao)
+}
+
+// Aceita `"3"`, `W/"3"` e `3`; `*` vale para qualquer versão
+pub fn parse_etag(valor: &str) -> Option<Option<usize>> {
+    let valor = valor.trim();
+    if valor == "*" {
+        return Some(None);
+    }
+    let valor = valor.strip_prefix("W/").unwrap_or(valor).trim_matches('"');
+    valor.parse().ok().map(Some)
+}
+
+impl<'r, T: Serialize> Responder<'r, 'static> for ComEtag<T> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        Response::build_from(Json(self.0).respond_to(req)?)
+            .raw_header("ETag", etag(self.1))
+            .ok()
+    }
+}
+
+impl<'r, T: Serialize> Responder<'r, 'static> for Cacheavel<T> {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let etag = format!("\"{}\"", self.etag);
+        if etag_em_cache(req, &etag) {
+            return Response::build()
+                .status(Status::NotModified)
+                .raw_header("ETag", etag)
+                .raw_header("Cache-Control", CACHE_CONTROL)
+                .raw_header("Vary", VARY)
+                .ok();
+        }
+        Response::build_from(Json(self.corpo).respond_to(req)?)
+            .raw_header("ETag", etag)
+            .raw_header("Cache-Control", CACHE_CONTROL)
+            .raw_header("Vary", VARY)
+            .ok()
+    }
+}
+
+impl<T: Serialize + JsonSchema + Send> OpenApiResponderInner for Cacheavel<T> {
+    fn responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let mut responses = Json::<T>::responses(gen)?;
+        if let RefOr::Object(response) = ensure_status_code_exists(&mut responses, 304) {
+            response.description = "O conteúdo não mudou desde o ETag enviado no If-None-Match".to_owned();
+        }
+        Ok(responses)
+    }
+}
+
+impl<T: Serialize + JsonSchema + Send> OpenApiResponderInner for ComEtag<T> {
+    fn responses(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let mut responses = Json::<T>::responses(gen)?;
+        if let Some(RefOr::Object(response)) = responses.responses.get_mut("200") {
+            let header = Header {
+                description: Some("Versão do recurso, para enviar no If-Match".to_owned()),
+                required: true,
+                deprecated: false,
+                allow_empty_value: false,
+                value: ParameterValue::Schema {
+                    style: None,
+                    explode: None,
+                    allow_reserved: false,
+                    schema: gen.json_schema_no_ref::<String>(),
+                    example: None,
+                    examples: None,
+                },
+                extensions: Object::default(),
+            };
+            response.headers.insert("ETag".to_owned(), RefOr::Object(header));
+        }
+        Ok(responses)
+    }
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_etag() {
+        assert_eq!(parse_etag("\"3\""), Some(Some(3)));
+        assert_eq!(parse_etag("W/\"3\""), Some(Some(3)));
+        assert_eq!(parse_etag("3"), Some(Some(3)));
+        assert_eq!(parse_etag("*"), Some(None));
+        assert_eq!(parse_etag("\"abc\""), None);
+    }
+
+    #[test]
+    fn test_etag_volta_pelo_parse() {
+        assert_eq!(parse_etag(&etag(12)), Some(Some(12)));
+    }
+}
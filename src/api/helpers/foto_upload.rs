@@ -0,0 +1,103 @@
+use std::collections::HashMap;
+
+use rocket::data::{self, Data, FromData};
+use rocket::form::Form;
+use rocket::fs::TempFile;
+use rocket::request::Request;
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::okapi::openapi3::{MediaType, Object, RequestBody};
+use rocket_okapi::okapi::schemars::schema::{InstanceType, SchemaObject};
+use rocket_okapi::okapi::Map;
+use rocket_okapi::request::OpenApiFromData;
+
+use crate::api::error_handling::{ErroDeCampo, ErrosDoCorpo};
+
+// Corpo multipart/form-data com o arquivo no campo `foto`. Formato e tamanho são
+// validados no caso de uso; aqui só o limite `file` do Rocket se aplica
+pub struct FotoUpload {
+    pub conteudo: Vec<u8>,
+    pub content_type: String,
+}
+
+fn erro_no_corpo(req: &Request<'_>, msg: String) -> ErroDeCampo {
+    let erro = ErroDeCampo {
+        campo: "foto".to_string(),
+        msg,
+    };
+    req.local_cache(|| ErrosDoCorpo(vec![erro.clone()]));
+    erro
+}
+
+#[rocket::async_trait]
+impl<'r> FromData<'r> for FotoUpload {
+    type Error = ErroDeCampo;
+
+    async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        let mut campos = match Form::<HashMap<String, TempFile<'r>>>::from_data(req, data).await {
+            data::Outcome::Success(campos) => campos.into_inner(),
+            data::Outcome::Failure((status, erros)) => {
+                return data::Outcome::Failure((status, erro_no_corpo(req, erros.to_string())));
+            }
+            data::Outcome::Forward(data) => return data::Outcome::Forward(data),
+        };
+        let foto = match campos.remove("foto") {
+            Some(foto) => foto,
+            None => {
+                return data::Outcome::Failure((
+                    rocket::http::Status::BadRequest,
+                    erro_no_corpo(req, "campo obrigatório".to_string()),
+                ));
+            }
+        };
+
+        let content_type = foto
+            .content_type()
+            .map(|content_type| format!("{}/{}", content_type.top(), content_type.sub()))
+            .unwrap_or_default();
+        let conteudo = match &foto {
+            TempFile::Buffered { content } => Ok(content.as_bytes().to_vec()),
+            arquivo => match arquivo.path() {
+                Some(caminho) => tokio::fs::read(caminho).await,
+                None => Ok(Vec::new()),
+            },
+        };
+        match conteudo {
+            Ok(conteudo) => data::Outcome::Success(FotoUpload { conteudo, content_type }),
+            Err(err) => data::Outcome::Failure((
+                rocket::http::Status::BadRequest,
+                erro_no_corpo(req, format!("não foi possível ler o arquivo: {}", err)),
+            )),
+        }
+    }
+}
+
+impl<'r> OpenApiFromData<'r> for FotoUpload {
+    fn request_body(_gen: &mut OpenApiGenerator) -> rocket_okapi::Result<RequestBody> {
+        let arquivo = SchemaObject {
+            instance_type: Some(InstanceType::String.into()),
+            format: Some("binary".to_owned()),
+            ..Default::default()
+        };
+        let mut formulario = SchemaObject {
+            instance_type: Some(InstanceType::Object.into()),
+            ..Default::default()
+        };
+        formulario.object().properties.insert("foto".to_owned(), arquivo.into());
+        formulario.object().required.insert("foto".to_owned());
+
+        let mut content = Map::new();
+        content.insert(
+            "multipart/form-data".to_owned(),
+            MediaType {
+                schema: Some(formulario),
+                ..Default::default()
+            },
+        );
+        Ok(RequestBody {
+            description: Some("Imagem PNG ou JPEG de até 5 MB no campo `foto`".to_owned()),
+            content,
+            required: true,
+            extensions: Object::default(),
+        })
+    }
+}
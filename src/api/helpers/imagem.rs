@@ -0,0 +1,68 @@
+use std::path::Path;
+
+use rocket::http::{ContentType, Status};
+use rocket::request::Request;
+use rocket::response::{self, Responder, Response};
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::okapi::openapi3::{MediaType, RefOr, Responses};
+use rocket_okapi::response::OpenApiResponderInner;
+use rocket_okapi::util::ensure_status_code_exists;
+
+use crate::api::helpers::etag::etag_em_cache;
+use crate::traits::storage_gateway::Arquivo;
+
+// A URL da foto é fixa por produto, então o navegador guarda por pouco tempo e
+// depois revalida pelo ETag, que muda a cada foto enviada
+const CACHE_CONTROL: &str = "public, max-age=300, must-revalidate";
+
+pub struct Imagem {
+    pub arquivo: Arquivo,
+    // Chave do arquivo no storage
+    pub chave: String,
+}
+
+impl Imagem {
+    fn etag(&self) -> String {
+        let nome = Path::new(&self.chave)
+            .file_stem()
+            .and_then(|nome| nome.to_str())
+            .unwrap_or(&self.chave);
+        format!("\"{}\"", nome)
+    }
+}
+
+impl<'r> Responder<'r, 'static> for Imagem {
+    fn respond_to(self, req: &'r Request<'_>) -> response::Result<'static> {
+        let etag = self.etag();
+        let mut resposta = Response::build();
+        resposta
+            .raw_header("ETag", etag.clone())
+            .raw_header("Cache-Control", CACHE_CONTROL);
+
+        if etag_em_cache(req, &etag) {
+            return resposta.status(Status::NotModified).ok();
+        }
+
+        let content_type = ContentType::parse_flexible(&self.arquivo.content_type).unwrap_or(ContentType::Binary);
+        resposta
+            .header(content_type)
+            .sized_body(self.arquivo.conteudo.len(), std::io::Cursor::new(self.arquivo.conteudo))
+            .ok()
+    }
+}
+
+impl OpenApiResponderInner for Imagem {
+    fn responses(_gen: &mut OpenApiGenerator) -> rocket_okapi::Result<Responses> {
+        let mut responses = Responses::default();
+        if let RefOr::Object(response) = ensure_status_code_exists(&mut responses, 200) {
+            response.description = "Imagem PNG ou JPEG, com ETag e Cache-Control".to_owned();
+            for content_type in ["image/png", "image/jpeg"] {
+                response.content.insert(content_type.to_owned(), MediaType::default());
+            }
+        }
+        if let RefOr::Object(response) = ensure_status_code_exists(&mut responses, 304) {
+            response.description = "A imagem não mudou desde o ETag enviado no If-None-Match".to_owned();
+        }
+        Ok(responses)
+    }
+}
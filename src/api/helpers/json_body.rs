@@ -0,0 +1,70 @@
+use rocket::data::{self, Data, FromData, Limits, ToByteUnit};
+use rocket::http::Status;
+use rocket::request::Request;
+use rocket::serde::json::Json;
+use rocket_okapi::gen::OpenApiGenerator;
+use rocket_okapi::okapi::openapi3::RequestBody;
+use rocket_okapi::request::OpenApiFromData;
+use schemars::JsonSchema;
+use serde::de::DeserializeOwned;
+
+use crate::api::error_handling::{ErroDeCampo, ErrosDoCorpo};
+
+// Igual ao Json<T> do Rocket, mas guarda qual campo falhou na desserialização
+// para que o catcher de 422 devolva os detalhes por campo
+pub struct JsonBody<T>(pub T);
+
+impl<T> JsonBody<T> {
+    pub fn into_inner(self) -> T {
+        self.0
+    }
+}
+
+fn erro_de_campo(erro: serde_path_to_error::Error<serde_json::Error>) -> ErroDeCampo {
+    let campo = erro.path().to_string();
+    ErroDeCampo {
+        campo: if campo == "." { String::new() } else { campo },
+        msg: erro.into_inner().to_string(),
+    }
+}
+
+#[rocket::async_trait]
+impl<'r, T: DeserializeOwned> FromData<'r> for JsonBody<T> {
+    type Error = ErroDeCampo;
+
+    async fn from_data(req: &'r Request<'_>, data: Data<'r>) -> data::Outcome<'r, Self> {
+        let limit = req.limits().get("json").unwrap_or(Limits::JSON);
+        let corpo = match data.open(limit).into_string().await {
+            Ok(corpo) if corpo.is_complete() => corpo.into_inner(),
+            _ => {
+                let erro = ErroDeCampo {
+                    campo: String::new(),
+                    msg: format!("corpo maior que {} ou ilegível", limit.max(0.bytes())),
+                };
+                return data::Outcome::Failure((Status::PayloadTooLarge, erro));
+            }
+        };
+
+        let mut deserializer = serde_json::Deserializer::from_str(&corpo);
+        match serde_path_to_error::deserialize(&mut deserializer) {
+            Ok(valor) => data::Outcome::Success(JsonBody(valor)),
+            Err(erro) => {
+                // Erro de sintaxe é 400, como no Json<T>; corpo válido com campos errados é 422
+                let status = if erro.inner().is_data() {
+                    Status::UnprocessableEntity
+                } else {
+                    Status::BadRequest
+                };
+                let erro = erro_de_campo(erro);
+                req.local_cache(|| ErrosDoCorpo(vec![erro.clone()]));
+                data::Outcome::Failure((status, erro))
+            }
+        }
+    }
+}
+
+impl<'r, T: JsonSchema + DeserializeOwned> OpenApiFromData<'r> for JsonBody<T> {
+    fn request_body(gen: &mut OpenApiGenerator) -> rocket_okapi::Result<RequestBody> {
+        <Json<T> as OpenApiFromData>::request_body(gen)
+    }
+}
@@ -0,0 +1,74 @@
+use super::config::{Config, Env};
+use super::telemetry;
+use crate::external::postgres;
+use crate::external::postgres::migrations::Migrator;
+
+pub fn main(comando: &str, argumento: Option<&str>) -> Result<(), String> {
+    let config = Config::load().map_err(|err| err.to_string())?;
+    telemetry::init(&config.env);
+
+    rocket::execute(async move {
+        let postgres_connection_manager =
+            postgres::PgConnectionManager::new(config.db_url.clone(), 1)?;
+        let mut client = postgres_connection_manager
+            .pool
+            .get()
+            .await
+            .map_err(|err| err.to_string())?;
+        let client = &mut client;
+        let migrator = Migrator::new();
+
+        match comando {
+            "up" => {
+                let aplicadas = migrator.up(client).await.map_err(|err| err.to_string())?;
+                if aplicadas.is_empty() {
+                    println!("Nenhuma migration pendente");
+                }
+                for migration in aplicadas {
+                    println!("Migration aplicada: {}", migration.name);
+                }
+            }
+            "down" => match migrator.down(client).await.map_err(|err| err.to_string())? {
+                Some(migration) => println!("Migration revertida: {}", migration.name),
+                None => println!("Nenhuma migration aplicada"),
+            },
+            "status" => {
+                let status = migrator.status(client).await.map_err(|err| err.to_string())?;
+                for migration in status {
+                    match migration.applied_at {
+                        Some(applied_at) => {
+                            println!("[x] {} (aplicada em {})", migration.name, applied_at.to_rfc3339())
+                        }
+                        None => println!("[ ] {}", migration.name),
+                    }
+                }
+            }
+            // Adota um banco que já tem o esquema até a versão informada
+            "baseline" => {
+                let version: i64 = argumento
+                    .and_then(|versao| versao.parse().ok())
+                    .ok_or("Informe a versão: migrate baseline <versao>")?;
+                let registradas = migrator
+                    .baseline(client, version)
+                    .await
+                    .map_err(|err| err.to_string())?;
+                for migration in registradas {
+                    println!("Migration registrada sem executar: {}", migration.name);
+                }
+            }
+            // Dados de demonstração, só fora de prod
+            "seed" => {
+                if config.env == Env::Prod {
+                    return Err("seed não é permitido em prod".to_string());
+                }
+                if migrator.seed(client).await.map_err(|err| err.to_string())? {
+                    println!("Dados de demonstração inseridos");
+                } else {
+                    println!("Banco já tem dados, nada foi inserido");
+                }
+            }
+            _ => return Err(format!("Comando de migração inválido: {} (use up, down, status, baseline ou seed)", comando)),
+        }
+        Ok(())
+    })
+}
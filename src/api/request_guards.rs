@@ -1,2 +1,5 @@
 pub mod authentication_guard;
-pub mod admin_guard;
\ No newline at end of file
+pub mod admin_guard;
+pub mod controller_guard;
+pub mod if_match_guard;
+pub mod loja_guard;
@@ -13,10 +13,21 @@ use rocket_okapi::{
 use crate::{base::domain_error::DomainError, entities::usuario::Tipo};
 
 use std::sync::Arc;
-use crate::traits::authentication_adapter::AuthenticationAdapter;
+use crate::traits::authentication_adapter::{AuthenticationAdapter, DadosDoToken};
 
 pub struct AdminUser {
-    user_id: String,
+    dados: DadosDoToken,
+}
+
+impl AdminUser {
+    pub fn usuario_id(&self) -> &str {
+        &self.dados.usuario_id
+    }
+
+    // Lojas que o admin gerencia; ele só pode dar acesso a essas
+    pub fn lojas(&self) -> Vec<usize> {
+        self.dados.lojas.clone()
+    }
 }
 
 #[rocket::async_trait]
@@ -30,10 +41,10 @@ impl<'r> FromRequest<'r> for AdminUser {
 
                 let auth_adapter = req.rocket().state::<Arc<dyn AuthenticationAdapter + Sync + Send>>().unwrap();
                 match auth_adapter.validate_token(token.to_string(), Some(Tipo::Admin)).await {
-                    Ok(user_id) => Outcome::Success(AdminUser { user_id }),
-                    Err(_) => {
-                        return Outcome::Failure((Status::Unauthorized, DomainError::Unauthorized))
-                    }
+                    Ok(dados) => Outcome::Success(AdminUser { dados }),
+                    // Token válido de quem não é admin
+                    Err(err @ DomainError::Forbidden(_)) => Outcome::Failure((Status::Forbidden, err)),
+                    Err(err) => Outcome::Failure((Status::Unauthorized, err)),
                 }
             }
             None => Outcome::Failure((Status::BadRequest, DomainError::Unauthorized)),
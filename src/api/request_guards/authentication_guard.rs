@@ -12,10 +12,16 @@ use rocket_okapi::{
 use crate::base::domain_error::DomainError;
 
 use std::sync::Arc;
-use crate::traits::authentication_adapter::AuthenticationAdapter;
+use crate::traits::authentication_adapter::{AuthenticationAdapter, DadosDoToken};
 
 pub struct AuthenticatedUser {
-    user_id: String,
+    dados: DadosDoToken,
+}
+
+impl AuthenticatedUser {
+    pub fn lojas(&self) -> Vec<usize> {
+        self.dados.lojas.clone()
+    }
 }
 
 #[rocket::async_trait]
@@ -28,13 +34,11 @@ impl<'r> FromRequest<'r> for AuthenticatedUser {
                 let token = token.replace("Bearer ", "");
                 let auth_adapter = req.rocket().state::<Arc<dyn AuthenticationAdapter + Sync + Send>>().unwrap();
                 match auth_adapter.validate_token(token.to_string(), None).await {
-                    Ok(user_id) => Outcome::Success(AuthenticatedUser { user_id }),
-                    Err(_) => {
-                        return Outcome::Failure((Status::Unauthorized, DomainError::Unauthorized))
-                    }
+                    Ok(dados) => Outcome::Success(AuthenticatedUser { dados }),
+                    Err(err) => Outcome::Failure((Status::Unauthorized, err)),
                 }
             }
-            None => Outcome::Failure((Status::BadRequest, DomainError::Invalid("Missing Token".to_string()))),
+            None => Outcome::Failure((Status::BadRequest, DomainError::validation("Authorization", "Token ausente"))),
         }
     }
 }
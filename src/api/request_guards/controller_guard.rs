@@ -0,0 +1,56 @@
+use rocket::{
+    http::Status,
+    request::{self, FromRequest, Outcome, Request},
+};
+use rocket_okapi::{
+    gen::OpenApiGenerator,
+    request::{OpenApiFromRequest, RequestHeaderInput},
+    OpenApiError,
+};
+use tracing::error;
+
+use crate::api::app_state::AppState;
+use crate::api::request_guards::loja_guard::LojaAtual;
+use crate::base::domain_error::DomainError;
+use crate::controllers::cozinha_controller::CozinhaController;
+use crate::controllers::pedido_controller::PedidoController;
+
+// Controllers montados pelo AppState para a loja do X-Loja
+macro_rules! controller_da_loja {
+    ($controller:ty, $construtor:ident) => {
+        #[rocket::async_trait]
+        impl<'r> FromRequest<'r> for $controller {
+            type Error = DomainError;
+
+            async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+                let loja = match req.guard::<LojaAtual>().await {
+                    Outcome::Success(loja) => loja,
+                    Outcome::Failure(falha) => return Outcome::Failure(falha),
+                    Outcome::Forward(forward) => return Outcome::Forward(forward),
+                };
+                match req.rocket().state::<AppState>() {
+                    Some(app_state) => Outcome::Success(app_state.$construtor(loja.0)),
+                    None => {
+                        error!("AppState não registrado no rocket");
+                        Outcome::Failure((Status::InternalServerError, DomainError::unavailable("AppState não registrado")))
+                    }
+                }
+            }
+        }
+
+        // Só o X-Loja aparece na documentação; o resto é injeção de dependência
+        // e não entrada da requisição
+        impl<'a> OpenApiFromRequest<'a> for $controller {
+            fn from_request_input(
+                gen: &mut OpenApiGenerator,
+                name: String,
+                required: bool,
+            ) -> Result<RequestHeaderInput, OpenApiError> {
+                LojaAtual::from_request_input(gen, name, required)
+            }
+        }
+    };
+}
+
+controller_da_loja!(PedidoController, pedido_controller);
+controller_da_loja!(CozinhaController, cozinha_controller);
@@ -0,0 +1,60 @@
+use rocket::{
+    http::Status,
+    request::{self, FromRequest, Outcome, Request},
+};
+use rocket_okapi::{
+    gen::OpenApiGenerator,
+    okapi::openapi3::{Object, Parameter, ParameterValue},
+    request::{OpenApiFromRequest, RequestHeaderInput},
+    OpenApiError,
+};
+
+use crate::api::helpers::etag::parse_etag;
+use crate::base::domain_error::DomainError;
+
+// Versão esperada pelo cliente; sem If-Match (ou com `*`) qualquer versão serve
+pub struct IfMatch(pub Option<usize>);
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for IfMatch {
+    type Error = DomainError;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        match req.headers().get_one("If-Match") {
+            None => Outcome::Success(IfMatch(None)),
+            Some(valor) => match parse_etag(valor) {
+                Some(versao) => Outcome::Success(IfMatch(versao)),
+                None => Outcome::Failure((
+                    Status::BadRequest,
+                    DomainError::validation("If-Match", "ETag inválido"),
+                )),
+            },
+        }
+    }
+}
+
+impl<'a> OpenApiFromRequest<'a> for IfMatch {
+    fn from_request_input(
+        gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> Result<RequestHeaderInput, OpenApiError> {
+        Ok(RequestHeaderInput::Parameter(Parameter {
+            name: "If-Match".to_owned(),
+            location: "header".to_owned(),
+            description: Some("ETag recebido ao ler o recurso; se a versão mudou a resposta é 412".to_owned()),
+            required: false,
+            deprecated: false,
+            allow_empty_value: false,
+            value: ParameterValue::Schema {
+                style: None,
+                explode: None,
+                allow_reserved: false,
+                schema: gen.json_schema_no_ref::<String>(),
+                example: None,
+                examples: None,
+            },
+            extensions: Object::default(),
+        }))
+    }
+}
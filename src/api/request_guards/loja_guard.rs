@@ -0,0 +1,104 @@
+use std::sync::Arc;
+
+use rocket::{
+    http::Status,
+    request::{self, FromRequest, Outcome, Request},
+};
+use rocket_okapi::{
+    gen::OpenApiGenerator,
+    okapi::openapi3::{Object, Parameter, ParameterValue},
+    request::{OpenApiFromRequest, RequestHeaderInput},
+    OpenApiError,
+};
+
+use crate::base::domain_error::DomainError;
+use crate::entities::loja::LOJA_PADRAO;
+use crate::traits::authentication_adapter::AuthenticationAdapter;
+use crate::traits::loja_gateway::LojaGateway;
+
+// Loja em que a requisição opera. Vem do cabeçalho X-Loja ou, quando não dá para
+// mandar cabeçalho (webhook), do parâmetro `?loja=`; sem nenhum dos dois é a
+// primeira loja do token, ou a loja padrão para quem não está logado
+pub struct LojaAtual(pub usize);
+
+fn loja_informada(req: &Request<'_>) -> Option<Result<usize, DomainError>> {
+    let valor = req
+        .headers()
+        .get_one("X-Loja")
+        .map(|valor| valor.trim().to_string())
+        .or_else(|| req.query_value::<String>("loja").and_then(|valor| valor.ok()))?;
+    Some(
+        valor
+            .parse::<usize>()
+            .map_err(|_| DomainError::validation("X-Loja", "Loja inválida")),
+    )
+}
+
+#[rocket::async_trait]
+impl<'r> FromRequest<'r> for LojaAtual {
+    type Error = DomainError;
+
+    async fn from_request(req: &'r Request<'_>) -> request::Outcome<Self, Self::Error> {
+        // Token inválido não é problema deste guard: o de autenticação responde por ele
+        let dados_do_token = match req.headers().get_one("Authorization") {
+            Some(token) => {
+                let token = token.replace("Bearer ", "");
+                let auth_adapter = req.rocket().state::<Arc<dyn AuthenticationAdapter + Sync + Send>>().unwrap();
+                auth_adapter.validate_token(token, None).await.ok()
+            }
+            None => None,
+        };
+
+        let loja_id = match loja_informada(req) {
+            Some(Ok(loja_id)) => loja_id,
+            Some(Err(err)) => return Outcome::Failure((Status::BadRequest, err)),
+            None => dados_do_token
+                .as_ref()
+                .and_then(|dados| dados.lojas.first().copied())
+                .unwrap_or(LOJA_PADRAO),
+        };
+
+        let loja_repository = req.rocket().state::<Arc<dyn LojaGateway + Sync + Send>>().unwrap();
+        match loja_repository.get_loja_by_id(loja_id).await {
+            Ok(_) => {}
+            Err(DomainError::NotFound { .. }) => {
+                return Outcome::Failure((Status::BadRequest, DomainError::validation("X-Loja", "Loja inválida")))
+            }
+            Err(err) => return Outcome::Failure((Status::ServiceUnavailable, err)),
+        }
+
+        match dados_do_token {
+            Some(dados) if !dados.acessa_loja(loja_id) => Outcome::Failure((
+                Status::Forbidden,
+                DomainError::forbidden("Usuário sem acesso a esta loja"),
+            )),
+            _ => Outcome::Success(LojaAtual(loja_id)),
+        }
+    }
+}
+
+impl<'a> OpenApiFromRequest<'a> for LojaAtual {
+    fn from_request_input(
+        gen: &mut OpenApiGenerator,
+        _name: String,
+        _required: bool,
+    ) -> Result<RequestHeaderInput, OpenApiError> {
+        Ok(RequestHeaderInput::Parameter(Parameter {
+            name: "X-Loja".to_owned(),
+            location: "header".to_owned(),
+            description: Some("Id da loja; sem ele vale a primeira loja do token".to_owned()),
+            required: false,
+            deprecated: false,
+            allow_empty_value: false,
+            value: ParameterValue::Schema {
+                style: None,
+                explode: None,
+                allow_reserved: false,
+                schema: gen.json_schema_no_ref::<usize>(),
+                example: None,
+                examples: None,
+            },
+            extensions: Object::default(),
+        }))
+    }
+}
@@ -1,5 +1,9 @@
 pub mod auth_route;
+pub mod cardapio_route;
+pub mod health_route;
+pub mod metrics_route;
 pub mod pedido_route;
 pub mod usuario_route;
 pub mod cliente_route;
-pub mod produto_route;
+pub mod produto_route;pub mod loja_route;
+pub mod cozinha_route;
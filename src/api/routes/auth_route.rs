@@ -1,10 +1,11 @@
 use std::sync::Arc;
 
-use rocket::http::Status;
 use rocket::serde::json::Json;
 use rocket::State;
 use rocket_okapi::{openapi, openapi_get_routes};
-use tokio::sync::Mutex;
+
+use crate::api::error_handling::ApiError;
+use crate::api::helpers::json_body::JsonBody;
 
 use crate::controllers::auth_controller::{
     AuthController,
@@ -19,10 +20,10 @@ use crate::traits::usuario_gateway::UsuarioGateway;
 #[openapi(tag = "Auth")]
 #[post("/login", data = "<login_input>")]
 async fn login(
-    usuario_repository: &State<Arc<Mutex<dyn UsuarioGateway + Send + Sync>>>,
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Send + Sync>>,
     authentication_adapter: &State<Arc<dyn AuthenticationAdapter + Sync + Send>>,
-    login_input: Json<LoginInput>,
-) -> Result<Json<AuthenticationResponse>, Status> {
+    login_input: JsonBody<LoginInput>,
+) -> Result<Json<AuthenticationResponse>, ApiError> {
     let usuario_repository = usuario_repository.inner().clone();
     let authentication_adapter = authentication_adapter.inner().clone();
     let auth_controller = AuthController::new(usuario_repository, authentication_adapter);
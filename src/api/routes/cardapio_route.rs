@@ -0,0 +1,52 @@
+use std::sync::Arc;
+
+use chrono_tz::Tz;
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::{openapi, openapi_get_routes};
+
+use crate::api::error_handling::ApiError;
+use crate::api::helpers::etag::Cacheavel;
+use crate::api::request_guards::loja_guard::LojaAtual;
+use crate::controllers::produto_controller::ProdutoController;
+use crate::entities::alergeno::Alergeno;
+use crate::entities::cardapio::Cardapio;
+use crate::entities::produto::Produto;
+use crate::traits::produto_gateway::ProdutoGateway;
+
+// Sem autenticação: o totem carrega o cardápio antes de qualquer login.
+// `sem_alergeno` pode se repetir, ex.: ?sem_alergeno=Gluten&sem_alergeno=Lactose
+#[openapi(tag = "Cardápio")]
+#[get("/?<sem_alergeno>")]
+async fn get_cardapio(
+    produto_repository: &State<Arc<dyn ProdutoGateway + Sync + Send>>,
+    loja: LojaAtual,
+    fuso_horario: &State<Tz>,
+    sem_alergeno: Vec<Alergeno>,
+) -> Result<Cacheavel<Cardapio>, ApiError> {
+    let produto_controller =
+        ProdutoController::new(produto_repository.na_loja(loja.0)).com_fuso_horario(*fuso_horario.inner());
+    let cardapio = produto_controller.get_cardapio(&sem_alergeno).await?;
+    let etag = cardapio.versao();
+    Ok(Cacheavel { corpo: cardapio, etag })
+}
+
+// Prévia de um produto personalizado, para o totem mostrar os alérgenos que sobram
+#[openapi(tag = "Cardápio")]
+#[get("/<id>?<sem_ingrediente>")]
+async fn get_produto_personalizado(
+    produto_repository: &State<Arc<dyn ProdutoGateway + Sync + Send>>,
+    loja: LojaAtual,
+    fuso_horario: &State<Tz>,
+    id: usize,
+    sem_ingrediente: Vec<String>,
+) -> Result<Json<Produto>, ApiError> {
+    let produto_controller =
+        ProdutoController::new(produto_repository.na_loja(loja.0)).com_fuso_horario(*fuso_horario.inner());
+    let produto = produto_controller.personalizar_produto(id, sem_ingrediente).await?;
+    Ok(Json(produto))
+}
+
+pub fn routes() -> Vec<rocket::Route> {
+    openapi_get_routes![get_cardapio, get_produto_personalizado]
+}
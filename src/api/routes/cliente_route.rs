@@ -5,9 +5,9 @@ use rocket::request::FromParam;
 use rocket::serde::json::Json;
 use rocket::State;
 use rocket_okapi::{openapi, openapi_get_routes};
-use tokio::sync::Mutex;
 
-use crate::api::error_handling::ErrorResponse;
+use crate::api::error_handling::{resposta_de_erro, ApiError, ErrorResponse};
+use crate::api::helpers::json_body::JsonBody;
 use crate::api::request_guards::authentication_guard::AuthenticatedUser;
 use crate::controllers::cliente_controller::ClienteController;
 use crate::traits::cliente_gateway::ClienteGateway;
@@ -25,9 +25,9 @@ impl<'a> FromParam<'a> for Cpf {
 #[openapi(tag = "Clientes")]
 #[get("/")]
 async fn lista_clientes(
-    cliente_repository: &State<Arc<Mutex<dyn ClienteGateway + Sync + Send>>>,
+    cliente_repository: &State<Arc<dyn ClienteGateway + Sync + Send>>,
     _logged_user_info: AuthenticatedUser,
-) -> Result<Json<Vec<Cliente>>, Status> {
+) -> Result<Json<Vec<Cliente>>, ApiError> {
     let cliente_controller = ClienteController::new(cliente_repository.inner().clone());
     let clientes = cliente_controller.lista_clientes().await?;
     Ok(Json(clientes))
@@ -36,9 +36,9 @@ async fn lista_clientes(
 #[openapi(tag = "Clientes")]
 #[get("/<cpf>")]
 async fn busca_cliente_por_cpf(
-    cliente_repository: &State<Arc<Mutex<dyn ClienteGateway + Sync + Send>>>,
+    cliente_repository: &State<Arc<dyn ClienteGateway + Sync + Send>>,
     cpf: Cpf,
-) -> Result<Json<Cliente>, Status> {
+) -> Result<Json<Cliente>, ApiError> {
     let cliente_controller = ClienteController::new(cliente_repository.inner().clone());
     let cliente = cliente_controller.busca_cliente_por_cpf(cpf).await?;
     Ok(Json(cliente))
@@ -47,9 +47,9 @@ async fn busca_cliente_por_cpf(
 #[openapi(tag = "Clientes")]
 #[post("/", data = "<cliente_input>")]
 async fn cadastro_cliente(
-    cliente_repository: &State<Arc<Mutex<dyn ClienteGateway + Sync + Send>>>,
-    cliente_input: Json<CreateClienteInput>,
-) -> Result<Json<Cliente>, Status> {
+    cliente_repository: &State<Arc<dyn ClienteGateway + Sync + Send>>,
+    cliente_input: JsonBody<CreateClienteInput>,
+) -> Result<Json<Cliente>, ApiError> {
     let cliente_controller = ClienteController::new(cliente_repository.inner().clone());
     let cliente_input = cliente_input.into_inner();
     let cliente = cliente_controller.cadastro_cliente(cliente_input).await?;
@@ -61,12 +61,8 @@ pub fn routes() -> Vec<rocket::Route> {
 }
 
 #[catch(404)]
-fn cliente_not_found() -> Json<ErrorResponse> {
-    let error = ErrorResponse {
-        msg: "Cliente não encontrado!".to_string(),
-        status: 404,
-    };
-    Json(error)
+fn cliente_not_found() -> (Status, Json<ErrorResponse>) {
+    resposta_de_erro(Status::NotFound, "nao_encontrado", "Cliente não encontrado!")
 }
 
 pub fn catchers() -> Vec<rocket::Catcher> {
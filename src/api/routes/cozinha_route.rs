@@ -0,0 +1,104 @@
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket_okapi::{openapi, openapi_get_routes};
+
+use crate::api::error_handling::{resposta_de_erro, ApiError, ErrorResponse};
+use crate::api::helpers::json_body::JsonBody;
+use crate::api::request_guards::admin_guard::AdminUser;
+use crate::api::request_guards::authentication_guard::AuthenticatedUser;
+use crate::controllers::cozinha_controller::CozinhaController;
+use crate::entities::estacao::{Estacao, TicketDeEstacao};
+use crate::entities::pedido::Pedido;
+use crate::use_cases::gerenciamento_de_estacoes_use_case::CreateEstacaoInput;
+
+#[openapi(tag = "Cozinha")]
+#[get("/estacoes")]
+async fn get_estacoes(
+    cozinha_controller: CozinhaController,
+    _logged_user_info: AuthenticatedUser,
+) -> Result<Json<Vec<Estacao>>, ApiError> {
+    let estacoes = cozinha_controller.get_estacoes().await?;
+    Ok(Json(estacoes))
+}
+
+#[openapi(tag = "Cozinha")]
+#[get("/estacoes/<id>")]
+async fn get_estacao(
+    cozinha_controller: CozinhaController,
+    id: usize,
+    _logged_user_info: AuthenticatedUser,
+) -> Result<Json<Estacao>, ApiError> {
+    let estacao = cozinha_controller.get_estacao(id).await?;
+    Ok(Json(estacao))
+}
+
+#[openapi(tag = "Cozinha")]
+#[post("/estacoes", data = "<estacao_input>")]
+async fn create_estacao(
+    cozinha_controller: CozinhaController,
+    estacao_input: JsonBody<CreateEstacaoInput>,
+    _admin: AdminUser,
+) -> Result<Json<Estacao>, ApiError> {
+    let estacao = cozinha_controller.create_estacao(estacao_input.into_inner()).await?;
+    Ok(Json(estacao))
+}
+
+#[openapi(tag = "Cozinha")]
+#[put("/estacoes/<id>", data = "<estacao_input>")]
+async fn update_estacao(
+    cozinha_controller: CozinhaController,
+    estacao_input: JsonBody<CreateEstacaoInput>,
+    id: usize,
+    _admin: AdminUser,
+) -> Result<Json<Estacao>, ApiError> {
+    let estacao = cozinha_controller.update_estacao(id, estacao_input.into_inner()).await?;
+    Ok(Json(estacao))
+}
+
+// Tickets ainda não prontos da estação, na ordem da fila da cozinha
+#[openapi(tag = "Cozinha")]
+#[get("/estacoes/<id>/fila")]
+async fn get_fila_da_estacao(
+    cozinha_controller: CozinhaController,
+    id: usize,
+    _logged_user_info: AuthenticatedUser,
+) -> Result<Json<Vec<TicketDeEstacao>>, ApiError> {
+    let fila = cozinha_controller.get_fila_da_estacao(id).await?;
+    Ok(Json(fila))
+}
+
+// Devolve o pedido, que fica Pronto quando o último ticket fica pronto
+#[openapi(tag = "Cozinha")]
+#[put("/estacoes/<id>/pedidos/<pedido_id>/status/<status>")]
+async fn put_status_do_ticket(
+    cozinha_controller: CozinhaController,
+    id: usize,
+    pedido_id: usize,
+    status: &str,
+    _logged_user_info: AuthenticatedUser,
+) -> Result<Json<Pedido>, ApiError> {
+    let pedido = cozinha_controller
+        .atualiza_status_do_ticket(id, pedido_id, status)
+        .await?;
+    Ok(Json(pedido))
+}
+
+pub fn routes() -> Vec<rocket::Route> {
+    openapi_get_routes![
+        get_estacoes,
+        get_estacao,
+        create_estacao,
+        update_estacao,
+        get_fila_da_estacao,
+        put_status_do_ticket
+    ]
+}
+
+#[catch(404)]
+fn estacao_not_found() -> (Status, Json<ErrorResponse>) {
+    resposta_de_erro(Status::NotFound, "nao_encontrado", "Estação não encontrada!")
+}
+
+pub fn catchers() -> Vec<rocket::Catcher> {
+    catchers![estacao_not_found]
+}
@@ -0,0 +1,36 @@
+use std::sync::Arc;
+
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+
+use crate::controllers::health_controller::HealthController;
+use crate::traits::health_check_adapter::HealthCheckAdapter;
+use crate::use_cases::verificacao_de_saude_use_case::{RelatorioSaude, SituacaoSaude};
+
+// Liveness só indica que o processo responde; dependências ficam na readiness
+// para que uma queda do banco não faça o Kubernetes reiniciar os pods
+#[get("/live")]
+fn live() -> Json<RelatorioSaude> {
+    Json(RelatorioSaude {
+        situacao: SituacaoSaude::Ok,
+        dependencias: vec![],
+    })
+}
+
+#[get("/ready")]
+async fn ready(
+    dependencias: &State<Vec<Arc<dyn HealthCheckAdapter + Sync + Send>>>,
+) -> (Status, Json<RelatorioSaude>) {
+    let health_controller = HealthController::new(dependencias.inner().clone());
+    let relatorio = health_controller.prontidao().await;
+    let status = match relatorio.situacao {
+        SituacaoSaude::Indisponivel => Status::ServiceUnavailable,
+        _ => Status::Ok,
+    };
+    (status, Json(relatorio))
+}
+
+pub fn routes() -> Vec<rocket::Route> {
+    routes![live, ready]
+}
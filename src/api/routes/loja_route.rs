@@ -0,0 +1,101 @@
+use std::sync::Arc;
+
+use rocket::http::Status;
+use rocket::serde::json::Json;
+use rocket::State;
+use rocket_okapi::{openapi, openapi_get_routes};
+
+use crate::api::error_handling::{resposta_de_erro, ApiError, ErrorResponse};
+use crate::api::helpers::json_body::JsonBody;
+use crate::api::request_guards::admin_guard::AdminUser;
+use crate::api::request_guards::authentication_guard::AuthenticatedUser;
+use crate::base::domain_error::DomainError;
+use crate::controllers::loja_controller::LojaController;
+use crate::entities::loja::Loja;
+use crate::traits::loja_gateway::LojaGateway;
+use crate::traits::usuario_gateway::UsuarioGateway;
+use crate::use_cases::gerenciamento_de_lojas_use_case::CreateLojaInput;
+
+// Só as lojas do token
+#[openapi(tag = "Lojas")]
+#[get("/")]
+async fn get_lojas(
+    loja_repository: &State<Arc<dyn LojaGateway + Sync + Send>>,
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    logged_user_info: AuthenticatedUser,
+) -> Result<Json<Vec<Loja>>, ApiError> {
+    let loja_controller = LojaController::new(
+        loja_repository.inner().clone(),
+        usuario_repository.inner().clone(),
+        logged_user_info.lojas(),
+    );
+    let lojas = loja_controller.get_lojas().await?;
+    Ok(Json(lojas))
+}
+
+#[openapi(tag = "Lojas")]
+#[get("/<id>")]
+async fn get_loja(
+    loja_repository: &State<Arc<dyn LojaGateway + Sync + Send>>,
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    id: usize,
+    logged_user_info: AuthenticatedUser,
+) -> Result<Json<Loja>, ApiError> {
+    let loja_controller = LojaController::new(
+        loja_repository.inner().clone(),
+        usuario_repository.inner().clone(),
+        logged_user_info.lojas(),
+    );
+    let loja = loja_controller.get_loja(id).await?;
+    Ok(Json(loja))
+}
+
+// O admin passa a gerenciar a loja criada; ela entra no token no próximo login
+#[openapi(tag = "Lojas")]
+#[post("/", data = "<loja_input>")]
+async fn create_loja(
+    loja_repository: &State<Arc<dyn LojaGateway + Sync + Send>>,
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    loja_input: JsonBody<CreateLojaInput>,
+    admin: AdminUser,
+) -> Result<Json<Loja>, ApiError> {
+    let loja_controller = LojaController::new(
+        loja_repository.inner().clone(),
+        usuario_repository.inner().clone(),
+        admin.lojas(),
+    );
+    let usuario_id = admin.usuario_id().parse().map_err(|_| DomainError::Unauthorized)?;
+    let loja = loja_controller.create_loja(usuario_id, loja_input.into_inner()).await?;
+    Ok(Json(loja))
+}
+
+#[openapi(tag = "Lojas")]
+#[put("/<id>", data = "<loja_input>")]
+async fn update_loja(
+    loja_repository: &State<Arc<dyn LojaGateway + Sync + Send>>,
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    loja_input: JsonBody<CreateLojaInput>,
+    id: usize,
+    admin: AdminUser,
+) -> Result<Json<Loja>, ApiError> {
+    let loja_controller = LojaController::new(
+        loja_repository.inner().clone(),
+        usuario_repository.inner().clone(),
+        admin.lojas(),
+    );
+    let loja = loja_controller.update_loja(id, loja_input.into_inner()).await?;
+    Ok(Json(loja))
+}
+
+pub fn routes() -> Vec<rocket::Route> {
+    openapi_get_routes![get_lojas, get_loja, create_loja, update_loja]
+}
+
+#[catch(404)]
+fn loja_not_found() -> (Status, Json<ErrorResponse>) {
+    resposta_de_erro(Status::NotFound, "nao_encontrado", "Loja não encontrada!")
+}
+
+pub fn catchers() -> Vec<rocket::Catcher> {
+    catchers![loja_not_found]
+}
@@ -0,0 +1,24 @@
+use deadpool_postgres::Pool;
+use rocket::http::ContentType;
+use rocket::State;
+
+use crate::external::metrics;
+
+#[get("/")]
+fn get_metrics(pool: &State<Option<Pool>>) -> (ContentType, String) {
+    if let Some(pool) = pool.inner() {
+        let status = pool.status();
+        metrics::registrar_pool(
+            status.size.saturating_sub(status.available),
+            status.available,
+            status.waiting,
+            status.max_size,
+        );
+    }
+    let content_type = ContentType::new("text", "plain").with_params(("version", "0.0.4"));
+    (content_type, metrics::render())
+}
+
+pub fn routes() -> Vec<rocket::Route> {
+    routes![get_metrics]
+}
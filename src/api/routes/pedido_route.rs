@@ -1,44 +1,24 @@
-use std::collections::HashMap;
-use std::sync::Arc;
-
 use rocket::http::Status;
 use rocket::serde::json::Json;
 use rocket::serde::json::Value;
-use rocket::State;
 use rocket::Data;
 use rocket_okapi::{openapi, openapi_get_routes};
-use tokio::sync::Mutex;
 
-use crate::api::error_handling::ErrorResponse;
+use crate::api::error_handling::{resposta_de_erro, ApiError, ErrorResponse};
+use crate::api::helpers::json_body::JsonBody;
 use crate::api::request_guards::authentication_guard::AuthenticatedUser;
 use crate::controllers::pedido_controller::PedidoController;
 use crate::entities::pagamento::Pagamento;
 use crate::entities::pedido::Pedido;
-
-use crate::traits::pagamento_webhook_adapter::PagamentoWebhookAdapter;
-use crate::traits::{
-    cliente_gateway::ClienteGateway, pagamento_gateway::PagamentoGateway,
-    pedido_gateway::PedidoGateway, produto_gateway::ProdutoGateway,
-};
+use crate::entities::tempo_de_preparo::{TempoDePreparo, DIAS_TEMPOS_DE_PREPARO_PADRAO};
 use crate::use_cases::pedidos_e_pagamentos_use_case::CreatePedidoInput;
 
 #[openapi(tag = "Pedidos")]
 #[get("/")]
 async fn get_pedidos(
-    pedido_repository: &State<Arc<Mutex<dyn PedidoGateway + Sync + Send>>>,
-    cliente_repository: &State<Arc<Mutex<dyn ClienteGateway + Sync + Send>>>,
-    produto_repository: &State<Arc<Mutex<dyn ProdutoGateway + Sync + Send>>>,
-    pagamento_repository: &State<Arc<Mutex<dyn PagamentoGateway + Sync + Send>>>,
-    metodos_pagamento: &State<HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>>>,
+    pedido_controller: PedidoController,
     _logged_user_info: AuthenticatedUser,
-) -> Result<Json<Vec<Pedido>>, Status> {
-    let pedido_controller = PedidoController::new(
-        pedido_repository.inner().clone(),
-        cliente_repository.inner().clone(),
-        produto_repository.inner().clone(),
-        pagamento_repository.inner().clone(),
-        metodos_pagamento.inner().clone(),
-    );
+) -> Result<Json<Vec<Pedido>>, ApiError> {
     let pedidos = pedido_controller.get_pedidos().await?;
     Ok(Json(pedidos))
 }
@@ -46,21 +26,10 @@ async fn get_pedidos(
 #[openapi(tag = "Pedidos")]
 #[get("/<id>")]
 async fn get_pedido_by_id(
-    pedido_repository: &State<Arc<Mutex<dyn PedidoGateway + Sync + Send>>>,
-    cliente_repository: &State<Arc<Mutex<dyn ClienteGateway + Sync + Send>>>,
-    produto_repository: &State<Arc<Mutex<dyn ProdutoGateway + Sync + Send>>>,
-    pagamento_repository: &State<Arc<Mutex<dyn PagamentoGateway + Sync + Send>>>,
-    metodos_pagamento: &State<HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>>>,
+    pedido_controller: PedidoController,
     id: usize,
     __logged_user_info: AuthenticatedUser,
-) -> Result<Json<Pedido>, Status> {
-    let pedido_controller = PedidoController::new(
-        pedido_repository.inner().clone(),
-        cliente_repository.inner().clone(),
-        produto_repository.inner().clone(),
-        pagamento_repository.inner().clone(),
-        metodos_pagamento.inner().clone(),
-    );
+) -> Result<Json<Pedido>, ApiError> {
     let pedido = pedido_controller.get_pedido_by_id(id).await?;
     Ok(Json(pedido))
 }
@@ -68,20 +37,9 @@ async fn get_pedido_by_id(
 #[openapi(tag = "Pedidos")]
 #[post("/", data = "<pedido_input>")]
 async fn post_novo_pedido(
-    pedido_repository: &State<Arc<Mutex<dyn PedidoGateway + Sync + Send>>>,
-    cliente_repository: &State<Arc<Mutex<dyn ClienteGateway + Sync + Send>>>,
-    produto_repository: &State<Arc<Mutex<dyn ProdutoGateway + Sync + Send>>>,
-    pagamento_repository: &State<Arc<Mutex<dyn PagamentoGateway + Sync + Send>>>,
-    metodos_pagamento: &State<HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>>>,
-    pedido_input: Json<CreatePedidoInput>,
-) -> Result<Json<Pedido>, Status> {
-    let pedido_controller = PedidoController::new(
-        pedido_repository.inner().clone(),
-        cliente_repository.inner().clone(),
-        produto_repository.inner().clone(),
-        pagamento_repository.inner().clone(),
-        metodos_pagamento.inner().clone(),
-    );
+    pedido_controller: PedidoController,
+    pedido_input: JsonBody<CreatePedidoInput>,
+) -> Result<Json<Pedido>, ApiError> {
     let pedido_input = pedido_input.into_inner();
     let novo_pedido = pedido_controller.novo_pedido(pedido_input).await?;
     Ok(Json(novo_pedido))
@@ -90,43 +48,35 @@ async fn post_novo_pedido(
 #[openapi(tag = "Pedidos")]
 #[get("/novos")]
 async fn get_pedidos_novos(
-    pedido_repository: &State<Arc<Mutex<dyn PedidoGateway + Sync + Send>>>,
-    cliente_repository: &State<Arc<Mutex<dyn ClienteGateway + Sync + Send>>>,
-    produto_repository: &State<Arc<Mutex<dyn ProdutoGateway + Sync + Send>>>,
-    pagamento_repository: &State<Arc<Mutex<dyn PagamentoGateway + Sync + Send>>>,
-    metodos_pagamento: &State<HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>>>,
+    pedido_controller: PedidoController,
     __logged_user_info: AuthenticatedUser,
-) -> Result<Json<Vec<Pedido>>, Status> {
-    let pedido_controller = PedidoController::new(
-        pedido_repository.inner().clone(),
-        cliente_repository.inner().clone(),
-        produto_repository.inner().clone(),
-        pagamento_repository.inner().clone(),
-        metodos_pagamento.inner().clone(),
-    );
+) -> Result<Json<Vec<Pedido>>, ApiError> {
     let pedidos_novos = pedido_controller.get_pedidos_novos().await?;
     Ok(Json(pedidos_novos))
 }
 
+// Médias de espera e preparo por produto dos pedidos prontos nos últimos `dias`
+#[openapi(tag = "Pedidos")]
+#[get("/tempos-de-preparo?<dias>")]
+async fn get_tempos_de_preparo(
+    pedido_controller: PedidoController,
+    dias: Option<i64>,
+    __logged_user_info: AuthenticatedUser,
+) -> Result<Json<Vec<TempoDePreparo>>, ApiError> {
+    let tempos = pedido_controller
+        .get_tempos_de_preparo(dias.unwrap_or(DIAS_TEMPOS_DE_PREPARO_PADRAO))
+        .await?;
+    Ok(Json(tempos))
+}
+
 #[openapi(tag = "Pedidos")]
 #[put("/<id>/status/<status>")]
 async fn put_status_pedido(
-    pedido_repository: &State<Arc<Mutex<dyn PedidoGateway + Sync + Send>>>,
-    cliente_repository: &State<Arc<Mutex<dyn ClienteGateway + Sync + Send>>>,
-    produto_repository: &State<Arc<Mutex<dyn ProdutoGateway + Sync + Send>>>,
-    pagamento_repository: &State<Arc<Mutex<dyn PagamentoGateway + Sync + Send>>>,
-    metodos_pagamento: &State<HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>>>,
+    pedido_controller: PedidoController,
     id: usize,
     status: &str,
     __logged_user_info: AuthenticatedUser,
-) -> Result<Json<Pedido>, Status> {
-    let pedido_controller = PedidoController::new(
-        pedido_repository.inner().clone(),
-        cliente_repository.inner().clone(),
-        produto_repository.inner().clone(),
-        pagamento_repository.inner().clone(),
-        metodos_pagamento.inner().clone(),
-    );
+) -> Result<Json<Pedido>, ApiError> {
     let pedido = pedido_controller.atualiza_status_pedido(id, status).await?;
     Ok(Json(pedido))
 }
@@ -134,21 +84,10 @@ async fn put_status_pedido(
 #[openapi(tag = "Pedidos")]
 #[put("/<id>/cliente/<cliente_id>")]
 async fn put_cliente_pedido(
-    pedido_repository: &State<Arc<Mutex<dyn PedidoGateway + Sync + Send>>>,
-    cliente_repository: &State<Arc<Mutex<dyn ClienteGateway + Sync + Send>>>,
-    produto_repository: &State<Arc<Mutex<dyn ProdutoGateway + Sync + Send>>>,
-    pagamento_repository: &State<Arc<Mutex<dyn PagamentoGateway + Sync + Send>>>,
-    metodos_pagamento: &State<HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>>>,
+    pedido_controller: PedidoController,
     id: usize,
     cliente_id: usize,
-) -> Result<Json<Pedido>, Status> {
-    let pedido_controller = PedidoController::new(
-        pedido_repository.inner().clone(),
-        cliente_repository.inner().clone(),
-        produto_repository.inner().clone(),
-        pagamento_repository.inner().clone(),
-        metodos_pagamento.inner().clone(),
-    );
+) -> Result<Json<Pedido>, ApiError> {
     let pedido = pedido_controller
         .atualiza_cliente_pedido(id, cliente_id)
         .await?;
@@ -158,22 +97,11 @@ async fn put_cliente_pedido(
 #[openapi(tag = "Pedidos")]
 #[put("/<id>/produto/<categoria>/<produto_id>")]
 async fn put_produto_by_categoria(
-    pedido_repository: &State<Arc<Mutex<dyn PedidoGateway + Sync + Send>>>,
-    cliente_repository: &State<Arc<Mutex<dyn ClienteGateway + Sync + Send>>>,
-    produto_repository: &State<Arc<Mutex<dyn ProdutoGateway + Sync + Send>>>,
-    pagamento_repository: &State<Arc<Mutex<dyn PagamentoGateway + Sync + Send>>>,
-    metodos_pagamento: &State<HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>>>,
+    pedido_controller: PedidoController,
     id: usize,
     categoria: &str,
     produto_id: usize,
-) -> Result<Json<Pedido>, Status> {
-    let pedido_controller = PedidoController::new(
-        pedido_repository.inner().clone(),
-        cliente_repository.inner().clone(),
-        produto_repository.inner().clone(),
-        pagamento_repository.inner().clone(),
-        metodos_pagamento.inner().clone(),
-    );
+) -> Result<Json<Pedido>, ApiError> {
     let pedido = pedido_controller
         .atualiza_produto_by_categoria(id, categoria, produto_id)
         .await?;
@@ -184,20 +112,9 @@ async fn put_produto_by_categoria(
 #[openapi(tag = "Pedidos")]
 #[get("/<id>/pagamento")]
 async fn get_pagamento_by_pedido_id(
-    pedido_repository: &State<Arc<Mutex<dyn PedidoGateway + Sync + Send>>>,
-    cliente_repository: &State<Arc<Mutex<dyn ClienteGateway + Sync + Send>>>,
-    produto_repository: &State<Arc<Mutex<dyn ProdutoGateway + Sync + Send>>>,
-    pagamento_repository: &State<Arc<Mutex<dyn PagamentoGateway + Sync + Send>>>,
-    metodos_pagamento: &State<HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>>>,
+    pedido_controller: PedidoController,
     id: usize,
-) -> Result<Json<Pagamento>, Status> {
-    let pedido_controller = PedidoController::new(
-        pedido_repository.inner().clone(),
-        cliente_repository.inner().clone(),
-        produto_repository.inner().clone(),
-        pagamento_repository.inner().clone(),
-        metodos_pagamento.inner().clone(),
-    );
+) -> Result<Json<Pagamento>, ApiError> {
     let pagamento = pedido_controller.get_pagamento_by_pedido_id(id).await?;
 
     Ok(Json(pagamento))
@@ -206,20 +123,9 @@ async fn get_pagamento_by_pedido_id(
 #[openapi(tag = "Pedidos")]
 #[post("/<id>/pagamento")]
 async fn pagar(
-    pedido_repository: &State<Arc<Mutex<dyn PedidoGateway + Sync + Send>>>,
-    cliente_repository: &State<Arc<Mutex<dyn ClienteGateway + Sync + Send>>>,
-    produto_repository: &State<Arc<Mutex<dyn ProdutoGateway + Sync + Send>>>,
-    pagamento_repository: &State<Arc<Mutex<dyn PagamentoGateway + Sync + Send>>>,
-    metodos_pagamento: &State<HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>>>,
+    pedido_controller: PedidoController,
     id: usize,
-) -> Result<Json<Pagamento>, Status> {
-    let pedido_controller = PedidoController::new(
-        pedido_repository.inner().clone(),
-        cliente_repository.inner().clone(),
-        produto_repository.inner().clone(),
-        pagamento_repository.inner().clone(),
-        metodos_pagamento.inner().clone(),
-    );
+) -> Result<Json<Pagamento>, ApiError> {
     let pagamento = pedido_controller.pagar(id).await?;
 
     Ok(Json(pagamento))
@@ -228,22 +134,10 @@ async fn pagar(
 #[openapi(tag = "Pedidos")]
 #[post("/<id>/webhook/pagamento", data = "<data>")]
 async fn webhook_pagamento(
-    pedido_repository: &State<Arc<Mutex<dyn PedidoGateway + Sync + Send>>>,
-    cliente_repository: &State<Arc<Mutex<dyn ClienteGateway + Sync + Send>>>,
-    produto_repository: &State<Arc<Mutex<dyn ProdutoGateway + Sync + Send>>>,
-    pagamento_repository: &State<Arc<Mutex<dyn PagamentoGateway + Sync + Send>>>,
-    metodos_pagamento: &State<HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>>>,
+    pedido_controller: PedidoController,
     id: usize,
-    data: Json<Value>,
-) -> Result<Json<Pagamento>, Status> {
-    let pedido_controller = PedidoController::new(
-        pedido_repository.inner().clone(),
-        cliente_repository.inner().clone(),
-        produto_repository.inner().clone(),
-        pagamento_repository.inner().clone(),
-        metodos_pagamento.inner().clone(),
-    );
-
+    data: JsonBody<Value>,
+) -> Result<Json<Pagamento>, ApiError> {
     let data_pagamento = data.into_inner();
     let pagamento = pedido_controller.webhook_pagamento(id, data_pagamento).await?;
     Ok(Json(pagamento))
@@ -252,8 +146,10 @@ async fn webhook_pagamento(
 pub fn routes() -> Vec<rocket::Route> {
     openapi_get_routes![
         get_pedidos,
+        get_pedido_by_id,
         post_novo_pedido,
         get_pedidos_novos,
+        get_tempos_de_preparo,
         put_status_pedido,
         put_cliente_pedido,
         put_produto_by_categoria,
@@ -264,12 +160,8 @@ pub fn routes() -> Vec<rocket::Route> {
 }
 
 #[catch(404)]
-fn pedido_not_found() -> Json<ErrorResponse> {
-    let error = ErrorResponse {
-        msg: "Pedido não encontrado!".to_string(),
-        status: 404,
-    };
-    Json(error)
+fn pedido_not_found() -> (Status, Json<ErrorResponse>) {
+    resposta_de_erro(Status::NotFound, "nao_encontrado", "Pedido não encontrado!")
 }
 
 pub fn catchers() -> Vec<rocket::Catcher> {
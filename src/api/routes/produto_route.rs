@@ -4,23 +4,34 @@ use rocket::http::Status;
 use rocket::serde::json::Json;
 use rocket::State;
 use rocket_okapi::{openapi, openapi_get_routes};
-use tokio::sync::Mutex;
 
-use crate::api::error_handling::ErrorResponse;
+use crate::api::error_handling::{resposta_de_erro, ApiError, ErrorResponse};
+use crate::api::helpers::etag::ComEtag;
+use crate::api::helpers::foto_upload::FotoUpload;
+use crate::api::helpers::imagem::Imagem;
+use crate::api::helpers::json_body::JsonBody;
 use crate::api::request_guards::authentication_guard::AuthenticatedUser;
+use crate::api::request_guards::if_match_guard::IfMatch;
+use crate::api::request_guards::loja_guard::LojaAtual;
+use crate::base::domain_error::DomainError;
+use crate::controllers::foto_de_produto_controller::FotoDeProdutoController;
 use crate::controllers::produto_controller::ProdutoController;
+use crate::traits::estacao_gateway::EstacaoGateway;
 use crate::traits::produto_gateway::ProdutoGateway;
-use crate::use_cases::gerenciamento_de_produtos_use_case::CreateProdutoInput;
+use crate::traits::storage_gateway::StorageGateway;
+use crate::use_cases::gerenciamento_de_produtos_use_case::{CreateProdutoInput, UpdateProdutoInput};
+use crate::entities::preco_historico::PrecoHistorico;
 use crate::entities::produto::Produto;
 use crate::api::request_guards::admin_guard::AdminUser;
 
 #[openapi(tag = "Produtos")]
 #[get("/")]
 async fn get_produto(
-    produto_repository: &State<Arc<Mutex<dyn ProdutoGateway + Sync + Send>>>,
+    produto_repository: &State<Arc<dyn ProdutoGateway + Sync + Send>>,
+    loja: LojaAtual,
     _logged_user_info: AuthenticatedUser,
-) -> Result<Json<Vec<Produto>>, Status> {
-    let produto_controller = ProdutoController::new(produto_repository.inner().clone());
+) -> Result<Json<Vec<Produto>>, ApiError> {
+    let produto_controller = ProdutoController::new(produto_repository.na_loja(loja.0));
     let produtos = produto_controller.get_produto().await?;
     Ok(Json(produtos))
 }
@@ -28,65 +39,178 @@ async fn get_produto(
 #[openapi(tag = "Produtos")]
 #[get("/<id>")]
 async fn get_produto_by_id(
-    produto_repository: &State<Arc<Mutex<dyn ProdutoGateway + Sync + Send>>>,
+    produto_repository: &State<Arc<dyn ProdutoGateway + Sync + Send>>,
+    loja: LojaAtual,
     id: usize,
     _logged_user_info: AuthenticatedUser,
-) -> Result<Json<Produto>, Status> {
-    let produto_controller = ProdutoController::new(produto_repository.inner().clone());
+) -> Result<ComEtag<Produto>, ApiError> {
+    let produto_controller = ProdutoController::new(produto_repository.na_loja(loja.0));
     let produto = produto_controller.get_produto_by_id(id).await?;
-    Ok(Json(produto))
+    let versao = produto.versao();
+    Ok(ComEtag(produto, versao))
 }
 
 #[openapi(tag = "Produtos")]
 #[post("/", data = "<produto_input>")]
 async fn create_produto(
-    produto_repository: &State<Arc<Mutex<dyn ProdutoGateway + Sync + Send>>>,
-    produto_input: Json<CreateProdutoInput>,
+    produto_repository: &State<Arc<dyn ProdutoGateway + Sync + Send>>,
+    estacao_repository: &State<Arc<dyn EstacaoGateway + Sync + Send>>,
+    loja: LojaAtual,
+    produto_input: JsonBody<CreateProdutoInput>,
     _logged_user_info: AuthenticatedUser,
-) -> Result<Json<Produto>, Status> {
-    let produto_controller = ProdutoController::new(produto_repository.inner().clone());
+) -> Result<ComEtag<Produto>, ApiError> {
+    let produto_controller = ProdutoController::new(produto_repository.na_loja(loja.0))
+        .com_estacoes(estacao_repository.na_loja(loja.0));
     let produto_input = produto_input.into_inner();
     let produto = produto_controller.create_produto(produto_input).await?;
-    Ok(Json(produto))
+    let versao = produto.versao();
+    Ok(ComEtag(produto, versao))
 }
 
 #[openapi(tag = "Produtos")]
 #[put("/<id>", data = "<produto_input>")]
 async fn update_produto(
-    produto_repository: &State<Arc<Mutex<dyn ProdutoGateway + Sync + Send>>>,
-    produto_input: Json<CreateProdutoInput>,
+    produto_repository: &State<Arc<dyn ProdutoGateway + Sync + Send>>,
+    estacao_repository: &State<Arc<dyn EstacaoGateway + Sync + Send>>,
+    loja: LojaAtual,
+    produto_input: JsonBody<CreateProdutoInput>,
     id: usize,
+    if_match: IfMatch,
     _logged_user_info: AuthenticatedUser,
-) -> Result<Json<Produto>, Status> {
-    let produto_controller = ProdutoController::new(produto_repository.inner().clone());
+) -> Result<ComEtag<Produto>, ApiError> {
+    let produto_controller = ProdutoController::new(produto_repository.na_loja(loja.0))
+        .com_estacoes(estacao_repository.na_loja(loja.0));
     let produto_input = produto_input.into_inner();
-    let produto = produto_controller.update_produto(id, produto_input).await?;
-    Ok(Json(produto))
+    let produto = produto_controller
+        .update_produto(id, produto_input, if_match.0)
+        .await
+        .map_err(|err| erro_de_versao(err, &if_match))?;
+    let versao = produto.versao();
+    Ok(ComEtag(produto, versao))
+}
+
+#[openapi(tag = "Produtos")]
+#[patch("/<id>", data = "<produto_input>")]
+async fn patch_produto(
+    produto_repository: &State<Arc<dyn ProdutoGateway + Sync + Send>>,
+    estacao_repository: &State<Arc<dyn EstacaoGateway + Sync + Send>>,
+    loja: LojaAtual,
+    produto_input: JsonBody<UpdateProdutoInput>,
+    id: usize,
+    if_match: IfMatch,
+    _logged_user_info: AuthenticatedUser,
+) -> Result<ComEtag<Produto>, ApiError> {
+    let produto_controller = ProdutoController::new(produto_repository.na_loja(loja.0))
+        .com_estacoes(estacao_repository.na_loja(loja.0));
+    let produto_input = produto_input.into_inner();
+    let produto = produto_controller
+        .patch_produto(id, produto_input, if_match.0)
+        .await
+        .map_err(|err| erro_de_versao(err, &if_match))?;
+    let versao = produto.versao();
+    Ok(ComEtag(produto, versao))
+}
+
+// Com If-Match, versão desatualizada é falha de pré-condição e não conflito
+fn erro_de_versao(err: DomainError, if_match: &IfMatch) -> ApiError {
+    match (err, if_match.0) {
+        (err @ DomainError::Conflict { .. }, Some(_)) => {
+            ApiError::new(Status::PreconditionFailed, "versao_desatualizada", err.to_string())
+        }
+        (err, _) => err.into(),
+    }
 }
 
 #[openapi(tag = "Produtos")]
 #[delete("/<id>")]
 async fn delete_produto(
-    produto_repository: &State<Arc<Mutex<dyn ProdutoGateway + Sync + Send>>>,
+    produto_repository: &State<Arc<dyn ProdutoGateway + Sync + Send>>,
+    loja: LojaAtual,
     id: usize,
     _logged_user_info: AdminUser,
-) -> Result<Json<String>, Status> {
-    let produto_controller = ProdutoController::new(produto_repository.inner().clone());
+) -> Result<Json<String>, ApiError> {
+    let produto_controller = ProdutoController::new(produto_repository.na_loja(loja.0));
     produto_controller.delete_produto(id).await?;
     Ok(Json("success".to_string()))
 }
 
+#[openapi(tag = "Produtos")]
+#[post("/<id>/restaurar")]
+async fn restaurar_produto(
+    produto_repository: &State<Arc<dyn ProdutoGateway + Sync + Send>>,
+    loja: LojaAtual,
+    id: usize,
+    _logged_user_info: AdminUser,
+) -> Result<ComEtag<Produto>, ApiError> {
+    let produto_controller = ProdutoController::new(produto_repository.na_loja(loja.0));
+    let produto = produto_controller.restaurar_produto(id).await?;
+    let versao = produto.versao();
+    Ok(ComEtag(produto, versao))
+}
+
+#[openapi(tag = "Produtos")]
+#[get("/<id>/precos")]
+async fn get_historico_de_precos(
+    produto_repository: &State<Arc<dyn ProdutoGateway + Sync + Send>>,
+    loja: LojaAtual,
+    id: usize,
+    _logged_user_info: AuthenticatedUser,
+) -> Result<Json<Vec<PrecoHistorico>>, ApiError> {
+    let produto_controller = ProdutoController::new(produto_repository.na_loja(loja.0));
+    let precos = produto_controller.get_historico_de_precos(id).await?;
+    Ok(Json(precos))
+}
+
+#[openapi(tag = "Produtos")]
+#[post("/<id>/foto", data = "<foto>")]
+async fn enviar_foto(
+    produto_repository: &State<Arc<dyn ProdutoGateway + Sync + Send>>,
+    loja: LojaAtual,
+    storage: &State<Arc<dyn StorageGateway + Sync + Send>>,
+    id: usize,
+    foto: FotoUpload,
+    _logged_user_info: AuthenticatedUser,
+) -> Result<ComEtag<Produto>, ApiError> {
+    let foto_controller = FotoDeProdutoController::new(produto_repository.na_loja(loja.0), storage.inner().clone());
+    let produto = foto_controller.enviar_foto(id, foto.conteudo, &foto.content_type).await?;
+    let versao = produto.versao();
+    Ok(ComEtag(produto, versao))
+}
+
+// Sem autenticação, para que a foto possa ser usada direto numa tag <img> do cardápio;
+// fora da loja padrão a loja vai no `?loja=`
+#[openapi(tag = "Produtos")]
+#[get("/<id>/foto?<miniatura>")]
+async fn get_foto(
+    produto_repository: &State<Arc<dyn ProdutoGateway + Sync + Send>>,
+    loja: LojaAtual,
+    storage: &State<Arc<dyn StorageGateway + Sync + Send>>,
+    id: usize,
+    miniatura: Option<bool>,
+) -> Result<Imagem, ApiError> {
+    let foto_controller = FotoDeProdutoController::new(produto_repository.na_loja(loja.0), storage.inner().clone());
+    let (chave, arquivo) = foto_controller.buscar_foto(id, miniatura.unwrap_or(false)).await?;
+    Ok(Imagem { arquivo, chave })
+}
+
 pub fn routes() -> Vec<rocket::Route> {
-    openapi_get_routes![get_produto, get_produto_by_id, create_produto, update_produto, delete_produto]
+    openapi_get_routes![
+        get_produto,
+        get_produto_by_id,
+        create_produto,
+        update_produto,
+        patch_produto,
+        delete_produto,
+        restaurar_produto,
+        get_historico_de_precos,
+        enviar_foto,
+        get_foto
+    ]
 }
 
 #[catch(404)]
-fn produto_not_found() -> Json<ErrorResponse> {
-    let error = ErrorResponse {
-        msg: "Produto não encontrado!".to_string(),
-        status: 404,
-    };
-    Json(error)
+fn produto_not_found() -> (Status, Json<ErrorResponse>) {
+    resposta_de_erro(Status::NotFound, "nao_encontrado", "Produto não encontrado!")
 }
 
 pub fn catchers() -> Vec<rocket::Catcher> {
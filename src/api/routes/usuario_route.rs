@@ -4,10 +4,11 @@ use rocket::http::Status;
 use rocket::serde::json::Json;
 use rocket::State;
 use rocket_okapi::{openapi, openapi_get_routes};
-use tokio::sync::Mutex;
 
-use crate::api::error_handling::ErrorResponse;
+use crate::api::error_handling::{resposta_de_erro, ApiError, ErrorResponse};
+use crate::api::helpers::json_body::JsonBody;
 use crate::api::request_guards::admin_guard::AdminUser;
+use crate::api::request_guards::loja_guard::LojaAtual;
 use crate::controllers::usuario_controller::UsuarioController;
 use crate::entities::usuario::Usuario;
 use crate::entities::cpf::Cpf;
@@ -15,13 +16,16 @@ use crate::traits::usuario_gateway::UsuarioGateway;
 use crate::use_cases::gerenciamento_de_usuarios_use_case::CreateUsuarioInput;
 
 
+// Só os usuários da loja atual
 #[openapi(tag = "Usuarios")]
 #[get("/")]
 async fn get_usuarios(
-    usuario_repository: &State<Arc<Mutex<dyn UsuarioGateway + Sync + Send>>>,
-    _logged_user_info: AdminUser,
-) -> Result<Json<Vec<Usuario>>, Status> {
-    let usuario_controller = UsuarioController::new(usuario_repository.inner().clone());
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    loja: LojaAtual,
+    admin: AdminUser,
+) -> Result<Json<Vec<Usuario>>, ApiError> {
+    let usuario_controller = UsuarioController::new(usuario_repository.na_loja(loja.0))
+        .com_lojas_permitidas(admin.lojas());
     let usuarios = usuario_controller.get_usuarios().await?;
     Ok(Json(usuarios))
 }
@@ -29,11 +33,13 @@ async fn get_usuarios(
 #[openapi(tag = "Usuarios")]
 #[get("/<id>")]
 async fn get_usuario(
-    usuario_repository: &State<Arc<Mutex<dyn UsuarioGateway + Sync + Send>>>,
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    loja: LojaAtual,
     id: usize,
-    _logged_user_info: AdminUser,
-) -> Result<Json<Usuario>, Status> {
-    let usuario_controller = UsuarioController::new(usuario_repository.inner().clone());
+    admin: AdminUser,
+) -> Result<Json<Usuario>, ApiError> {
+    let usuario_controller = UsuarioController::new(usuario_repository.na_loja(loja.0))
+        .com_lojas_permitidas(admin.lojas());
     let usuario = usuario_controller.get_usuario(id).await?;
     Ok(Json(usuario))
 }
@@ -41,11 +47,13 @@ async fn get_usuario(
 #[openapi(tag = "Usuarios")]
 #[post("/", data = "<usuario_input>")]
 async fn create_usuario(
-    usuario_repository: &State<Arc<Mutex<dyn UsuarioGateway + Sync + Send>>>,
-    usuario_input: Json<CreateUsuarioInput>,
-    _logged_user_info: AdminUser,
-) -> Result<Json<Usuario>, Status> {
-    let usuario_controller = UsuarioController::new(usuario_repository.inner().clone());
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    loja: LojaAtual,
+    usuario_input: JsonBody<CreateUsuarioInput>,
+    admin: AdminUser,
+) -> Result<Json<Usuario>, ApiError> {
+    let usuario_controller = UsuarioController::new(usuario_repository.na_loja(loja.0))
+        .com_lojas_permitidas(admin.lojas());
     let usuario_input: CreateUsuarioInput = usuario_input.into_inner();
     let usuario = usuario_controller.create_usuario(usuario_input).await?;
     Ok(Json(usuario))
@@ -54,25 +62,30 @@ async fn create_usuario(
 #[openapi(tag = "Usuarios")]
 #[put("/<id>", data = "<usuario_input>")]
 async fn update_usuario(
-    usuario_repository: &State<Arc<Mutex<dyn UsuarioGateway + Sync + Send>>>,
-    usuario_input: Json<CreateUsuarioInput>,
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    loja: LojaAtual,
+    usuario_input: JsonBody<CreateUsuarioInput>,
     id: usize,
-    _logged_user_info: AdminUser,
-) -> Result<Json<Usuario>, Status> {
-    let usuario_controller = UsuarioController::new(usuario_repository.inner().clone());
+    admin: AdminUser,
+) -> Result<Json<Usuario>, ApiError> {
+    let usuario_controller = UsuarioController::new(usuario_repository.na_loja(loja.0))
+        .com_lojas_permitidas(admin.lojas());
     let usuario_input: CreateUsuarioInput = usuario_input.into_inner();
     let usuario = usuario_controller.update_usuario(id, usuario_input).await?;
     Ok(Json(usuario))
 }
 
+// Tira o usuário da loja atual; o cadastro só é apagado quando não sobra nenhuma loja
 #[openapi(tag = "Usuarios")]
 #[delete("/<cpf>")]
 async fn delete_usuario(
-    usuario_repository: &State<Arc<Mutex<dyn UsuarioGateway + Sync + Send>>>,
+    usuario_repository: &State<Arc<dyn UsuarioGateway + Sync + Send>>,
+    loja: LojaAtual,
     cpf: Cpf,
-    _logged_user_info: AdminUser,
-) -> Result<Json<String>, Status> {
-    let usuario_controller = UsuarioController::new(usuario_repository.inner().clone());
+    admin: AdminUser,
+) -> Result<Json<String>, ApiError> {
+    let usuario_controller = UsuarioController::new(usuario_repository.na_loja(loja.0))
+        .com_lojas_permitidas(admin.lojas());
     usuario_controller.delete_usuario(cpf).await?;
     Ok(Json("success".to_string()))
 }
@@ -82,12 +95,8 @@ pub fn routes() -> Vec<rocket::Route> {
 }
 
 #[catch(404)]
-fn usuario_not_found() -> Json<ErrorResponse> {
-    let error = ErrorResponse {
-        msg: "Usuário não encontrado!".to_string(),
-        status: 404,
-    };
-    Json(error)
+fn usuario_not_found() -> (Status, Json<ErrorResponse>) {
+    resposta_de_erro(Status::NotFound, "nao_encontrado", "Usuário não encontrado!")
 }
 
 pub fn catchers() -> Vec<rocket::Catcher> {
@@ -1,164 +1,41 @@
 use rocket::response::Redirect;
+use rocket::{Build, Rocket};
 use rocket_okapi::settings::UrlObject;
 use rocket_okapi::swagger_ui::*;
-use std::collections::HashMap;
 use std::process;
-use std::net::{IpAddr, Ipv4Addr};
-use std::sync::Arc;
-use tokio::sync::Mutex;
+use tracing::{error, info};
 
 use super::error_handling::generic_catchers;
-use super::routes::{auth_route, cliente_route, pedido_route, produto_route, usuario_route};
-use crate::adapters::jwt_authentication_adapter::JWTAuthenticationAdapter;
-use crate::adapters::mercadopago_pagamento_webhook_adapter::MercadoPagoPagamentoWebhookAdapter;
-use crate::api::config::{Config, Env};
-use crate::external::pagamento::mock::MockPagamentoSuccesso;
-use crate::external::postgres;
-use crate::gateways::aws_cognito_gateway::AwsCognitoRepository;
-use crate::gateways::{
-    in_memory_cliente_gateway::InMemoryClienteRepository,
-    in_memory_pagamento_gateway::InMemoryPagamentoRepository,
-    in_memory_pedido_gateway::InMemoryPedidoRepository,
-    in_memory_usuario_gateway::InMemoryUsuarioRepository,
-    postgres_cliente_gateway::PostgresClienteRepository,
-    postgres_pagamento_gateway::PostgresPagamentoRepository,
-    postgres_pedido_gateway::PostgresPedidoRepository,
-    postgres_produto_gateway::PostgresProdutoRepository,
-    postgres_usuario_gateway::PostgresUsuarioGateway,
-};
-use crate::traits::authentication_adapter::AuthenticationAdapter;
-use crate::traits::pagamento_adapter::PagamentoAdapter;
-use crate::traits::pagamento_webhook_adapter::PagamentoWebhookAdapter;
-use crate::traits::{
-    cliente_gateway::ClienteGateway, pagamento_gateway::PagamentoGateway,
-    pedido_gateway::PedidoGateway, produto_gateway::ProdutoGateway,
-    usuario_gateway::UsuarioGateway,
+use super::fairings::metrics::MetricsFairing;
+use super::fairings::request_id::{rastrear, RequestIdFairing};
+use super::telemetry;
+use super::routes::{
+    auth_route, cardapio_route, cliente_route, cozinha_route, health_route, loja_route, metrics_route, pedido_route, produto_route,
+    usuario_route,
 };
+use super::app_state::AppState;
+use super::dependencies::AppDependencies;
+use crate::api::config::Config;
 
 #[get("/")]
 fn redirect_to_docs() -> Redirect {
     Redirect::to(uri!("/docs"))
 }
 
-#[rocket::main]
-pub async fn main() -> Result<(), rocket::Error> {
-    let config = Config::build();
-
-    let jwt_authentication_adapter: Arc<dyn AuthenticationAdapter + Sync + Send> =
-        Arc::new(JWTAuthenticationAdapter::new(config.secret.clone()));
-
-    println!("Loading environment variables...");
-    let usuario_repository: Arc<Mutex<dyn UsuarioGateway + Sync + Send>> = if config.env
-        == Env::Test
-    {
-        println!("Using in memory database");
-        Arc::new(Mutex::new(InMemoryUsuarioRepository::new()))
-    } else {
-        println!("Connecting to database: {}", config.db_url.clone());
-        let postgres_connection_manager = postgres::PgConnectionManager::new(config.db_url.clone())
-            .await
-            .unwrap();
-        let tables = postgres::get_tables();
-
-        Arc::new(Mutex::new(
-            PostgresUsuarioGateway::new(postgres_connection_manager.client, tables).await,
-        ))
-    };
-
-    let cliente_repository: Arc<Mutex<dyn ClienteGateway + Sync + Send>> = if config.env
-        == Env::Test
-    {
-        println!("Using in memory database");
-        Arc::new(Mutex::new(InMemoryClienteRepository::new()))
-    } else {
-        println!("Connecting to database: {}", config.db_url);
-
-        let user_pool_id = match std::env::var("AWS_COGNITO_USER_POOL_ID") {
-            Ok(val) => val,
-            Err(_) => {
-                eprintln!("AWS_COGNITO_USER_POOL_ID environment variable not set.");
-                process::exit(1);
-            }
-        };
-        Arc::new(Mutex::new(
-            AwsCognitoRepository::new(String::from(user_pool_id)).await,
-        ))
-    };
-
-    //let pagamento_adapter: Arc<Mutex<dyn PagamentoAdapter + Sync + Send>> = Arc::new(Mutex::new(MockPagamentoSuccesso {}));
-
-    // Cloning cliente_repository to share ownership
-    let cloned_cliente_repository = Arc::clone(&cliente_repository);
-
-    let postgres_connection_manager = postgres::PgConnectionManager::new(config.db_url.clone())
-        .await
-        .unwrap();
-    let tables = postgres::get_tables();
-    let produto_repository: Arc<Mutex<dyn ProdutoGateway + Sync + Send>> = Arc::new(Mutex::new(
-        PostgresProdutoRepository::new(postgres_connection_manager.client, tables).await,
-    ));
-
-    // Cloning produto_repository to share ownership
-    let cloned_produto_repository = Arc::clone(&produto_repository);
-
-    let pedido_repository: Arc<Mutex<dyn PedidoGateway + Sync + Send>> = if config.env == Env::Test
-    {
-        println!("Using in memory database");
-        Arc::new(Mutex::new(InMemoryPedidoRepository::new()))
-    } else {
-        println!("Connecting to database: {}", config.db_url);
-        let postgres_connection_manager = postgres::PgConnectionManager::new(config.db_url.clone())
-            .await
-            .unwrap();
-        let tables = postgres::get_tables();
-
-        Arc::new(Mutex::new(
-            PostgresPedidoRepository::new(
-                postgres_connection_manager.client,
-                tables,
-                cloned_cliente_repository,
-                cloned_produto_repository,
-            )
-            .await,
-        ))
-    };
-
-    let cloned_pedido_repository = Arc::clone(&pedido_repository);
-
-    let pagamento_repository: Arc<Mutex<dyn PagamentoGateway + Sync + Send>> = if config.env
-        == Env::Test
-    {
-        println!("Using in memory database");
-        Arc::new(Mutex::new(InMemoryPagamentoRepository::new()))
-    } else {
-        println!("Connecting to database: {}", config.db_url.clone());
-        let postgres_connection_manager = postgres::PgConnectionManager::new(config.db_url.clone())
-            .await
-            .unwrap();
-        let tables = postgres::get_tables();
-
-        Arc::new(Mutex::new(
-            PostgresPagamentoRepository::new(
-                postgres_connection_manager.client,
-                tables,
-                cloned_pedido_repository,
-            )
-            .await,
-        ))
-    };
-
-    let mercado_pago_adapter: Arc<dyn PagamentoWebhookAdapter + Sync + Send> =
-        Arc::new(MercadoPagoPagamentoWebhookAdapter::new());
+// Monta a aplicação sem endereço nem porta; o main só carrega a configuração e
+// faz o launch, e testes ou quem embute a API podem passar as próprias dependências
+pub fn build_rocket(dependencies: AppDependencies) -> Rocket<Build> {
+    let app_state = AppState::new(&dependencies);
 
-    let mut metodos_pagamento: HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>> = HashMap::new();
-    metodos_pagamento.insert("Mercado Pago".to_string(), mercado_pago_adapter);
+    // Fotos de produto passam do limite padrão de 1 MiB; o caso de uso recusa acima de 5 MB
+    let figment = rocket::Config::figment()
+        .merge(("limits.file", "6 MiB"))
+        .merge(("limits.data-form", "7 MiB"));
 
-    let server_config = rocket::Config::figment()
-        .merge(("address", IpAddr::V4(Ipv4Addr::new(0, 0, 0, 0))))
-        .merge(("port", 3000));
-
-    rocket::build()
+    rocket::custom(figment)
         .mount("/", routes![redirect_to_docs])
+        .attach(RequestIdFairing)
+        .attach(MetricsFairing)
         .register("/", generic_catchers())
         .mount(
             "/docs/",
@@ -166,33 +43,75 @@ pub async fn main() -> Result<(), rocket::Error> {
                 urls: vec![
                     UrlObject::new("Auth", "/auth/openapi.json"),
                     UrlObject::new("Usuarios", "/usuarios/openapi.json"),
+                    UrlObject::new("Lojas", "/lojas/openapi.json"),
                     UrlObject::new("Clientes", "/clientes/openapi.json"),
                     UrlObject::new("Produtos", "/produtos/openapi.json"),
+                    UrlObject::new("Cardápio", "/cardapio/openapi.json"),
                     UrlObject::new("Pedidos", "/pedidos/openapi.json"),
+                    UrlObject::new("Cozinha", "/cozinha/openapi.json"),
                 ],
                 ..Default::default()
             }),
         )
-        .mount("/auth", auth_route::routes())
-        .mount("/usuarios", usuario_route::routes())
-        .mount("/clientes", cliente_route::routes())
-        .mount("/produtos", produto_route::routes())
-        .mount("/pedidos", pedido_route::routes())
+        .mount("/auth", rastrear(auth_route::routes()))
+        .mount("/usuarios", rastrear(usuario_route::routes()))
+        .mount("/lojas", rastrear(loja_route::routes()))
+        .mount("/clientes", rastrear(cliente_route::routes()))
+        .mount("/produtos", rastrear(produto_route::routes()))
+        .mount("/cardapio", rastrear(cardapio_route::routes()))
+        .mount("/pedidos", rastrear(pedido_route::routes()))
+        .mount("/cozinha", rastrear(cozinha_route::routes()))
+        .mount("/metrics", metrics_route::routes())
+        .mount("/health", health_route::routes())
         .register("/usuarios", usuario_route::catchers())
+        .register("/lojas", loja_route::catchers())
         .register("/clientes", cliente_route::catchers())
         .register("/produtos", produto_route::catchers())
         .register("/pedidos", pedido_route::catchers())
-        .manage(jwt_authentication_adapter)
-        .manage(usuario_repository)
-        .manage(cliente_repository)
-        .manage(produto_repository)
-        .manage(pedido_repository)
-        .manage(pagamento_repository)
-        .manage(metodos_pagamento)
-        .configure(server_config)
-        .launch()
-        .await?;
+        .register("/cozinha", cozinha_route::catchers())
+        .manage(app_state)
+        .manage(dependencies.authentication_adapter)
+        .manage(dependencies.usuario_repository)
+        .manage(dependencies.loja_repository)
+        .manage(dependencies.cliente_repository)
+        .manage(dependencies.produto_repository)
+        .manage(dependencies.estacao_repository)
+        .manage(dependencies.storage)
+        .manage(dependencies.fuso_horario)
+        .manage(dependencies.pool)
+        .manage(dependencies.health_checks)
+}
+
+// rocket::Error é grande demais para um Result; a Box deixa só um ponteiro no caminho de sucesso
+#[rocket::main]
+pub async fn main() -> Result<(), Box<rocket::Error>> {
+    let config = match Config::load() {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("{}", err);
+            process::exit(1);
+        }
+    };
+    telemetry::init(&config.env);
+
+
+    let dependencies = match AppDependencies::from_config(&config).await {
+        Ok(dependencies) => dependencies,
+        Err(err) => {
+            error!(error = %err, "Failed to initialize dependencies");
+            process::exit(1);
+        }
+    };
+
+    // Mantém o que o build_rocket configurou, como os limites de upload
+    let rocket = build_rocket(dependencies);
+    let server_config = rocket
+        .figment()
+        .clone()
+        .merge(("address", config.api_address))
+        .merge(("port", config.api_port));
+    rocket.configure(server_config).launch().await?;
 
-    println!("Server running on {}", config.env.to_string());
+    info!(env = %config.env, "Server shut down");
     Ok(())
 }
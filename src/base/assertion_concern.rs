@@ -1,26 +1,16 @@
-use chrono::DateTime;
-
 use crate::base::domain_error::DomainError;
 
-pub fn assert_argument_not_empty(value: String) -> Result<(), DomainError> {
+pub fn assert_argument_not_empty(campo: &str, value: String) -> Result<(), DomainError> {
     if value.is_empty() {
-        Err(DomainError::Empty)
+        Err(DomainError::empty(campo))
     } else {
         Ok(())
     }
 }
 
-pub fn assert_argument_timestamp_format(value: String) -> Result<(), DomainError> {
-    let format = "%Y-%m-%d %H:%M:%S%.3f%z";
-    match DateTime::parse_from_str(&value, format) {
-        Ok(_) => Ok(()),
-        Err(_) => Err(DomainError::Invalid(value))
-    }
-}
-
-pub fn assert_argument_not_negative(value: f64) -> Result<(), DomainError> {
+pub fn assert_argument_not_negative(campo: &str, value: f64) -> Result<(), DomainError> {
     if value < 0.0 {
-        Err(DomainError::NonPositive)
+        Err(DomainError::non_positive(campo))
     } else {
         Ok(())
     }
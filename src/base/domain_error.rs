@@ -1,9 +1,160 @@
+use std::error::Error;
+use std::fmt;
+
+// Erro de origem (driver do banco, client HTTP...) guardado para diagnóstico
+pub type Causa = Box<dyn Error + Send + Sync>;
+
 #[derive(Debug)]
 pub enum DomainError {
-    AlreadyExists,
-    Empty,
+    // Entrada que não respeita uma regra do domínio; `campo` fica vazio quando a regra envolve o registro todo
+    Validation { campo: String, msg: String, source: Option<Causa> },
+    // Operação incompatível com o estado atual, ex.: CPF já cadastrado
+    Conflict { entidade: &'static str, msg: String, source: Option<Causa> },
+    NotFound { entidade: &'static str, id: String },
+    // Sem credenciais válidas
     Unauthorized,
-    NotFound,
-    Invalid(String),
-    NonPositive
-}
\ No newline at end of file
+    // Autenticado, mas sem permissão para a operação
+    Forbidden(String),
+    // Falha ao chamar um serviço de fora, como o Mercado Pago ou o Cognito
+    ExternalService { servico: &'static str, msg: String, source: Option<Causa> },
+    // Falha ao ler ou gravar no repositório
+    Persistence { msg: String, source: Option<Causa> },
+    // Recurso da própria aplicação indisponível no momento, como o pool de conexões
+    Unavailable { msg: String, source: Option<Causa> },
+}
+
+impl DomainError {
+    pub fn validation(campo: &str, msg: impl Into<String>) -> Self {
+        DomainError::Validation {
+            campo: campo.to_string(),
+            msg: msg.into(),
+            source: None,
+        }
+    }
+
+    pub fn empty(campo: &str) -> Self {
+        DomainError::validation(campo, "não pode ser vazio")
+    }
+
+    pub fn non_positive(campo: &str) -> Self {
+        DomainError::validation(campo, "não pode ser negativo")
+    }
+
+    pub fn conflict(entidade: &'static str, msg: impl Into<String>) -> Self {
+        DomainError::Conflict {
+            entidade,
+            msg: msg.into(),
+            source: None,
+        }
+    }
+
+    pub fn not_found(entidade: &'static str, id: impl ToString) -> Self {
+        DomainError::NotFound {
+            entidade,
+            id: id.to_string(),
+        }
+    }
+
+    pub fn forbidden(msg: impl Into<String>) -> Self {
+        DomainError::Forbidden(msg.into())
+    }
+
+    pub fn external_service(servico: &'static str, msg: impl Into<String>) -> Self {
+        DomainError::ExternalService {
+            servico,
+            msg: msg.into(),
+            source: None,
+        }
+    }
+
+    pub fn persistence(msg: impl Into<String>) -> Self {
+        DomainError::Persistence {
+            msg: msg.into(),
+            source: None,
+        }
+    }
+
+    pub fn unavailable(msg: impl Into<String>) -> Self {
+        DomainError::Unavailable {
+            msg: msg.into(),
+            source: None,
+        }
+    }
+
+    // Anexa o erro de origem; variantes que não carregam causa ficam como estão
+    pub fn com_causa(mut self, causa: impl Into<Causa>) -> Self {
+        match &mut self {
+            DomainError::Validation { source, .. }
+            | DomainError::Conflict { source, .. }
+            | DomainError::ExternalService { source, .. }
+            | DomainError::Persistence { source, .. }
+            | DomainError::Unavailable { source, .. } => *source = Some(causa.into()),
+            DomainError::NotFound { .. } | DomainError::Unauthorized | DomainError::Forbidden(_) => (),
+        }
+        self
+    }
+}
+
+impl fmt::Display for DomainError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DomainError::Validation { campo, msg, .. } if campo.is_empty() => write!(f, "{}", msg),
+            DomainError::Validation { campo, msg, .. } => write!(f, "{}: {}", campo, msg),
+            DomainError::Conflict { msg, .. } => write!(f, "{}", msg),
+            DomainError::NotFound { entidade, id } if id.is_empty() => write!(f, "{} não encontrado", entidade),
+            DomainError::NotFound { entidade, id } => write!(f, "{} {} não encontrado", entidade, id),
+            DomainError::Unauthorized => write!(f, "Credenciais inválidas"),
+            DomainError::Forbidden(msg) => write!(f, "{}", msg),
+            DomainError::ExternalService { servico, msg, .. } => write!(f, "{}: {}", servico, msg),
+            DomainError::Persistence { msg, .. } => write!(f, "{}", msg),
+            DomainError::Unavailable { msg, .. } => write!(f, "{}", msg),
+        }
+    }
+}
+
+impl Error for DomainError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match self {
+            DomainError::Validation { source, .. }
+            | DomainError::Conflict { source, .. }
+            | DomainError::ExternalService { source, .. }
+            | DomainError::Persistence { source, .. }
+            | DomainError::Unavailable { source, .. } => {
+                source.as_deref().map(|causa| causa as &(dyn Error + 'static))
+            }
+            DomainError::NotFound { .. } | DomainError::Unauthorized | DomainError::Forbidden(_) => None,
+        }
+    }
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_mensagens() {
+        assert_eq!(DomainError::empty("nome").to_string(), "nome: não pode ser vazio");
+        assert_eq!(DomainError::not_found("Produto", 7).to_string(), "Produto 7 não encontrado");
+        assert_eq!(
+            DomainError::external_service("Mercado Pago", "status 500").to_string(),
+            "Mercado Pago: status 500"
+        );
+    }
+
+    #[test]
+    fn test_causa_fica_na_cadeia() {
+        let causa = std::io::Error::other("conexão recusada");
+        let erro = DomainError::persistence("Erro ao buscar produtos").com_causa(causa);
+
+        assert_eq!(erro.source().unwrap().to_string(), "conexão recusada");
+    }
+
+    #[test]
+    fn test_com_causa_ignora_variantes_sem_causa() {
+        let erro = DomainError::not_found("Pedido", 1).com_causa("qualquer coisa");
+
+        assert!(matches!(erro, DomainError::NotFound { entidade: "Pedido", .. }));
+        assert!(erro.source().is_none());
+    }
+}
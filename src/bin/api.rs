@@ -1,6 +1,17 @@
 use tech_challenge::api;
 
 fn main() {
+  let args: Vec<String> = std::env::args().skip(1).collect();
+
+  if args.first().map(String::as_str) == Some("migrate") {
+    let comando = args.get(1).map(String::as_str).unwrap_or("up");
+    if let Err(e) = api::migrate::main(comando, args.get(2).map(String::as_str)) {
+      println!("Error: {}", e);
+      std::process::exit(1);
+    }
+    return;
+  }
+
   match api::server::main() {
     Ok(_) => println!("Server finished"),
     Err(e) => println!("Error: {}", e),
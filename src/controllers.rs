@@ -2,4 +2,8 @@ pub mod auth_controller;
 pub mod cliente_controller;
 pub mod pedido_controller;
 pub mod produto_controller;
+pub mod foto_de_produto_controller;
 pub mod usuario_controller;
+pub mod health_controller;
+pub mod loja_controller;
+pub mod cozinha_controller;
@@ -2,7 +2,6 @@ use std::sync::Arc;
 
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
-use tokio::sync::Mutex;
 
 use crate::base::domain_error::DomainError;
 use crate::entities::cpf::Cpf;
@@ -30,7 +29,7 @@ pub struct AuthController {
 
 impl AuthController {
     pub fn new(
-            usuario_repository: Arc<Mutex<dyn UsuarioGateway + Sync + Send>>,
+            usuario_repository: Arc<dyn UsuarioGateway + Sync + Send>,
             authentication_adapter: Arc<dyn AuthenticationAdapter + Sync + Send>
         ) -> AuthController {
         let usuario_use_case = UsuarioUseCase::new(usuario_repository);
@@ -43,7 +42,7 @@ impl AuthController {
         match usuario {
             Ok(usuario) => {
                 if !usuario.validate_senha(&login_input.senha) {
-                    return Err(DomainError::Invalid("Senha inválida".to_string()));
+                    return Err(DomainError::Unauthorized);
                 }
                 let token = &self.authentication_adapter.get_token(usuario.clone()).await?;
                 Ok(AuthenticationResponse {
@@ -51,7 +50,9 @@ impl AuthController {
                     usuario,
                 })
             }
-            Err(_) => return Err(DomainError::Invalid("Usuário não encontrado".to_string())),
+            // Não revela se o CPF existe: usuário inexistente e senha errada dão o mesmo erro
+            Err(DomainError::NotFound { .. }) => Err(DomainError::Unauthorized),
+            Err(err) => Err(err),
         }
     }
 }
\ No newline at end of file
@@ -1,4 +1,3 @@
-use tokio::sync::Mutex;
 use std::sync::Arc;
 
 use crate::base::domain_error::DomainError;
@@ -12,7 +11,7 @@ pub struct ClienteController {
 }
 
 impl ClienteController {
-    pub fn new(cliente_repository: Arc<Mutex<dyn ClienteGateway + Sync + Send>> ) -> ClienteController {
+    pub fn new(cliente_repository: Arc<dyn ClienteGateway + Sync + Send> ) -> ClienteController {
         let cliente_use_case = ClienteUseCase::new(cliente_repository);
         ClienteController {
             cliente_use_case
@@ -0,0 +1,60 @@
+use std::str::FromStr;
+use std::sync::Arc;
+
+use crate::base::domain_error::DomainError;
+use crate::entities::estacao::{Estacao, StatusDoTicket, TicketDeEstacao};
+use crate::entities::pedido::Pedido;
+use crate::use_cases::{
+    gerenciamento_de_estacoes_use_case::{CreateEstacaoInput, EstacaoUseCase},
+    preparacao_e_entrega_use_case::PreparacaoeEntregaUseCase,
+};
+
+pub struct CozinhaController {
+    estacao_use_case: Arc<EstacaoUseCase>,
+    preparacao_e_entrega_use_case: Arc<PreparacaoeEntregaUseCase>,
+}
+
+impl CozinhaController {
+    pub fn new(
+        estacao_use_case: Arc<EstacaoUseCase>,
+        preparacao_e_entrega_use_case: Arc<PreparacaoeEntregaUseCase>,
+    ) -> CozinhaController {
+        CozinhaController {
+            estacao_use_case,
+            preparacao_e_entrega_use_case,
+        }
+    }
+
+    pub async fn get_estacoes(&self) -> Result<Vec<Estacao>, DomainError> {
+        self.estacao_use_case.get_estacoes().await
+    }
+
+    pub async fn get_estacao(&self, id: usize) -> Result<Estacao, DomainError> {
+        self.estacao_use_case.get_estacao_by_id(id).await
+    }
+
+    pub async fn create_estacao(&self, estacao: CreateEstacaoInput) -> Result<Estacao, DomainError> {
+        self.estacao_use_case.create_estacao(estacao).await
+    }
+
+    pub async fn update_estacao(&self, id: usize, estacao: CreateEstacaoInput) -> Result<Estacao, DomainError> {
+        self.estacao_use_case.update_estacao(id, estacao).await
+    }
+
+    pub async fn get_fila_da_estacao(&self, id: usize) -> Result<Vec<TicketDeEstacao>, DomainError> {
+        self.preparacao_e_entrega_use_case.get_fila_da_estacao(id).await
+    }
+
+    pub async fn atualiza_status_do_ticket(
+        &self,
+        estacao_id: usize,
+        pedido_id: usize,
+        status: &str,
+    ) -> Result<Pedido, DomainError> {
+        let status = StatusDoTicket::from_str(status)
+            .map_err(|_| DomainError::validation("status", "Status inválido"))?;
+        self.preparacao_e_entrega_use_case
+            .atualiza_status_do_ticket(estacao_id, pedido_id, status)
+            .await
+    }
+}
@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use crate::base::domain_error::DomainError;
+use crate::entities::produto::Produto;
+use crate::traits::produto_gateway::ProdutoGateway;
+use crate::traits::storage_gateway::{Arquivo, StorageGateway};
+use crate::use_cases::fotos_de_produtos_use_case::FotoDeProdutoUseCase;
+
+pub struct FotoDeProdutoController {
+    foto_de_produto_use_case: FotoDeProdutoUseCase,
+}
+
+impl FotoDeProdutoController {
+    pub fn new(
+        produto_repository: Arc<dyn ProdutoGateway + Sync + Send>,
+        storage: Arc<dyn StorageGateway + Sync + Send>,
+    ) -> FotoDeProdutoController {
+        let foto_de_produto_use_case = FotoDeProdutoUseCase::new(produto_repository, storage);
+        FotoDeProdutoController { foto_de_produto_use_case }
+    }
+
+    pub async fn enviar_foto(
+        &self,
+        id: usize,
+        conteudo: Vec<u8>,
+        content_type: &str,
+    ) -> Result<Produto, DomainError> {
+        self.foto_de_produto_use_case.enviar_foto(id, conteudo, content_type).await
+    }
+
+    pub async fn buscar_foto(
+        &self,
+        id: usize,
+        miniatura: bool,
+    ) -> Result<(String, Arquivo), DomainError> {
+        self.foto_de_produto_use_case.buscar_foto(id, miniatura).await
+    }
+}
@@ -0,0 +1,21 @@
+use std::sync::Arc;
+
+use crate::traits::health_check_adapter::HealthCheckAdapter;
+use crate::use_cases::verificacao_de_saude_use_case::{RelatorioSaude, VerificacaoDeSaudeUseCase};
+
+pub struct HealthController {
+    verificacao_de_saude_use_case: VerificacaoDeSaudeUseCase,
+}
+
+impl HealthController {
+    pub fn new(dependencias: Vec<Arc<dyn HealthCheckAdapter + Sync + Send>>) -> HealthController {
+        let verificacao_de_saude_use_case = VerificacaoDeSaudeUseCase::new(dependencias);
+        HealthController {
+            verificacao_de_saude_use_case,
+        }
+    }
+
+    pub async fn prontidao(&self) -> RelatorioSaude {
+        self.verificacao_de_saude_use_case.prontidao().await
+    }
+}
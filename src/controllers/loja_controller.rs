@@ -0,0 +1,38 @@
+use std::sync::Arc;
+
+use crate::base::domain_error::DomainError;
+use crate::entities::loja::Loja;
+use crate::traits::loja_gateway::LojaGateway;
+use crate::traits::usuario_gateway::UsuarioGateway;
+use crate::use_cases::gerenciamento_de_lojas_use_case::{CreateLojaInput, LojaUseCase};
+
+pub struct LojaController {
+    loja_use_case: LojaUseCase,
+}
+
+impl LojaController {
+    pub fn new(
+        loja_repository: Arc<dyn LojaGateway + Sync + Send>,
+        usuario_repository: Arc<dyn UsuarioGateway + Sync + Send>,
+        lojas_do_usuario: Vec<usize>,
+    ) -> LojaController {
+        let loja_use_case = LojaUseCase::new(loja_repository, usuario_repository, lojas_do_usuario);
+        LojaController { loja_use_case }
+    }
+
+    pub async fn get_lojas(&self) -> Result<Vec<Loja>, DomainError> {
+        self.loja_use_case.get_lojas().await
+    }
+
+    pub async fn get_loja(&self, id: usize) -> Result<Loja, DomainError> {
+        self.loja_use_case.get_loja_by_id(id).await
+    }
+
+    pub async fn create_loja(&self, usuario_id: usize, loja_input: CreateLojaInput) -> Result<Loja, DomainError> {
+        self.loja_use_case.create_loja(usuario_id, loja_input).await
+    }
+
+    pub async fn update_loja(&self, id: usize, loja_input: CreateLojaInput) -> Result<Loja, DomainError> {
+        self.loja_use_case.update_loja(id, loja_input).await
+    }
+}
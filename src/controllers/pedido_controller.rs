@@ -1,18 +1,12 @@
-use std::collections::HashMap;
 use std::sync::Arc;
 
 use rocket::http::hyper::Method;
-use tokio::sync::Mutex;
 
 use crate::base::domain_error::DomainError;
 use crate::entities::pagamento::{self, Pagamento};
 use crate::entities::pedido::{self, Pedido};
-use crate::traits::pagamento_webhook_adapter::PagamentoWebhookAdapter;
+use crate::entities::tempo_de_preparo::TempoDePreparo;
 use serde_json::Value;
-use crate::traits::{
-    cliente_gateway::ClienteGateway, pagamento_gateway::PagamentoGateway,
-    pedido_gateway::PedidoGateway, produto_gateway::ProdutoGateway,
-};
 
 use crate::use_cases::{
     pedidos_e_pagamentos_use_case::CreatePedidoInput,
@@ -21,27 +15,15 @@ use crate::use_cases::{
 };
 
 pub struct PedidoController {
-    pedidos_e_pagamentos_use_case: PedidosEPagamentosUseCase,
-    preparacao_e_entrega_use_case: PreparacaoeEntregaUseCase,
+    pedidos_e_pagamentos_use_case: Arc<PedidosEPagamentosUseCase>,
+    preparacao_e_entrega_use_case: Arc<PreparacaoeEntregaUseCase>,
 }
 
 impl PedidoController {
     pub fn new(
-        pedido_repository: Arc<Mutex<dyn PedidoGateway + Sync + Send>>,
-        cliente_repository: Arc<Mutex<dyn ClienteGateway + Sync + Send>>,
-        produto_repository: Arc<Mutex<dyn ProdutoGateway + Sync + Send>>,
-        pagamento_repository: Arc<Mutex<dyn PagamentoGateway + Sync + Send>>,
-        metodos_pagamento: HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>>
+        pedidos_e_pagamentos_use_case: Arc<PedidosEPagamentosUseCase>,
+        preparacao_e_entrega_use_case: Arc<PreparacaoeEntregaUseCase>,
     ) -> PedidoController {
-        let pedidos_e_pagamentos_use_case = PedidosEPagamentosUseCase::new(
-            pedido_repository.clone(),
-            cliente_repository,
-            produto_repository,
-            pagamento_repository,
-            metodos_pagamento,
-        );
-        let preparacao_e_entrega_use_case = PreparacaoeEntregaUseCase::new(pedido_repository);
-
         PedidoController {
             pedidos_e_pagamentos_use_case,
             preparacao_e_entrega_use_case,
@@ -49,28 +31,42 @@ impl PedidoController {
     }
 
     pub async fn get_pedidos(&self) -> Result<Vec<Pedido>, DomainError> {
-        self.pedidos_e_pagamentos_use_case.lista_pedidos().await
+        let mut pedidos = self.pedidos_e_pagamentos_use_case.lista_pedidos().await?;
+        self.preparacao_e_entrega_use_case.organizar_fila(&mut pedidos).await?;
+        Ok(pedidos)
     }
 
     pub async fn get_pedido_by_id(&self, id: usize) -> Result<Pedido, DomainError> {
-        self.pedidos_e_pagamentos_use_case
+        let mut pedido = self.pedidos_e_pagamentos_use_case
             .seleciona_pedido_por_id(id)
-            .await
+            .await?;
+        self.preparacao_e_entrega_use_case
+            .com_previsao(std::slice::from_mut(&mut pedido))
+            .await?;
+        Ok(pedido)
     }
 
     pub async fn novo_pedido(
         &self,
         pedido_input: CreatePedidoInput,
     ) -> Result<Pedido, DomainError> {
-        self.pedidos_e_pagamentos_use_case
+        let mut pedido = self.pedidos_e_pagamentos_use_case
             .novo_pedido(pedido_input)
-            .await
+            .await?;
+        self.preparacao_e_entrega_use_case
+            .com_previsao(std::slice::from_mut(&mut pedido))
+            .await?;
+        Ok(pedido)
     }
 
     pub async fn get_pedidos_novos(&self) -> Result<Vec<Pedido>, DomainError> {
         self.preparacao_e_entrega_use_case.get_pedidos_novos().await
     }
 
+    pub async fn get_tempos_de_preparo(&self, dias: i64) -> Result<Vec<TempoDePreparo>, DomainError> {
+        self.preparacao_e_entrega_use_case.get_tempos_de_preparo(dias).await
+    }
+
     pub async fn atualiza_status_pedido(
         &self,
         id: usize,
@@ -84,7 +80,7 @@ impl PedidoController {
             "Pago" => pedido::Status::Pago,
             "Pendente" => pedido::Status::Pendente,
             "Pronto" => pedido::Status::Pronto,
-            _ => return Err(DomainError::Invalid("Status inválido".to_string())),
+            _ => return Err(DomainError::validation("status", "Status inválido")),
         };
         self.preparacao_e_entrega_use_case
             .atualiza_status(id, status)
@@ -123,7 +119,7 @@ impl PedidoController {
                     .adicionar_bebida(id, produto_id)
                     .await
             }
-            _ => Err(DomainError::Invalid("Categoria inválida".to_string())),
+            _ => Err(DomainError::validation("categoria", "Categoria inválida")),
         }
     }
 
@@ -1,21 +1,40 @@
 use std::sync::Arc;
-use tokio::sync::Mutex;
+
+use chrono_tz::Tz;
 
 use crate::base::domain_error::DomainError;
+use crate::entities::alergeno::Alergeno;
+use crate::entities::cardapio::Cardapio;
+use crate::entities::preco_historico::PrecoHistorico;
 use crate::entities::produto::Produto;
+use crate::traits::estacao_gateway::EstacaoGateway;
 use crate::traits::produto_gateway::ProdutoGateway;
-use crate::use_cases::gerenciamento_de_produtos_use_case::{CreateProdutoInput, ProdutoUseCase};
+use crate::use_cases::gerenciamento_de_produtos_use_case::{
+    CreateProdutoInput, ProdutoUseCase, UpdateProdutoInput,
+};
 
 pub struct ProdutoController {
     produto_use_case: ProdutoUseCase,
 }
 
 impl ProdutoController {
-    pub fn new(produto_repository: Arc<Mutex<dyn ProdutoGateway + Sync + Send>>) -> ProdutoController {
+    pub fn new(produto_repository: Arc<dyn ProdutoGateway + Sync + Send>) -> ProdutoController {
         let produto_use_case = ProdutoUseCase::new(produto_repository);
         ProdutoController { produto_use_case }
     }
 
+    pub fn com_fuso_horario(self, fuso_horario: Tz) -> ProdutoController {
+        ProdutoController {
+            produto_use_case: self.produto_use_case.com_fuso_horario(fuso_horario),
+        }
+    }
+
+    pub fn com_estacoes(self, estacao_repository: Arc<dyn EstacaoGateway + Sync + Send>) -> ProdutoController {
+        ProdutoController {
+            produto_use_case: self.produto_use_case.com_estacoes(estacao_repository),
+        }
+    }
+
     pub async fn get_produto(
         &self,
     ) -> Result<Vec<Produto>, DomainError> {
@@ -29,6 +48,18 @@ impl ProdutoController {
         self.produto_use_case.get_produto_by_id(id).await
     }
 
+    pub async fn get_cardapio(&self, sem_alergenos: &[Alergeno]) -> Result<Cardapio, DomainError> {
+        self.produto_use_case.get_cardapio(sem_alergenos).await
+    }
+
+    pub async fn personalizar_produto(
+        &self,
+        id: usize,
+        sem_ingredientes: Vec<String>,
+    ) -> Result<Produto, DomainError> {
+        self.produto_use_case.personalizar_produto(id, sem_ingredientes).await
+    }
+
     pub async fn create_produto(
         &self,
         produto_input: CreateProdutoInput,
@@ -40,8 +71,18 @@ impl ProdutoController {
         &self,
         id: usize,
         produto_input: CreateProdutoInput,
+        versao: Option<usize>,
+    ) -> Result<Produto, DomainError> {
+        self.produto_use_case.update_produto(id, produto_input, versao).await
+    }
+
+    pub async fn patch_produto(
+        &self,
+        id: usize,
+        produto_input: UpdateProdutoInput,
+        versao: Option<usize>,
     ) -> Result<Produto, DomainError> {
-        self.produto_use_case.update_produto(id, produto_input).await
+        self.produto_use_case.patch_produto(id, produto_input, versao).await
     }
 
     pub async fn delete_produto(
@@ -50,4 +91,18 @@ impl ProdutoController {
     ) -> Result<(), DomainError> {
         self.produto_use_case.delete_produto(id).await
     }
+
+    pub async fn restaurar_produto(
+        &self,
+        id: usize,
+    ) -> Result<Produto, DomainError> {
+        self.produto_use_case.restaurar_produto(id).await
+    }
+
+    pub async fn get_historico_de_precos(
+        &self,
+        id: usize,
+    ) -> Result<Vec<PrecoHistorico>, DomainError> {
+        self.produto_use_case.get_historico_de_precos(id).await
+    }
 }
@@ -1,5 +1,4 @@
 use std::sync::Arc;
-use tokio::sync::Mutex;
 
 use crate::base::domain_error::DomainError;
 use crate::entities::usuario::Usuario;
@@ -12,13 +11,18 @@ pub struct UsuarioController {
 }
 
 impl UsuarioController {
-    pub fn new(usuario_repository: Arc<Mutex<dyn UsuarioGateway + Sync + Send>>) -> UsuarioController {
+    pub fn new(usuario_repository: Arc<dyn UsuarioGateway + Sync + Send>) -> UsuarioController {
         let usuario_use_case = UsuarioUseCase::new(usuario_repository);
         UsuarioController {
             usuario_use_case,
         }
     }
 
+    pub fn com_lojas_permitidas(mut self, lojas_permitidas: Vec<usize>) -> UsuarioController {
+        self.usuario_use_case = self.usuario_use_case.com_lojas_permitidas(lojas_permitidas);
+        self
+    }
+
     pub async fn get_usuarios(
         &self,
     ) -> Result<Vec<Usuario>, DomainError> {
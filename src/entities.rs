@@ -4,4 +4,13 @@ pub mod cliente;
 pub mod pedido;
 pub mod cpf;
 pub mod ingredientes;
-pub mod pagamento;
\ No newline at end of file
+pub mod pagamento;
+pub mod preco_historico;
+pub mod cardapio;
+pub mod alergeno;
+pub mod informacao_nutricional;
+pub mod janela_de_venda;
+pub mod loja;
+pub mod tempo_de_preparo;
+pub mod fila_da_cozinha;
+pub mod estacao;
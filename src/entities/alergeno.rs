@@ -0,0 +1,48 @@
+use std::collections::{BTreeMap, BTreeSet};
+
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// Principais alérgenos da rotulagem obrigatória (RDC 26/2015 da Anvisa)
+#[derive(
+    Clone, Copy, Serialize, Deserialize, Debug, JsonSchema, PartialEq, Eq, PartialOrd, Ord, Hash, FromFormField,
+)]
+pub enum Alergeno {
+    Gluten,
+    Lactose,
+    Ovo,
+    Soja,
+    Amendoim,
+    Nozes,
+    Peixe,
+    FrutosDoMar,
+    Gergelim,
+}
+
+// Alérgenos de cada ingrediente do produto, para que remover um ingrediente remova
+// também os alérgenos que só ele trazia
+pub type AlergenosPorIngrediente = BTreeMap<String, BTreeSet<Alergeno>>;
+
+pub fn alergenos_do_produto(por_ingrediente: &AlergenosPorIngrediente) -> BTreeSet<Alergeno> {
+    por_ingrediente.values().flatten().copied().collect()
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_alergenos_do_produto_une_os_ingredientes() {
+        let por_ingrediente = AlergenosPorIngrediente::from([
+            ("pão".to_string(), BTreeSet::from([Alergeno::Gluten, Alergeno::Soja])),
+            ("queijo".to_string(), BTreeSet::from([Alergeno::Lactose])),
+            ("maionese".to_string(), BTreeSet::from([Alergeno::Ovo, Alergeno::Soja])),
+        ]);
+
+        assert_eq!(
+            alergenos_do_produto(&por_ingrediente),
+            BTreeSet::from([Alergeno::Gluten, Alergeno::Lactose, Alergeno::Ovo, Alergeno::Soja])
+        );
+    }
+}
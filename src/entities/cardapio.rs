@@ -0,0 +1,130 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+use crate::entities::produto::{Categoria, Produto};
+
+// Ordem em que as categorias aparecem no totem, a mesma da montagem do combo
+pub const ORDEM_DAS_CATEGORIAS: [Categoria; 4] = [
+    Categoria::Lanche,
+    Categoria::Acompanhamento,
+    Categoria::Bebida,
+    Categoria::Sobremesa,
+];
+
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
+pub struct SecaoDoCardapio {
+    categoria: Categoria,
+    // Posição da seção no cardápio, começando em 1
+    ordem: usize,
+    produtos: Vec<Produto>,
+}
+
+impl SecaoDoCardapio {
+    // Getters
+    pub fn categoria(&self) -> &Categoria {
+        &self.categoria
+    }
+
+    pub fn ordem(&self) -> usize {
+        self.ordem
+    }
+
+    pub fn produtos(&self) -> &Vec<Produto> {
+        &self.produtos
+    }
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
+pub struct Cardapio {
+    secoes: Vec<SecaoDoCardapio>,
+}
+
+impl Cardapio {
+    // Recebe os produtos já separados por categoria, na ordem de ORDEM_DAS_CATEGORIAS.
+    // Categorias sem produtos ficam de fora e, dentro de cada uma, os produtos seguem o id
+    pub fn new(produtos_por_categoria: Vec<(Categoria, Vec<Produto>)>) -> Self {
+        let secoes = produtos_por_categoria
+            .into_iter()
+            .filter(|(_, produtos)| !produtos.is_empty())
+            .enumerate()
+            .map(|(posicao, (categoria, mut produtos))| {
+                produtos.sort_by_key(|produto| *produto.id());
+                SecaoDoCardapio {
+                    categoria,
+                    ordem: posicao + 1,
+                    produtos,
+                }
+            })
+            .collect();
+        Cardapio { secoes }
+    }
+
+    pub fn secoes(&self) -> &Vec<SecaoDoCardapio> {
+        &self.secoes
+    }
+
+    // Muda sempre que qualquer informação exibida muda; usada como ETag
+    pub fn versao(&self) -> String {
+        let conteudo = serde_json::to_vec(self).unwrap_or_default();
+        Sha256::digest(conteudo)
+            .iter()
+            .take(8)
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::ingredientes::Ingredientes;
+    use chrono::Utc;
+
+    fn produto(id: usize, categoria: Categoria, preco: f64) -> Produto {
+        let agora = Utc::now();
+        Produto::new(
+            id,
+            format!("Produto {}", id),
+            "foto.png".to_string(),
+            "Descrição".to_string(),
+            categoria,
+            preco,
+            Ingredientes::new(vec![]).unwrap(),
+            agora,
+            agora,
+        )
+    }
+
+    #[test]
+    fn test_cardapio_ordena_e_omite_categorias_vazias() {
+        let cardapio = Cardapio::new(vec![
+            (Categoria::Lanche, vec![produto(3, Categoria::Lanche, 10.0), produto(1, Categoria::Lanche, 12.0)]),
+            (Categoria::Acompanhamento, vec![]),
+            (Categoria::Bebida, vec![produto(2, Categoria::Bebida, 5.0)]),
+        ]);
+
+        let secoes = cardapio.secoes();
+        assert_eq!(secoes.len(), 2);
+        assert_eq!(secoes[0].categoria(), &Categoria::Lanche);
+        assert_eq!(secoes[0].ordem(), 1);
+        let ids: Vec<usize> = secoes[0].produtos().iter().map(|produto| *produto.id()).collect();
+        assert_eq!(ids, vec![1, 3]);
+        assert_eq!(secoes[1].categoria(), &Categoria::Bebida);
+        assert_eq!(secoes[1].ordem(), 2);
+    }
+
+    #[test]
+    fn test_versao_muda_com_o_conteudo() {
+        let lanche = produto(1, Categoria::Lanche, 10.0);
+        let cardapio = Cardapio::new(vec![(Categoria::Lanche, vec![lanche.clone()])]);
+        let mesmo = Cardapio::new(vec![(Categoria::Lanche, vec![lanche.clone()])]);
+        let mut mais_caro = lanche;
+        mais_caro.set_preco(11.0).unwrap();
+        let alterado = Cardapio::new(vec![(Categoria::Lanche, vec![mais_caro])]);
+
+        assert_eq!(cardapio.versao(), mesmo.versao());
+        assert_ne!(cardapio.versao(), alterado.versao());
+    }
+}
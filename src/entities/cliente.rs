@@ -1,4 +1,4 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -15,8 +15,8 @@ pub struct Cliente {
     nome: String,
     email: String,
     cpf: Cpf,
-    data_criacao: String,
-    data_atualizacao: String,
+    data_criacao: DateTime<Utc>,
+    data_atualizacao: DateTime<Utc>,
 }
 
 impl Cliente {
@@ -25,8 +25,8 @@ impl Cliente {
         nome: String,
         email: String,
         cpf: Cpf,
-        data_criacao: String,
-        data_atualizacao: String,
+        data_criacao: DateTime<Utc>,
+        data_atualizacao: DateTime<Utc>,
     ) -> Self {
         Cliente {
             id,
@@ -39,10 +39,8 @@ impl Cliente {
     }
 
     pub fn validate_entity(&self) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(self.nome.clone())?;
-        assertion_concern::assert_argument_not_empty(self.email.clone())?;
-        assertion_concern::assert_argument_timestamp_format(self.data_criacao.clone())?;
-        assertion_concern::assert_argument_timestamp_format(self.data_atualizacao.clone())?;
+        assertion_concern::assert_argument_not_empty("nome", self.nome.clone())?;
+        assertion_concern::assert_argument_not_empty("email", self.email.clone())?;
         Ok(())
     }
 
@@ -63,23 +61,23 @@ impl Cliente {
         &self.cpf
     }
 
-    pub fn data_criacao(&self) -> &String {
+    pub fn data_criacao(&self) -> &DateTime<Utc> {
         &self.data_criacao
     }
 
-    pub fn data_atualizacao(&self) -> &String {
+    pub fn data_atualizacao(&self) -> &DateTime<Utc> {
         &self.data_atualizacao
     }
 
     // Setters
     pub fn set_nome(&mut self, nome: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(nome.clone())?;
+        assertion_concern::assert_argument_not_empty("nome", nome.clone())?;
         self.nome = nome;
         Ok(())
     }
 
     pub fn set_email(&mut self, email: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(email.clone())?;
+        assertion_concern::assert_argument_not_empty("email", email.clone())?;
         self.email = email;
         Ok(())
     }
@@ -88,10 +86,8 @@ impl Cliente {
         self.cpf = cpf;
     }
 
-    pub fn set_data_atualizacao(&mut self, data_atualizacao: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_timestamp_format(data_atualizacao.clone())?;
+    pub fn set_data_atualizacao(&mut self, data_atualizacao: DateTime<Utc>) {
         self.data_atualizacao = data_atualizacao;
-        Ok(())
     }
 }
 
@@ -99,16 +95,17 @@ impl Cliente {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Duration;
     use crate::entities::cpf::Cpf;
 
     fn create_valid_cliente() -> Cliente {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         Cliente::new(
             1,
             "Fulano da Silva".to_string(),
             "fulano.silva@exemplo.com".to_string(),
             Cpf::new("123.456.789-09".to_string()).unwrap(),
-            _now.clone(),
+            _now,
             _now,
         )
     }
@@ -129,40 +126,40 @@ mod tests {
 
     #[test]
     fn test_cliente_validate_entity_empty_nome() {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         let cliente = Cliente::new(
             1,
             "".to_string(),
             "fulano.silva@exemplo.com".to_string(),
             Cpf::new("123.456.789-09".to_string()).unwrap(),
-            _now.clone(),
+            _now,
             _now,
         );
 
         let result = cliente.validate_entity();
         assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
+            matches!(result, Err(DomainError::Validation { .. })),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
             result
         );
     }
 
     #[test]
     fn test_cliente_validate_entity_empty_email() {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         let cliente = Cliente::new(
             1,
             "Fulano da Silva".to_string(),
             "".to_string(),
             Cpf::new("123.456.789-09".to_string()).unwrap(),
-            _now.clone(),
+            _now,
             _now,
         );
 
         let result = cliente.validate_entity();
         assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
+            matches!(result, Err(DomainError::Validation { .. })),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
             result
         );
     }
@@ -181,8 +178,8 @@ mod tests {
         let mut cliente = create_valid_cliente();
         let result = cliente.set_nome("".to_string());
         assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
+            matches!(result, Err(DomainError::Validation { .. })),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
             result
         );
     }
@@ -192,20 +189,18 @@ mod tests {
         let mut cliente = create_valid_cliente();
         let result = cliente.set_email("".to_string());
         assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
+            matches!(result, Err(DomainError::Validation { .. })),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
             result
         );
     }
 
     #[test]
-    fn test_cliente_set_data_atualizacao_invalid_format() {
+    fn test_cliente_set_data_atualizacao() {
         let mut cliente = create_valid_cliente();
-        let result = cliente.set_data_atualizacao("18-02-2024".to_string());
-        assert!(
-            matches!(result, Err(DomainError::Invalid(_))),
-            "Esperado Err(DomainError::Invalid), obtido {:?}",
-            result
-        );
+        let data_atualizacao = *cliente.data_criacao() + Duration::minutes(5);
+        cliente.set_data_atualizacao(data_atualizacao);
+        assert_eq!(cliente.data_atualizacao(), &data_atualizacao);
+        assert!(cliente.data_atualizacao() > cliente.data_criacao());
     }
 }
@@ -10,7 +10,7 @@ pub struct Cpf(pub String);
 impl Cpf {
     pub fn new(codigo: String) -> Result<Self, DomainError> {
         if codigo.is_empty() {
-            return Err(DomainError::Empty);
+            return Err(DomainError::empty("cpf"));
         }
         // Default admin user
         if codigo == "000.000.000-00" {
@@ -21,10 +21,10 @@ impl Cpf {
             if Cpf::validate(codigo.clone()) {
                 Ok(Cpf(codigo))
             } else {
-                Err(DomainError::Invalid("CPF".to_string()))
+                Err(DomainError::validation("cpf", "CPF inválido"))
             }
         } else {
-            Err(DomainError::Invalid("CPF".to_string()))
+            Err(DomainError::validation("cpf", "CPF inválido"))
         }
     }
 
@@ -0,0 +1,230 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::base::{assertion_concern, domain_error::DomainError};
+use crate::entities::loja::{loja_padrao, LOJA_PADRAO};
+use crate::entities::produto::Produto;
+
+// Ponto da cozinha onde uma parte dos produtos é preparada, ex.: chapa, fritadeira, bebidas
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, PartialEq)]
+pub struct Estacao {
+    id: usize,
+    nome: String,
+    data_criacao: DateTime<Utc>,
+    data_atualizacao: DateTime<Utc>,
+    // Preenchida pelo gateway com a loja em que a estação foi cadastrada
+    #[serde(default = "loja_padrao")]
+    loja_id: usize,
+}
+
+impl Estacao {
+    pub fn new(
+        id: usize,
+        nome: String,
+        data_criacao: DateTime<Utc>,
+        data_atualizacao: DateTime<Utc>,
+    ) -> Self {
+        Estacao {
+            id,
+            nome,
+            data_criacao,
+            data_atualizacao,
+            loja_id: LOJA_PADRAO,
+        }
+    }
+
+    pub fn validate_entity(&self) -> Result<(), DomainError> {
+        assertion_concern::assert_argument_not_empty("nome", self.nome.clone())?;
+        Ok(())
+    }
+
+    // Getters
+    pub fn id(&self) -> &usize {
+        &self.id
+    }
+
+    pub fn nome(&self) -> &String {
+        &self.nome
+    }
+
+    pub fn data_criacao(&self) -> &DateTime<Utc> {
+        &self.data_criacao
+    }
+
+    pub fn data_atualizacao(&self) -> &DateTime<Utc> {
+        &self.data_atualizacao
+    }
+
+    pub fn loja_id(&self) -> usize {
+        self.loja_id
+    }
+
+    // Setters
+    pub fn set_nome(&mut self, nome: String) -> Result<(), DomainError> {
+        assertion_concern::assert_argument_not_empty("nome", nome.clone())?;
+        self.nome = nome;
+        Ok(())
+    }
+
+    pub fn set_data_atualizacao(&mut self, data_atualizacao: DateTime<Utc>) {
+        self.data_atualizacao = data_atualizacao;
+    }
+
+    pub fn set_loja_id(&mut self, loja_id: usize) {
+        self.loja_id = loja_id;
+    }
+}
+
+// Recebido => EmPreparacao => Pronto
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, PartialEq)]
+pub enum StatusDoTicket {
+    Recebido,
+    EmPreparacao,
+    Pronto,
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, PartialEq)]
+pub struct ItemDoTicket {
+    produto_id: usize,
+    nome: String,
+}
+
+impl ItemDoTicket {
+    pub fn produto_id(&self) -> &usize {
+        &self.produto_id
+    }
+
+    pub fn nome(&self) -> &String {
+        &self.nome
+    }
+}
+
+// Parte do pedido que cabe a uma estação: os itens dela, com status próprio
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, PartialEq)]
+pub struct TicketDeEstacao {
+    pedido_id: usize,
+    estacao_id: usize,
+    itens: Vec<ItemDoTicket>,
+    status: StatusDoTicket,
+    // Vazia enquanto o ticket não saiu de Recebido
+    data_atualizacao: Option<DateTime<Utc>>,
+}
+
+impl TicketDeEstacao {
+    pub fn pedido_id(&self) -> &usize {
+        &self.pedido_id
+    }
+
+    pub fn estacao_id(&self) -> &usize {
+        &self.estacao_id
+    }
+
+    pub fn itens(&self) -> &Vec<ItemDoTicket> {
+        &self.itens
+    }
+
+    pub fn status(&self) -> &StatusDoTicket {
+        &self.status
+    }
+
+    pub fn data_atualizacao(&self) -> Option<&DateTime<Utc>> {
+        self.data_atualizacao.as_ref()
+    }
+
+    pub fn set_status(&mut self, status: StatusDoTicket, data: DateTime<Utc>) {
+        self.status = status;
+        self.data_atualizacao = Some(data);
+    }
+}
+
+// Um ticket por estação com itens do pedido, na ordem das estações; itens sem estação ficam
+// de fora. Os tickets que já existiam para a mesma estação mantêm o status
+pub fn tickets_do_pedido(
+    pedido_id: usize,
+    itens: &[&Produto],
+    anteriores: &[TicketDeEstacao],
+) -> Vec<TicketDeEstacao> {
+    let mut por_estacao: BTreeMap<usize, Vec<ItemDoTicket>> = BTreeMap::new();
+    for produto in itens {
+        if let Some(estacao_id) = produto.estacao_id() {
+            por_estacao.entry(estacao_id).or_default().push(ItemDoTicket {
+                produto_id: *produto.id(),
+                nome: produto.nome().clone(),
+            });
+        }
+    }
+    por_estacao
+        .into_iter()
+        .map(|(estacao_id, itens)| {
+            let anterior = anteriores.iter().find(|ticket| ticket.estacao_id == estacao_id);
+            TicketDeEstacao {
+                pedido_id,
+                estacao_id,
+                itens,
+                status: anterior.map_or(StatusDoTicket::Recebido, |ticket| ticket.status.clone()),
+                data_atualizacao: anterior.and_then(|ticket| ticket.data_atualizacao),
+            }
+        })
+        .collect()
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{ingredientes::Ingredientes, produto::Categoria};
+
+    fn produto(id: usize, estacao_id: Option<usize>) -> Produto {
+        let mut produto = Produto::new(
+            id,
+            format!("Produto {}", id),
+            "foto.png".to_string(),
+            "descricao".to_string(),
+            Categoria::Lanche,
+            10.0,
+            Ingredientes::new(vec![]).unwrap(),
+            Utc::now(),
+            Utc::now(),
+        );
+        produto.set_estacao_id(estacao_id);
+        produto
+    }
+
+    #[test]
+    fn test_estacao_validate_entity_empty_nome() {
+        let estacao = Estacao::new(1, "".to_string(), Utc::now(), Utc::now());
+        assert!(matches!(estacao.validate_entity(), Err(DomainError::Validation { .. })));
+    }
+
+    #[test]
+    fn test_tickets_agrupam_itens_por_estacao() {
+        let lanche = produto(1, Some(2));
+        let sobremesa = produto(2, Some(2));
+        let bebida = produto(3, Some(1));
+        let sem_estacao = produto(4, None);
+
+        let tickets = tickets_do_pedido(7, &[&lanche, &sobremesa, &bebida, &sem_estacao], &[]);
+
+        assert_eq!(tickets.len(), 2);
+        assert_eq!(*tickets[0].estacao_id(), 1);
+        assert_eq!(tickets[1].itens().len(), 2);
+        assert!(tickets.iter().all(|ticket| *ticket.pedido_id() == 7));
+        assert!(tickets.iter().all(|ticket| *ticket.status() == StatusDoTicket::Recebido));
+    }
+
+    #[test]
+    fn test_tickets_mantem_status_da_estacao() {
+        let lanche = produto(1, Some(1));
+        let mut anteriores = tickets_do_pedido(7, &[&lanche], &[]);
+        anteriores[0].set_status(StatusDoTicket::Pronto, Utc::now());
+
+        let bebida = produto(3, Some(2));
+        let tickets = tickets_do_pedido(7, &[&lanche, &bebida], &anteriores);
+
+        assert_eq!(*tickets[0].status(), StatusDoTicket::Pronto);
+        assert_eq!(*tickets[1].status(), StatusDoTicket::Recebido);
+    }
+}
@@ -0,0 +1,114 @@
+use crate::entities::pedido::{Pedido, Status};
+
+// Só pedidos pagos chegam à cozinha; saem dela quando ficam prontos
+pub const STATUS_NA_FILA_DA_COZINHA: [Status; 2] = [Status::Pago, Status::EmPreparacao];
+
+pub fn na_fila_da_cozinha(status: &Status) -> bool {
+    STATUS_NA_FILA_DA_COZINHA.contains(status)
+}
+
+// Pronto aguardando retirada, em preparação, recebido; o resto vai para o fim
+fn prioridade_do_status(status: &Status) -> u8 {
+    match status {
+        Status::Pronto => 0,
+        Status::EmPreparacao => 1,
+        Status::Pago => 2,
+        _ => 3,
+    }
+}
+
+// Ordem de atendimento: por status e, dentro de cada status, do que entrou na fila há mais
+// tempo para o mais recente. Priorizando atrasados, os pedidos acima do SLA passam à frente
+// dos demais do mesmo status, sem furar os que já estão mais adiantados
+#[derive(Clone, Copy, Debug, Default, PartialEq)]
+pub struct PoliticaDaFila {
+    priorizar_atrasados: bool,
+}
+
+impl PoliticaDaFila {
+    pub fn priorizando_atrasados(mut self, priorizar_atrasados: bool) -> Self {
+        self.priorizar_atrasados = priorizar_atrasados;
+        self
+    }
+
+    pub fn priorizar_atrasados(&self) -> bool {
+        self.priorizar_atrasados
+    }
+
+    pub fn ordenar(&self, pedidos: &mut [Pedido]) {
+        pedidos.sort_by_key(|pedido| {
+            (
+                prioridade_do_status(pedido.status()),
+                !(self.priorizar_atrasados && pedido.atrasado()),
+                *pedido.entrada_na_fila(),
+                *pedido.id(),
+            )
+        });
+    }
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::{Duration, Utc};
+
+    fn pedido(id: usize, status: Status, minutos_na_fila: i64, atrasado: bool) -> Pedido {
+        let entrada = Utc::now() - Duration::minutes(minutos_na_fila);
+        let mut pedido = Pedido::new(
+            id,
+            None,
+            None,
+            None,
+            None,
+            "Mercado Pago".to_string(),
+            Status::Pendente,
+            entrada,
+            entrada,
+        );
+        pedido.registrar_status(Status::Pago, entrada);
+        if status != Status::Pago {
+            pedido.registrar_status(status, Utc::now());
+        }
+        pedido.set_atrasado(atrasado);
+        pedido
+    }
+
+    fn ids(pedidos: &[Pedido]) -> Vec<usize> {
+        pedidos.iter().map(|pedido| *pedido.id()).collect()
+    }
+
+    #[test]
+    fn test_na_fila_da_cozinha_so_pedidos_pagos() {
+        assert!(na_fila_da_cozinha(&Status::Pago));
+        assert!(na_fila_da_cozinha(&Status::EmPreparacao));
+        assert!(!na_fila_da_cozinha(&Status::Pendente));
+        assert!(!na_fila_da_cozinha(&Status::Pronto));
+    }
+
+    #[test]
+    fn test_ordena_por_status_e_tempo_na_fila() {
+        let mut pedidos = vec![
+            pedido(1, Status::Pago, 5, false),
+            pedido(2, Status::Pago, 30, true),
+            pedido(3, Status::EmPreparacao, 10, false),
+            pedido(4, Status::Pronto, 1, false),
+            pedido(5, Status::Pago, 20, false),
+        ];
+
+        PoliticaDaFila::default().ordenar(&mut pedidos);
+        assert_eq!(ids(&pedidos), vec![4, 3, 2, 5, 1]);
+    }
+
+    #[test]
+    fn test_atrasados_passam_a_frente_do_mesmo_status() {
+        let mut pedidos = vec![
+            pedido(1, Status::Pago, 30, false),
+            pedido(2, Status::Pago, 25, true),
+            pedido(3, Status::EmPreparacao, 10, false),
+        ];
+
+        PoliticaDaFila::default().priorizando_atrasados(true).ordenar(&mut pedidos);
+        assert_eq!(ids(&pedidos), vec![3, 2, 1]);
+    }
+}
@@ -0,0 +1,99 @@
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::base::domain_error::DomainError;
+
+// Valores por porção; campos ausentes não foram informados
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, PartialEq, Default)]
+pub struct InformacaoNutricional {
+    // kcal
+    #[serde(default)]
+    calorias: Option<f64>,
+    // Gramas
+    #[serde(default)]
+    proteinas: Option<f64>,
+    #[serde(default)]
+    carboidratos: Option<f64>,
+    #[serde(default)]
+    gorduras: Option<f64>,
+    // Miligramas
+    #[serde(default)]
+    sodio: Option<f64>,
+}
+
+impl InformacaoNutricional {
+    pub fn new(
+        calorias: Option<f64>,
+        proteinas: Option<f64>,
+        carboidratos: Option<f64>,
+        gorduras: Option<f64>,
+        sodio: Option<f64>,
+    ) -> Result<Self, DomainError> {
+        let informacao = InformacaoNutricional {
+            calorias,
+            proteinas,
+            carboidratos,
+            gorduras,
+            sodio,
+        };
+        informacao.validate_entity()?;
+        Ok(informacao)
+    }
+
+    pub fn validate_entity(&self) -> Result<(), DomainError> {
+        let valores = [
+            ("calorias", self.calorias),
+            ("proteinas", self.proteinas),
+            ("carboidratos", self.carboidratos),
+            ("gorduras", self.gorduras),
+            ("sodio", self.sodio),
+        ];
+        for (campo, valor) in valores {
+            if valor.is_some_and(|valor| valor < 0.0 || !valor.is_finite()) {
+                return Err(DomainError::validation(
+                    &format!("informacao_nutricional.{}", campo),
+                    "Valor nutricional não pode ser negativo",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    // Getters
+    pub fn calorias(&self) -> Option<f64> {
+        self.calorias
+    }
+
+    pub fn proteinas(&self) -> Option<f64> {
+        self.proteinas
+    }
+
+    pub fn carboidratos(&self) -> Option<f64> {
+        self.carboidratos
+    }
+
+    pub fn gorduras(&self) -> Option<f64> {
+        self.gorduras
+    }
+
+    pub fn sodio(&self) -> Option<f64> {
+        self.sodio
+    }
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_informacao_nutricional_recusa_valor_negativo() {
+        assert!(InformacaoNutricional::new(Some(500.0), Some(25.0), None, None, None).is_ok());
+
+        let erro = InformacaoNutricional::new(Some(500.0), None, None, Some(-1.0), None).unwrap_err();
+        assert!(matches!(
+            erro,
+            DomainError::Validation { ref campo, .. } if campo == "informacao_nutricional.gorduras"
+        ));
+    }
+}
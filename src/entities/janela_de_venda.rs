@@ -0,0 +1,211 @@
+use chrono::{Datelike, NaiveDate, NaiveDateTime, NaiveTime, Weekday};
+use chrono_tz::Tz;
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::base::domain_error::DomainError;
+
+// Fuso usado quando a loja não configura outro
+pub const FUSO_HORARIO_PADRAO: Tz = chrono_tz::America::Sao_Paulo;
+
+#[derive(Clone, Copy, Serialize, Deserialize, Debug, JsonSchema, PartialEq, Eq)]
+pub enum DiaDaSemana {
+    Domingo,
+    Segunda,
+    Terca,
+    Quarta,
+    Quinta,
+    Sexta,
+    Sabado,
+}
+
+impl From<Weekday> for DiaDaSemana {
+    fn from(dia: Weekday) -> Self {
+        match dia {
+            Weekday::Sun => DiaDaSemana::Domingo,
+            Weekday::Mon => DiaDaSemana::Segunda,
+            Weekday::Tue => DiaDaSemana::Terca,
+            Weekday::Wed => DiaDaSemana::Quarta,
+            Weekday::Thu => DiaDaSemana::Quinta,
+            Weekday::Fri => DiaDaSemana::Sexta,
+            Weekday::Sat => DiaDaSemana::Sabado,
+        }
+    }
+}
+
+// Período em que o produto é vendido, no horário da loja. Campos ausentes não restringem:
+// sem `dias` vale a semana toda, sem `inicio`/`fim` o dia todo e sem datas vale sempre.
+// `fim` e `data_fim` são exclusivo e inclusivo, respectivamente
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, PartialEq, Default)]
+pub struct JanelaDeVenda {
+    #[serde(default)]
+    dias: Vec<DiaDaSemana>,
+    #[serde(default)]
+    inicio: Option<NaiveTime>,
+    #[serde(default)]
+    fim: Option<NaiveTime>,
+    #[serde(default)]
+    data_inicio: Option<NaiveDate>,
+    #[serde(default)]
+    data_fim: Option<NaiveDate>,
+}
+
+impl JanelaDeVenda {
+    pub fn new(
+        dias: Vec<DiaDaSemana>,
+        inicio: Option<NaiveTime>,
+        fim: Option<NaiveTime>,
+        data_inicio: Option<NaiveDate>,
+        data_fim: Option<NaiveDate>,
+    ) -> Result<Self, DomainError> {
+        let janela = JanelaDeVenda {
+            dias,
+            inicio,
+            fim,
+            data_inicio,
+            data_fim,
+        };
+        janela.validate_entity()?;
+        Ok(janela)
+    }
+
+    // Janelas que atravessam a meia-noite devem ser divididas em duas
+    pub fn validate_entity(&self) -> Result<(), DomainError> {
+        if let (Some(inicio), Some(fim)) = (self.inicio, self.fim) {
+            if fim <= inicio {
+                return Err(DomainError::validation(
+                    "janelas_de_venda",
+                    "O horário de fim deve ser depois do início",
+                ));
+            }
+        }
+        if let (Some(data_inicio), Some(data_fim)) = (self.data_inicio, self.data_fim) {
+            if data_fim < data_inicio {
+                return Err(DomainError::validation(
+                    "janelas_de_venda",
+                    "A data de fim não pode ser antes da data de início",
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    pub fn contem(&self, momento: &NaiveDateTime) -> bool {
+        let data = momento.date();
+        let hora = momento.time();
+        (self.dias.is_empty() || self.dias.contains(&data.weekday().into()))
+            && self.inicio.is_none_or(|inicio| hora >= inicio)
+            && self.fim.is_none_or(|fim| hora < fim)
+            && self.data_inicio.is_none_or(|data_inicio| data >= data_inicio)
+            && self.data_fim.is_none_or(|data_fim| data <= data_fim)
+    }
+
+    // Getters
+    pub fn dias(&self) -> &Vec<DiaDaSemana> {
+        &self.dias
+    }
+
+    pub fn inicio(&self) -> Option<NaiveTime> {
+        self.inicio
+    }
+
+    pub fn fim(&self) -> Option<NaiveTime> {
+        self.fim
+    }
+
+    pub fn data_inicio(&self) -> Option<NaiveDate> {
+        self.data_inicio
+    }
+
+    pub fn data_fim(&self) -> Option<NaiveDate> {
+        self.data_fim
+    }
+}
+
+// Sem janelas o produto é vendido a qualquer momento; com várias, basta estar em uma delas
+pub fn disponivel_em(janelas: &[JanelaDeVenda], momento: &NaiveDateTime) -> bool {
+    janelas.is_empty() || janelas.iter().any(|janela| janela.contem(momento))
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn momento(data: &str, hora: &str) -> NaiveDateTime {
+        NaiveDateTime::new(data.parse().unwrap(), hora.parse().unwrap())
+    }
+
+    #[test]
+    fn test_cafe_da_manha_em_dias_uteis() {
+        let janela = JanelaDeVenda::new(
+            vec![
+                DiaDaSemana::Segunda,
+                DiaDaSemana::Terca,
+                DiaDaSemana::Quarta,
+                DiaDaSemana::Quinta,
+                DiaDaSemana::Sexta,
+            ],
+            Some("07:00:00".parse().unwrap()),
+            Some("10:30:00".parse().unwrap()),
+            None,
+            None,
+        )
+        .unwrap();
+        let janelas = vec![janela];
+
+        // 2024-03-04 é uma segunda-feira
+        assert!(disponivel_em(&janelas, &momento("2024-03-04", "07:00:00")));
+        assert!(disponivel_em(&janelas, &momento("2024-03-04", "10:29:59")));
+        assert!(!disponivel_em(&janelas, &momento("2024-03-04", "10:30:00")));
+        assert!(!disponivel_em(&janelas, &momento("2024-03-04", "06:59:59")));
+        assert!(!disponivel_em(&janelas, &momento("2024-03-09", "08:00:00")));
+    }
+
+    #[test]
+    fn test_periodo_de_datas_e_varias_janelas() {
+        let natal = JanelaDeVenda::new(
+            vec![],
+            None,
+            None,
+            Some("2024-12-01".parse().unwrap()),
+            Some("2024-12-25".parse().unwrap()),
+        )
+        .unwrap();
+        let fim_de_semana =
+            JanelaDeVenda::new(vec![DiaDaSemana::Sabado, DiaDaSemana::Domingo], None, None, None, None).unwrap();
+        let janelas = vec![natal, fim_de_semana];
+
+        assert!(disponivel_em(&janelas, &momento("2024-12-25", "23:00:00")));
+        assert!(!disponivel_em(&janelas, &momento("2024-12-26", "12:00:00")));
+        assert!(disponivel_em(&janelas, &momento("2024-12-28", "12:00:00")));
+        assert!(disponivel_em(&[], &momento("2024-12-26", "12:00:00")));
+    }
+
+    #[test]
+    fn test_janela_invalida() {
+        let virando_a_noite = JanelaDeVenda::new(
+            vec![],
+            Some("22:00:00".parse().unwrap()),
+            Some("02:00:00".parse().unwrap()),
+            None,
+            None,
+        );
+        assert!(matches!(virando_a_noite, Err(DomainError::Validation { .. })));
+
+        let datas_invertidas = JanelaDeVenda::new(
+            vec![],
+            None,
+            None,
+            Some("2024-12-25".parse().unwrap()),
+            Some("2024-12-01".parse().unwrap()),
+        );
+        assert!(matches!(datas_invertidas, Err(DomainError::Validation { .. })));
+    }
+
+    #[test]
+    fn test_horario_sem_segundos() {
+        let janela: JanelaDeVenda = serde_json::from_str(r#"{"inicio": "07:00", "fim": "10:30"}"#).unwrap();
+        assert_eq!(janela.inicio(), Some(NaiveTime::from_hms_opt(7, 0, 0).unwrap()));
+    }
+}
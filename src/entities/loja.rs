@@ -0,0 +1,108 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::base::{assertion_concern, domain_error::DomainError};
+
+// Loja criada pela migration 0008_lojas; os dados de antes do suporte a várias lojas ficam nela
+// e é onde acontecem as requisições sem loja informada
+pub const LOJA_PADRAO: usize = 1;
+
+// Valor padrão do `loja_id` das entidades que pertencem a uma loja
+pub fn loja_padrao() -> usize {
+    LOJA_PADRAO
+}
+
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, PartialEq)]
+pub struct Loja {
+    id: usize,
+    nome: String,
+    data_criacao: DateTime<Utc>,
+    data_atualizacao: DateTime<Utc>,
+}
+
+impl Loja {
+    pub fn new(
+        id: usize,
+        nome: String,
+        data_criacao: DateTime<Utc>,
+        data_atualizacao: DateTime<Utc>,
+    ) -> Self {
+        Loja {
+            id,
+            nome,
+            data_criacao,
+            data_atualizacao,
+        }
+    }
+
+    pub fn validate_entity(&self) -> Result<(), DomainError> {
+        assertion_concern::assert_argument_not_empty("nome", self.nome.clone())?;
+        Ok(())
+    }
+
+    // Getters
+    pub fn id(&self) -> &usize {
+        &self.id
+    }
+
+    pub fn nome(&self) -> &String {
+        &self.nome
+    }
+
+    pub fn data_criacao(&self) -> &DateTime<Utc> {
+        &self.data_criacao
+    }
+
+    pub fn data_atualizacao(&self) -> &DateTime<Utc> {
+        &self.data_atualizacao
+    }
+
+    // Setters
+    pub fn set_nome(&mut self, nome: String) -> Result<(), DomainError> {
+        assertion_concern::assert_argument_not_empty("nome", nome.clone())?;
+        self.nome = nome;
+        Ok(())
+    }
+
+    pub fn set_data_atualizacao(&mut self, data_atualizacao: DateTime<Utc>) {
+        self.data_atualizacao = data_atualizacao;
+    }
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn create_valid_loja() -> Loja {
+        let _now = Utc::now();
+        Loja::new(1, "Loja Centro".to_string(), _now, _now)
+    }
+
+    #[test]
+    fn test_loja_validate_entity_valid() {
+        let loja = create_valid_loja();
+        assert!(loja.validate_entity().is_ok());
+        assert_eq!(loja.nome(), "Loja Centro");
+    }
+
+    #[test]
+    fn test_loja_validate_entity_empty_nome() {
+        let _now = Utc::now();
+        let loja = Loja::new(1, "".to_string(), _now, _now);
+        let result = loja.validate_entity();
+        assert!(
+            matches!(result, Err(DomainError::Validation { .. })),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
+            result
+        );
+    }
+
+    #[test]
+    fn test_loja_set_nome_empty() {
+        let mut loja = create_valid_loja();
+        assert!(loja.set_nome("".to_string()).is_err());
+        assert_eq!(loja.nome(), "Loja Centro");
+    }
+}
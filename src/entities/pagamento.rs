@@ -1,4 +1,4 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -7,6 +7,7 @@ use crate::{
         assertion_concern,
         domain_error::DomainError,
     },
+    entities::loja::{loja_padrao, LOJA_PADRAO},
 };
 
 #[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
@@ -17,7 +18,9 @@ pub struct Pagamento {
     valor: f64,
     metodo: String,
     referencia: String,
-    data_criacao: String,
+    data_criacao: DateTime<Utc>,
+    #[serde(default = "loja_padrao")]
+    loja_id: usize,
 }
 
 impl Pagamento {
@@ -28,7 +31,7 @@ impl Pagamento {
         valor: f64,
         metodo: String,
         referencia: String,
-        data_criacao: String,
+        data_criacao: DateTime<Utc>,
     ) -> Self {
         Pagamento {
             id,
@@ -38,12 +41,12 @@ impl Pagamento {
             metodo,
             referencia,
             data_criacao,
+            loja_id: LOJA_PADRAO,
         }
     }
 
     pub fn validate_entity(&self) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(self.metodo.clone())?;
-        assertion_concern::assert_argument_timestamp_format(self.data_criacao.clone())?;
+        assertion_concern::assert_argument_not_empty("metodo", self.metodo.clone())?;
         Ok(())
     }
 
@@ -73,25 +76,29 @@ impl Pagamento {
     }
 
 
-    pub fn data_criacao(&self) -> &String {
+    pub fn data_criacao(&self) -> &DateTime<Utc> {
         &self.data_criacao
     }
 
+    pub fn loja_id(&self) -> usize {
+        self.loja_id
+    }
+
     // Setters
     pub fn set_estado(&mut self, estado: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(estado.clone())?;
+        assertion_concern::assert_argument_not_empty("estado", estado.clone())?;
         self.estado = estado;
         Ok(())
     }
 
     pub fn set_valor(&mut self, valor: f64) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_negative(valor.clone())?;
+        assertion_concern::assert_argument_not_negative("valor", valor.clone())?;
         self.valor = valor;
         Ok(())
     }
     
     pub fn set_metodo(&mut self, metodo: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(metodo.clone())?;
+        assertion_concern::assert_argument_not_empty("metodo", metodo.clone())?;
         self.metodo = metodo;
         Ok(())
     }
@@ -100,6 +107,10 @@ impl Pagamento {
         self.referencia = referencia;
     }
 
+    pub fn set_loja_id(&mut self, loja_id: usize) {
+        self.loja_id = loja_id;
+    }
+
 }
 
 // Unit Tests
@@ -108,7 +119,7 @@ mod tests {
     use super::*;
 
     fn create_valid_pagamento() -> Pagamento {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         Pagamento::new(
             1,
             1,
@@ -116,7 +127,7 @@ mod tests {
             100.00,
             "MercadoPago".to_string(),
             "aaabbbccc".to_string(),
-            _now.clone(),
+            _now,
         )
     }
 
@@ -140,7 +151,7 @@ mod tests {
 
     #[test]
     fn test_pagamento_validate_entity_empty_metodo() {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         let pagamento = Pagamento::new(
             1,
             1,
@@ -148,12 +159,12 @@ mod tests {
             100.00,
             "".to_string(),
             "aaabbbccc".to_string(),
-            _now.clone(),
+            _now,
         );
         let result = pagamento.validate_entity();
         assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
+            matches!(result, Err(DomainError::Validation { .. })),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
             result
         );
     }
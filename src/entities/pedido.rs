@@ -1,10 +1,16 @@
-use chrono::Utc;
+use chrono::{DateTime, Duration, Utc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
 use crate::{
     base::{assertion_concern, domain_error::DomainError},
-    entities::{cliente::Cliente, produto::Produto},
+    entities::{
+        cliente::Cliente,
+        estacao::{tickets_do_pedido, StatusDoTicket, TicketDeEstacao},
+        loja::{loja_padrao, LOJA_PADRAO},
+        produto::Produto,
+        tempo_de_preparo::MudancaDeStatus,
+    },
 };
 
 // Considerar Ordem de Status
@@ -30,8 +36,26 @@ pub struct Pedido {
     bebida: Option<Produto>,
     pagamento: String,
     status: Status,
-    data_criacao: String,
-    data_atualizacao: String,
+    data_criacao: DateTime<Utc>,
+    data_atualizacao: DateTime<Utc>,
+    #[serde(default = "loja_padrao")]
+    loja_id: usize,
+    // Quando o pedido entrou em cada status, em ordem cronológica
+    #[serde(default)]
+    historico_de_status: Vec<MudancaDeStatus>,
+    // Calculados a partir do histórico: da entrada na fila ao início do preparo e do início até ficar pronto
+    #[serde(default)]
+    tempo_de_espera_segundos: Option<i64>,
+    #[serde(default)]
+    tempo_de_preparo_segundos: Option<i64>,
+    // Preenchidos pela cozinha a partir das médias dos produtos e do SLA
+    #[serde(default)]
+    previsao_de_pronto: Option<DateTime<Utc>>,
+    #[serde(default)]
+    atrasado: bool,
+    // Um por estação com itens do pedido, recalculados quando os itens mudam
+    #[serde(default)]
+    tickets: Vec<TicketDeEstacao>,
 }
 
 impl Pedido {
@@ -43,8 +67,8 @@ impl Pedido {
         bebida: Option<Produto>,
         pagamento: String,
         status: Status,
-        data_criacao: String,
-        data_atualizacao: String,
+        data_criacao: DateTime<Utc>,
+        data_atualizacao: DateTime<Utc>,
     ) -> Self {
         Pedido {
             id,
@@ -53,17 +77,26 @@ impl Pedido {
             acompanhamento,
             bebida,
             pagamento,
-            status,
+            status: status.clone(),
             data_criacao,
             data_atualizacao,
+            loja_id: LOJA_PADRAO,
+            historico_de_status: vec![MudancaDeStatus::new(status, data_criacao)],
+            tempo_de_espera_segundos: None,
+            tempo_de_preparo_segundos: None,
+            previsao_de_pronto: None,
+            atrasado: false,
+            tickets: Vec::new(),
         }
+        .com_tempos_calculados()
+        .com_tickets_calculados()
     }
 
     pub fn validate_entity(&self) -> Result<(), DomainError> {
         if self.lanche.is_none() && self.acompanhamento.is_none() && self.bebida.is_none() {
-            return Err(DomainError::Invalid(
-                "Pedido deve conter pelo menos um item entre Lanche, Acompanhamento ou Bebida"
-                    .to_string(),
+            return Err(DomainError::validation(
+                "",
+                "Pedido deve conter pelo menos um item entre Lanche, Acompanhamento ou Bebida",
             ));
         };
 
@@ -76,13 +109,9 @@ impl Pedido {
             | Status::Finalizado
             | Status::Cancelado => (),
             _ => {
-                return Err(DomainError::Invalid(
-                    "Status do Pedido é inválido".to_string(),
-                ))
+                return Err(DomainError::validation("status", "Status do Pedido é inválido"))
             }
         };
-        assertion_concern::assert_argument_timestamp_format(self.data_criacao.clone())?;
-        assertion_concern::assert_argument_timestamp_format(self.data_atualizacao.clone())?;
         Ok(())
     }
 
@@ -115,14 +144,79 @@ impl Pedido {
         &self.status
     }
 
-    pub fn data_criacao(&self) -> &String {
+    pub fn data_criacao(&self) -> &DateTime<Utc> {
         &self.data_criacao
     }
 
-    pub fn data_atualizacao(&self) -> &String {
+    pub fn data_atualizacao(&self) -> &DateTime<Utc> {
         &self.data_atualizacao
     }
 
+    pub fn loja_id(&self) -> usize {
+        self.loja_id
+    }
+
+    pub fn historico_de_status(&self) -> &Vec<MudancaDeStatus> {
+        &self.historico_de_status
+    }
+
+    // Primeira vez que o pedido entrou no status
+    pub fn data_do_status(&self, status: &Status) -> Option<&DateTime<Utc>> {
+        self.historico_de_status
+            .iter()
+            .find(|mudanca| mudanca.status() == status)
+            .map(MudancaDeStatus::data)
+    }
+
+    // O pedido entra na fila da cozinha quando é pago; sem pagamento registrado vale a criação
+    pub fn entrada_na_fila(&self) -> &DateTime<Utc> {
+        self.data_do_status(&Status::Pago).unwrap_or(&self.data_criacao)
+    }
+
+    pub fn tempo_de_espera(&self) -> Option<Duration> {
+        self.tempo_de_espera_segundos.map(Duration::seconds)
+    }
+
+    pub fn tempo_de_preparo(&self) -> Option<Duration> {
+        self.tempo_de_preparo_segundos.map(Duration::seconds)
+    }
+
+    pub fn previsao_de_pronto(&self) -> Option<&DateTime<Utc>> {
+        self.previsao_de_pronto.as_ref()
+    }
+
+    pub fn atrasado(&self) -> bool {
+        self.atrasado
+    }
+
+    pub fn tickets(&self) -> &Vec<TicketDeEstacao> {
+        &self.tickets
+    }
+
+    pub fn ticket_da_estacao(&self, estacao_id: usize) -> Option<&TicketDeEstacao> {
+        self.tickets.iter().find(|ticket| *ticket.estacao_id() == estacao_id)
+    }
+
+    // Só vale para pedidos em que todos os itens passam por alguma estação
+    pub fn pronto_em_todas_as_estacoes(&self) -> bool {
+        let itens = self.itens();
+        !itens.is_empty()
+            && itens.iter().all(|produto| produto.estacao_id().is_some())
+            && self.tickets.iter().all(|ticket| *ticket.status() == StatusDoTicket::Pronto)
+    }
+
+    fn itens(&self) -> Vec<&Produto> {
+        [&self.lanche, &self.acompanhamento, &self.bebida]
+            .into_iter()
+            .flatten()
+            .collect()
+    }
+
+    // Pedidos prontos, entregues ou cancelados já saíram da cozinha
+    pub fn saiu_da_cozinha(&self) -> bool {
+        matches!(self.status, Status::Pronto | Status::Finalizado | Status::Cancelado)
+    }
+
     pub fn valor(&self) -> f64 {
 
         let mut total = 0.0;
@@ -149,14 +243,17 @@ impl Pedido {
 
     pub fn set_lanche(&mut self, lanche: Option<Produto>) {
         self.lanche = lanche;
+        self.recalcular_tickets();
     }
 
     pub fn set_acompanhamento(&mut self, acompanhamento: Option<Produto>) {
         self.acompanhamento = acompanhamento;
+        self.recalcular_tickets();
     }
 
     pub fn set_bebida(&mut self, bebida: Option<Produto>) {
         self.bebida = bebida;
+        self.recalcular_tickets();
     }
 
     pub fn set_pagamento(&mut self, pagamento: String) {
@@ -167,10 +264,79 @@ impl Pedido {
         self.status = status;
     }
 
-    pub fn set_data_atualizacao(&mut self, data_atualizacao: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_timestamp_format(data_atualizacao.clone())?;
+    pub fn set_data_atualizacao(&mut self, data_atualizacao: DateTime<Utc>) {
         self.data_atualizacao = data_atualizacao;
-        Ok(())
+    }
+
+    pub fn set_loja_id(&mut self, loja_id: usize) {
+        self.loja_id = loja_id;
+    }
+
+    // Troca o status guardando o momento da mudança
+    pub fn registrar_status(&mut self, status: Status, data: DateTime<Utc>) {
+        self.historico_de_status.push(MudancaDeStatus::new(status.clone(), data));
+        self.status = status;
+        self.data_atualizacao = data;
+        self.recalcular_tempos();
+    }
+
+    // Histórico lido do banco; vazio mantém o registro da criação
+    pub fn set_historico_de_status(&mut self, mut historico_de_status: Vec<MudancaDeStatus>) {
+        if historico_de_status.is_empty() {
+            return;
+        }
+        historico_de_status.sort_by_key(|mudanca| *mudanca.data());
+        self.historico_de_status = historico_de_status;
+        self.recalcular_tempos();
+    }
+
+    pub fn registrar_status_do_ticket(
+        &mut self,
+        estacao_id: usize,
+        status: StatusDoTicket,
+        data: DateTime<Utc>,
+    ) -> Result<(), DomainError> {
+        let id = self.id;
+        match self.tickets.iter_mut().find(|ticket| *ticket.estacao_id() == estacao_id) {
+            Some(ticket) => {
+                ticket.set_status(status, data);
+                Ok(())
+            }
+            None => Err(DomainError::not_found("Ticket", format!("{}/{}", id, estacao_id))),
+        }
+    }
+
+    pub fn set_previsao_de_pronto(&mut self, previsao_de_pronto: Option<DateTime<Utc>>) {
+        self.previsao_de_pronto = previsao_de_pronto;
+    }
+
+    pub fn set_atrasado(&mut self, atrasado: bool) {
+        self.atrasado = atrasado;
+    }
+
+    fn com_tempos_calculados(mut self) -> Self {
+        self.recalcular_tempos();
+        self
+    }
+
+    fn com_tickets_calculados(mut self) -> Self {
+        self.recalcular_tickets();
+        self
+    }
+
+    fn recalcular_tickets(&mut self) {
+        self.tickets = tickets_do_pedido(self.id, &self.itens(), &self.tickets);
+    }
+
+    fn recalcular_tempos(&mut self) {
+        let inicio_do_preparo = self.data_do_status(&Status::EmPreparacao).copied();
+        let pronto = self.data_do_status(&Status::Pronto).copied();
+        self.tempo_de_espera_segundos =
+            inicio_do_preparo.map(|inicio| (inicio - *self.entrada_na_fila()).num_seconds().max(0));
+        self.tempo_de_preparo_segundos = match (inicio_do_preparo, pronto) {
+            (Some(inicio), Some(pronto)) => Some((pronto - inicio).num_seconds().max(0)),
+            _ => None,
+        };
     }
 
     pub fn get_total_valor_pedido(&self) -> f64 {
@@ -197,6 +363,7 @@ impl Pedido {
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Duration;
     use crate::entities::cliente::Cliente;
     use crate::entities::cpf::Cpf;
     use crate::entities::ingredientes::Ingredientes;
@@ -204,19 +371,19 @@ mod tests {
     use crate::entities::produto::Produto;
 
     fn create_valid_cliente() -> Cliente {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         Cliente::new(
             1,
             "Fulano da Silva".to_string(),
             "fulano.silva@exemplo.com".to_string(),
             Cpf::new("123.456.789-09".to_string()).unwrap(),
-            _now.clone(),
+            _now,
             _now,
         )
     }
 
     fn create_valid_produto() -> Produto {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         Produto::new(
             1,
             "Cheeseburger".to_string(),
@@ -230,13 +397,13 @@ mod tests {
                 "Queijo".to_string(),
             ])
             .unwrap(),
-            _now.clone(),
+            _now,
             _now,
         )
     }
 
     fn create_valid_pedido() -> Pedido {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         let cliente = create_valid_cliente();
         let produto = create_valid_produto();
         Pedido::new(
@@ -247,7 +414,7 @@ mod tests {
             None,
             "Cartão de Crédito".to_string(),
             Status::Pendente,
-            _now.clone(),
+            _now,
             _now,
         )
     }
@@ -271,7 +438,7 @@ mod tests {
 
     #[test]
     fn test_pedido_validate_entity_no_items() {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         let cliente = create_valid_cliente();
         let pedido = Pedido::new(
             1,
@@ -281,25 +448,65 @@ mod tests {
             None,
             "Mercado Pago".to_string(),
             Status::Pendente,
-            _now.clone(),
+            _now,
             _now,
         );
         let result = pedido.validate_entity();
         assert!(
-            matches!(result, Err(DomainError::Invalid(_))),
-            "Esperado Err(DomainError::Invalid), obtido {:?}",
+            matches!(result, Err(DomainError::Validation { .. })),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
             result
         );
     }
 
     #[test]
-    fn test_pedido_set_data_atualizacao_invalid_format() {
+    fn test_pedido_set_data_atualizacao() {
         let mut pedido = create_valid_pedido();
-        let result = pedido.set_data_atualizacao("18-02-2024".to_string());
-        assert!(
-            matches!(result, Err(DomainError::Invalid(_))),
-            "Esperado Err(DomainError::Invalid), obtido {:?}",
-            result
-        );
+        let data_atualizacao = *pedido.data_criacao() + Duration::minutes(5);
+        pedido.set_data_atualizacao(data_atualizacao);
+        assert_eq!(pedido.data_atualizacao(), &data_atualizacao);
+        assert!(pedido.data_atualizacao() > pedido.data_criacao());
+    }
+
+    #[test]
+    fn test_pedido_registrar_status_calcula_tempos() {
+        let mut pedido = create_valid_pedido();
+        let criacao = *pedido.data_criacao();
+        pedido.registrar_status(Status::Pago, criacao + Duration::minutes(1));
+        pedido.registrar_status(Status::EmPreparacao, criacao + Duration::minutes(4));
+        assert_eq!(pedido.tempo_de_espera(), Some(Duration::minutes(3)));
+        assert_eq!(pedido.tempo_de_preparo(), None);
+
+        pedido.registrar_status(Status::Pronto, criacao + Duration::minutes(16));
+        assert_eq!(pedido.tempo_de_preparo(), Some(Duration::minutes(12)));
+        assert_eq!(pedido.status(), &Status::Pronto);
+        assert_eq!(pedido.historico_de_status().len(), 4);
+        assert!(pedido.saiu_da_cozinha());
+    }
+
+    #[test]
+    fn test_pedido_pronto_em_todas_as_estacoes() {
+        let mut pedido = create_valid_pedido();
+        assert!(pedido.tickets().is_empty());
+        assert!(!pedido.pronto_em_todas_as_estacoes());
+
+        let mut lanche = create_valid_produto();
+        lanche.set_estacao_id(Some(1));
+        pedido.set_lanche(Some(lanche));
+        let mut bebida = create_valid_produto();
+        bebida.set_estacao_id(Some(2));
+        pedido.set_bebida(Some(bebida));
+        assert_eq!(pedido.tickets().len(), 2);
+
+        pedido.registrar_status_do_ticket(1, StatusDoTicket::Pronto, Utc::now()).unwrap();
+        assert!(!pedido.pronto_em_todas_as_estacoes());
+        pedido.registrar_status_do_ticket(2, StatusDoTicket::Pronto, Utc::now()).unwrap();
+        assert!(pedido.pronto_em_todas_as_estacoes());
+
+        // Um item sem estação não tem quem o marque como pronto
+        pedido.set_acompanhamento(Some(create_valid_produto()));
+        assert!(!pedido.pronto_em_todas_as_estacoes());
+        assert!(pedido.registrar_status_do_ticket(3, StatusDoTicket::Pronto, Utc::now()).is_err());
     }
 }
+
@@ -0,0 +1,72 @@
+use chrono::{DateTime, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+// Preço praticado por um produto a partir de `vigente_desde`, até a próxima alteração
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, PartialEq)]
+pub struct PrecoHistorico {
+    produto_id: usize,
+    preco: f64,
+    vigente_desde: DateTime<Utc>,
+}
+
+impl PrecoHistorico {
+    pub fn new(produto_id: usize, preco: f64, vigente_desde: DateTime<Utc>) -> Self {
+        PrecoHistorico {
+            produto_id,
+            preco,
+            vigente_desde,
+        }
+    }
+
+    // Getters
+    pub fn produto_id(&self) -> &usize {
+        &self.produto_id
+    }
+
+    pub fn preco(&self) -> f64 {
+        self.preco
+    }
+
+    pub fn vigente_desde(&self) -> &DateTime<Utc> {
+        &self.vigente_desde
+    }
+}
+
+// Preço do produto no momento `data`; antes do primeiro registro vale o mais antigo
+pub fn preco_em(historico: &[PrecoHistorico], produto_id: usize, data: &DateTime<Utc>) -> Option<f64> {
+    let mut precos: Vec<&PrecoHistorico> = historico
+        .iter()
+        .filter(|preco| preco.produto_id == produto_id)
+        .collect();
+    precos.sort_by_key(|preco| preco.vigente_desde);
+    precos
+        .iter()
+        .rev()
+        .find(|preco| preco.vigente_desde <= *data)
+        .or(precos.first())
+        .map(|preco| preco.preco)
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Duration;
+
+    #[test]
+    fn test_preco_em() {
+        let inicio = Utc::now() - Duration::days(10);
+        let historico = vec![
+            PrecoHistorico::new(1, 12.0, inicio + Duration::days(5)),
+            PrecoHistorico::new(1, 10.0, inicio),
+            PrecoHistorico::new(2, 4.0, inicio),
+        ];
+
+        assert_eq!(preco_em(&historico, 1, &(inicio + Duration::days(1))), Some(10.0));
+        assert_eq!(preco_em(&historico, 1, &(inicio + Duration::days(5))), Some(12.0));
+        assert_eq!(preco_em(&historico, 1, &Utc::now()), Some(12.0));
+        assert_eq!(preco_em(&historico, 1, &(inicio - Duration::days(1))), Some(10.0));
+        assert_eq!(preco_em(&historico, 3, &Utc::now()), None);
+    }
+}
@@ -1,4 +1,6 @@
-use chrono::Utc;
+use std::collections::BTreeSet;
+
+use chrono::{DateTime, NaiveDateTime, Utc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 
@@ -7,7 +9,14 @@ use crate::{
         assertion_concern,
         domain_error::DomainError,
     },
-    entities::ingredientes::Ingredientes,
+    entities::{
+        alergeno::{alergenos_do_produto, Alergeno, AlergenosPorIngrediente},
+        informacao_nutricional::InformacaoNutricional,
+        ingredientes::Ingredientes,
+        janela_de_venda::{disponivel_em, JanelaDeVenda},
+        loja::{loja_padrao, LOJA_PADRAO},
+        preco_historico::{preco_em, PrecoHistorico},
+    },
 };
 
 #[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, PartialEq)]
@@ -27,8 +36,36 @@ pub struct Produto {
     categoria: Categoria,
     preco: f64,
     ingredientes: Ingredientes,
-    data_criacao: String,
-    data_atualizacao: String,
+    data_criacao: DateTime<Utc>,
+    data_atualizacao: DateTime<Utc>,
+    // Incrementada a cada atualização gravada; exposta como ETag
+    #[serde(default = "versao_inicial")]
+    versao: usize,
+    // Produto arquivado sai do cardápio, mas continua disponível para pedidos antigos
+    #[serde(default)]
+    arquivado: bool,
+    #[serde(default)]
+    data_arquivamento: Option<DateTime<Utc>>,
+    #[serde(default)]
+    alergenos_por_ingrediente: AlergenosPorIngrediente,
+    // União dos alérgenos dos ingredientes, recalculada a cada alteração deles
+    #[serde(default)]
+    alergenos: BTreeSet<Alergeno>,
+    #[serde(default)]
+    informacao_nutricional: Option<InformacaoNutricional>,
+    // Sem janelas o produto pode ser pedido a qualquer momento
+    #[serde(default)]
+    janelas_de_venda: Vec<JanelaDeVenda>,
+    // Estação da cozinha que prepara o produto; sem ela o produto não gera ticket
+    #[serde(default)]
+    estacao_id: Option<usize>,
+    // Preenchida pelo gateway com a loja em que o produto foi cadastrado
+    #[serde(default = "loja_padrao")]
+    loja_id: usize,
+}
+
+fn versao_inicial() -> usize {
+    1
 }
 
 impl Produto {
@@ -40,8 +77,8 @@ impl Produto {
         categoria: Categoria,
         preco: f64,
         ingredientes: Ingredientes,
-        data_criacao: String,
-        data_atualizacao: String,
+        data_criacao: DateTime<Utc>,
+        data_atualizacao: DateTime<Utc>,
     ) -> Self {
         Produto {
             id,
@@ -53,24 +90,28 @@ impl Produto {
             ingredientes,
             data_criacao,
             data_atualizacao,
+            versao: versao_inicial(),
+            arquivado: false,
+            data_arquivamento: None,
+            alergenos_por_ingrediente: AlergenosPorIngrediente::new(),
+            alergenos: BTreeSet::new(),
+            informacao_nutricional: None,
+            janelas_de_venda: Vec::new(),
+            estacao_id: None,
+            loja_id: LOJA_PADRAO,
         }
     }
 
     pub fn validate_entity(&self) -> Result<(), DomainError> {
-        match self.categoria {
-            Categoria::Lanche | Categoria::Acompanhamento | Categoria::Bebida => (),
-            // | Categoria::Sobremesa => (),
-            _ => {
-                return Err(DomainError::Invalid(
-                    "Categoria do Produto é inválida".to_string(),
-                ))
-            }
-        };
-        assertion_concern::assert_argument_not_empty(self.nome.clone())?;
-        assertion_concern::assert_argument_not_empty(self.descricao.clone())?;
-        assertion_concern::assert_argument_not_negative(self.preco.clone())?;
-        assertion_concern::assert_argument_timestamp_format(self.data_criacao.clone())?;
-        assertion_concern::assert_argument_timestamp_format(self.data_atualizacao.clone())?;
+        assertion_concern::assert_argument_not_empty("nome", self.nome.clone())?;
+        assertion_concern::assert_argument_not_empty("descricao", self.descricao.clone())?;
+        assertion_concern::assert_argument_not_negative("preco", self.preco.clone())?;
+        if let Some(informacao_nutricional) = &self.informacao_nutricional {
+            informacao_nutricional.validate_entity()?;
+        }
+        for janela in &self.janelas_de_venda {
+            janela.validate_entity()?;
+        }
         Ok(())
     }
 
@@ -103,17 +144,58 @@ impl Produto {
         &self.ingredientes
     }
 
-    pub fn data_criacao(&self) -> &String {
+    pub fn data_criacao(&self) -> &DateTime<Utc> {
         &self.data_criacao
     }
 
-    pub fn data_atualizacao(&self) -> &String {
+    pub fn data_atualizacao(&self) -> &DateTime<Utc> {
         &self.data_atualizacao
     }
 
+    pub fn versao(&self) -> usize {
+        self.versao
+    }
+
+    pub fn arquivado(&self) -> bool {
+        self.arquivado
+    }
+
+    pub fn data_arquivamento(&self) -> Option<&DateTime<Utc>> {
+        self.data_arquivamento.as_ref()
+    }
+
+    pub fn alergenos_por_ingrediente(&self) -> &AlergenosPorIngrediente {
+        &self.alergenos_por_ingrediente
+    }
+
+    pub fn alergenos(&self) -> &BTreeSet<Alergeno> {
+        &self.alergenos
+    }
+
+    pub fn informacao_nutricional(&self) -> Option<&InformacaoNutricional> {
+        self.informacao_nutricional.as_ref()
+    }
+
+    pub fn janelas_de_venda(&self) -> &Vec<JanelaDeVenda> {
+        &self.janelas_de_venda
+    }
+
+    pub fn estacao_id(&self) -> Option<usize> {
+        self.estacao_id
+    }
+
+    pub fn loja_id(&self) -> usize {
+        self.loja_id
+    }
+
+    // `momento` no horário local da loja
+    pub fn disponivel_em(&self, momento: &NaiveDateTime) -> bool {
+        disponivel_em(&self.janelas_de_venda, momento)
+    }
+
     // Setters
     pub fn set_nome(&mut self, nome: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(nome.clone())?;
+        assertion_concern::assert_argument_not_empty("nome", nome.clone())?;
         self.nome = nome;
         Ok(())
     }
@@ -123,7 +205,7 @@ impl Produto {
     }
 
     pub fn set_descricao(&mut self, descricao: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(descricao.clone())?;
+        assertion_concern::assert_argument_not_empty("descricao", descricao.clone())?;
         self.descricao = descricao;
         Ok(())
     }
@@ -133,29 +215,121 @@ impl Produto {
     }
 
     pub fn set_preco(&mut self, preco: f64) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_negative(preco.clone())?;
+        assertion_concern::assert_argument_not_negative("preco", preco.clone())?;
         self.preco = preco;
         Ok(())
     }
 
+    // Ingredientes que saem da lista levam junto os seus alérgenos
     pub fn set_ingredientes(&mut self, ingredientes: Ingredientes) {
+        let nomes = ingredientes.to_vec_string();
+        self.alergenos_por_ingrediente
+            .retain(|ingrediente, _| nomes.contains(ingrediente));
+        self.alergenos = alergenos_do_produto(&self.alergenos_por_ingrediente);
         self.ingredientes = ingredientes;
     }
 
-    pub fn set_data_atualizacao(&mut self, data_atualizacao: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_timestamp_format(data_atualizacao.clone())?;
-        self.data_atualizacao = data_atualizacao;
+    pub fn set_alergenos_por_ingrediente(
+        &mut self,
+        alergenos_por_ingrediente: AlergenosPorIngrediente,
+    ) -> Result<(), DomainError> {
+        let nomes = self.ingredientes.to_vec_string();
+        if let Some(ingrediente) = alergenos_por_ingrediente
+            .keys()
+            .find(|ingrediente| !nomes.contains(ingrediente))
+        {
+            return Err(DomainError::validation(
+                "alergenos_por_ingrediente",
+                format!("Ingrediente {} não está na lista de ingredientes", ingrediente),
+            ));
+        }
+        self.alergenos = alergenos_do_produto(&alergenos_por_ingrediente);
+        self.alergenos_por_ingrediente = alergenos_por_ingrediente;
+        Ok(())
+    }
+
+    pub fn set_informacao_nutricional(
+        &mut self,
+        informacao_nutricional: Option<InformacaoNutricional>,
+    ) -> Result<(), DomainError> {
+        if let Some(informacao_nutricional) = &informacao_nutricional {
+            informacao_nutricional.validate_entity()?;
+        }
+        self.informacao_nutricional = informacao_nutricional;
+        Ok(())
+    }
+
+    pub fn set_janelas_de_venda(&mut self, janelas_de_venda: Vec<JanelaDeVenda>) -> Result<(), DomainError> {
+        for janela in &janelas_de_venda {
+            janela.validate_entity()?;
+        }
+        self.janelas_de_venda = janelas_de_venda;
         Ok(())
     }
+
+    // Produto personalizado sem os ingredientes indicados, com os alérgenos recalculados
+    pub fn sem_ingredientes(mut self, removidos: &[String]) -> Result<Produto, DomainError> {
+        let nomes = self.ingredientes.to_vec_string();
+        if let Some(ingrediente) = removidos.iter().find(|ingrediente| !nomes.contains(ingrediente)) {
+            return Err(DomainError::validation(
+                "sem_ingrediente",
+                format!("Ingrediente {} não faz parte do produto", ingrediente),
+            ));
+        }
+        let restantes = nomes
+            .into_iter()
+            .filter(|ingrediente| !removidos.contains(ingrediente))
+            .collect();
+        self.set_ingredientes(Ingredientes::new(restantes)?);
+        Ok(self)
+    }
+
+    pub fn set_data_atualizacao(&mut self, data_atualizacao: DateTime<Utc>) {
+        self.data_atualizacao = data_atualizacao;
+    }
+
+    pub fn set_versao(&mut self, versao: usize) {
+        self.versao = versao;
+    }
+
+    pub fn set_estacao_id(&mut self, estacao_id: Option<usize>) {
+        self.estacao_id = estacao_id;
+    }
+
+    pub fn set_loja_id(&mut self, loja_id: usize) {
+        self.loja_id = loja_id;
+    }
+
+    // Produto com o preço vigente em `data`, ou seja, o que foi cobrado num pedido feito nessa data
+    pub fn no_preco_de(mut self, historico: &[PrecoHistorico], data: &DateTime<Utc>) -> Produto {
+        if let Some(preco) = preco_em(historico, self.id, data) {
+            self.preco = preco;
+        }
+        self
+    }
+
+    // Arquivar de novo mantém a data original
+    pub fn arquivar(&mut self, data_arquivamento: DateTime<Utc>) {
+        if !self.arquivado {
+            self.arquivado = true;
+            self.data_arquivamento = Some(data_arquivamento);
+        }
+    }
+
+    pub fn restaurar(&mut self) {
+        self.arquivado = false;
+        self.data_arquivamento = None;
+    }
 }
 
 // Unit Tests
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Duration;
 
     fn create_valid_produto() -> Produto {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         Produto::new(
             1,
             "Cheeseburger".to_string(),
@@ -169,7 +343,7 @@ mod tests {
                 "Queijo".to_string(),
             ])
             .unwrap(),
-            _now.clone(),
+            _now,
             _now,
         )
     }
@@ -183,6 +357,7 @@ mod tests {
         assert_eq!(produto.descricao(), "O clássico pão, carne e queijo!");
         assert_eq!(produto.categoria(), &Categoria::Lanche);
         assert_eq!(produto.preco(), 9.99);
+        assert_eq!(produto.versao(), 1);
     }
 
     #[test]
@@ -193,7 +368,7 @@ mod tests {
 
     #[test]
     fn test_produto_validate_entity_empty_nome() {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         let produto = Produto::new(
             1,
             "".to_string(),
@@ -207,20 +382,20 @@ mod tests {
                 "Queijo".to_string(),
             ])
             .unwrap(),
-            _now.clone(),
+            _now,
             _now,
         );
         let result = produto.validate_entity();
         assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
+            matches!(result, Err(DomainError::Validation { .. })),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
             result
         );
     }
 
     #[test]
     fn test_produto_validate_entity_negative_preco() {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         let produto = Produto::new(
             1,
             "Cheeseburger".to_string(),
@@ -234,13 +409,13 @@ mod tests {
                 "Queijo".to_string(),
             ])
             .unwrap(),
-            _now.clone(),
+            _now,
             _now,
         );
         let result = produto.validate_entity();
         assert!(
-            matches!(result, Err(DomainError::NonPositive)),
-            "Esperado Err(DomainError::NonPositive), obtido {:?}",
+            matches!(result, Err(DomainError::Validation { .. })),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
             result
         );
     }
@@ -268,13 +443,20 @@ mod tests {
         assert_eq!(produto.categoria(), &Categoria::Bebida);
     }
 
+    #[test]
+    fn test_produto_sobremesa_valida() {
+        let mut produto = create_valid_produto();
+        produto.set_categoria(Categoria::Sobremesa);
+        assert!(produto.validate_entity().is_ok());
+    }
+
     #[test]
     fn test_produto_set_nome_empty() {
         let mut produto = create_valid_produto();
         let result = produto.set_nome("".to_string());
         assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
+            matches!(result, Err(DomainError::Validation { .. })),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
             result
         );
     }
@@ -284,20 +466,63 @@ mod tests {
         let mut produto = create_valid_produto();
         let result = produto.set_preco(-1.0);
         assert!(
-            matches!(result, Err(DomainError::NonPositive)),
-            "Esperado Err(DomainError::NonPositive), obtido {:?}",
+            matches!(result, Err(DomainError::Validation { .. })),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
             result
         );
     }
 
     #[test]
-    fn test_produto_set_data_atualizacao_invalid_format() {
+    fn test_produto_set_data_atualizacao() {
         let mut produto = create_valid_produto();
-        let result = produto.set_data_atualizacao("18-02-2024".to_string());
-        assert!(
-            matches!(result, Err(DomainError::Invalid(_))),
-            "Esperado Err(DomainError::Invalid), obtido {:?}",
-            result
-        );
+        let data_atualizacao = *produto.data_criacao() + Duration::minutes(5);
+        produto.set_data_atualizacao(data_atualizacao);
+        assert_eq!(produto.data_atualizacao(), &data_atualizacao);
+        assert!(produto.data_atualizacao() > produto.data_criacao());
+    }
+
+    #[test]
+    fn test_produto_arquivar_e_restaurar() {
+        let mut produto = create_valid_produto();
+        let data_arquivamento = *produto.data_criacao() + Duration::minutes(5);
+        produto.arquivar(data_arquivamento);
+        produto.arquivar(data_arquivamento + Duration::minutes(5));
+        assert!(produto.arquivado());
+        assert_eq!(produto.data_arquivamento(), Some(&data_arquivamento));
+
+        produto.restaurar();
+        assert!(!produto.arquivado());
+        assert!(produto.data_arquivamento().is_none());
+    }
+
+    #[test]
+    fn test_produto_sem_ingrediente_recalcula_alergenos() {
+        let mut produto = create_valid_produto();
+        produto
+            .set_alergenos_por_ingrediente(AlergenosPorIngrediente::from([
+                ("Pão".to_string(), BTreeSet::from([Alergeno::Gluten])),
+                ("Queijo".to_string(), BTreeSet::from([Alergeno::Lactose])),
+            ]))
+            .unwrap();
+        assert_eq!(produto.alergenos(), &BTreeSet::from([Alergeno::Gluten, Alergeno::Lactose]));
+
+        let sem_queijo = produto.clone().sem_ingredientes(&["Queijo".to_string()]).unwrap();
+        assert_eq!(sem_queijo.alergenos(), &BTreeSet::from([Alergeno::Gluten]));
+        assert!(!sem_queijo.alergenos_por_ingrediente().contains_key("Queijo"));
+        assert_eq!(sem_queijo.ingredientes().to_vec_string(), vec!["Pão", "Hambúrguer"]);
+
+        let result = produto.sem_ingredientes(&["Picles".to_string()]);
+        assert!(matches!(result, Err(DomainError::Validation { .. })));
+    }
+
+    #[test]
+    fn test_produto_alergeno_de_ingrediente_inexistente() {
+        let mut produto = create_valid_produto();
+        let result = produto.set_alergenos_por_ingrediente(AlergenosPorIngrediente::from([(
+            "Amendoim".to_string(),
+            BTreeSet::from([Alergeno::Amendoim]),
+        )]));
+        assert!(matches!(result, Err(DomainError::Validation { .. })));
+        assert!(produto.alergenos().is_empty());
     }
 }
@@ -0,0 +1,174 @@
+use std::collections::BTreeMap;
+
+use chrono::{DateTime, Duration, Utc};
+use schemars::JsonSchema;
+use serde::{Deserialize, Serialize};
+
+use crate::entities::pedido::{Pedido, Status};
+
+// SLA da cozinha quando `sla_cozinha_minutos` não é configurado: tempo máximo entre o pedido
+// entrar na fila e ficar pronto
+pub const SLA_COZINHA_PADRAO_MINUTOS: u32 = 20;
+
+// Janela de pedidos prontos considerada nas médias quando nenhuma é informada
+pub const DIAS_TEMPOS_DE_PREPARO_PADRAO: i64 = 30;
+
+// Momento em que o pedido passou para `status`
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, PartialEq)]
+pub struct MudancaDeStatus {
+    status: Status,
+    data: DateTime<Utc>,
+}
+
+impl MudancaDeStatus {
+    pub fn new(status: Status, data: DateTime<Utc>) -> Self {
+        MudancaDeStatus { status, data }
+    }
+
+    // Getters
+    pub fn status(&self) -> &Status {
+        &self.status
+    }
+
+    pub fn data(&self) -> &DateTime<Utc> {
+        &self.data
+    }
+}
+
+// Médias de um produto nos pedidos que já ficaram prontos, para o planejamento da cozinha
+#[derive(Clone, Serialize, Deserialize, Debug, JsonSchema, PartialEq)]
+pub struct TempoDePreparo {
+    produto_id: usize,
+    pedidos: usize,
+    espera_media_segundos: f64,
+    preparo_medio_segundos: f64,
+}
+
+impl TempoDePreparo {
+    pub fn new(produto_id: usize, pedidos: usize, espera_media_segundos: f64, preparo_medio_segundos: f64) -> Self {
+        TempoDePreparo {
+            produto_id,
+            pedidos,
+            espera_media_segundos,
+            preparo_medio_segundos,
+        }
+    }
+
+    // Getters
+    pub fn produto_id(&self) -> &usize {
+        &self.produto_id
+    }
+
+    pub fn pedidos(&self) -> usize {
+        self.pedidos
+    }
+
+    pub fn espera_media(&self) -> Duration {
+        Duration::milliseconds((self.espera_media_segundos * 1000.0) as i64)
+    }
+
+    pub fn preparo_medio(&self) -> Duration {
+        Duration::milliseconds((self.preparo_medio_segundos * 1000.0) as i64)
+    }
+}
+
+// Médias por produto dos pedidos que ficaram prontos a partir de `desde`; um pedido conta
+// uma vez para cada item, e pedidos sem início de preparo registrado ficam de fora
+pub fn tempos_de_preparo_por_produto(pedidos: &[Pedido], desde: &DateTime<Utc>) -> Vec<TempoDePreparo> {
+    let mut somas: BTreeMap<usize, (usize, i64, i64)> = BTreeMap::new();
+    for pedido in pedidos {
+        let (Some(espera), Some(preparo), Some(pronto)) =
+            (pedido.tempo_de_espera(), pedido.tempo_de_preparo(), pedido.data_do_status(&Status::Pronto))
+        else {
+            continue;
+        };
+        if pronto < desde {
+            continue;
+        }
+        let itens = [pedido.lanche(), pedido.acompanhamento(), pedido.bebida()];
+        for produto in itens.into_iter().flatten() {
+            let soma = somas.entry(*produto.id()).or_insert((0, 0, 0));
+            soma.0 += 1;
+            soma.1 += espera.num_milliseconds();
+            soma.2 += preparo.num_milliseconds();
+        }
+    }
+    somas
+        .into_iter()
+        .map(|(produto_id, (pedidos, espera, preparo))| {
+            TempoDePreparo::new(
+                produto_id,
+                pedidos,
+                espera as f64 / pedidos as f64 / 1000.0,
+                preparo as f64 / pedidos as f64 / 1000.0,
+            )
+        })
+        .collect()
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::ingredientes::Ingredientes;
+    use crate::entities::produto::{Categoria, Produto};
+
+    fn produto(id: usize) -> Produto {
+        Produto::new(
+            id,
+            format!("Produto {}", id),
+            "foto.png".to_string(),
+            "descricao".to_string(),
+            Categoria::Lanche,
+            10.0,
+            Ingredientes::new(vec![]).unwrap(),
+            Utc::now(),
+            Utc::now(),
+        )
+    }
+
+    fn pedido_preparado(inicio: DateTime<Utc>, espera: i64, preparo: i64, bebida: Option<Produto>) -> Pedido {
+        let mut pedido = Pedido::new(
+            1,
+            None,
+            Some(produto(1)),
+            None,
+            bebida,
+            "Mercado Pago".to_string(),
+            Status::Pago,
+            inicio,
+            inicio,
+        );
+        pedido.registrar_status(Status::EmPreparacao, inicio + Duration::minutes(espera));
+        pedido.registrar_status(Status::Pronto, inicio + Duration::minutes(espera + preparo));
+        pedido
+    }
+
+    #[test]
+    fn test_tempos_de_preparo_por_produto() {
+        let inicio = Utc::now() - Duration::hours(2);
+        let pedidos = vec![
+            pedido_preparado(inicio, 2, 10, None),
+            pedido_preparado(inicio, 4, 20, Some(produto(4))),
+            Pedido::new(3, None, Some(produto(1)), None, None, "Mercado Pago".to_string(), Status::Pago, inicio, inicio),
+        ];
+
+        let tempos = tempos_de_preparo_por_produto(&pedidos, &(inicio - Duration::days(1)));
+
+        assert_eq!(tempos.len(), 2);
+        assert_eq!(tempos[0].produto_id(), &1);
+        assert_eq!(tempos[0].pedidos(), 2);
+        assert_eq!(tempos[0].espera_media(), Duration::minutes(3));
+        assert_eq!(tempos[0].preparo_medio(), Duration::minutes(15));
+        assert_eq!(tempos[1].produto_id(), &4);
+        assert_eq!(tempos[1].preparo_medio(), Duration::minutes(20));
+    }
+
+    #[test]
+    fn test_tempos_de_preparo_ignora_pedidos_prontos_antes_do_periodo() {
+        let inicio = Utc::now() - Duration::days(10);
+        let pedidos = vec![pedido_preparado(inicio, 2, 10, None)];
+
+        assert!(tempos_de_preparo_por_produto(&pedidos, &(Utc::now() - Duration::days(1))).is_empty());
+    }
+}
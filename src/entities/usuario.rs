@@ -1,4 +1,4 @@
-use chrono::Utc;
+use chrono::{DateTime, Utc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use std::fmt;
@@ -59,8 +59,12 @@ pub struct Usuario {
     senha: String,
     tipo: Tipo,
     status: Status,
-    data_criacao: String,
-    data_atualizacao: String,
+    data_criacao: DateTime<Utc>,
+    data_atualizacao: DateTime<Utc>,
+    // Lojas em que o usuário trabalha. Vazio só antes de gravar: o gateway vincula o
+    // usuário à loja em que ele foi cadastrado
+    #[serde(default)]
+    lojas: Vec<usize>,
 }
 
 impl Usuario {
@@ -72,8 +76,8 @@ impl Usuario {
         senha: String,
         tipo: Tipo,
         status: Status,
-        data_criacao: String,
-        data_atualizacao: String,
+        data_criacao: DateTime<Utc>,
+        data_atualizacao: DateTime<Utc>,
     ) -> Self {
         Usuario {
             id,
@@ -85,6 +89,7 @@ impl Usuario {
             status,
             data_criacao,
             data_atualizacao,
+            lojas: Vec::new(),
         }
     }
 
@@ -92,24 +97,18 @@ impl Usuario {
         match self.status {
             Status::Ativo | Status::Inativo => (),
             _ => {
-                return Err(DomainError::Invalid(
-                    "Status do Usuário é inválido".to_string(),
-                ))
+                return Err(DomainError::validation("status", "Status do Usuário é inválido"))
             }
         };
         match self.tipo {
             Tipo::Admin | Tipo::Cozinha => (),
             _ => {
-                return Err(DomainError::Invalid(
-                    "Tipo do Usuário é inválido".to_string(),
-                ))
+                return Err(DomainError::validation("tipo", "Tipo do Usuário é inválido"))
             }
         };
-        assertion_concern::assert_argument_not_empty(self.nome.clone())?;
-        assertion_concern::assert_argument_not_empty(self.email.clone())?;
-        assertion_concern::assert_argument_not_empty(self.senha.clone())?;
-        assertion_concern::assert_argument_timestamp_format(self.data_criacao.clone())?;
-        assertion_concern::assert_argument_timestamp_format(self.data_atualizacao.clone())?;
+        assertion_concern::assert_argument_not_empty("nome", self.nome.clone())?;
+        assertion_concern::assert_argument_not_empty("email", self.email.clone())?;
+        assertion_concern::assert_argument_not_empty("senha", self.senha.clone())?;
         Ok(())
     }
 
@@ -146,23 +145,31 @@ impl Usuario {
         &self.status
     }
 
-    pub fn data_criacao(&self) -> &String {
+    pub fn data_criacao(&self) -> &DateTime<Utc> {
         &self.data_criacao
     }
 
-    pub fn data_atualizacao(&self) -> &String {
+    pub fn data_atualizacao(&self) -> &DateTime<Utc> {
         &self.data_atualizacao
     }
 
+    pub fn lojas(&self) -> &Vec<usize> {
+        &self.lojas
+    }
+
+    pub fn trabalha_na_loja(&self, loja_id: usize) -> bool {
+        self.lojas.contains(&loja_id)
+    }
+
     // Setters
     pub fn set_nome(&mut self, nome: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(nome.clone())?;
+        assertion_concern::assert_argument_not_empty("nome", nome.clone())?;
         self.nome = nome;
         Ok(())
     }
 
     pub fn set_email(&mut self, email: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(email.clone())?;
+        assertion_concern::assert_argument_not_empty("email", email.clone())?;
         self.email = email;
         Ok(())
     }
@@ -172,7 +179,7 @@ impl Usuario {
     }
 
     pub fn set_senha(&mut self, senha: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_not_empty(senha.clone())?;
+        assertion_concern::assert_argument_not_empty("senha", senha.clone())?;
         self.senha = senha;
         Ok(())
     }
@@ -185,19 +192,24 @@ impl Usuario {
         self.status = status;
     }
 
-    pub fn set_data_atualizacao(&mut self, data_atualizacao: String) -> Result<(), DomainError> {
-        assertion_concern::assert_argument_timestamp_format(data_atualizacao.clone())?;
+    pub fn set_data_atualizacao(&mut self, data_atualizacao: DateTime<Utc>) {
         self.data_atualizacao = data_atualizacao;
-        Ok(())
+    }
+
+    pub fn set_lojas(&mut self, mut lojas: Vec<usize>) {
+        lojas.sort_unstable();
+        lojas.dedup();
+        self.lojas = lojas;
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use chrono::Duration;
 
     fn create_valid_usuario() -> Usuario {
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         Usuario::new(
             1,
             "Fulano da Silva".to_string(),
@@ -206,7 +218,7 @@ mod tests {
             "senha_segura".to_string(),
             Tipo::Admin,
             Status::Ativo,
-            _now.clone(),
+            _now,
             _now,
         )
     }
@@ -247,8 +259,8 @@ mod tests {
         let mut usuario = create_valid_usuario();
         let result = usuario.set_nome("".to_string());
         assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
+            matches!(result, Err(DomainError::Validation { .. })),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
             result
         );
     }
@@ -258,8 +270,8 @@ mod tests {
         let mut usuario = create_valid_usuario();
         let result = usuario.set_email("".to_string());
         assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
+            matches!(result, Err(DomainError::Validation { .. })),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
             result
         );
     }
@@ -269,20 +281,28 @@ mod tests {
         let mut usuario = create_valid_usuario();
         let result = usuario.set_senha("".to_string());
         assert!(
-            matches!(result, Err(DomainError::Empty)),
-            "Esperado Err(DomainError::Empty), obtido {:?}",
+            matches!(result, Err(DomainError::Validation { .. })),
+            "Esperado Err(DomainError::Validation), obtido {:?}",
             result
         );
     }
 
     #[test]
-    fn test_usuario_set_data_atualizacao_invalid_format() {
+    fn test_usuario_set_lojas_sem_repeticao() {
         let mut usuario = create_valid_usuario();
-        let result = usuario.set_data_atualizacao("18-02-2024".to_string());
-        assert!(
-            matches!(result, Err(DomainError::Invalid(_))),
-            "Esperado Err(DomainError::Invalid), obtido {:?}",
-            result
-        );
+        assert!(usuario.lojas().is_empty());
+        usuario.set_lojas(vec![3, 1, 3]);
+        assert_eq!(usuario.lojas(), &vec![1, 3]);
+        assert!(usuario.trabalha_na_loja(3));
+        assert!(!usuario.trabalha_na_loja(2));
+    }
+
+    #[test]
+    fn test_usuario_set_data_atualizacao() {
+        let mut usuario = create_valid_usuario();
+        let data_atualizacao = *usuario.data_criacao() + Duration::minutes(5);
+        usuario.set_data_atualizacao(data_atualizacao);
+        assert_eq!(usuario.data_atualizacao(), &data_atualizacao);
+        assert!(usuario.data_atualizacao() > usuario.data_criacao());
     }
 }
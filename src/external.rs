@@ -1,2 +1,3 @@
 pub mod pagamento;
-pub mod postgres;
\ No newline at end of file
+pub mod postgres;
+pub mod metrics;
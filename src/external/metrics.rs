@@ -0,0 +1,168 @@
+use std::sync::LazyLock;
+use std::time::Duration;
+
+use prometheus::{
+    Encoder, HistogramOpts, HistogramVec, IntCounterVec, IntGauge, IntGaugeVec, Opts, Registry,
+    TextEncoder,
+};
+
+use crate::entities::pedido::Status;
+
+// Registro próprio em vez do global do crate, para que /metrics exponha só as métricas da aplicação
+pub static REGISTRY: LazyLock<Registry> = LazyLock::new(Registry::new);
+
+pub static HTTP_REQUESTS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    registrar(IntCounterVec::new(
+        Opts::new("http_requests_total", "Requisições HTTP atendidas"),
+        &["method", "route", "status"],
+    ))
+});
+
+pub static HTTP_REQUEST_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    registrar(HistogramVec::new(
+        HistogramOpts::new(
+            "http_request_duration_seconds",
+            "Latência das requisições HTTP",
+        ),
+        &["method", "route"],
+    ))
+});
+
+pub static PEDIDOS_CRIADOS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    registrar(IntCounterVec::new(
+        Opts::new("pedidos_criados_total", "Pedidos criados por status inicial"),
+        &["status"],
+    ))
+});
+
+pub static PAGAMENTOS_TOTAL: LazyLock<IntCounterVec> = LazyLock::new(|| {
+    registrar(IntCounterVec::new(
+        Opts::new(
+            "pagamentos_total",
+            "Resultados de pagamento por método (aprovado, recusado ou falha)",
+        ),
+        &["metodo", "resultado"],
+    ))
+});
+
+pub static WEBHOOK_PAGAMENTO_DURATION: LazyLock<HistogramVec> = LazyLock::new(|| {
+    registrar(HistogramVec::new(
+        HistogramOpts::new(
+            "webhook_pagamento_duration_seconds",
+            "Tempo de processamento dos webhooks de pagamento",
+        ),
+        &["metodo"],
+    ))
+});
+
+pub static DB_POOL_CONEXOES: LazyLock<IntGaugeVec> = LazyLock::new(|| {
+    registrar(IntGaugeVec::new(
+        Opts::new("db_pool_conexoes", "Conexões do pool do Postgres por estado (aguardando conta requisições na fila)"),
+        &["estado"],
+    ))
+});
+
+pub static FILA_COZINHA_PEDIDOS: LazyLock<IntGauge> = LazyLock::new(|| {
+    registrar(IntGauge::new(
+        "fila_cozinha_pedidos",
+        "Pedidos na fila da cozinha na última consulta de pedidos novos",
+    ))
+});
+
+pub static FILA_COZINHA_ATRASADOS: LazyLock<IntGauge> = LazyLock::new(|| {
+    registrar(IntGauge::new(
+        "fila_cozinha_atrasados",
+        "Pedidos acima do SLA da cozinha na última consulta de pedidos novos",
+    ))
+});
+
+fn registrar<T: prometheus::core::Collector + Clone + 'static>(
+    metrica: Result<T, prometheus::Error>,
+) -> T {
+    let metrica = metrica.expect("definição de métrica inválida");
+    REGISTRY
+        .register(Box::new(metrica.clone()))
+        .expect("métrica registrada duas vezes");
+    metrica
+}
+
+pub fn registrar_requisicao(method: &str, route: &str, status: u16, duracao: Duration) {
+    HTTP_REQUESTS_TOTAL
+        .with_label_values(&[method, route, &status.to_string()])
+        .inc();
+    HTTP_REQUEST_DURATION
+        .with_label_values(&[method, route])
+        .observe(duracao.as_secs_f64());
+}
+
+pub fn registrar_pedido_criado(status: &Status) {
+    PEDIDOS_CRIADOS_TOTAL
+        .with_label_values(&[&status.to_string()])
+        .inc();
+}
+
+pub fn registrar_pagamento(metodo: &str, resultado: &str) {
+    PAGAMENTOS_TOTAL
+        .with_label_values(&[metodo, resultado])
+        .inc();
+}
+
+pub fn registrar_webhook_pagamento(metodo: &str, duracao: Duration) {
+    WEBHOOK_PAGAMENTO_DURATION
+        .with_label_values(&[metodo])
+        .observe(duracao.as_secs_f64());
+}
+
+pub fn registrar_pool(em_uso: usize, disponiveis: usize, aguardando: usize, maximo: usize) {
+    DB_POOL_CONEXOES.with_label_values(&["em_uso"]).set(em_uso as i64);
+    DB_POOL_CONEXOES
+        .with_label_values(&["disponivel"])
+        .set(disponiveis as i64);
+    DB_POOL_CONEXOES
+        .with_label_values(&["aguardando"])
+        .set(aguardando as i64);
+    DB_POOL_CONEXOES.with_label_values(&["maximo"]).set(maximo as i64);
+}
+
+pub fn registrar_fila_cozinha(tamanho: usize, atrasados: usize) {
+    FILA_COZINHA_PEDIDOS.set(tamanho as i64);
+    FILA_COZINHA_ATRASADOS.set(atrasados as i64);
+}
+
+// Formato texto de exposição do Prometheus (version=0.0.4)
+pub fn render() -> String {
+    let mut buffer = Vec::new();
+    TextEncoder::new()
+        .encode(&REGISTRY.gather(), &mut buffer)
+        .expect("falha ao serializar métricas");
+    String::from_utf8(buffer).expect("métricas não são UTF-8")
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_render_expoe_metricas_registradas() {
+        registrar_requisicao("GET", "/pedidos/<id>", 200, Duration::from_millis(12));
+        registrar_pedido_criado(&Status::Pendente);
+        registrar_pagamento("Mercado Pago", "aprovado");
+        registrar_webhook_pagamento("Mercado Pago", Duration::from_millis(30));
+        registrar_pool(2, 3, 0, 16);
+        registrar_fila_cozinha(4, 1);
+
+        let saida = render();
+
+        assert!(saida.contains(
+            "http_requests_total{method=\"GET\",route=\"/pedidos/<id>\",status=\"200\"}"
+        ));
+        assert!(saida.contains("http_request_duration_seconds_bucket"));
+        assert!(saida.contains("pedidos_criados_total{status=\"Pendente\"}"));
+        assert!(saida.contains("pagamentos_total{metodo=\"Mercado Pago\",resultado=\"aprovado\"}"));
+        assert!(saida.contains("webhook_pagamento_duration_seconds_count{metodo=\"Mercado Pago\"}"));
+        assert!(saida.contains("db_pool_conexoes{estado=\"maximo\"} 16"));
+        assert!(saida.contains("fila_cozinha_pedidos 4"));
+        assert!(saida.contains("fila_cozinha_atrasados 1"));
+    }
+}
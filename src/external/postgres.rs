@@ -1,58 +1,99 @@
-
-pub mod table;
+pub mod migrations;
 pub mod usuario;
 pub mod cliente;
 pub mod pedido;
 pub mod produto;
 pub mod pagamento;
+pub mod loja;
+pub mod estacao;
+
+use std::ops::Deref;
+use std::sync::Arc;
+use std::time::Duration;
+
+use deadpool_postgres::{Manager, ManagerConfig, Object, Pool, RecyclingMethod, Runtime};
+use tokio_postgres::error::SqlState;
+use tokio_postgres::{Client, NoTls};
+use tracing::error;
 
-use tokio_postgres::{NoTls, Error, Client};
-use tokio;
+use crate::base::domain_error::DomainError;
 
-use self::table::{Table, TablesNames};
-use self::usuario::get_usuario_table_columns;
-use self::cliente::get_cliente_table_columns;
-use self::produto::get_produto_table_columns;
-use self::pedido::get_pedido_table_columns;
-use self::pagamento::get_pagamento_table_columns;
 pub struct PgConnectionManager {
-  pub client: Client,
+  pub pool: Pool,
 }
 
 impl PgConnectionManager {
-  pub async fn new(db_url: String) -> Result<Self, Error> {
-    let (client, connection)  = tokio_postgres::connect(&db_url, NoTls).await?;
-    tokio::spawn(async move {
-        if let Err(e) = connection.await {
-            eprintln!("connection error: {}", e);
-        }
-    });
-
-    Ok(PgConnectionManager { client })
+  pub fn new(db_url: String, pool_size: usize) -> Result<Self, String> {
+    let pg_config: tokio_postgres::Config = db_url
+      .parse()
+      .map_err(|err: tokio_postgres::Error| err.to_string())?;
+
+    // Verified roda uma query antes de devolver a conexão, descartando as que caíram
+    let manager_config = ManagerConfig {
+      recycling_method: RecyclingMethod::Verified,
+    };
+    let manager = Manager::from_config(pg_config, NoTls, manager_config);
+
+    let pool = Pool::builder(manager)
+      .max_size(pool_size)
+      .runtime(Runtime::Tokio1)
+      .wait_timeout(Some(Duration::from_secs(5)))
+      .create_timeout(Some(Duration::from_secs(5)))
+      .recycle_timeout(Some(Duration::from_secs(5)))
+      .build()
+      .map_err(|err| err.to_string())?;
+
+    Ok(PgConnectionManager { pool })
+  }
+}
+
+pub async fn get_client(pool: &Pool) -> Result<Object, DomainError> {
+  pool.get().await.map_err(|err| {
+    error!(error = %err, "Erro ao obter conexão com o banco");
+    DomainError::unavailable("Banco de dados indisponível").com_causa(err)
+  })
+}
+
+// Violação de chave única vira conflito; qualquer outra falha do banco é de persistência
+impl From<tokio_postgres::Error> for DomainError {
+  fn from(err: tokio_postgres::Error) -> Self {
+    if err.code() == Some(&SqlState::UNIQUE_VIOLATION) {
+      DomainError::conflict("Registro", "Registro já existe").com_causa(err)
+    } else {
+      DomainError::persistence("Erro ao acessar o banco de dados").com_causa(err)
+    }
+  }
+}
+
+// Gateways que podem participar de uma transação usam o executor em vez do pool direto
+#[derive(Clone)]
+pub enum PgExecutor {
+  Pool(Pool),
+  Transacao(Arc<Object>),
+}
+
+// A conexão do pool fica em uma Box para as duas variantes terem o mesmo tamanho
+pub enum PgClient {
+  Pool(Box<Object>),
+  Transacao(Arc<Object>),
+}
+
+impl Deref for PgClient {
+  type Target = Client;
+
+  fn deref(&self) -> &Client {
+    match self {
+      PgClient::Pool(client) => client,
+      PgClient::Transacao(client) => client,
+    }
   }
 }
 
-pub fn get_tables() -> Vec<Table> {
-  vec![
-    Table {
-      name: TablesNames::Usuario,
-      columns: get_usuario_table_columns(),
-    },
-    Table {
-      name: TablesNames::Cliente,
-      columns: get_cliente_table_columns(),
-    },
-    Table {
-      name: TablesNames::Produto,
-      columns: get_produto_table_columns(),
-    },
-    Table {
-      name: TablesNames::Pedido,
-      columns: get_pedido_table_columns(),
-    },
-    Table {
-      name: TablesNames::Pagamento,
-      columns: get_pagamento_table_columns(),
-    },
-  ]
+impl PgExecutor {
+  pub async fn client(&self) -> Result<PgClient, DomainError> {
+    match self {
+      PgExecutor::Pool(pool) => Ok(PgClient::Pool(Box::new(get_client(pool).await?))),
+      PgExecutor::Transacao(client) => Ok(PgClient::Transacao(client.clone())),
+    }
+  }
 }
@@ -0,0 +1,29 @@
+use chrono::{DateTime, Utc};
+use postgres_from_row::FromRow;
+
+use crate::entities::estacao::Estacao;
+
+impl FromRow for Estacao {
+    fn from_row(row: &tokio_postgres::Row) -> Self {
+        let id: i32 = row.get("id");
+        let loja_id: i32 = row.get("loja_id");
+        let data_criacao: DateTime<Utc> = row.get("data_criacao");
+        let data_atualizacao: DateTime<Utc> = row.get("data_atualizacao");
+        let mut estacao = Estacao::new(id as usize, row.get("nome"), data_criacao, data_atualizacao);
+        estacao.set_loja_id(loja_id as usize);
+        estacao
+    }
+
+    fn try_from_row(row: &tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let id: i32 = row.try_get("id")?;
+        let loja_id: i32 = row.try_get("loja_id")?;
+        let mut estacao = Estacao::new(
+            id as usize,
+            row.try_get("nome")?,
+            row.try_get("data_criacao")?,
+            row.try_get("data_atualizacao")?,
+        );
+        estacao.set_loja_id(loja_id as usize);
+        Ok(estacao)
+    }
+}
@@ -0,0 +1,23 @@
+use chrono::{DateTime, Utc};
+use postgres_from_row::FromRow;
+
+use crate::entities::loja::Loja;
+
+impl FromRow for Loja {
+    fn from_row(row: &tokio_postgres::Row) -> Self {
+        let id: i32 = row.get("id");
+        let data_criacao: DateTime<Utc> = row.get("data_criacao");
+        let data_atualizacao: DateTime<Utc> = row.get("data_atualizacao");
+        Loja::new(id as usize, row.get("nome"), data_criacao, data_atualizacao)
+    }
+
+    fn try_from_row(row: &tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let id: i32 = row.try_get("id")?;
+        Ok(Loja::new(
+            id as usize,
+            row.try_get("nome")?,
+            row.try_get("data_criacao")?,
+            row.try_get("data_atualizacao")?,
+        ))
+    }
+}
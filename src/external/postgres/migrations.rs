@@ -0,0 +1,465 @@
+use std::fmt;
+
+use chrono::{DateTime, Utc};
+use sha2::{Digest, Sha256};
+use tokio_postgres::{Client, Transaction};
+use tracing::warn;
+
+// Chave do advisory lock usado para que duas instâncias não migrem ao mesmo tempo
+const MIGRATION_LOCK_KEY: i64 = 25_000_027;
+
+const LOCK_MIGRATIONS: &str = "SELECT pg_advisory_xact_lock($1)";
+const CREATE_SCHEMA_MIGRATIONS: &str = "CREATE TABLE IF NOT EXISTS schema_migrations (version BIGINT PRIMARY KEY, name TEXT NOT NULL, checksum TEXT NOT NULL, applied_at TIMESTAMPTZ NOT NULL DEFAULT CURRENT_TIMESTAMP)";
+const QUERY_SCHEMA_MIGRATIONS: &str = "SELECT version, name, checksum, applied_at FROM schema_migrations ORDER BY version ASC";
+const INSERT_SCHEMA_MIGRATION: &str = "INSERT INTO schema_migrations (version, name, checksum) VALUES ($1, $2, $3)";
+const DELETE_SCHEMA_MIGRATION: &str = "DELETE FROM schema_migrations WHERE version = $1";
+// Bancos criados pelo antigo script 0001_create_table.sql têm as tabelas, mas não o schema_migrations
+const QUERY_ESQUEMA_LEGADO: &str = "SELECT to_regclass('public.usuario') IS NOT NULL";
+const QUERY_BANCO_COM_DADOS: &str = "SELECT EXISTS (SELECT 1 FROM cliente) OR EXISTS (SELECT 1 FROM produto) OR EXISTS (SELECT 1 FROM pedido)";
+
+// Dados de demonstração para desenvolvimento; ficam fora da sequência de migrations
+// para nunca chegarem a um banco de produção
+pub const SEED_DEMO: &str = include_str!("../../../seeds/demo.sql");
+
+// Versões que já fizeram parte da sequência e foram retiradas; bancos que as aplicaram
+// continuam válidos. A 2 eram os dados de demonstração, hoje em `SEED_DEMO`
+const MIGRATIONS_REMOVIDAS: &[i64] = &[2];
+
+// Conteúdos anteriores de migrations que mudaram depois de publicadas; bancos que aplicaram
+// essas versões continuam válidos. A 6 também marcava alérgenos, hoje em `SEED_DEMO`
+const CHECKSUMS_ANTERIORES: &[(i64, &str)] = &[(
+    6,
+    "01c1853d6970512b024d6ff514a3bea3db4c03ffe6de046395e7dbe4e0dd0891",
+)];
+
+pub struct Migration {
+    pub version: i64,
+    pub name: &'static str,
+    pub up: &'static str,
+    pub down: &'static str,
+}
+
+impl Migration {
+    pub fn checksum(&self) -> String {
+        // Ignora CR para que o checkout no Windows não mude o checksum
+        let conteudo = self.up.replace('\r', "");
+        Sha256::digest(conteudo.as_bytes())
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect()
+    }
+}
+
+macro_rules! migration {
+    ($version:expr, $name:literal) => {
+        Migration {
+            version: $version,
+            name: $name,
+            up: include_str!(concat!("../../../migrations/", $name, ".up.sql")),
+            down: include_str!(concat!("../../../migrations/", $name, ".down.sql")),
+        }
+    };
+}
+
+pub const MIGRATIONS: &[Migration] = &[
+    migration!(1, "0001_create_table"),
+    migration!(3, "0003_timestamptz"),
+    migration!(4, "0004_produto_versao"),
+    migration!(5, "0005_produto_arquivado_e_precos"),
+    migration!(6, "0006_produto_alergenos"),
+    migration!(7, "0007_produto_janelas_de_venda"),
+    migration!(8, "0008_lojas"),
+    migration!(9, "0009_pedido_status_historico"),
+    migration!(10, "0010_estacoes"),
+];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct AppliedMigration {
+    pub version: i64,
+    pub name: String,
+    pub checksum: String,
+    pub applied_at: DateTime<Utc>,
+}
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub name: String,
+    pub applied_at: Option<DateTime<Utc>>,
+}
+
+#[derive(Debug)]
+pub enum MigrationError {
+    Database(tokio_postgres::Error),
+    ChecksumMismatch { version: i64, name: String },
+    Unknown { version: i64, name: String },
+    BaselineInvalido(String),
+}
+
+impl fmt::Display for MigrationError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MigrationError::Database(err) => write!(f, "Erro no banco de dados: {}", err),
+            MigrationError::ChecksumMismatch { version, name } => write!(
+                f,
+                "Migration {} ({}) foi alterada depois de aplicada",
+                version, name
+            ),
+            MigrationError::Unknown { version, name } => write!(
+                f,
+                "Migration {} ({}) está aplicada no banco mas não existe no binário",
+                version, name
+            ),
+            MigrationError::BaselineInvalido(msg) => write!(f, "Baseline inválido: {}", msg),
+        }
+    }
+}
+
+impl std::error::Error for MigrationError {}
+
+impl From<tokio_postgres::Error> for MigrationError {
+    fn from(err: tokio_postgres::Error) -> Self {
+        MigrationError::Database(err)
+    }
+}
+
+fn validate_applied(
+    migrations: &[Migration],
+    applied: &[AppliedMigration],
+) -> Result<(), MigrationError> {
+    for aplicada in applied.iter().filter(|a| !MIGRATIONS_REMOVIDAS.contains(&a.version)) {
+        match migrations.iter().find(|m| m.version == aplicada.version) {
+            Some(migration) if migration.checksum() == aplicada.checksum => {}
+            Some(migration)
+                if CHECKSUMS_ANTERIORES.contains(&(migration.version, aplicada.checksum.as_str())) => {}
+            Some(_) => {
+                return Err(MigrationError::ChecksumMismatch {
+                    version: aplicada.version,
+                    name: aplicada.name.clone(),
+                })
+            }
+            None => {
+                return Err(MigrationError::Unknown {
+                    version: aplicada.version,
+                    name: aplicada.name.clone(),
+                })
+            }
+        }
+    }
+    Ok(())
+}
+
+pub fn pending<'a>(
+    migrations: &'a [Migration],
+    applied: &[AppliedMigration],
+) -> Result<Vec<&'a Migration>, MigrationError> {
+    validate_applied(migrations, applied)?;
+    let mut pendentes: Vec<&Migration> = migrations
+        .iter()
+        .filter(|m| !applied.iter().any(|a| a.version == m.version))
+        .collect();
+    pendentes.sort_by_key(|m| m.version);
+    Ok(pendentes)
+}
+
+// Migrations até `version`, que um banco já existente tem sem que tenham sido registradas
+pub fn baseline<'a>(
+    migrations: &'a [Migration],
+    applied: &[AppliedMigration],
+    version: i64,
+) -> Result<Vec<&'a Migration>, MigrationError> {
+    if !applied.is_empty() {
+        return Err(MigrationError::BaselineInvalido(
+            "o banco já tem migrations registradas".to_string(),
+        ));
+    }
+    if !migrations.iter().any(|m| m.version == version) {
+        return Err(MigrationError::BaselineInvalido(format!(
+            "migration {} não existe",
+            version
+        )));
+    }
+    let mut anteriores: Vec<&Migration> = migrations.iter().filter(|m| m.version <= version).collect();
+    anteriores.sort_by_key(|m| m.version);
+    Ok(anteriores)
+}
+
+pub fn status(migrations: &[Migration], applied: &[AppliedMigration]) -> Vec<MigrationStatus> {
+    let mut status: Vec<MigrationStatus> = migrations
+        .iter()
+        .map(|m| MigrationStatus {
+            version: m.version,
+            name: m.name.to_string(),
+            applied_at: applied
+                .iter()
+                .find(|a| a.version == m.version)
+                .map(|a| a.applied_at),
+        })
+        .collect();
+    status.sort_by_key(|s| s.version);
+    status
+}
+
+pub struct Migrator {
+    migrations: &'static [Migration],
+}
+
+impl Migrator {
+    pub fn new() -> Self {
+        Migrator {
+            migrations: MIGRATIONS,
+        }
+    }
+
+    async fn applied(client: &impl tokio_postgres::GenericClient) -> Result<Vec<AppliedMigration>, MigrationError> {
+        let rows = client.query(QUERY_SCHEMA_MIGRATIONS, &[]).await?;
+        Ok(rows
+            .iter()
+            .map(|row| AppliedMigration {
+                version: row.get("version"),
+                name: row.get("name"),
+                checksum: row.get("checksum"),
+                applied_at: row.get("applied_at"),
+            })
+            .collect())
+    }
+
+    async fn registrar(transaction: &Transaction<'_>, migration: &Migration) -> Result<(), MigrationError> {
+        transaction
+            .execute(
+                INSERT_SCHEMA_MIGRATION,
+                &[&migration.version, &migration.name, &migration.checksum()],
+            )
+            .await?;
+        Ok(())
+    }
+
+    // Aplica todas as migrations pendentes numa única transação. Um banco criado pelo
+    // script antigo, sem schema_migrations, é adotado com a 0001 como já aplicada
+    pub async fn up(&self, client: &mut Client) -> Result<Vec<&'static Migration>, MigrationError> {
+        let transaction = client.transaction().await?;
+        transaction.execute(LOCK_MIGRATIONS, &[&MIGRATION_LOCK_KEY]).await?;
+        transaction.batch_execute(CREATE_SCHEMA_MIGRATIONS).await?;
+
+        let mut applied = Self::applied(&transaction).await?;
+        if applied.is_empty() {
+            let legado: bool = transaction.query_one(QUERY_ESQUEMA_LEGADO, &[]).await?.get(0);
+            if legado {
+                warn!("Banco sem schema_migrations com as tabelas da 0001; registrando a 0001 como aplicada");
+                for migration in baseline(self.migrations, &applied, 1)? {
+                    Self::registrar(&transaction, migration).await?;
+                }
+                applied = Self::applied(&transaction).await?;
+            }
+        }
+        let pendentes = pending(self.migrations, &applied)?;
+        for migration in pendentes.iter() {
+            transaction.batch_execute(migration.up).await?;
+            Self::registrar(&transaction, migration).await?;
+        }
+        transaction.commit().await?;
+        Ok(pendentes)
+    }
+
+    // Registra como aplicadas, sem executá-las, as migrations até `version` de um banco
+    // que já tem esse esquema mas nunca foi versionado
+    pub async fn baseline(&self, client: &mut Client, version: i64) -> Result<Vec<&'static Migration>, MigrationError> {
+        let transaction = client.transaction().await?;
+        transaction.execute(LOCK_MIGRATIONS, &[&MIGRATION_LOCK_KEY]).await?;
+        transaction.batch_execute(CREATE_SCHEMA_MIGRATIONS).await?;
+
+        let applied = Self::applied(&transaction).await?;
+        let registradas = baseline(self.migrations, &applied, version)?;
+        for migration in registradas.iter() {
+            Self::registrar(&transaction, migration).await?;
+        }
+        transaction.commit().await?;
+        Ok(registradas)
+    }
+
+    // Reverte apenas a última migration aplicada
+    pub async fn down(&self, client: &mut Client) -> Result<Option<&'static Migration>, MigrationError> {
+        let transaction = client.transaction().await?;
+        transaction.execute(LOCK_MIGRATIONS, &[&MIGRATION_LOCK_KEY]).await?;
+        transaction.batch_execute(CREATE_SCHEMA_MIGRATIONS).await?;
+
+        let applied = Self::applied(&transaction).await?;
+        validate_applied(self.migrations, &applied)?;
+        let ultima = match applied
+            .iter()
+            .filter(|a| !MIGRATIONS_REMOVIDAS.contains(&a.version))
+            .max_by_key(|a| a.version)
+        {
+            Some(ultima) => ultima,
+            None => return Ok(None),
+        };
+        let migration = self
+            .migrations
+            .iter()
+            .find(|m| m.version == ultima.version)
+            .expect("migration validada acima");
+
+        transaction.batch_execute(migration.down).await?;
+        transaction
+            .execute(DELETE_SCHEMA_MIGRATION, &[&migration.version])
+            .await?;
+        transaction.commit().await?;
+        Ok(Some(migration))
+    }
+
+    // Só insere os dados de demonstração num banco sem clientes, produtos nem pedidos,
+    // então pode rodar a cada início sem duplicar nada
+    pub async fn seed(&self, client: &mut Client) -> Result<bool, MigrationError> {
+        let transaction = client.transaction().await?;
+        transaction.execute(LOCK_MIGRATIONS, &[&MIGRATION_LOCK_KEY]).await?;
+        let com_dados: bool = transaction.query_one(QUERY_BANCO_COM_DADOS, &[]).await?.get(0);
+        if com_dados {
+            return Ok(false);
+        }
+        transaction.batch_execute(SEED_DEMO).await?;
+        transaction.commit().await?;
+        Ok(true)
+    }
+
+    pub async fn status(&self, client: &mut Client) -> Result<Vec<MigrationStatus>, MigrationError> {
+        client.batch_execute(CREATE_SCHEMA_MIGRATIONS).await?;
+        let applied = Self::applied(client).await?;
+        validate_applied(self.migrations, &applied)?;
+        Ok(status(self.migrations, &applied))
+    }
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn migration_aplicada(migration: &Migration) -> AppliedMigration {
+        AppliedMigration {
+            version: migration.version,
+            name: migration.name.to_string(),
+            checksum: migration.checksum(),
+            applied_at: Utc::now(),
+        }
+    }
+
+    #[test]
+    fn test_migrations_ordenadas_e_unicas() {
+        let versoes: Vec<i64> = MIGRATIONS.iter().map(|m| m.version).collect();
+        let mut ordenadas = versoes.clone();
+        ordenadas.sort();
+        ordenadas.dedup();
+        assert_eq!(versoes, ordenadas);
+    }
+
+    #[test]
+    fn test_migrations_tem_up_e_down() {
+        for migration in MIGRATIONS {
+            assert!(!migration.up.trim().is_empty(), "{} sem up", migration.name);
+            assert!(!migration.down.trim().is_empty(), "{} sem down", migration.name);
+        }
+    }
+
+    #[test]
+    fn test_checksum_ignora_crlf() {
+        let lf = Migration { version: 1, name: "lf", up: "SELECT 1;\nSELECT 2;\n", down: "" };
+        let crlf = Migration { version: 1, name: "crlf", up: "SELECT 1;\r\nSELECT 2;\r\n", down: "" };
+        assert_eq!(lf.checksum(), crlf.checksum());
+        assert_eq!(lf.checksum().len(), 64);
+    }
+
+    #[test]
+    fn test_pending_sem_migrations_aplicadas() {
+        let pendentes = pending(MIGRATIONS, &[]).unwrap();
+        assert_eq!(pendentes.len(), MIGRATIONS.len());
+        assert_eq!(pendentes[0].version, 1);
+    }
+
+    #[test]
+    fn test_pending_ignora_aplicadas() {
+        let applied = vec![migration_aplicada(&MIGRATIONS[0])];
+        let pendentes = pending(MIGRATIONS, &applied).unwrap();
+        assert_eq!(pendentes.len(), MIGRATIONS.len() - 1);
+        assert!(pendentes.iter().all(|m| m.version != MIGRATIONS[0].version));
+    }
+
+    #[test]
+    fn test_pending_checksum_diferente() {
+        let mut aplicada = migration_aplicada(&MIGRATIONS[0]);
+        aplicada.checksum = "alterado".to_string();
+        let result = pending(MIGRATIONS, &[aplicada]);
+        assert!(matches!(
+            result,
+            Err(MigrationError::ChecksumMismatch { version: 1, .. })
+        ));
+    }
+
+    #[test]
+    fn test_pending_migration_desconhecida() {
+        let aplicada = AppliedMigration {
+            version: 999,
+            name: "9999_inexistente".to_string(),
+            checksum: String::new(),
+            applied_at: Utc::now(),
+        };
+        let result = pending(MIGRATIONS, &[aplicada]);
+        assert!(matches!(result, Err(MigrationError::Unknown { version: 999, .. })));
+    }
+
+    #[test]
+    fn test_pending_aceita_migration_removida() {
+        let removida = AppliedMigration {
+            version: 2,
+            name: "0002_insert_basic".to_string(),
+            checksum: "qualquer".to_string(),
+            applied_at: Utc::now(),
+        };
+        let applied = vec![migration_aplicada(&MIGRATIONS[0]), removida];
+        let pendentes = pending(MIGRATIONS, &applied).unwrap();
+        assert_eq!(pendentes.len(), MIGRATIONS.len() - 1);
+    }
+
+    #[test]
+    fn test_pending_aceita_checksum_anterior() {
+        let mut aplicadas: Vec<AppliedMigration> = MIGRATIONS
+            .iter()
+            .filter(|m| m.version <= 6)
+            .map(migration_aplicada)
+            .collect();
+        let (versao, checksum) = CHECKSUMS_ANTERIORES[0];
+        aplicadas.iter_mut().find(|a| a.version == versao).unwrap().checksum = checksum.to_string();
+        assert!(pending(MIGRATIONS, &aplicadas).is_ok());
+
+        // O checksum anterior só vale para a própria versão
+        aplicadas.iter_mut().find(|a| a.version == 5).unwrap().checksum = checksum.to_string();
+        assert!(matches!(pending(MIGRATIONS, &aplicadas), Err(MigrationError::ChecksumMismatch { .. })));
+    }
+
+    #[test]
+    fn test_seed_fora_das_migrations() {
+        assert!(MIGRATIONS.iter().all(|m| !m.up.contains("INSERT INTO cliente")));
+        assert!(SEED_DEMO.contains("INSERT INTO cliente"));
+    }
+
+    #[test]
+    fn test_baseline_registra_ate_a_versao() {
+        let registradas = baseline(MIGRATIONS, &[], 3).unwrap();
+        assert_eq!(registradas.iter().map(|m| m.version).collect::<Vec<_>>(), vec![1, 3]);
+    }
+
+    #[test]
+    fn test_baseline_recusa_banco_versionado_ou_versao_inexistente() {
+        let applied = vec![migration_aplicada(&MIGRATIONS[0])];
+        assert!(matches!(baseline(MIGRATIONS, &applied, 3), Err(MigrationError::BaselineInvalido(_))));
+        assert!(matches!(baseline(MIGRATIONS, &[], 2), Err(MigrationError::BaselineInvalido(_))));
+    }
+
+    #[test]
+    fn test_status() {
+        let applied = vec![migration_aplicada(&MIGRATIONS[0])];
+        let status = status(MIGRATIONS, &applied);
+        assert_eq!(status.len(), MIGRATIONS.len());
+        assert!(status[0].applied_at.is_some());
+        assert!(status[1..].iter().all(|s| s.applied_at.is_none()));
+    }
+}
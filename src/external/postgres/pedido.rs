@@ -2,91 +2,12 @@ use chrono::{DateTime, Utc};
 use schemars::JsonSchema;
 use serde::{Deserialize, Serialize};
 use postgres_from_row::FromRow;
-use std::collections::HashMap;
 
+use crate::base::domain_error::DomainError;
+use crate::entities::estacao::StatusDoTicket;
 use crate::entities::pedido::Status;
+use crate::entities::tempo_de_preparo::{MudancaDeStatus, TempoDePreparo};
 
-use super::table::{ColumnDefault, ColumnNullable, ColumnTypes};
-
-pub fn get_pedido_table_columns() -> HashMap<String, (ColumnTypes, ColumnNullable, ColumnDefault)>
-{
-    let mut columns = HashMap::new();
-    columns.insert(
-        "id".to_string(),
-        (
-            ColumnTypes::Index,
-            ColumnNullable(false),
-            ColumnDefault(None),
-        ),
-    );
-    columns.insert(
-        "cliente_id".to_string(),
-        (
-            ColumnTypes::Integer,
-            ColumnNullable(true),
-            ColumnDefault(None),
-        ),
-    );
-    columns.insert(
-        "lanche_id".to_string(),
-        (
-            ColumnTypes::Integer,
-            ColumnNullable(true),
-            ColumnDefault(None),
-        ),
-    );
-    columns.insert(
-        "acompanhamento_id".to_string(),
-        (
-            ColumnTypes::Integer,
-            ColumnNullable(true),
-            ColumnDefault(None),
-        ),
-    );
-    columns.insert(
-        "bebida_id".to_string(),
-        (
-            ColumnTypes::Integer,
-            ColumnNullable(true),
-            ColumnDefault(None),
-        ),
-    );
-    columns.insert(
-        "pagamento".to_string(),
-        (
-            ColumnTypes::Text,
-            ColumnNullable(true),
-            ColumnDefault(None),
-        ),
-    );
-    columns.insert(
-        "status".to_string(),
-        (
-            ColumnTypes::Text,
-            ColumnNullable(false),
-            ColumnDefault(None),
-        ),
-    );
-
-    columns.insert(
-        "data_criacao".to_string(),
-        (
-            ColumnTypes::Timestamp,
-            ColumnNullable(true),
-            ColumnDefault(Some("CURRENT_TIMESTAMP".to_string())),
-        ),
-    );
-    columns.insert(
-        "data_atualizacao".to_string(),
-        (
-            ColumnTypes::Timestamp,
-            ColumnNullable(true),
-            ColumnDefault(Some("CURRENT_TIMESTAMP".to_string())),
-        ),
-    );
-
-    columns
-}
 
 #[derive(Clone, Serialize, Deserialize, Debug, JsonSchema)]
 pub struct ProxyPedido {
@@ -97,8 +18,8 @@ pub struct ProxyPedido {
     bebida_id: Option<usize>,
     pagamento: String,
     status: Status,
-    data_criacao: String,
-    data_atualizacao: String,
+    data_criacao: DateTime<Utc>,
+    data_atualizacao: DateTime<Utc>,
 }
 
 impl ProxyPedido {
@@ -110,8 +31,8 @@ impl ProxyPedido {
         bebida_id: Option<usize>,
         pagamento: String,
         status: Status,
-        data_criacao: String,
-        data_atualizacao: String,
+        data_criacao: DateTime<Utc>,
+        data_atualizacao: DateTime<Utc>,
     ) -> Self {
         ProxyPedido {
             id,
@@ -153,11 +74,11 @@ impl ProxyPedido {
         &self.status
     }
 
-    pub fn data_criacao(&self) -> &String {
+    pub fn data_criacao(&self) -> &DateTime<Utc> {
         &self.data_criacao
     }
 
-    pub fn data_atualizacao(&self) -> &String {
+    pub fn data_atualizacao(&self) -> &DateTime<Utc> {
         &self.data_atualizacao
     }
 }
@@ -170,10 +91,8 @@ impl FromRow for ProxyPedido {
         let acompanhamento_id: Option<i32> = row.get("acompanhamento_id");
         let bebida_id: Option<i32> = row.get("bebida_id");
         
-        let data_criacao: std::time::SystemTime = row.get("data_criacao");
-        let data_criacao: DateTime<Utc> = data_criacao.into();
-        let data_atualizacao: std::time::SystemTime = row.get("data_atualizacao");
-        let data_atualizacao: DateTime<Utc> = data_atualizacao.into();
+        let data_criacao: DateTime<Utc> = row.get("data_criacao");
+        let data_atualizacao: DateTime<Utc> = row.get("data_atualizacao");
         
 ProxyPedido::new(
             id as usize,
@@ -183,8 +102,8 @@ ProxyPedido::new(
             bebida_id.map(|id| id as usize),
             row.get("pagamento"),
             row.get::<_, &str>("status").parse::<Status>().unwrap(),
-            data_criacao.format("%Y-%m-%d %H:%M:%S%.3f%z").to_string(),
-            data_atualizacao.format("%Y-%m-%d %H:%M:%S%.3f%z").to_string(),
+            data_criacao,
+            data_atualizacao,
         )
     }
 
@@ -195,10 +114,8 @@ ProxyPedido::new(
         let acompanhamento_id: Option<i32> = row.try_get("acompanhamento_id")?;
         let bebida_id: Option<i32> = row.try_get("bebida_id")?;
     
-        let data_criacao: std::time::SystemTime = row.try_get("data_criacao")?;
-        let data_criacao: DateTime<Utc> = data_criacao.into();
-        let data_atualizacao: std::time::SystemTime = row.try_get("data_atualizacao")?;
-        let data_atualizacao: DateTime<Utc> = data_atualizacao.into();
+        let data_criacao: DateTime<Utc> = row.try_get("data_criacao")?;
+        let data_atualizacao: DateTime<Utc> = row.try_get("data_atualizacao")?;
     
         Ok(ProxyPedido::new(
             id as usize,
@@ -208,8 +125,55 @@ ProxyPedido::new(
             bebida_id.map(|id| id as usize),
             row.try_get("pagamento")?,
             row.try_get::<_, &str>("status")?.parse::<Status>().unwrap(),
-            data_criacao.format("%Y-%m-%d %H:%M:%S%.3f%z").to_string(),
-            data_atualizacao.format("%Y-%m-%d %H:%M:%S%.3f%z").to_string(),
+            data_criacao,
+            data_atualizacao,
+        ))
+    }
+}
+
+// Linha de pedido_status com o pedido a que pertence
+pub fn mudanca_de_status_from_row(row: &tokio_postgres::Row) -> Result<(usize, MudancaDeStatus), DomainError> {
+    let pedido_id: i32 = row.try_get("pedido_id")?;
+    let status: &str = row.try_get("status")?;
+    let status = status
+        .parse::<Status>()
+        .map_err(|_| DomainError::persistence(format!("Status de pedido inválido no histórico: {}", status)))?;
+    Ok((pedido_id as usize, MudancaDeStatus::new(status, row.try_get("data")?)))
+}
+
+// Linha de pedido_estacao: pedido, estação, status do ticket e quando mudou
+pub fn status_do_ticket_from_row(
+    row: &tokio_postgres::Row,
+) -> Result<(usize, usize, StatusDoTicket, DateTime<Utc>), DomainError> {
+    let pedido_id: i32 = row.try_get("pedido_id")?;
+    let estacao_id: i32 = row.try_get("estacao_id")?;
+    let status: &str = row.try_get("status")?;
+    let status = status
+        .parse::<StatusDoTicket>()
+        .map_err(|_| DomainError::persistence(format!("Status de ticket inválido: {}", status)))?;
+    Ok((pedido_id as usize, estacao_id as usize, status, row.try_get("data_atualizacao")?))
+}
+
+impl FromRow for TempoDePreparo {
+    fn from_row(row: &tokio_postgres::Row) -> Self {
+        let produto_id: Option<i32> = row.get("produto_id");
+        let pedidos: i64 = row.get("pedidos");
+        TempoDePreparo::new(
+            produto_id.unwrap_or_default() as usize,
+            pedidos as usize,
+            row.get("espera_media_segundos"),
+            row.get("preparo_medio_segundos"),
+        )
+    }
+
+    fn try_from_row(row: &tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let produto_id: Option<i32> = row.try_get("produto_id")?;
+        let pedidos: i64 = row.try_get("pedidos")?;
+        Ok(TempoDePreparo::new(
+            produto_id.unwrap_or_default() as usize,
+            pedidos as usize,
+            row.try_get("espera_media_segundos")?,
+            row.try_get("preparo_medio_segundos")?,
         ))
     }
 }
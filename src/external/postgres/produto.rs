@@ -1,92 +1,16 @@
 use chrono::{DateTime, NaiveDateTime, TimeZone, Utc};
 use postgres_from_row::FromRow;
-use std::collections::HashMap;
+use tokio_postgres::types::Json;
 use std::time::SystemTime;
 
+use crate::entities::alergeno::AlergenosPorIngrediente;
+use crate::entities::informacao_nutricional::InformacaoNutricional;
+use crate::entities::janela_de_venda::JanelaDeVenda;
 use crate::entities::produto::Categoria;
 use crate::entities::produto::Produto;
 use crate::entities::ingredientes::Ingredientes;
+use crate::entities::preco_historico::PrecoHistorico;
 
-use super::table::{ColumnDefault, ColumnNullable, ColumnTypes};
-
-pub fn get_produto_table_columns() -> HashMap<String, (ColumnTypes, ColumnNullable, ColumnDefault)>
-{
-    let mut columns = HashMap::new();
-    columns.insert(
-        "id".to_string(),
-        (
-            ColumnTypes::Index,
-            ColumnNullable(false),
-            ColumnDefault(None),
-        ),
-    );
-    columns.insert(
-        "nome".to_string(),
-        (
-            ColumnTypes::Text,
-            ColumnNullable(false),
-            ColumnDefault(None),
-        ),
-    );
-    columns.insert(
-        "foto".to_string(),
-        (
-            ColumnTypes::Text,
-            ColumnNullable(false),
-            ColumnDefault(None),
-        ),
-    );
-    columns.insert(
-        "descricao".to_string(),
-        (
-            ColumnTypes::Text,
-            ColumnNullable(false),
-            ColumnDefault(None),
-        ),
-    );
-    columns.insert(
-        "categoria".to_string(),
-        (
-            ColumnTypes::Text,
-            ColumnNullable(false),
-            ColumnDefault(None),
-        ),
-    );
-    columns.insert(
-        "preco".to_string(),
-        (
-            ColumnTypes::Float,
-            ColumnNullable(false),
-            ColumnDefault(None),
-        ),
-    );
-    columns.insert(
-        "ingredientes".to_string(),
-        (
-            ColumnTypes::Text,
-            ColumnNullable(false),
-            ColumnDefault(None),
-        ),
-    );
-    columns.insert(
-        "data_criacao".to_string(),
-        (
-            ColumnTypes::Timestamp,
-            ColumnNullable(true),
-            ColumnDefault(Some("CURRENT_TIMESTAMP".to_string())),
-        ),
-    );
-    columns.insert(
-        "data_atualizacao".to_string(),
-        (
-            ColumnTypes::Timestamp,
-            ColumnNullable(true),
-            ColumnDefault(Some("CURRENT_TIMESTAMP".to_string())),
-        ),
-    );
-
-    columns
-}
 
 impl FromRow for Produto {
     fn from_row(row: &tokio_postgres::Row) -> Self {
@@ -98,12 +22,10 @@ impl FromRow for Produto {
             Ok(ing) => ing,
             Err(e) => panic!("Failed to create Ingredientes: {:?}", e),
         };
-        let data_criacao: std::time::SystemTime = row.get("data_criacao");
-        let data_criacao: DateTime<Utc> = data_criacao.into();
-        let data_atualizacao: std::time::SystemTime = row.get("data_atualizacao");
-        let data_atualizacao: DateTime<Utc> = data_atualizacao.into();
+        let data_criacao: DateTime<Utc> = row.get("data_criacao");
+        let data_atualizacao: DateTime<Utc> = row.get("data_atualizacao");
 
-        Produto::new(
+        let mut produto = Produto::new(
             id as usize,
             row.get("nome"),
             row.get("foto"),
@@ -111,11 +33,28 @@ impl FromRow for Produto {
             row.get::<_, &str>("categoria").parse::<Categoria>().unwrap(),
             preco,
             ingredientes,
-            data_criacao.format("%Y-%m-%d %H:%M:%S%.3f%z").to_string(),
-            data_atualizacao
-                .format("%Y-%m-%d %H:%M:%S%.3f%z")
-                .to_string(),
-        )
+            data_criacao,
+            data_atualizacao,
+        );
+        produto.set_versao(row.get::<_, i32>("versao") as usize);
+        if row.get::<_, bool>("arquivado") {
+            produto.arquivar(row.get::<_, Option<DateTime<Utc>>>("data_arquivamento").unwrap_or(data_atualizacao));
+        }
+        let alergenos: Json<AlergenosPorIngrediente> = row.get("alergenos");
+        if let Err(e) = produto.set_alergenos_por_ingrediente(alergenos.0) {
+            panic!("Failed to set alergenos: {:?}", e);
+        }
+        let informacao_nutricional: Option<Json<InformacaoNutricional>> = row.get("informacao_nutricional");
+        if let Err(e) = produto.set_informacao_nutricional(informacao_nutricional.map(|json| json.0)) {
+            panic!("Failed to set informacao_nutricional: {:?}", e);
+        }
+        let janelas_de_venda: Json<Vec<JanelaDeVenda>> = row.get("janelas_de_venda");
+        if let Err(e) = produto.set_janelas_de_venda(janelas_de_venda.0) {
+            panic!("Failed to set janelas_de_venda: {:?}", e);
+        }
+        produto.set_estacao_id(row.get::<_, Option<i32>>("estacao_id").map(|id| id as usize));
+        produto.set_loja_id(row.get::<_, i32>("loja_id") as usize);
+        produto
     }
 
     fn try_from_row(row: &tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
@@ -130,7 +69,7 @@ impl FromRow for Produto {
             Err(e) => panic!("Failed to create Ingredientes: {:?}", e),
         };
 
-        Ok(Produto::new(
+        let mut produto = Produto::new(
             id as usize,
             row.try_get("nome")?,
             row.try_get("foto")?,
@@ -140,6 +79,42 @@ impl FromRow for Produto {
             ingredientes,
             row.get("data_criacao"),
             row.get("data_atualizacao"),
+        );
+        produto.set_versao(row.try_get::<_, i32>("versao")? as usize);
+        if row.try_get::<_, bool>("arquivado")? {
+            let data_arquivamento: Option<DateTime<Utc>> = row.try_get("data_arquivamento")?;
+            produto.arquivar(data_arquivamento.unwrap_or(*produto.data_atualizacao()));
+        }
+        let alergenos: Json<AlergenosPorIngrediente> = row.try_get("alergenos")?;
+        if let Err(e) = produto.set_alergenos_por_ingrediente(alergenos.0) {
+            panic!("Failed to set alergenos: {:?}", e);
+        }
+        let informacao_nutricional: Option<Json<InformacaoNutricional>> = row.try_get("informacao_nutricional")?;
+        if let Err(e) = produto.set_informacao_nutricional(informacao_nutricional.map(|json| json.0)) {
+            panic!("Failed to set informacao_nutricional: {:?}", e);
+        }
+        let janelas_de_venda: Json<Vec<JanelaDeVenda>> = row.try_get("janelas_de_venda")?;
+        if let Err(e) = produto.set_janelas_de_venda(janelas_de_venda.0) {
+            panic!("Failed to set janelas_de_venda: {:?}", e);
+        }
+        produto.set_estacao_id(row.try_get::<_, Option<i32>>("estacao_id")?.map(|id| id as usize));
+        produto.set_loja_id(row.try_get::<_, i32>("loja_id")? as usize);
+        Ok(produto)
+    }
+}
+
+impl FromRow for PrecoHistorico {
+    fn from_row(row: &tokio_postgres::Row) -> Self {
+        let produto_id: i32 = row.get("produto_id");
+        PrecoHistorico::new(produto_id as usize, row.get("preco"), row.get("vigente_desde"))
+    }
+
+    fn try_from_row(row: &tokio_postgres::Row) -> Result<Self, tokio_postgres::Error> {
+        let produto_id: i32 = row.try_get("produto_id")?;
+        Ok(PrecoHistorico::new(
+            produto_id as usize,
+            row.try_get("preco")?,
+            row.try_get("vigente_desde")?,
         ))
     }
 }
@@ -1,5 +1,9 @@
 pub mod in_memory_usuario_gateway;
 pub mod postgres_usuario_gateway;
+pub mod in_memory_loja_gateway;
+pub mod postgres_loja_gateway;
+pub mod in_memory_estacao_gateway;
+pub mod postgres_estacao_gateway;
 pub mod in_memory_cliente_gateway;
 pub mod postgres_cliente_gateway;
 pub mod postgres_pedido_gateway;
@@ -8,4 +12,15 @@ pub mod in_memory_pagamento_gateway;
 pub mod postgres_pagamento_gateway;
 pub mod postgres_produto_gateway;
 pub mod in_memory_produto_gateway;
+pub mod in_memory_dados_demo;
 pub mod aws_cognito_gateway;
+pub mod in_memory_storage_gateway;
+pub mod local_storage_gateway;
+pub mod s3_storage_gateway;
+
+pub mod in_memory_unit_of_work;
+pub mod postgres_unit_of_work;
+pub mod sem_transacao_unit_of_work;
+
+#[cfg(test)]
+mod contrato_pedido_gateway;
@@ -3,11 +3,13 @@ use aws_config::meta::region::RegionProviderChain;
 use aws_sdk_cognitoidentityprovider::operation::list_users;
 use aws_sdk_cognitoidentityprovider::types::AttributeType;
 use aws_sdk_cognitoidentityprovider::{config::Region, meta::PKG_VERSION, Client};
-use chrono::Utc;
+use chrono::{DateTime, NaiveDateTime, Utc};
+use tracing::{error, info, warn};
 
 use crate::{
     base::domain_error::DomainError, entities::cliente::Cliente, entities::cpf::Cpf,
     traits::cliente_gateway::ClienteGateway,
+    traits::health_check_adapter::HealthCheckAdapter,
 };
 
 fn option_to_string(option: Option<&str>) -> String {
@@ -17,6 +19,21 @@ fn option_to_string(option: Option<&str>) -> String {
     }
 }
 
+// Usuários antigos foram gravados com "%Y-%m-%d %H:%M:%S%.3f%z"; os novos em RFC 3339.
+fn parse_data(value: &str) -> Result<DateTime<Utc>, DomainError> {
+    if let Ok(data) = DateTime::parse_from_rfc3339(value) {
+        return Ok(data.with_timezone(&Utc));
+    }
+    if let Ok(data) = DateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.3f%z") {
+        return Ok(data.with_timezone(&Utc));
+    }
+    NaiveDateTime::parse_from_str(value, "%Y-%m-%d %H:%M:%S%.3f")
+        .map(|data| data.and_utc())
+        .map_err(|err| {
+            DomainError::external_service("Cognito", format!("Data inválida: {:?}", value)).com_causa(err)
+        })
+}
+
 pub struct AwsCognitoRepository {
     client: Client,
     user_pool_id: String,
@@ -77,31 +94,38 @@ impl ClienteGateway for AwsCognitoRepository {
                         Ok(cpf) => {
                             match id.parse::<usize>() {
                                 Ok(id_value) => {
-                                    let cliente = Cliente::new(
-                                        id_value,
-                                        nome,
-                                        email,
-                                        cpf,
-                                        data_criacao,
-                                        data_atualizacao,
-                                    );
-                
-                                    clientes.push(cliente);
+                                    let datas = parse_data(&data_criacao)
+                                        .and_then(|criacao| Ok((criacao, parse_data(&data_atualizacao)?)));
+                                    match datas {
+                                        Ok((data_criacao, data_atualizacao)) => {
+                                            let cliente = Cliente::new(
+                                                id_value,
+                                                nome,
+                                                email,
+                                                cpf,
+                                                data_criacao,
+                                                data_atualizacao,
+                                            );
+
+                                            clientes.push(cliente);
+                                        }
+                                        Err(err) => warn!(id = %id, error = %err, "Data inválida para cliente do Cognito"),
+                                    }
                                 },
                                 Err(error) => {
-                                    println!("Failed to convert string, ID: {}", id);
+                                    warn!(id = %id, "Id de cliente inválido no Cognito");
                                 }
                             }
                             
                         },
-                        Err(err) => println!("Invalid CPF for user: {}", nome),
+                        Err(err) => warn!(id = %id, "CPF inválido para cliente do Cognito"),
                     };
                 }
                 Ok(clientes)
             }
             Err(err) => {
-                println!("Error during aws cognito request: {}", err);
-                Err(DomainError::NotFound)
+                error!(error = %err, "Erro na requisição ao AWS Cognito");
+                Err(DomainError::external_service("Cognito", "Erro ao listar clientes").com_causa(err))
             }
 
         }
@@ -113,7 +137,7 @@ impl ClienteGateway for AwsCognitoRepository {
         let clientes = match clientes_result {
             Ok(clientes) => clientes,
             Err(err) => {
-                println!("Error retrieving clientes");
+                error!("Erro ao buscar clientes");
                 return Err(err);
             }
         };
@@ -124,8 +148,7 @@ impl ClienteGateway for AwsCognitoRepository {
             }
         }
 
-        Err(DomainError::NotFound)
-
+        Err(DomainError::not_found("Cliente", cpf.0))
     }
 
     async fn get_cliente_by_id(&self, id: usize) -> Result<Cliente, DomainError> {
@@ -134,7 +157,7 @@ impl ClienteGateway for AwsCognitoRepository {
         let clientes = match clientes_result {
             Ok(clientes) => clientes,
             Err(err) => {
-                println!("Error retrieving clientes");
+                error!("Erro ao buscar clientes");
                 return Err(err);
             }
         };
@@ -146,11 +169,22 @@ impl ClienteGateway for AwsCognitoRepository {
             }
         }
 
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Cliente", id))
+    }
+
+    // Uma única listagem do user pool atende todos os ids
+    async fn get_clientes_by_ids(&self, ids: Vec<usize>) -> Result<Vec<Cliente>, DomainError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let clientes = self.get_clientes().await?;
+        Ok(clientes
+            .into_iter()
+            .filter(|cliente| ids.contains(cliente.id()))
+            .collect())
     }
 
-    async fn create_cliente(&mut self, cliente: Cliente) -> Result<Cliente, DomainError> {
-        println!("chegueeeei");
+    async fn create_cliente(&self, cliente: Cliente) -> Result<Cliente, DomainError> {
         // Convert the `Cliente` object into AWS Cognito attributes
 
         let cpf_string = &cliente.cpf().0;
@@ -159,14 +193,16 @@ impl ClienteGateway for AwsCognitoRepository {
 
         let id = cpf_string.replace(".", "").replace("-", "");
         let string_id: &str = &id;
+        let data_criacao = cliente.data_criacao().to_rfc3339();
+        let data_atualizacao = cliente.data_atualizacao().to_rfc3339();
         // List of attribute specifications
         let attribute_specs = vec![
             ("custom:id", string_id),
             ("custom:nome", cliente.nome()),
             ("custom:email", cliente.email()),
             ("custom:cpf", cpf_string),
-            ("custom:data_criacao", cliente.data_criacao()),
-            ("custom:data_atualizacao", cliente.data_atualizacao()),
+            ("custom:data_criacao", &data_criacao),
+            ("custom:data_atualizacao", &data_atualizacao),
         ];
 
         // Iterate over attribute specifications
@@ -182,7 +218,7 @@ impl ClienteGateway for AwsCognitoRepository {
                     attributes.push(attr);
                 },
                 Err(err) => {
-                    println!("Failed to build attribute {}: {}", name, err);
+                    error!(atributo = name, error = %err, "Falha ao montar atributo do Cognito");
                 }
             }
         }
@@ -198,18 +234,20 @@ impl ClienteGateway for AwsCognitoRepository {
 
         match response {
             Ok(resp) => {
-                println!("Successfully created user: {}", cliente.id());
+                info!(cliente_id = *cliente.id(), "Cliente criado no Cognito");
                 Ok(cliente)
             },
             Err(err) => {
-                println!("SDK ERROR: {}",err.to_string());
-                println!("Failed to create user: {}", cpf_string);
-                Err(DomainError::Invalid("Cliente".to_string()))
+                error!(cliente_id = *cliente.id(), error = %err, "Falha ao criar cliente no Cognito");
+                if err.as_service_error().is_some_and(|err| err.is_username_exists_exception()) {
+                    return Err(DomainError::conflict("Cliente", "CPF já cadastrado").com_causa(err));
+                }
+                Err(DomainError::external_service("Cognito", "Erro ao criar cliente").com_causa(err))
             }
         }
     }
 
-    async fn delete_cliente(&mut self, cpf: Cpf) -> Result<(), DomainError> {
+    async fn delete_cliente(&self, cpf: Cpf) -> Result<(), DomainError> {
         let cpf_string = cpf.0;
         let response = self.client
             .admin_delete_user()
@@ -223,9 +261,53 @@ impl ClienteGateway for AwsCognitoRepository {
                 Ok(())
             },
             Err(err) => {
-                println!("Failed to delete user: {}", cpf_string);
-                Err(DomainError::NotFound)
+                error!(error = %err, "Falha ao remover cliente do Cognito");
+                if err.as_service_error().is_some_and(|err| err.is_user_not_found_exception()) {
+                    return Err(DomainError::not_found("Cliente", cpf_string));
+                }
+                Err(DomainError::external_service("Cognito", "Erro ao remover cliente").com_causa(err))
             }
         }
     }
 }
+
+#[async_trait]
+impl HealthCheckAdapter for AwsCognitoRepository {
+    fn nome(&self) -> String {
+        "cognito".to_string()
+    }
+
+    fn critico(&self) -> bool {
+        true
+    }
+
+    async fn verificar(&self) -> Result<(), String> {
+        self.client
+            .describe_user_pool()
+            .user_pool_id(&self.user_pool_id)
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_data_formatos_aceitos() {
+        let esperada = DateTime::parse_from_rfc3339("2024-05-01T12:30:00Z").unwrap().with_timezone(&Utc);
+        assert_eq!(parse_data("2024-05-01T12:30:00Z").unwrap(), esperada);
+        assert_eq!(parse_data("2024-05-01 12:30:00.000+0000").unwrap(), esperada);
+        assert_eq!(parse_data("2024-05-01 12:30:00.000").unwrap(), esperada);
+    }
+
+    #[test]
+    fn test_parse_data_invalida_nao_vira_1970() {
+        let result = parse_data("ontem");
+        assert!(matches!(result, Err(DomainError::ExternalService { servico: "Cognito", .. })));
+    }
+}
@@ -0,0 +1,55 @@
+// Comportamento que todo PedidoGateway precisa ter, verificado contra cada implementação
+use std::collections::BTreeSet;
+
+use chrono::Utc;
+
+use crate::entities::fila_da_cozinha::na_fila_da_cozinha;
+use crate::entities::pedido::{Pedido, Status};
+use crate::traits::pedido_gateway::PedidoGateway;
+
+fn ids(pedidos: &[Pedido]) -> BTreeSet<usize> {
+    pedidos.iter().map(|pedido| *pedido.id()).collect()
+}
+
+// `repository` precisa estar em uma loja sem outros pedidos
+pub async fn verifica_fila_da_cozinha(repository: &dyn PedidoGateway) {
+    let status = [
+        Status::Pendente,
+        Status::Pago,
+        Status::EmPreparacao,
+        Status::Pronto,
+        Status::Finalizado,
+        Status::Cancelado,
+    ];
+    let mut na_fila = BTreeSet::new();
+    let mut nao_finalizados = BTreeSet::new();
+    for status in status {
+        let pedido = repository
+            .create_pedido(Pedido::new(
+                0,
+                None,
+                None,
+                None,
+                None,
+                "Mercado Pago".to_string(),
+                Status::Pendente,
+                Utc::now(),
+                Utc::now(),
+            ))
+            .await
+            .unwrap();
+        let id = *pedido.id();
+        if status != Status::Pendente {
+            repository.atualiza_status(id, status.clone()).await.unwrap();
+        }
+        if na_fila_da_cozinha(&status) {
+            na_fila.insert(id);
+        }
+        if status != Status::Finalizado {
+            nao_finalizados.insert(id);
+        }
+    }
+
+    assert_eq!(ids(&repository.get_pedidos_novos().await.unwrap()), na_fila);
+    assert_eq!(ids(&repository.lista_pedidos().await.unwrap()), nao_finalizados);
+}
@@ -1,33 +1,22 @@
-use chrono::Utc;
-use tokio::time::{sleep, Duration};
+use tokio::sync::Mutex;
+use tracing::debug;
 
 use crate::base::domain_error::DomainError;
 use crate::entities::cliente::Cliente;
 use crate::traits::cliente_gateway::ClienteGateway;
 use crate::entities::cpf::Cpf;
+use crate::gateways::in_memory_dados_demo;
 
-#[derive(Clone)]
 pub struct InMemoryClienteRepository {
-    _clientes: Vec<Cliente>,
+    _clientes: Mutex<Vec<Cliente>>,
 }
 
 impl InMemoryClienteRepository {
     pub fn new() -> Self {
-        let _id = 0;
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
-        let cliente = Cliente::new(
-            _id,
-            "Fulano da Silva".to_string(),
-            "fulano.silva@exemplo.com".to_string(),
-            Cpf::new("000.000.000-00".to_string()).unwrap(),
-            _now.clone(),
-            _now,
-        );
-
-        println!("Usando repositório em memória!");
+        debug!("Usando repositório em memória!");
 
         InMemoryClienteRepository {
-            _clientes: vec![cliente],
+            _clientes: Mutex::new(in_memory_dados_demo::clientes()),
         }
     }
 }
@@ -35,57 +24,68 @@ impl InMemoryClienteRepository {
 #[async_trait]
 impl ClienteGateway for InMemoryClienteRepository {
     async fn get_clientes(&self) -> Result<Vec<Cliente>, DomainError> {
-        let clientes = self._clientes.clone();
-        sleep(Duration::from_secs(1)).await;
+        let clientes = self._clientes.lock().await.clone();
         Ok(clientes)
     }
 
     async fn get_cliente_by_cpf(&self, cpf: Cpf) -> Result<Cliente, DomainError> {
-        sleep(Duration::from_secs(1)).await;
-        for cliente in &self._clientes {
+        for cliente in self._clientes.lock().await.iter() {
             if cliente.cpf().to_owned() == cpf {
                 return Ok(cliente.clone());
             }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Cliente", cpf.0))
     }
 
     async fn get_cliente_by_id(&self, id: usize) -> Result<Cliente, DomainError> {
         let id = id as i32;
-        sleep(Duration::from_secs(1)).await;
-        for cliente in &self._clientes {
+        for cliente in self._clientes.lock().await.iter() {
             if cliente.id().to_owned() == id as usize {
                 return Ok(cliente.clone());
             }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Cliente", id))
     }
 
-    async fn create_cliente(&mut self, cliente: Cliente) -> Result<Cliente, DomainError> {
-        sleep(Duration::from_secs(1)).await;
-        let existing_cliente = self.get_cliente_by_cpf(cliente.cpf().to_owned()).await;
+    async fn get_clientes_by_ids(&self, ids: Vec<usize>) -> Result<Vec<Cliente>, DomainError> {
+        Ok(self
+            ._clientes
+            .lock()
+            .await
+            .iter()
+            .filter(|cliente| ids.contains(cliente.id()))
+            .cloned()
+            .collect())
+    }
 
-        if existing_cliente.is_ok() {
-            return Err(DomainError::AlreadyExists);
+    async fn create_cliente(&self, cliente: Cliente) -> Result<Cliente, DomainError> {
+        let mut cliente_list = self._clientes.lock().await;
+        if cliente_list.iter().any(|existente| existente.cpf() == cliente.cpf()) {
+            return Err(DomainError::conflict("Cliente", "CPF já cadastrado"));
         }
 
-        let mut cliente_list = self._clientes.clone();
+        let cliente = Cliente::new(
+            in_memory_dados_demo::proximo_id(cliente_list.iter().map(Cliente::id)),
+            cliente.nome().clone(),
+            cliente.email().clone(),
+            cliente.cpf().clone(),
+            *cliente.data_criacao(),
+            *cliente.data_atualizacao(),
+        );
         cliente_list.push(cliente.clone());
 
-        self._clientes = cliente_list;
-
-        Ok(cliente.clone())
+        Ok(cliente)
     }
 
-    async fn delete_cliente(&mut self, cpf: Cpf) -> Result<(), DomainError> {
-        let cliente_list = &mut self._clientes;
+    async fn delete_cliente(&self, cpf: Cpf) -> Result<(), DomainError> {
+        let mut cliente_list = self._clientes.lock().await;
         for (index, cliente) in cliente_list.iter_mut().enumerate() {
             if cliente.cpf().to_owned() == cpf {
                 cliente_list.remove(index);
                 return Ok(());
             }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Cliente", cpf.0))
     }
 }
 
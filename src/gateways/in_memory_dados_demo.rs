@@ -0,0 +1,198 @@
+use std::collections::BTreeSet;
+
+use chrono::Utc;
+
+use crate::entities::alergeno::{Alergeno, AlergenosPorIngrediente};
+use crate::entities::cliente::Cliente;
+use crate::entities::cpf::Cpf;
+use crate::entities::ingredientes::Ingredientes;
+use crate::entities::loja::{Loja, LOJA_PADRAO};
+use crate::entities::pagamento::Pagamento;
+use crate::entities::pedido::{Pedido, Status};
+use crate::entities::produto::{Categoria, Produto};
+use crate::entities::usuario::Usuario;
+
+// Mesmos dados de seeds/demo.sql, para que o modo em memória
+// se comporte como um banco de desenvolvimento com `seed_demo`
+
+pub fn lojas() -> Vec<Loja> {
+    let _now = Utc::now();
+    vec![Loja::new(LOJA_PADRAO, "Loja padrão".to_string(), _now, _now)]
+}
+
+pub fn usuarios() -> Vec<Usuario> {
+    let _now = Utc::now();
+    let mut admin = Usuario::new(
+        1,
+        "Administrador".to_string(),
+        "admin@fastfood.com.br".to_string(),
+        Cpf::new("000.000.000-00".to_string()).unwrap(),
+        "melhor_projeto".to_string(),
+        "Admin".parse().unwrap(),
+        "Ativo".parse().unwrap(),
+        _now,
+        _now,
+    );
+    admin.set_lojas(vec![LOJA_PADRAO]);
+    vec![admin]
+}
+
+pub fn clientes() -> Vec<Cliente> {
+    let _now = Utc::now();
+    vec![Cliente::new(
+        1,
+        "Fulano da Silva".to_string(),
+        "fulano.silva@exemplo.com".to_string(),
+        Cpf::new("123.456.789-09".to_string()).unwrap(),
+        _now,
+        _now,
+    )]
+}
+
+fn produto(
+    id: usize,
+    nome: &str,
+    foto: &str,
+    descricao: &str,
+    categoria: Categoria,
+    preco: f64,
+    ingredientes: &[&str],
+) -> Produto {
+    let _now = Utc::now();
+    let mut produto = Produto::new(
+        id,
+        nome.to_string(),
+        foto.to_string(),
+        descricao.to_string(),
+        categoria,
+        preco,
+        Ingredientes::new(ingredientes.iter().map(|ingrediente| ingrediente.to_string()).collect())
+            .unwrap(),
+        _now,
+        _now,
+    );
+    // Como em seeds/demo.sql: os queijos são os únicos alérgenos da demonstração
+    let alergenos: AlergenosPorIngrediente = ingredientes
+        .iter()
+        .filter(|ingrediente| ingrediente.starts_with("queijo"))
+        .map(|ingrediente| (ingrediente.to_string(), BTreeSet::from([Alergeno::Lactose])))
+        .collect();
+    produto.set_alergenos_por_ingrediente(alergenos).unwrap();
+    produto
+}
+
+pub fn produtos() -> Vec<Produto> {
+    vec![
+        produto(
+            1,
+            "Hamburguer",
+            "hamburguer.jpg",
+            "Delicioso hamburguer artesanal",
+            Categoria::Lanche,
+            10.99,
+            &["carne bovina", "queijo cheddar", "alface"],
+        ),
+        produto(
+            2,
+            "Cheeseburguer",
+            "cheeseburguer.jpg",
+            "Irresistível cheeseburguer com queijo derretido",
+            Categoria::Lanche,
+            12.99,
+            &["carne bovina", "queijo cheddar", "cebola"],
+        ),
+        produto(
+            3,
+            "Hamburguer de Frango",
+            "lanche_frango.jpg",
+            "Delicioso lanche com carne de frango grelhada",
+            Categoria::Lanche,
+            11.99,
+            &["carne frango", "queijo prato", "alface"],
+        ),
+        produto(
+            4,
+            "Refrigerante",
+            "refrigerante.jpg",
+            "Bebida gelada para acompanhar",
+            Categoria::Bebida,
+            3.99,
+            &[],
+        ),
+        produto(
+            5,
+            "Batata Frita",
+            "batata_frita.jpg",
+            "Batatas crocantes e saborosas",
+            Categoria::Acompanhamento,
+            5.99,
+            &[],
+        ),
+        produto(
+            6,
+            "Sorvete",
+            "sorvete.jpg",
+            "Sorvete cremoso para adoçar o paladar",
+            Categoria::Sobremesa,
+            7.99,
+            &[],
+        ),
+    ]
+}
+
+pub fn pedidos() -> Vec<Pedido> {
+    let _now = Utc::now();
+    let clientes = clientes();
+    let produtos = produtos();
+    vec![
+        Pedido::new(
+            1,
+            clientes.first().cloned(),
+            produtos.first().cloned(),
+            produtos.get(4).cloned(),
+            produtos.get(3).cloned(),
+            "Mercado Pago".to_string(),
+            Status::Pendente,
+            _now,
+            _now,
+        ),
+        Pedido::new(
+            2,
+            None,
+            produtos.first().cloned(),
+            None,
+            None,
+            "Mercado Pago".to_string(),
+            Status::Pronto,
+            _now,
+            _now,
+        ),
+    ]
+}
+
+pub fn pagamentos() -> Vec<Pagamento> {
+    vec![Pagamento::new(
+        1,
+        1,
+        "pendente".to_string(),
+        100.00,
+        "Mercado Pago".to_string(),
+        "abc1234567890".to_string(),
+        Utc::now(),
+    )]
+}
+
+// Repositórios em memória não têm sequência: o próximo id é o maior existente + 1
+pub fn proximo_id<'a>(ids: impl Iterator<Item = &'a usize>) -> usize {
+    ids.max().map_or(1, |id| id + 1)
+}
+
+// Copia de `origem` para `destino` só os registros com os ids informados, inserindo os que faltam
+pub fn aplicar_escritas<T: Clone>(destino: &mut Vec<T>, origem: &[T], ids: &BTreeSet<usize>, id: fn(&T) -> &usize) {
+    for registro in origem.iter().filter(|registro| ids.contains(id(registro))) {
+        match destino.iter_mut().find(|atual| id(atual) == id(registro)) {
+            Some(atual) => *atual = registro.clone(),
+            None => destino.push(registro.clone()),
+        }
+    }
+}
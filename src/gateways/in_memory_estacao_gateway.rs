@@ -0,0 +1,89 @@
+use chrono::Utc;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use crate::base::domain_error::DomainError;
+use crate::entities::estacao::Estacao;
+use crate::entities::loja::LOJA_PADRAO;
+use crate::gateways::in_memory_dados_demo;
+use crate::traits::estacao_gateway::EstacaoGateway;
+
+// As estações de todas as lojas ficam no mesmo vetor, compartilhado pelas cópias de `com_loja`
+pub struct InMemoryEstacaoRepository {
+    _estacoes: Arc<Mutex<Vec<Estacao>>>,
+    loja_id: usize,
+}
+
+impl InMemoryEstacaoRepository {
+    pub fn new() -> Self {
+        debug!("Usando repositório em memória!");
+
+        InMemoryEstacaoRepository {
+            _estacoes: Arc::new(Mutex::new(Vec::new())),
+            loja_id: LOJA_PADRAO,
+        }
+    }
+
+    pub fn com_loja(&self, loja_id: usize) -> Self {
+        InMemoryEstacaoRepository {
+            _estacoes: self._estacoes.clone(),
+            loja_id,
+        }
+    }
+}
+
+#[async_trait]
+impl EstacaoGateway for InMemoryEstacaoRepository {
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn EstacaoGateway + Sync + Send> {
+        Arc::new(self.com_loja(loja_id))
+    }
+
+    async fn get_estacoes(&self) -> Result<Vec<Estacao>, DomainError> {
+        Ok(self
+            ._estacoes
+            .lock()
+            .await
+            .iter()
+            .filter(|estacao| estacao.loja_id() == self.loja_id)
+            .cloned()
+            .collect())
+    }
+
+    async fn get_estacao_by_id(&self, id: usize) -> Result<Estacao, DomainError> {
+        self._estacoes
+            .lock()
+            .await
+            .iter()
+            .find(|estacao| *estacao.id() == id && estacao.loja_id() == self.loja_id)
+            .cloned()
+            .ok_or(DomainError::not_found("Estação", id))
+    }
+
+    async fn create_estacao(&self, estacao: Estacao) -> Result<Estacao, DomainError> {
+        let mut estacoes = self._estacoes.lock().await;
+        let mut estacao = Estacao::new(
+            in_memory_dados_demo::proximo_id(estacoes.iter().map(Estacao::id)),
+            estacao.nome().clone(),
+            *estacao.data_criacao(),
+            *estacao.data_atualizacao(),
+        );
+        estacao.set_loja_id(self.loja_id);
+        estacoes.push(estacao.clone());
+        Ok(estacao)
+    }
+
+    async fn update_estacao(&self, estacao_atualizada: Estacao) -> Result<Estacao, DomainError> {
+        for estacao in self._estacoes.lock().await.iter_mut() {
+            if estacao.id() == estacao_atualizada.id() && estacao.loja_id() == self.loja_id {
+                estacao.set_nome(estacao_atualizada.nome().clone())?;
+                estacao.set_data_atualizacao(Utc::now());
+                return Ok(estacao.clone());
+            }
+        }
+        Err(DomainError::not_found("Estação", estacao_atualizada.id()))
+    }
+}
+
+unsafe impl Sync for InMemoryEstacaoRepository {}
+unsafe impl Send for InMemoryEstacaoRepository {}
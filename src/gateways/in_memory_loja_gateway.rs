@@ -0,0 +1,66 @@
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use chrono::Utc;
+
+use crate::base::domain_error::DomainError;
+use crate::entities::loja::Loja;
+use crate::gateways::in_memory_dados_demo;
+use crate::traits::loja_gateway::LojaGateway;
+
+pub struct InMemoryLojaRepository {
+    _lojas: Mutex<Vec<Loja>>,
+}
+
+impl InMemoryLojaRepository {
+    pub fn new() -> Self {
+        debug!("Usando repositório em memória!");
+
+        InMemoryLojaRepository {
+            _lojas: Mutex::new(in_memory_dados_demo::lojas()),
+        }
+    }
+}
+
+#[async_trait]
+impl LojaGateway for InMemoryLojaRepository {
+    async fn get_lojas(&self) -> Result<Vec<Loja>, DomainError> {
+        Ok(self._lojas.lock().await.clone())
+    }
+
+    async fn get_loja_by_id(&self, id: usize) -> Result<Loja, DomainError> {
+        self._lojas
+            .lock()
+            .await
+            .iter()
+            .find(|loja| *loja.id() == id)
+            .cloned()
+            .ok_or(DomainError::not_found("Loja", id))
+    }
+
+    async fn create_loja(&self, loja: Loja) -> Result<Loja, DomainError> {
+        let mut lojas = self._lojas.lock().await;
+        let loja = Loja::new(
+            in_memory_dados_demo::proximo_id(lojas.iter().map(Loja::id)),
+            loja.nome().clone(),
+            *loja.data_criacao(),
+            *loja.data_atualizacao(),
+        );
+        lojas.push(loja.clone());
+        Ok(loja)
+    }
+
+    async fn update_loja(&self, loja_atualizada: Loja) -> Result<Loja, DomainError> {
+        for loja in self._lojas.lock().await.iter_mut() {
+            if loja.id() == loja_atualizada.id() {
+                loja.set_nome(loja_atualizada.nome().clone())?;
+                loja.set_data_atualizacao(Utc::now());
+                return Ok(loja.clone());
+            }
+        }
+        Err(DomainError::not_found("Loja", loja_atualizada.id()))
+    }
+}
+
+unsafe impl Sync for InMemoryLojaRepository {}
+unsafe impl Send for InMemoryLojaRepository {}
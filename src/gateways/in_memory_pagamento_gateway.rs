@@ -1,78 +1,112 @@
-use chrono::Utc;
-use tokio::time::{sleep, Duration};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::debug;
 
 use crate::base::domain_error::DomainError;
 use crate::entities::cliente::Cliente;
+use crate::entities::loja::LOJA_PADRAO;
 use crate::entities::pagamento::Pagamento;
 use crate::entities::produto::{Categoria, Produto};
+use crate::gateways::in_memory_dados_demo;
 
 use crate::traits::pagamento_gateway::PagamentoGateway;
 
-#[derive(Clone)]
+// Os dados de todas as lojas ficam no mesmo vetor, compartilhado pelas cópias de `com_loja`
 pub struct InMemoryPagamentoRepository {
-    _pagamentos: Vec<Pagamento>,
+    _pagamentos: Arc<Mutex<Vec<Pagamento>>>,
+    loja_id: usize,
 }
 
 impl InMemoryPagamentoRepository {
     pub fn new() -> Self {
-        let current_date = Utc::now().naive_utc().format("%Y-%m-%d").to_string();
+        debug!("Usando repositório em memória!");
 
-        let pagamento = Pagamento::new(
-            1,
-            1,
-            "pago".to_string(),
-            100.00,
-            "MercadoPago".to_string(),
-            "1234".to_string(),
-            current_date,
-        );
+        InMemoryPagamentoRepository {
+            _pagamentos: Arc::new(Mutex::new(in_memory_dados_demo::pagamentos())),
+            loja_id: LOJA_PADRAO,
+        }
+    }
 
-        println!("Usando repositório em memória!");
+    pub fn com_loja(&self, loja_id: usize) -> Self {
+        InMemoryPagamentoRepository {
+            _pagamentos: self._pagamentos.clone(),
+            loja_id,
+        }
+    }
 
+    // Cópia independente dos dados, na mesma loja
+    pub async fn snapshot(&self) -> Self {
         InMemoryPagamentoRepository {
-            _pagamentos: vec![pagamento],
+            _pagamentos: Arc::new(Mutex::new(self._pagamentos.lock().await.clone())),
+            loja_id: self.loja_id,
         }
     }
+
+    // Aplica só os pagamentos que a transação gravou na cópia
+    pub async fn aplicar(&self, snapshot: &Self, pagamentos: &BTreeSet<usize>) {
+        in_memory_dados_demo::aplicar_escritas(
+            &mut *self._pagamentos.lock().await,
+            &snapshot._pagamentos.lock().await,
+            pagamentos,
+            Pagamento::id,
+        );
+    }
 }
 
 #[async_trait]
 impl PagamentoGateway for InMemoryPagamentoRepository {
-    async fn create_pagamento(&mut self, pagamento: Pagamento) -> Result<Pagamento, DomainError> {
-        let pagamentos = &mut self._pagamentos;
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn PagamentoGateway + Sync + Send> {
+        Arc::new(self.com_loja(loja_id))
+    }
+
+    async fn create_pagamento(&self, pagamento: Pagamento) -> Result<Pagamento, DomainError> {
+        let mut pagamentos = self._pagamentos.lock().await;
+        let mut pagamento = Pagamento::new(
+            in_memory_dados_demo::proximo_id(pagamentos.iter().map(Pagamento::id)),
+            *pagamento.id_pedido(),
+            pagamento.estado().clone(),
+            pagamento.valor(),
+            pagamento.metodo().clone(),
+            pagamento.referencia().clone(),
+            *pagamento.data_criacao(),
+        );
+        pagamento.set_loja_id(self.loja_id);
         pagamentos.push(pagamento.clone());
         Ok(pagamento)
     }
 
     async fn get_pagamento_by_id_pedido(
-        &mut self,
+        &self,
         id_pagamento: usize,
     ) -> Result<Pagamento, DomainError> {
         let id = id_pagamento as i32;
-        sleep(Duration::from_secs(1)).await;
-        for pagamento in &self._pagamentos {
-            if pagamento.id_pedido().to_owned() == id as usize {
-                return Ok(pagamento.clone());
-            }
-        }
-        Err(DomainError::NotFound)
+        self._pagamentos
+            .lock()
+            .await
+            .iter()
+            .filter(|pagamento| pagamento.loja_id() == self.loja_id && *pagamento.id_pedido() == id as usize)
+            .max_by_key(|pagamento| *pagamento.data_criacao())
+            .cloned()
+            .ok_or(DomainError::not_found("Pagamento do pedido", id_pagamento))
     }
 
     async fn update_pagamento(
-        &mut self,
+        &self,
         updated_pagamento: Pagamento,
     ) -> Result<Pagamento, DomainError> {
-        sleep(Duration::from_secs(1)).await;
-        for pagamento in &mut self._pagamentos {
-            if pagamento.id() == updated_pagamento.id() {
+        for pagamento in self._pagamentos.lock().await.iter_mut() {
+            if pagamento.id() == updated_pagamento.id() && pagamento.loja_id() == self.loja_id {
                 *pagamento = updated_pagamento.clone();
+                pagamento.set_loja_id(self.loja_id);
                 return Ok(pagamento.clone());
             }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Pagamento", updated_pagamento.id()))
     }
 
-    // async fn atualiza_status(&mut self, id: usize, status: Status) -> Result<Pagamento, DomainError> {
-    //     let pagamentos = &mut self._pagamentos;
+    // async fn atualiza_status(&self, id: usize, status: Status) -> Result<Pagamento, DomainError> {
+    //     let pagamentos = self._pagamentos.lock().await;
     //     if (status == Status::Invalido){
     //         return Err::<Pagamento, _>(DomainError::Invalid("status".to_string()));
     //     }
@@ -86,7 +120,7 @@ impl PagamentoGateway for InMemoryPagamentoRepository {
     // }
 
     // async fn get_pagamento_by_id(&self, pagamento_id: usize) -> Result<Pagamento, DomainError> {
-    //     let pagamentos = &self._pagamentos;
+    //     let pagamentos = self._pagamentos.lock().await;
     //     for pagamento in pagamentos.iter() {
     //         if *pagamento.id() == pagamento_id {
     //             return Ok(pagamento.clone());
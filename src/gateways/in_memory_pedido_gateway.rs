@@ -1,82 +1,72 @@
-use chrono::Utc;
-use tokio::time::{sleep, Duration};
+use chrono::{DateTime, Utc};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::debug;
 
 use crate::base::domain_error::DomainError;
 use crate::entities::cliente::Cliente;
+use crate::entities::estacao::StatusDoTicket;
+use crate::entities::fila_da_cozinha::na_fila_da_cozinha;
+use crate::entities::loja::LOJA_PADRAO;
 use crate::entities::pedido::{Pedido, Status};
-use crate::entities::produto::{Categoria, Produto};
-
-use crate::entities::cpf::Cpf;
-use crate::entities::ingredientes::Ingredientes;
+use crate::entities::produto::Produto;
 use crate::entities::pagamento::Pagamento;
+use crate::entities::tempo_de_preparo::{tempos_de_preparo_por_produto, TempoDePreparo};
+use crate::gateways::in_memory_dados_demo;
 
 use crate::traits::pedido_gateway::PedidoGateway;
 
-#[derive(Clone)]
+// Os dados de todas as lojas ficam nos mesmos vetores, compartilhados pelas cópias de `com_loja`
 pub struct InMemoryPedidoRepository {
-    _pedidos: Vec<Pedido>,
-    _pagamentos: Vec<Pagamento>,
+    _pedidos: Arc<Mutex<Vec<Pedido>>>,
+    _pagamentos: Arc<Mutex<Vec<Pagamento>>>,
+    loja_id: usize,
 }
 
 impl InMemoryPedidoRepository {
     pub fn new() -> Self {
-        let current_date = Utc::now().naive_utc().format("%Y-%m-%d").to_string();
-
-        let cliente = Cliente::new(
-            1,
-            "Fulano da Silva".to_string(),
-            "fulano.silva@exemplo.com".to_string(),
-            Cpf::new("123.456.789-09".to_string()).unwrap(),
-            "2024-01-17".to_string(),
-            "2024-01-17".to_string(),
-        );
+        debug!("Usando repositório em memória!");
 
-        let lanche = Produto::new(
-            1,
-            "Cheeseburger".to_string(),
-            "cheeseburger.png".to_string(),
-            "O clássico pão, carne e queijo!".to_string(),
-            Categoria::Lanche,
-            9.99,
-            Ingredientes::new(vec![
-                "Pão".to_string(),
-                "Hambúrguer".to_string(),
-                "Queijo".to_string(),
-            ])
-            .unwrap(),
-            "2024-01-17".to_string(),
-            "2024-01-17".to_string(),
-        );
-
-        let pedido = Pedido::new(
-            1,
-            Some(cliente),
-            Some(lanche),
-            None,
-            None,
-            "mercadopago".to_string(),
-            Status::Pendente,
-            current_date.clone(),
-            current_date.clone(),
-        );
-
-        let pagamento = Pagamento::new(
-            1,
-            1,
-            "pago".to_string(),
-            100.0,
-            "MercadoPago".to_string(),
-            "1234".to_string(),
-            current_date,
-        );
+        InMemoryPedidoRepository {
+            _pedidos: Arc::new(Mutex::new(in_memory_dados_demo::pedidos())),
+            _pagamentos: Arc::new(Mutex::new(in_memory_dados_demo::pagamentos())),
+            loja_id: LOJA_PADRAO,
+        }
+    }
 
-        println!("Usando repositório em memória!");
+    pub fn com_loja(&self, loja_id: usize) -> Self {
+        InMemoryPedidoRepository {
+            _pedidos: self._pedidos.clone(),
+            _pagamentos: self._pagamentos.clone(),
+            loja_id,
+        }
+    }
 
+    // Cópia independente dos dados, na mesma loja
+    pub async fn snapshot(&self) -> Self {
         InMemoryPedidoRepository {
-            _pedidos: vec![pedido],
-            _pagamentos: vec![pagamento],
+            _pedidos: Arc::new(Mutex::new(self._pedidos.lock().await.clone())),
+            _pagamentos: Arc::new(Mutex::new(self._pagamentos.lock().await.clone())),
+            loja_id: self.loja_id,
         }
     }
+
+    // Aplica só os registros que a transação gravou na cópia, preservando o que mudou fora dela
+    pub async fn aplicar(&self, snapshot: &Self, pedidos: &BTreeSet<usize>, pagamentos: &BTreeSet<usize>) {
+        in_memory_dados_demo::aplicar_escritas(
+            &mut *self._pedidos.lock().await,
+            &snapshot._pedidos.lock().await,
+            pedidos,
+            Pedido::id,
+        );
+        in_memory_dados_demo::aplicar_escritas(
+            &mut *self._pagamentos.lock().await,
+            &snapshot._pagamentos.lock().await,
+            pagamentos,
+            Pagamento::id,
+        );
+    }
 }
 
 async fn get_status_by_string(status: String) -> Status {
@@ -94,126 +84,195 @@ async fn get_status_by_string(status: String) -> Status {
 
 #[async_trait]
 impl PedidoGateway for InMemoryPedidoRepository {
-    async fn lista_pedidos(&mut self) -> Result<Vec<Pedido>, DomainError> {
-        Ok(self._pedidos.clone())
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn PedidoGateway + Sync + Send> {
+        Arc::new(self.com_loja(loja_id))
+    }
+
+    async fn lista_pedidos(&self) -> Result<Vec<Pedido>, DomainError> {
+        Ok(self
+            ._pedidos
+            .lock()
+            .await
+            .iter()
+            .filter(|pedido| pedido.loja_id() == self.loja_id && *pedido.status() != Status::Finalizado)
+            .cloned()
+            .collect())
     }
 
     async fn get_pedidos_novos(&self) -> Result<Vec<Pedido>, DomainError> {
-        let mut pedidos: Vec<Pedido> = Vec::new();
-        for pedido in &self._pedidos {
-            if *pedido.status() == Status::Pendente {
-                pedidos.push(pedido.clone());
-            }
-        }
-        sleep(Duration::from_secs(1)).await;
-        Ok(pedidos)
+        Ok(self
+            ._pedidos
+            .lock()
+            .await
+            .iter()
+            .filter(|pedido| pedido.loja_id() == self.loja_id && na_fila_da_cozinha(pedido.status()))
+            .cloned()
+            .collect())
     }
 
-    async fn atualiza_status(&mut self, id: usize, status: Status) -> Result<Pedido, DomainError> {
-        let pedidos = &mut self._pedidos;
+    async fn get_tempos_de_preparo(&self, desde: DateTime<Utc>) -> Result<Vec<TempoDePreparo>, DomainError> {
+        let pedidos: Vec<Pedido> = self
+            ._pedidos
+            .lock()
+            .await
+            .iter()
+            .filter(|pedido| pedido.loja_id() == self.loja_id)
+            .cloned()
+            .collect();
+        Ok(tempos_de_preparo_por_produto(&pedidos, &desde))
+    }
+
+    async fn atualiza_status(&self, id: usize, status: Status) -> Result<Pedido, DomainError> {
+        let mut pedidos = self._pedidos.lock().await;
         if (status == Status::Invalido) {
-            return Err::<Pedido, _>(DomainError::Invalid("status".to_string()));
+            return Err::<Pedido, _>(DomainError::validation("status", "Status inválido"));
         }
         for pedido in pedidos.iter_mut() {
-            if *pedido.id() == id {
-                pedido.set_status(status.clone());
+            if *pedido.id() == id && pedido.loja_id() == self.loja_id {
+                pedido.registrar_status(status.clone(), Utc::now());
                 return Ok(pedido.clone());
             }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Pedido", id))
     }
 
-    async fn create_pedido(&mut self, pedido: Pedido) -> Result<Pedido, DomainError> {
-        let pedidos = &mut self._pedidos;
+    async fn atualiza_status_do_ticket(
+        &self,
+        pedido_id: usize,
+        estacao_id: usize,
+        status: StatusDoTicket,
+    ) -> Result<Pedido, DomainError> {
+        let mut pedidos = self._pedidos.lock().await;
+        match pedidos
+            .iter_mut()
+            .find(|pedido| *pedido.id() == pedido_id && pedido.loja_id() == self.loja_id)
+        {
+            Some(pedido) => {
+                pedido.registrar_status_do_ticket(estacao_id, status, Utc::now())?;
+                Ok(pedido.clone())
+            }
+            None => Err(DomainError::not_found("Pedido", pedido_id)),
+        }
+    }
+
+    async fn create_pedido(&self, pedido: Pedido) -> Result<Pedido, DomainError> {
+        let mut pedidos = self._pedidos.lock().await;
+        let mut pedido = Pedido::new(
+            in_memory_dados_demo::proximo_id(pedidos.iter().map(Pedido::id)),
+            pedido.cliente().cloned(),
+            pedido.lanche().cloned(),
+            pedido.acompanhamento().cloned(),
+            pedido.bebida().cloned(),
+            pedido.pagamento().clone(),
+            pedido.status().clone(),
+            *pedido.data_criacao(),
+            *pedido.data_atualizacao(),
+        );
+        pedido.set_loja_id(self.loja_id);
         pedidos.push(pedido.clone());
         Ok(pedido)
     }
 
     async fn get_pedido_by_id(&self, pedido_id: usize) -> Result<Pedido, DomainError> {
-        let pedidos = &self._pedidos;
+        let pedidos = self._pedidos.lock().await;
         for pedido in pedidos.iter() {
-            if *pedido.id() == pedido_id {
+            if *pedido.id() == pedido_id && pedido.loja_id() == self.loja_id {
                 return Ok(pedido.clone());
             }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Pedido", pedido_id))
     }
 
     async fn cadastrar_cliente(
-        &mut self,
+        &self,
         pedido_id: usize,
         cliente: Cliente,
     ) -> Result<Pedido, DomainError> {
-        let pedidos = &mut self._pedidos;
+        let mut pedidos = self._pedidos.lock().await;
         for pedido in pedidos.iter_mut() {
-            if *pedido.id() == pedido_id {
+            if *pedido.id() == pedido_id && pedido.loja_id() == self.loja_id {
                 pedido.set_cliente(Some(cliente.clone()));
                 return Ok(pedido.clone());
             }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Pedido", pedido_id))
     }
 
     async fn cadastrar_lanche(
-        &mut self,
+        &self,
         pedido_id: usize,
         lanche: Produto,
     ) -> Result<Pedido, DomainError> {
-        let pedidos = &mut self._pedidos;
+        let mut pedidos = self._pedidos.lock().await;
         for pedido in pedidos.iter_mut() {
-            if *pedido.id() == pedido_id {
+            if *pedido.id() == pedido_id && pedido.loja_id() == self.loja_id {
                 pedido.set_lanche(Some(lanche.clone()));
                 return Ok(pedido.clone());
             }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Pedido", pedido_id))
     }
 
     async fn cadastrar_acompanhamento(
-        &mut self,
+        &self,
         pedido_id: usize,
         acompanhamento: Produto,
     ) -> Result<Pedido, DomainError> {
-        let pedidos = &mut self._pedidos;
+        let mut pedidos = self._pedidos.lock().await;
         for pedido in pedidos.iter_mut() {
-            if *pedido.id() == pedido_id {
+            if *pedido.id() == pedido_id && pedido.loja_id() == self.loja_id {
                 pedido.set_acompanhamento(Some(acompanhamento.clone()));
                 return Ok(pedido.clone());
             }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Pedido", pedido_id))
     }
 
     async fn cadastrar_bebida(
-        &mut self,
+        &self,
         pedido_id: usize,
         bebida: Produto,
     ) -> Result<Pedido, DomainError> {
-        let pedidos = &mut self._pedidos;
+        let mut pedidos = self._pedidos.lock().await;
         for pedido in pedidos.iter_mut() {
-            if *pedido.id() == pedido_id {
+            if *pedido.id() == pedido_id && pedido.loja_id() == self.loja_id {
                 pedido.set_bebida(Some(bebida.clone()));
                 return Ok(pedido.clone());
             }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Pedido", pedido_id))
     }
 
     async fn cadastrar_pagamento(
-        &mut self,
+        &self,
         pagamento: Pagamento,
     ) -> Result<Pagamento, DomainError> {
-        let pedidos = &mut self._pedidos;
+        let mut pedidos = self._pedidos.lock().await;
         for pedido in pedidos.iter_mut() {
-            if *pedido.id() == *pagamento.id_pedido() {
-                let pagamentos = &mut self._pagamentos;
+            if *pedido.id() == *pagamento.id_pedido() && pedido.loja_id() == self.loja_id {
+                let mut pagamento = pagamento.clone();
+                pagamento.set_loja_id(self.loja_id);
+                let mut pagamentos = self._pagamentos.lock().await;
                 pagamentos.push(pagamento.clone());
-                return Ok(pagamento.clone());
+                return Ok(pagamento);
             }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Pedido", pagamento.id_pedido()))
     }
 }
 
 unsafe impl Sync for InMemoryPedidoRepository {}
 unsafe impl Send for InMemoryPedidoRepository {}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::gateways::contrato_pedido_gateway;
+
+    #[tokio::test]
+    async fn test_fila_da_cozinha() {
+        let repository = InMemoryPedidoRepository::new().com_loja(99);
+        contrato_pedido_gateway::verifica_fila_da_cozinha(&repository).await;
+    }
+}
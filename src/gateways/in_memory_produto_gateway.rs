@@ -4,114 +4,186 @@ use crate::{
     base::domain_error::DomainError,
 };
 
-use chrono::Utc;
+use crate::entities::loja::LOJA_PADRAO;
+use crate::entities::preco_historico::PrecoHistorico;
 use crate::entities::produto::Categoria;
-use crate::entities::ingredientes::Ingredientes;
+use crate::gateways::in_memory_dados_demo;
 
-use tokio::time::{sleep, Duration};
+use chrono::Utc;
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::debug;
 
+// Os dados de todas as lojas ficam nos mesmos vetores, compartilhados pelas cópias de `com_loja`
 pub struct InMemoryProdutoRepository {
-    _produto: Vec<Produto>,
+    _produto: Arc<Mutex<Vec<Produto>>>,
+    _precos: Arc<Mutex<Vec<PrecoHistorico>>>,
+    loja_id: usize,
 }
 
 impl InMemoryProdutoRepository {
     pub fn new() -> Self {
-        let _id = 0;
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
-
-        let categoria = Categoria::Lanche;
-
-        let ingredientes = Ingredientes::new(vec![
-            String::from("Carne"),
-            String::from("Pao"),
-            String::from("Alface"),
-        ]).unwrap();
-
-        let produto = Produto::new(
-            _id,
-            "Hamburguer".to_string(),
-            "hamburguer.png".to_string(),
-            "hamburguer com uma carne e salada".to_string(),
-            categoria,
-            15.99,
-            ingredientes,
-            _now.clone(),
-            _now,
-        );
+        debug!("Usando repositório em memória!");
 
-        println!("Usando repositório em memória!");
+        let produtos = in_memory_dados_demo::produtos();
+        let precos = produtos.iter().map(preco_atual).collect();
+        InMemoryProdutoRepository {
+            _produto: Arc::new(Mutex::new(produtos)),
+            _precos: Arc::new(Mutex::new(precos)),
+            loja_id: LOJA_PADRAO,
+        }
+    }
 
+    pub fn com_loja(&self, loja_id: usize) -> Self {
         InMemoryProdutoRepository {
-            _produto: vec![produto],
+            _produto: self._produto.clone(),
+            _precos: self._precos.clone(),
+            loja_id,
         }
     }
 }
 
+fn preco_atual(produto: &Produto) -> PrecoHistorico {
+    PrecoHistorico::new(*produto.id(), produto.preco(), *produto.data_atualizacao())
+}
+
 #[async_trait]
 impl ProdutoGateway for InMemoryProdutoRepository {
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn ProdutoGateway + Sync + Send> {
+        Arc::new(self.com_loja(loja_id))
+    }
+
     async fn get_produtos(&self) -> Result<Vec<Produto>, DomainError> {
-        let produtos = self._produto.clone();
-        sleep(Duration::from_secs(1)).await;
-        Ok(produtos)
+        Ok(self
+            ._produto
+            .lock()
+            .await
+            .iter()
+            .filter(|produto| produto.loja_id() == self.loja_id && !produto.arquivado())
+            .cloned()
+            .collect())
     }
 
     async fn get_produto_by_id(&self, id: usize) -> Result<Produto, DomainError> {
-        sleep(Duration::from_secs(1)).await;
-        for produto in &self._produto {
-            if produto.id().to_owned() == id {
+        for produto in self._produto.lock().await.iter() {
+            if produto.id().to_owned() == id && produto.loja_id() == self.loja_id {
                 return Ok(produto.clone());
             }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Produto", id))
+    }
+
+    async fn get_produtos_by_ids(&self, ids: Vec<usize>) -> Result<Vec<Produto>, DomainError> {
+        Ok(self
+            ._produto
+            .lock()
+            .await
+            .iter()
+            .filter(|produto| produto.loja_id() == self.loja_id && ids.contains(produto.id()))
+            .cloned()
+            .collect())
     }
 
     async fn get_produtos_by_categoria(&self, categoria: Categoria) -> Result<Vec<Produto>, DomainError> {
-        sleep(Duration::from_secs(1)).await;
         let mut produtos = Vec::new();
-        for produto in &self._produto {
-            if produto.categoria().to_owned() == categoria {
+        for produto in self._produto.lock().await.iter() {
+            if produto.loja_id() == self.loja_id && produto.categoria().to_owned() == categoria && !produto.arquivado() {
                 produtos.push(produto.clone());
             }
         }
         Ok(produtos)
     }
 
-    async fn create_produto(&mut self, produto: Produto) -> Result<Produto, DomainError> {
-        sleep(Duration::from_secs(1)).await;
-        let existing_produto = self.get_produto_by_id(produto.id().to_owned()).await;
-
-        if existing_produto.is_ok() {
-            return Err(DomainError::AlreadyExists);
-        }
-
-        let mut produto_list = self._produto.clone();
+    async fn create_produto(&self, produto: Produto) -> Result<Produto, DomainError> {
+        let mut produto_list = self._produto.lock().await;
+        let mut novo_produto = Produto::new(
+            in_memory_dados_demo::proximo_id(produto_list.iter().map(Produto::id)),
+            produto.nome().clone(),
+            produto.foto().clone(),
+            produto.descricao().clone(),
+            produto.categoria().clone(),
+            produto.preco(),
+            produto.ingredientes().clone(),
+            *produto.data_criacao(),
+            *produto.data_atualizacao(),
+        );
+        novo_produto.set_alergenos_por_ingrediente(produto.alergenos_por_ingrediente().clone())?;
+        novo_produto.set_informacao_nutricional(produto.informacao_nutricional().cloned())?;
+        novo_produto.set_janelas_de_venda(produto.janelas_de_venda().clone())?;
+        novo_produto.set_estacao_id(produto.estacao_id());
+        novo_produto.set_loja_id(self.loja_id);
+        let produto = novo_produto;
         produto_list.push(produto.clone());
+        self._precos.lock().await.push(preco_atual(&produto));
 
-        self._produto = produto_list;
-
-        Ok(produto.clone())
+        Ok(produto)
     }
 
-    async fn update_produto(&mut self, new_produto_data: Produto) -> Result<Produto, DomainError> {
-        sleep(Duration::from_secs(1)).await;
-        let mut produto_list = self._produto.clone();
-        for produto in &mut produto_list.iter_mut() {
-        if produto.id() == new_produto_data.id() {
-            *produto = new_produto_data.clone();
-            return Ok(produto.clone());
-        }
+    async fn update_produto(&self, new_produto_data: Produto) -> Result<Produto, DomainError> {
+        for produto in self._produto.lock().await.iter_mut() {
+            if produto.id() == new_produto_data.id() && produto.loja_id() == self.loja_id {
+                if produto.versao() != new_produto_data.versao() {
+                    return Err(DomainError::conflict("Produto", "Produto foi alterado por outra requisição"));
+                }
+                let preco_anterior = produto.preco();
+                *produto = new_produto_data.clone();
+                produto.set_loja_id(self.loja_id);
+                produto.set_versao(new_produto_data.versao() + 1);
+                produto.set_data_atualizacao(Utc::now());
+                if produto.preco() != preco_anterior {
+                    self._precos.lock().await.push(preco_atual(produto));
+                }
+                return Ok(produto.clone());
+            }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Produto", new_produto_data.id()))
     }
 
-    async fn delete_produto(&mut self, id: usize) -> Result<(), DomainError> {
-        let produto_list = &mut self._produto;
-        for (index, produto) in produto_list.iter_mut().enumerate() {
-            if produto.id().to_owned() == id {
-                produto_list.remove(index);
+    async fn delete_produto(&self, id: usize) -> Result<(), DomainError> {
+        for produto in self._produto.lock().await.iter_mut() {
+            if *produto.id() == id && produto.loja_id() == self.loja_id {
+                if !produto.arquivado() {
+                    let _now = Utc::now();
+                    produto.arquivar(_now);
+                    produto.set_versao(produto.versao() + 1);
+                    produto.set_data_atualizacao(_now);
+                }
                 return Ok(());
             }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Produto", id))
+    }
+
+    async fn restaurar_produto(&self, id: usize) -> Result<Produto, DomainError> {
+        for produto in self._produto.lock().await.iter_mut() {
+            if *produto.id() == id && produto.loja_id() == self.loja_id {
+                if produto.arquivado() {
+                    produto.restaurar();
+                    produto.set_versao(produto.versao() + 1);
+                    produto.set_data_atualizacao(Utc::now());
+                }
+                return Ok(produto.clone());
+            }
+        }
+        Err(DomainError::not_found("Produto", id))
     }
-}
\ No newline at end of file
+
+    async fn get_historico_de_precos(&self, ids: Vec<usize>) -> Result<Vec<PrecoHistorico>, DomainError> {
+        let ids: Vec<usize> = self
+            ._produto
+            .lock()
+            .await
+            .iter()
+            .filter(|produto| produto.loja_id() == self.loja_id && ids.contains(produto.id()))
+            .map(|produto| *produto.id())
+            .collect();
+        Ok(self
+            ._precos
+            .lock()
+            .await
+            .iter()
+            .filter(|preco| ids.contains(preco.produto_id()))
+            .cloned()
+            .collect())
+    }
+}
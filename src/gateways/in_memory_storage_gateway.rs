@@ -0,0 +1,43 @@
+use std::collections::HashMap;
+
+use tokio::sync::Mutex;
+use tracing::debug;
+
+use crate::base::domain_error::DomainError;
+use crate::traits::storage_gateway::{Arquivo, StorageGateway};
+
+pub struct InMemoryStorageGateway {
+    _arquivos: Mutex<HashMap<String, Arquivo>>,
+}
+
+impl InMemoryStorageGateway {
+    pub fn new() -> Self {
+        debug!("Usando storage em memória!");
+
+        InMemoryStorageGateway {
+            _arquivos: Mutex::new(HashMap::new()),
+        }
+    }
+}
+
+#[async_trait]
+impl StorageGateway for InMemoryStorageGateway {
+    async fn salvar(&self, chave: &str, arquivo: Arquivo) -> Result<(), DomainError> {
+        self._arquivos.lock().await.insert(chave.to_string(), arquivo);
+        Ok(())
+    }
+
+    async fn buscar(&self, chave: &str) -> Result<Arquivo, DomainError> {
+        self._arquivos
+            .lock()
+            .await
+            .get(chave)
+            .cloned()
+            .ok_or_else(|| DomainError::not_found("Arquivo", chave))
+    }
+
+    async fn remover(&self, chave: &str) -> Result<(), DomainError> {
+        self._arquivos.lock().await.remove(chave);
+        Ok(())
+    }
+}
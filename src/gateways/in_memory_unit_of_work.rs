@@ -0,0 +1,275 @@
+use chrono::{DateTime, Utc};
+use std::collections::BTreeSet;
+use std::sync::Arc;
+use tokio::sync::{Mutex, OwnedMutexGuard};
+
+use crate::base::domain_error::DomainError;
+use crate::entities::cliente::Cliente;
+use crate::entities::estacao::StatusDoTicket;
+use crate::entities::pagamento::Pagamento;
+use crate::entities::pedido::{Pedido, Status};
+use crate::entities::produto::Produto;
+use crate::entities::tempo_de_preparo::TempoDePreparo;
+use crate::gateways::in_memory_pagamento_gateway::InMemoryPagamentoRepository;
+use crate::gateways::in_memory_pedido_gateway::InMemoryPedidoRepository;
+use crate::traits::pagamento_gateway::PagamentoGateway;
+use crate::traits::pedido_gateway::PedidoGateway;
+use crate::traits::unit_of_work::{Transacao, UnitOfWork};
+
+pub struct InMemoryUnitOfWork {
+    pedido_repository: Arc<InMemoryPedidoRepository>,
+    pagamento_repository: Arc<InMemoryPagamentoRepository>,
+    lock: Arc<Mutex<()>>,
+}
+
+impl InMemoryUnitOfWork {
+    pub fn new(
+        pedido_repository: Arc<InMemoryPedidoRepository>,
+        pagamento_repository: Arc<InMemoryPagamentoRepository>,
+    ) -> Self {
+        InMemoryUnitOfWork {
+            pedido_repository,
+            pagamento_repository,
+            lock: Arc::new(Mutex::new(())),
+        }
+    }
+}
+
+#[async_trait]
+impl UnitOfWork for InMemoryUnitOfWork {
+    // As cópias por loja compartilham o lock, então continuam serializadas entre si
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn UnitOfWork + Sync + Send> {
+        Arc::new(InMemoryUnitOfWork {
+            pedido_repository: Arc::new(self.pedido_repository.com_loja(loja_id)),
+            pagamento_repository: Arc::new(self.pagamento_repository.com_loja(loja_id)),
+            lock: self.lock.clone(),
+        })
+    }
+
+    // As escritas vão para cópias e só são aplicadas nos repositórios no commit;
+    // o lock serializa as transações, como um nível de isolamento serializable.
+    async fn begin(&self) -> Result<Box<dyn Transacao + Sync + Send>, DomainError> {
+        let guard = self.lock.clone().lock_owned().await;
+        Ok(Box::new(InMemoryTransacao {
+            pedido_repository: self.pedido_repository.clone(),
+            pagamento_repository: self.pagamento_repository.clone(),
+            pedido_staged: Arc::new(PedidosNaTransacao {
+                repository: Arc::new(self.pedido_repository.snapshot().await),
+                escritas: Arc::new(EscritasDePedidos::default()),
+            }),
+            pagamento_staged: Arc::new(PagamentosNaTransacao {
+                repository: Arc::new(self.pagamento_repository.snapshot().await),
+                escritas: Arc::new(Mutex::new(BTreeSet::new())),
+            }),
+            guard: Mutex::new(Some(guard)),
+        }))
+    }
+}
+
+pub struct InMemoryTransacao {
+    pedido_repository: Arc<InMemoryPedidoRepository>,
+    pagamento_repository: Arc<InMemoryPagamentoRepository>,
+    pedido_staged: Arc<PedidosNaTransacao>,
+    pagamento_staged: Arc<PagamentosNaTransacao>,
+    guard: Mutex<Option<OwnedMutexGuard<()>>>,
+}
+
+#[async_trait]
+impl Transacao for InMemoryTransacao {
+    fn pedido_repository(&self) -> Arc<dyn PedidoGateway + Sync + Send> {
+        self.pedido_staged.clone()
+    }
+
+    fn pagamento_repository(&self) -> Arc<dyn PagamentoGateway + Sync + Send> {
+        self.pagamento_staged.clone()
+    }
+
+    // Só os registros gravados pela transação são copiados: escritas feitas fora dela
+    // enquanto estava aberta continuam valendo
+    async fn commit(&self) -> Result<(), DomainError> {
+        let mut guard = self.guard.lock().await;
+        if guard.is_none() {
+            return Err(DomainError::conflict("Transação", "Transação já finalizada"));
+        }
+        let escritas = &self.pedido_staged.escritas;
+        self.pedido_repository
+            .aplicar(
+                &self.pedido_staged.repository,
+                &*escritas.pedidos.lock().await,
+                &*escritas.pagamentos.lock().await,
+            )
+            .await;
+        self.pagamento_repository
+            .aplicar(&self.pagamento_staged.repository, &*self.pagamento_staged.escritas.lock().await)
+            .await;
+        guard.take();
+        Ok(())
+    }
+
+    async fn rollback(&self) -> Result<(), DomainError> {
+        let mut guard = self.guard.lock().await;
+        if guard.take().is_none() {
+            return Err(DomainError::conflict("Transação", "Transação já finalizada"));
+        }
+        Ok(())
+    }
+}
+
+#[derive(Default)]
+struct EscritasDePedidos {
+    pedidos: Mutex<BTreeSet<usize>>,
+    pagamentos: Mutex<BTreeSet<usize>>,
+}
+
+// Repositório da transação: lê e grava na cópia e anota os ids gravados, que o commit aplica
+struct PedidosNaTransacao {
+    repository: Arc<InMemoryPedidoRepository>,
+    escritas: Arc<EscritasDePedidos>,
+}
+
+impl PedidosNaTransacao {
+    async fn gravou(&self, pedido: Result<Pedido, DomainError>) -> Result<Pedido, DomainError> {
+        if let Ok(pedido) = &pedido {
+            self.escritas.pedidos.lock().await.insert(*pedido.id());
+        }
+        pedido
+    }
+}
+
+#[async_trait]
+impl PedidoGateway for PedidosNaTransacao {
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn PedidoGateway + Sync + Send> {
+        Arc::new(PedidosNaTransacao {
+            repository: Arc::new(self.repository.com_loja(loja_id)),
+            escritas: self.escritas.clone(),
+        })
+    }
+
+    async fn create_pedido(&self, pedido: Pedido) -> Result<Pedido, DomainError> {
+        self.gravou(self.repository.create_pedido(pedido).await).await
+    }
+
+    async fn lista_pedidos(&self) -> Result<Vec<Pedido>, DomainError> {
+        self.repository.lista_pedidos().await
+    }
+
+    async fn get_pedidos_novos(&self) -> Result<Vec<Pedido>, DomainError> {
+        self.repository.get_pedidos_novos().await
+    }
+
+    async fn get_tempos_de_preparo(&self, desde: DateTime<Utc>) -> Result<Vec<TempoDePreparo>, DomainError> {
+        self.repository.get_tempos_de_preparo(desde).await
+    }
+
+    async fn get_pedido_by_id(&self, pedido_id: usize) -> Result<Pedido, DomainError> {
+        self.repository.get_pedido_by_id(pedido_id).await
+    }
+
+    async fn cadastrar_cliente(&self, pedido_id: usize, cliente: Cliente) -> Result<Pedido, DomainError> {
+        self.gravou(self.repository.cadastrar_cliente(pedido_id, cliente).await).await
+    }
+
+    async fn cadastrar_lanche(&self, pedido_id: usize, lanche: Produto) -> Result<Pedido, DomainError> {
+        self.gravou(self.repository.cadastrar_lanche(pedido_id, lanche).await).await
+    }
+
+    async fn cadastrar_acompanhamento(&self, pedido_id: usize, acompanhamento: Produto) -> Result<Pedido, DomainError> {
+        self.gravou(self.repository.cadastrar_acompanhamento(pedido_id, acompanhamento).await).await
+    }
+
+    async fn cadastrar_bebida(&self, pedido_id: usize, bebida: Produto) -> Result<Pedido, DomainError> {
+        self.gravou(self.repository.cadastrar_bebida(pedido_id, bebida).await).await
+    }
+
+    async fn cadastrar_pagamento(&self, pagamento: Pagamento) -> Result<Pagamento, DomainError> {
+        let pagamento = self.repository.cadastrar_pagamento(pagamento).await?;
+        self.escritas.pagamentos.lock().await.insert(*pagamento.id());
+        Ok(pagamento)
+    }
+
+    async fn atualiza_status(&self, pedido_id: usize, status: Status) -> Result<Pedido, DomainError> {
+        self.gravou(self.repository.atualiza_status(pedido_id, status).await).await
+    }
+
+    async fn atualiza_status_do_ticket(
+        &self,
+        pedido_id: usize,
+        estacao_id: usize,
+        status: StatusDoTicket,
+    ) -> Result<Pedido, DomainError> {
+        self.gravou(self.repository.atualiza_status_do_ticket(pedido_id, estacao_id, status).await).await
+    }
+}
+
+struct PagamentosNaTransacao {
+    repository: Arc<InMemoryPagamentoRepository>,
+    escritas: Arc<Mutex<BTreeSet<usize>>>,
+}
+
+impl PagamentosNaTransacao {
+    async fn gravou(&self, pagamento: Result<Pagamento, DomainError>) -> Result<Pagamento, DomainError> {
+        if let Ok(pagamento) = &pagamento {
+            self.escritas.lock().await.insert(*pagamento.id());
+        }
+        pagamento
+    }
+}
+
+#[async_trait]
+impl PagamentoGateway for PagamentosNaTransacao {
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn PagamentoGateway + Sync + Send> {
+        Arc::new(PagamentosNaTransacao {
+            repository: Arc::new(self.repository.com_loja(loja_id)),
+            escritas: self.escritas.clone(),
+        })
+    }
+
+    async fn create_pagamento(&self, pagamento: Pagamento) -> Result<Pagamento, DomainError> {
+        self.gravou(self.repository.create_pagamento(pagamento).await).await
+    }
+
+    async fn get_pagamento_by_id_pedido(&self, id_pedido: usize) -> Result<Pagamento, DomainError> {
+        self.repository.get_pagamento_by_id_pedido(id_pedido).await
+    }
+
+    async fn update_pagamento(&self, pagamento: Pagamento) -> Result<Pagamento, DomainError> {
+        self.gravou(self.repository.update_pagamento(pagamento).await).await
+    }
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn test_commit_preserva_escrita_feita_fora_da_transacao() {
+        let pedido_repository = Arc::new(InMemoryPedidoRepository::new());
+        let pagamento_repository = Arc::new(InMemoryPagamentoRepository::new());
+        let unit_of_work = InMemoryUnitOfWork::new(pedido_repository.clone(), pagamento_repository.clone());
+        let pedidos = pedido_repository.lista_pedidos().await.unwrap();
+        let (fora, dentro) = (*pedidos[0].id(), *pedidos[1].id());
+
+        let transacao = unit_of_work.begin().await.unwrap();
+        transacao.pedido_repository().atualiza_status(dentro, Status::Cancelado).await.unwrap();
+        pedido_repository.atualiza_status(fora, Status::Pronto).await.unwrap();
+        transacao.commit().await.unwrap();
+
+        assert_eq!(*pedido_repository.get_pedido_by_id(fora).await.unwrap().status(), Status::Pronto);
+        assert_eq!(*pedido_repository.get_pedido_by_id(dentro).await.unwrap().status(), Status::Cancelado);
+    }
+
+    #[tokio::test]
+    async fn test_rollback_descarta_escritas_da_transacao() {
+        let pedido_repository = Arc::new(InMemoryPedidoRepository::new());
+        let unit_of_work =
+            InMemoryUnitOfWork::new(pedido_repository.clone(), Arc::new(InMemoryPagamentoRepository::new()));
+        let pedido = pedido_repository.lista_pedidos().await.unwrap().remove(0);
+
+        let transacao = unit_of_work.begin().await.unwrap();
+        transacao.pedido_repository().atualiza_status(*pedido.id(), Status::Cancelado).await.unwrap();
+        transacao.rollback().await.unwrap();
+
+        assert_eq!(pedido_repository.get_pedido_by_id(*pedido.id()).await.unwrap().status(), pedido.status());
+    }
+}
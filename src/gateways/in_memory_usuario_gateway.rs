@@ -1,105 +1,136 @@
-use chrono::Utc;
-use tokio::time::{sleep, Duration};
+use std::sync::Arc;
+use tokio::sync::Mutex;
+use tracing::debug;
 
 use crate::base::domain_error::DomainError;
+use crate::entities::loja::LOJA_PADRAO;
 use crate::entities::usuario::Usuario;
 use crate::traits::usuario_gateway::UsuarioGateway;
 use crate::entities::cpf::Cpf;
+use crate::gateways::in_memory_dados_demo;
 
-#[derive(Clone)]
+// Sem loja as buscas valem para todas as lojas; as cópias de `com_loja` compartilham o vetor
 pub struct InMemoryUsuarioRepository {
-    _usuarios: Vec<Usuario>,
+    _usuarios: Arc<Mutex<Vec<Usuario>>>,
+    loja_id: Option<usize>,
 }
 
 impl InMemoryUsuarioRepository {
     pub fn new() -> Self {
-        let _id = 0;
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
-        let usuario = Usuario::new(
-            _id,
-            "Administrador".to_string(),
-            "admin@fastfood.com.br".to_string(),
-            Cpf::new("000.000.000-00".to_string()).unwrap(),
-            "melhor_projeto".to_string(),
-            "Admin".parse().unwrap(),
-            "Ativo".parse().unwrap(),
-            _now.clone(),
-            _now,
-        );
+        debug!("Usando repositório em memória!");
 
-        println!("Usando repositório em memória!");
+        InMemoryUsuarioRepository {
+            _usuarios: Arc::new(Mutex::new(in_memory_dados_demo::usuarios())),
+            loja_id: None,
+        }
+    }
 
+    pub fn com_loja(&self, loja_id: usize) -> Self {
         InMemoryUsuarioRepository {
-            _usuarios: vec![usuario],
+            _usuarios: self._usuarios.clone(),
+            loja_id: Some(loja_id),
         }
     }
+
+    fn visivel(&self, usuario: &Usuario) -> bool {
+        self.loja_id.is_none_or(|loja_id| usuario.trabalha_na_loja(loja_id))
+    }
 }
 
 #[async_trait]
 impl UsuarioGateway for InMemoryUsuarioRepository {
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn UsuarioGateway + Sync + Send> {
+        Arc::new(self.com_loja(loja_id))
+    }
+
     async fn get_usuarios(&self) -> Result<Vec<Usuario>, DomainError> {
-        let usuarios = self._usuarios.clone();
-        sleep(Duration::from_secs(1)).await;
+        let usuarios = self
+            ._usuarios
+            .lock()
+            .await
+            .iter()
+            .filter(|usuario| self.visivel(usuario))
+            .cloned()
+            .collect();
         Ok(usuarios)
     }
 
     async fn get_usuario_by_id(&self, id: usize) -> Result<Usuario, DomainError> {
-        sleep(Duration::from_secs(1)).await;
-        for usuario in &self._usuarios {
-            if usuario.id().to_owned() == id {
+        for usuario in self._usuarios.lock().await.iter() {
+            if usuario.id().to_owned() == id && self.visivel(usuario) {
                 return Ok(usuario.clone());
             }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Usuário", id))
     }
 
     async fn get_usuario_by_cpf(&self, cpf: Cpf) -> Result<Usuario, DomainError> {
-        sleep(Duration::from_secs(1)).await;
-        for usuario in &self._usuarios {
+        for usuario in self._usuarios.lock().await.iter() {
             if usuario.cpf().to_owned() == cpf {
                 return Ok(usuario.clone());
             }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Usuário", cpf.0))
     }
 
-    async fn create_usuario(&mut self, usuario: Usuario) -> Result<Usuario, DomainError> {
-        sleep(Duration::from_secs(1)).await;
-        let existing_usuario = self.get_usuario_by_id(usuario.id().to_owned()).await;
-
-        if existing_usuario.is_ok() {
-            return Err(DomainError::AlreadyExists);
+    async fn create_usuario(&self, usuario: Usuario) -> Result<Usuario, DomainError> {
+        let mut usuario_list = self._usuarios.lock().await;
+        if usuario_list.iter().any(|existente| existente.cpf() == usuario.cpf()) {
+            return Err(DomainError::conflict("Usuário", "CPF já cadastrado"));
         }
 
-        let mut usuario_list = self._usuarios.clone();
+        let mut novo_usuario = Usuario::new(
+            in_memory_dados_demo::proximo_id(usuario_list.iter().map(Usuario::id)),
+            usuario.nome().clone(),
+            usuario.email().clone(),
+            usuario.cpf().clone(),
+            usuario.senha().clone(),
+            usuario.tipo().clone(),
+            usuario.status().clone(),
+            *usuario.data_criacao(),
+            *usuario.data_atualizacao(),
+        );
+        if usuario.lojas().is_empty() {
+            novo_usuario.set_lojas(vec![self.loja_id.unwrap_or(LOJA_PADRAO)]);
+        } else {
+            novo_usuario.set_lojas(usuario.lojas().clone());
+        }
+        let usuario = novo_usuario;
         usuario_list.push(usuario.clone());
 
-        self._usuarios = usuario_list;
-
-        Ok(usuario.clone())
+        Ok(usuario)
     }
 
-    async fn update_usuario(&mut self, dados_usuario_atualizado: Usuario) -> Result<Usuario, DomainError> {
-        sleep(Duration::from_secs(1)).await;
-        let mut usuario_list = self._usuarios.clone();
-        for usuario in &mut usuario_list.iter_mut() {
-        if usuario.id() == dados_usuario_atualizado.id() {
-            *usuario = dados_usuario_atualizado.clone();
-            return Ok(usuario.clone());
-        }
+    async fn update_usuario(&self, dados_usuario_atualizado: Usuario) -> Result<Usuario, DomainError> {
+        for usuario in self._usuarios.lock().await.iter_mut() {
+            if usuario.id() == dados_usuario_atualizado.id() && self.visivel(usuario) {
+                let lojas = usuario.lojas().clone();
+                *usuario = dados_usuario_atualizado.clone();
+                if usuario.lojas().is_empty() {
+                    usuario.set_lojas(lojas);
+                }
+                return Ok(usuario.clone());
+            }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Usuário", dados_usuario_atualizado.id()))
     }
 
-    async fn delete_usuario(&mut self, cpf: Cpf) -> Result<(), DomainError> {
-        let usuario_list = &mut self._usuarios;
+    // Na loja, o usuário só deixa de trabalhar nela; é excluído quando não sobra nenhuma loja
+    async fn delete_usuario(&self, cpf: Cpf) -> Result<(), DomainError> {
+        let mut usuario_list = self._usuarios.lock().await;
         for (index, usuario) in usuario_list.iter_mut().enumerate() {
-            if usuario.cpf().to_owned() == cpf {
-                usuario_list.remove(index);
+            if usuario.cpf().to_owned() == cpf && self.visivel(usuario) {
+                if let Some(loja_id) = self.loja_id {
+                    let lojas = usuario.lojas().iter().copied().filter(|loja| *loja != loja_id).collect();
+                    usuario.set_lojas(lojas);
+                }
+                if self.loja_id.is_none() || usuario.lojas().is_empty() {
+                    usuario_list.remove(index);
+                }
                 return Ok(());
             }
         }
-        Err(DomainError::NotFound)
+        Err(DomainError::not_found("Usuário", cpf.0))
     }
 }
 
@@ -0,0 +1,114 @@
+use std::io::ErrorKind;
+use std::path::{Component, Path, PathBuf};
+
+use tracing::info;
+
+use crate::base::domain_error::DomainError;
+use crate::traits::storage_gateway::{Arquivo, StorageGateway};
+
+// Guarda os arquivos num diretório local; o content type sai da extensão da chave
+pub struct LocalStorageGateway {
+    diretorio: PathBuf,
+}
+
+impl LocalStorageGateway {
+    pub fn new(diretorio: impl Into<PathBuf>) -> Self {
+        let diretorio = diretorio.into();
+        info!(diretorio = %diretorio.display(), "Usando storage local");
+        LocalStorageGateway { diretorio }
+    }
+
+    // Só aceita caminhos relativos sem `..`, para nunca sair do diretório configurado
+    fn caminho(&self, chave: &str) -> Result<PathBuf, DomainError> {
+        let relativo = Path::new(chave);
+        let valido = !chave.is_empty()
+            && relativo
+                .components()
+                .all(|componente| matches!(componente, Component::Normal(_)));
+        if !valido {
+            return Err(DomainError::validation("chave", "Chave de arquivo inválida"));
+        }
+        Ok(self.diretorio.join(relativo))
+    }
+}
+
+pub fn content_type_da_chave(chave: &str) -> &'static str {
+    match Path::new(chave).extension().and_then(|extensao| extensao.to_str()) {
+        Some("png") => "image/png",
+        Some("jpg") | Some("jpeg") => "image/jpeg",
+        _ => "application/octet-stream",
+    }
+}
+
+#[async_trait]
+impl StorageGateway for LocalStorageGateway {
+    async fn salvar(&self, chave: &str, arquivo: Arquivo) -> Result<(), DomainError> {
+        let caminho = self.caminho(chave)?;
+        if let Some(pasta) = caminho.parent() {
+            tokio::fs::create_dir_all(pasta)
+                .await
+                .map_err(|err| DomainError::persistence("Erro ao criar diretório do arquivo").com_causa(err))?;
+        }
+        tokio::fs::write(&caminho, arquivo.conteudo)
+            .await
+            .map_err(|err| DomainError::persistence("Erro ao gravar arquivo").com_causa(err))
+    }
+
+    async fn buscar(&self, chave: &str) -> Result<Arquivo, DomainError> {
+        let caminho = self.caminho(chave)?;
+        match tokio::fs::read(&caminho).await {
+            Ok(conteudo) => Ok(Arquivo {
+                conteudo,
+                content_type: content_type_da_chave(chave).to_string(),
+            }),
+            Err(err) if err.kind() == ErrorKind::NotFound => Err(DomainError::not_found("Arquivo", chave)),
+            Err(err) => Err(DomainError::persistence("Erro ao ler arquivo").com_causa(err)),
+        }
+    }
+
+    async fn remover(&self, chave: &str) -> Result<(), DomainError> {
+        let caminho = self.caminho(chave)?;
+        match tokio::fs::remove_file(&caminho).await {
+            Err(err) if err.kind() != ErrorKind::NotFound => {
+                Err(DomainError::persistence("Erro ao remover arquivo").com_causa(err))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn storage() -> LocalStorageGateway {
+        LocalStorageGateway::new(std::env::temp_dir().join(format!("storage-{}", uuid::Uuid::new_v4())))
+    }
+
+    #[tokio::test]
+    async fn test_salvar_buscar_e_remover() {
+        let storage = storage();
+        let arquivo = Arquivo {
+            conteudo: vec![1, 2, 3],
+            content_type: "image/png".to_string(),
+        };
+
+        storage.salvar("produtos/1/foto.png", arquivo.clone()).await.unwrap();
+        assert_eq!(storage.buscar("produtos/1/foto.png").await.unwrap(), arquivo);
+
+        storage.remover("produtos/1/foto.png").await.unwrap();
+        storage.remover("produtos/1/foto.png").await.unwrap();
+        let result = storage.buscar("produtos/1/foto.png").await;
+        assert!(matches!(result, Err(DomainError::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_chave_nao_sai_do_diretorio() {
+        let storage = storage();
+        for chave in ["../segredo.png", "/etc/passwd", "produtos/../../x.png", ""] {
+            let result = storage.buscar(chave).await;
+            assert!(matches!(result, Err(DomainError::Validation { .. })), "{}", chave);
+        }
+    }
+}
@@ -1,5 +1,6 @@
 use postgres_from_row::FromRow;
-use tokio_postgres::Client;
+use deadpool_postgres::Pool;
+use tracing::{info, instrument};
 
 use crate::{
     base::domain_error::DomainError, 
@@ -7,39 +8,39 @@ use crate::{
     traits::cliente_gateway::ClienteGateway, 
     entities::cpf::Cpf,
 };
+use crate::external::postgres::PgExecutor;
 
-use crate::external::postgres::table::Table;
 
 const CREATE_CLIENTE: &str = "INSERT INTO cliente (nome, email, cpf, data_criacao, data_atualizacao) VALUES ($1, $2, $3, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP) RETURNING *";
 const QUERY_CLIENTE_BY_CPF: &str = "SELECT * FROM cliente WHERE cpf = $1";
 const QUERY_CLIENTE_BY_ID: &str = "SELECT * FROM cliente WHERE id = $1";
+const QUERY_CLIENTES_BY_IDS: &str = "SELECT * FROM cliente WHERE id = ANY($1)";
 const QUERY_CLIENTES: &str = "SELECT * FROM cliente";
 const DELETE_CLIENTE: &str = "DELETE FROM cliente WHERE cpf = $1 RETURNING *";
 
 pub struct PostgresClienteRepository {
-    client: Client,
-    tables: Vec<Table>,
+    executor: PgExecutor,
 }
 
 impl PostgresClienteRepository {
-    pub async fn new(client: Client, tables: Vec<Table>) -> Self {
-        let repo = PostgresClienteRepository { client, tables };
-        repo.check_for_tables().await;
+    pub async fn new(pool: Pool) -> Self {
+        let repo = PostgresClienteRepository {
+            executor: PgExecutor::Pool(pool),
+        };
         repo
     }
 
-    async fn check_for_tables(&self) {
-        for table in self.tables.iter() {
-            let query = table.get_create_if_not_exists_query();
-            self.client.execute(query.as_str(), &[]).await.unwrap();
-        }
+    pub fn em_transacao(&self, executor: PgExecutor) -> Self {
+        PostgresClienteRepository { executor }
     }
 }
 
 #[async_trait]
 impl ClienteGateway for PostgresClienteRepository {
+    #[instrument(level = "debug", skip(self))]
     async fn get_clientes(&self) -> Result<Vec<Cliente>, DomainError> {
-        let clientes = self.client.query(QUERY_CLIENTES, &[]).await.unwrap();
+        let client = self.executor.client().await?;
+        let clientes = client.query(QUERY_CLIENTES, &[]).await?;
         let mut clientes_vec = Vec::new();
         for cliente in clientes {
             clientes_vec.push(Cliente::from_row(&cliente));
@@ -47,47 +48,66 @@ impl ClienteGateway for PostgresClienteRepository {
         Ok(clientes_vec)
     }
 
+    #[instrument(level = "debug", skip(self, cpf))]
     async fn get_cliente_by_cpf(&self, cpf: Cpf) -> Result<Cliente, DomainError> {
-        let cliente = self.client.query_one(QUERY_CLIENTE_BY_CPF, &[&cpf.0]).await;
+        let client = self.executor.client().await?;
+        let cliente = client.query_opt(QUERY_CLIENTE_BY_CPF, &[&cpf.0]).await?;
         match cliente {
-            Ok(cliente) => Ok(Cliente::from_row(&cliente)),
-            Err(_) => Err(DomainError::NotFound),
+            Some(cliente) => Ok(Cliente::from_row(&cliente)),
+            None => Err(DomainError::not_found("Cliente", cpf.0)),
         }
     }
 
+    #[instrument(level = "debug", skip(self))]
     async fn get_cliente_by_id(&self, id: usize) -> Result<Cliente, DomainError> {
-        let id = id as i32;
-        let cliente = self.client.query_one(QUERY_CLIENTE_BY_ID, &[&id]).await;
+        let client = self.executor.client().await?;
+        let cliente = client.query_opt(QUERY_CLIENTE_BY_ID, &[&(id as i32)]).await?;
         match cliente {
-            Ok(cliente) => Ok(Cliente::from_row(&cliente)),
-            Err(_) => Err(DomainError::NotFound),
+            Some(cliente) => Ok(Cliente::from_row(&cliente)),
+            None => Err(DomainError::not_found("Cliente", id)),
+        }
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn get_clientes_by_ids(&self, ids: Vec<usize>) -> Result<Vec<Cliente>, DomainError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
         }
+        let client = self.executor.client().await?;
+        let ids: Vec<i32> = ids.into_iter().map(|id| id as i32).collect();
+        let clientes = client
+            .query(QUERY_CLIENTES_BY_IDS, &[&ids])
+            .await?;
+        Ok(clientes.iter().map(Cliente::from_row).collect())
     }
 
-    async fn create_cliente(&mut self, cliente: Cliente) -> Result<Cliente, DomainError> {
-        let new_cliente = self
-            .client
+    #[instrument(level = "debug", skip(self, cliente))]
+    async fn create_cliente(&self, cliente: Cliente) -> Result<Cliente, DomainError> {
+        let client = self.executor.client().await?;
+        let new_cliente = client
             .query(
                 CREATE_CLIENTE,
                 &[&cliente.nome(), &cliente.email(), &cliente.cpf().0],
             )
-            .await
-            .unwrap();
+            .await?;
         let new_cliente = new_cliente.get(0);
         match new_cliente {
             Some(cliente) => {
-                println!("Novo cliente cadastrado: {:?}", cliente);
-                Ok(Cliente::from_row(cliente))
+                let cliente = Cliente::from_row(cliente);
+                info!(cliente_id = *cliente.id(), "Novo cliente cadastrado");
+                Ok(cliente)
             }
-            None => Err(DomainError::Invalid("Cliente".to_string())),
+            None => Err(DomainError::persistence("Cliente não foi criado")),
         }
     }
 
-    async fn delete_cliente(&mut self, cpf: Cpf) -> Result<(), DomainError> {
-        let deleted_cliente = self.client.query_one(DELETE_CLIENTE, &[&cpf.0]).await;
+    #[instrument(level = "debug", skip(self, cpf))]
+    async fn delete_cliente(&self, cpf: Cpf) -> Result<(), DomainError> {
+        let client = self.executor.client().await?;
+        let deleted_cliente = client.query_opt(DELETE_CLIENTE, &[&cpf.0]).await?;
         match deleted_cliente {
-            Ok(_) => Ok(()),
-            _ => Err(DomainError::NotFound),
+            Some(_) => Ok(()),
+            None => Err(DomainError::not_found("Cliente", cpf.0)),
         }
     }
 }
@@ -0,0 +1,79 @@
+use std::sync::Arc;
+
+use deadpool_postgres::Pool;
+use postgres_from_row::FromRow;
+use tracing::{info, instrument};
+
+use crate::base::domain_error::DomainError;
+use crate::entities::estacao::Estacao;
+use crate::entities::loja::LOJA_PADRAO;
+use crate::external::postgres::get_client;
+use crate::traits::estacao_gateway::EstacaoGateway;
+
+pub struct PostgresEstacaoRepository {
+    pool: Pool,
+    loja_id: usize,
+}
+
+const CREATE_ESTACAO: &str = "INSERT INTO estacao (nome, loja_id, data_criacao, data_atualizacao) VALUES ($1, $2, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP) RETURNING id, nome, loja_id, data_criacao, data_atualizacao";
+const UPDATE_ESTACAO: &str = "UPDATE estacao SET nome = $2, data_atualizacao = CURRENT_TIMESTAMP WHERE id = $1 AND loja_id = $3 RETURNING id, nome, loja_id, data_criacao, data_atualizacao";
+const QUERY_ESTACAO_BY_ID: &str = "SELECT id, nome, loja_id, data_criacao, data_atualizacao FROM estacao WHERE id = $1 AND loja_id = $2";
+const QUERY_ESTACOES: &str = "SELECT id, nome, loja_id, data_criacao, data_atualizacao FROM estacao WHERE loja_id = $1 ORDER BY id";
+
+impl PostgresEstacaoRepository {
+    pub async fn new(pool: Pool) -> Self {
+        PostgresEstacaoRepository { pool, loja_id: LOJA_PADRAO }
+    }
+
+    pub fn com_loja(&self, loja_id: usize) -> Self {
+        PostgresEstacaoRepository {
+            pool: self.pool.clone(),
+            loja_id,
+        }
+    }
+
+    fn loja(&self) -> i32 {
+        self.loja_id as i32
+    }
+}
+
+#[async_trait]
+impl EstacaoGateway for PostgresEstacaoRepository {
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn EstacaoGateway + Sync + Send> {
+        Arc::new(self.com_loja(loja_id))
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn get_estacoes(&self) -> Result<Vec<Estacao>, DomainError> {
+        let client = get_client(&self.pool).await?;
+        let estacoes = client.query(QUERY_ESTACOES, &[&self.loja()]).await?;
+        Ok(estacoes.iter().map(Estacao::from_row).collect())
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn get_estacao_by_id(&self, id: usize) -> Result<Estacao, DomainError> {
+        let client = get_client(&self.pool).await?;
+        match client.query_opt(QUERY_ESTACAO_BY_ID, &[&(id as i32), &self.loja()]).await? {
+            Some(estacao) => Ok(Estacao::from_row(&estacao)),
+            None => Err(DomainError::not_found("Estação", id)),
+        }
+    }
+
+    #[instrument(level = "debug", skip(self, estacao))]
+    async fn create_estacao(&self, estacao: Estacao) -> Result<Estacao, DomainError> {
+        let client = get_client(&self.pool).await?;
+        let estacao = Estacao::from_row(&client.query_one(CREATE_ESTACAO, &[estacao.nome(), &self.loja()]).await?);
+        info!(estacao_id = *estacao.id(), loja_id = self.loja_id, "Nova estação cadastrada");
+        Ok(estacao)
+    }
+
+    #[instrument(level = "debug", skip(self, estacao))]
+    async fn update_estacao(&self, estacao: Estacao) -> Result<Estacao, DomainError> {
+        let client = get_client(&self.pool).await?;
+        let id = *estacao.id() as i32;
+        match client.query_opt(UPDATE_ESTACAO, &[&id, estacao.nome(), &self.loja()]).await? {
+            Some(estacao) => Ok(Estacao::from_row(&estacao)),
+            None => Err(DomainError::not_found("Estação", id)),
+        }
+    }
+}
@@ -0,0 +1,60 @@
+use postgres_from_row::FromRow;
+use deadpool_postgres::Pool;
+use tracing::{info, instrument};
+
+use crate::base::domain_error::DomainError;
+use crate::entities::loja::Loja;
+use crate::external::postgres::get_client;
+use crate::traits::loja_gateway::LojaGateway;
+
+pub struct PostgresLojaRepository {
+    pool: Pool,
+}
+
+const CREATE_LOJA: &str = "INSERT INTO loja (nome, data_criacao, data_atualizacao) VALUES ($1, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP) RETURNING id, nome, data_criacao, data_atualizacao";
+const UPDATE_LOJA: &str = "UPDATE loja SET nome = $2, data_atualizacao = CURRENT_TIMESTAMP WHERE id = $1 RETURNING id, nome, data_criacao, data_atualizacao";
+const QUERY_LOJA_BY_ID: &str = "SELECT id, nome, data_criacao, data_atualizacao FROM loja WHERE id = $1";
+const QUERY_LOJAS: &str = "SELECT id, nome, data_criacao, data_atualizacao FROM loja ORDER BY id";
+
+impl PostgresLojaRepository {
+    pub async fn new(pool: Pool) -> Self {
+        PostgresLojaRepository { pool }
+    }
+}
+
+#[async_trait]
+impl LojaGateway for PostgresLojaRepository {
+    #[instrument(level = "debug", skip(self))]
+    async fn get_lojas(&self) -> Result<Vec<Loja>, DomainError> {
+        let client = get_client(&self.pool).await?;
+        let lojas = client.query(QUERY_LOJAS, &[]).await?;
+        Ok(lojas.iter().map(Loja::from_row).collect())
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn get_loja_by_id(&self, id: usize) -> Result<Loja, DomainError> {
+        let client = get_client(&self.pool).await?;
+        match client.query_opt(QUERY_LOJA_BY_ID, &[&(id as i32)]).await? {
+            Some(loja) => Ok(Loja::from_row(&loja)),
+            None => Err(DomainError::not_found("Loja", id)),
+        }
+    }
+
+    #[instrument(level = "debug", skip(self, loja))]
+    async fn create_loja(&self, loja: Loja) -> Result<Loja, DomainError> {
+        let client = get_client(&self.pool).await?;
+        let loja = Loja::from_row(&client.query_one(CREATE_LOJA, &[loja.nome()]).await?);
+        info!(loja_id = *loja.id(), "Nova loja cadastrada");
+        Ok(loja)
+    }
+
+    #[instrument(level = "debug", skip(self, loja))]
+    async fn update_loja(&self, loja: Loja) -> Result<Loja, DomainError> {
+        let client = get_client(&self.pool).await?;
+        let id = *loja.id() as i32;
+        match client.query_opt(UPDATE_LOJA, &[&id, loja.nome()]).await? {
+            Some(loja) => Ok(Loja::from_row(&loja)),
+            None => Err(DomainError::not_found("Loja", id)),
+        }
+    }
+}
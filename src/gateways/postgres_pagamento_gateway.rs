@@ -2,12 +2,13 @@ use bytes::BytesMut;
 use postgres_from_row::FromRow;
 use std::error::Error;
 use std::sync::Arc;
-use tokio::sync::Mutex;
 use tokio_postgres::types::{FromSql, ToSql, Type};
-use tokio_postgres::Client;
+use deadpool_postgres::Pool;
+use tracing::instrument;
 
 use crate::base::domain_error::DomainError;
 use crate::entities::cliente::Cliente;
+use crate::entities::loja::LOJA_PADRAO;
 use crate::entities::pagamento::Pagamento;
 use crate::entities::pedido::Pedido;
 use crate::entities::produto::Produto;
@@ -15,54 +16,72 @@ use crate::traits::cliente_gateway::ClienteGateway;
 use crate::traits::pagamento_gateway::PagamentoGateway;
 use crate::traits::produto_gateway::ProdutoGateway;
 use crate::traits::pedido_gateway::PedidoGateway;
+use crate::external::postgres::PgExecutor;
 
-use crate::external::postgres::table::Table;
 
 
-const CREATE_PAGAMENTO: &str = "INSERT INTO pagamento (id_pedido, estado, valor, metodo, referencia, data_criacao) VALUES ($1, $2, $3, $4, $5, CURRENT_TIMESTAMP) RETURNING id, id_pedido, estado, valor, metodo, referencia, data_criacao";
-const QUERY_PAGAMENTO_BY_ID_PEDIDO: &str = "SELECT * FROM pagamento WHERE id_pedido = $1 order by data_criacao DESC limit 1";
-const UPDATE_PAGAMENTO: &str = "UPDATE pagamento SET id_pedido = $2, estado = $3, valor = $4, metodo = $5, referencia = $6 WHERE id = $1 RETURNING id, id_pedido, estado, metodo, valor, referencia, data_criacao";
+// O pedido precisa ser da loja; sem linha de retorno o pagamento não é criado
+const CREATE_PAGAMENTO: &str = "INSERT INTO pagamento (id_pedido, estado, valor, metodo, referencia, loja_id, data_criacao) SELECT id, $2, $3, $4, $5, loja_id, CURRENT_TIMESTAMP FROM pedido WHERE id = $1 AND loja_id = $6 RETURNING id, id_pedido, estado, valor, metodo, referencia, data_criacao, loja_id";
+const QUERY_PAGAMENTO_BY_ID_PEDIDO: &str = "SELECT * FROM pagamento WHERE id_pedido = $1 AND loja_id = $2 order by data_criacao DESC limit 1";
+const UPDATE_PAGAMENTO: &str = "UPDATE pagamento SET id_pedido = $2, estado = $3, valor = $4, metodo = $5, referencia = $6 WHERE id = $1 AND loja_id = $7 RETURNING id, id_pedido, estado, metodo, valor, referencia, data_criacao, loja_id";
 
 
+// Toda consulta filtra por `loja_id`; `com_loja` cria a cópia que acessa outra loja
 pub struct PostgresPagamentoRepository {
-    client: Client,
-    tables: Vec<Table>,
-    pedido_repository: Arc<Mutex<dyn PedidoGateway + Send + Sync>>,
+    executor: PgExecutor,
+    pedido_repository: Arc<dyn PedidoGateway + Send + Sync>,
+    loja_id: usize,
 }
 
 impl PostgresPagamentoRepository {
     pub async fn new(
-        client: Client,
-        tables: Vec<Table>,
-        pedido_repository: Arc<Mutex<dyn PedidoGateway + Send + Sync>>,
+        pool: Pool,
+        pedido_repository: Arc<dyn PedidoGateway + Send + Sync>,
     ) -> Self {
         let repo = PostgresPagamentoRepository {
-            client,
-            tables,
+            executor: PgExecutor::Pool(pool),
             pedido_repository,
+            loja_id: LOJA_PADRAO,
         };
-        repo.check_for_tables().await;
         repo
     }
 
-    async fn check_for_tables(&self) {
-        for table in self.tables.iter() {
-            let query = table.get_create_if_not_exists_query();
-            self.client.execute(query.as_str(), &[]).await.unwrap();
+    pub fn em_transacao(&self, executor: PgExecutor) -> Self {
+        PostgresPagamentoRepository {
+            executor,
+            pedido_repository: self.pedido_repository.clone(),
+            loja_id: self.loja_id,
         }
     }
 
+    pub fn com_loja(&self, loja_id: usize) -> Self {
+        PostgresPagamentoRepository {
+            executor: self.executor.clone(),
+            pedido_repository: self.pedido_repository.na_loja(loja_id),
+            loja_id,
+        }
+    }
+
+    fn loja(&self) -> i32 {
+        self.loja_id as i32
+    }
+
 }
 
 #[async_trait]
 impl PagamentoGateway for PostgresPagamentoRepository {
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn PagamentoGateway + Sync + Send> {
+        Arc::new(self.com_loja(loja_id))
+    }
+
+    #[instrument(level = "debug", skip(self, pagamento))]
     async fn create_pagamento(
-        &mut self,
+        &self,
         pagamento: Pagamento
     ) -> Result<Pagamento, DomainError> {
+        let client = self.executor.client().await?;
         let _id_pedido = *pagamento.id_pedido() as i32;
-        let new_pagamento = self
-            .client
+        let new_pagamento = client
             .query(
                 CREATE_PAGAMENTO,
                 &[
@@ -71,37 +90,40 @@ impl PagamentoGateway for PostgresPagamentoRepository {
                     &pagamento.valor(),
                     &pagamento.metodo(),
                     &pagamento.referencia(),
+                    &self.loja(),
                 ],
             )
-            .await
-            .unwrap();
+            .await?;
         let new_pagamento = new_pagamento.get(0);
         match new_pagamento {
             Some(pagamento) => Ok(Pagamento::from_row(pagamento)),
-            None => Err(DomainError::Invalid("Pagamento".to_string())),
+            None => Err(DomainError::not_found("Pedido", _id_pedido)),
         }
     }
 
-    async fn get_pagamento_by_id_pedido(&mut self, id_pedido: usize) -> Result<Pagamento, DomainError> {
+    #[instrument(level = "debug", skip(self))]
+    async fn get_pagamento_by_id_pedido(&self, id_pedido: usize) -> Result<Pagamento, DomainError> {
+        let client = self.executor.client().await?;
         let _id_pedido = id_pedido as i32;
 
-        let pagamento_row = self.client.query(QUERY_PAGAMENTO_BY_ID_PEDIDO, &[&_id_pedido]).await.unwrap();
+        let pagamento_row = client.query(QUERY_PAGAMENTO_BY_ID_PEDIDO, &[&_id_pedido, &self.loja()]).await?;
         let pagamento = pagamento_row.get(0);
         match pagamento {
             Some(pagamento) => Ok(Pagamento::from_row(&pagamento)),
-            None => Err(DomainError::NotFound),
+            None => Err(DomainError::not_found("Pagamento do pedido", id_pedido)),
         }
     }
 
+    #[instrument(level = "debug", skip(self, pagamento))]
     async fn update_pagamento(
-        &mut self,
+        &self,
         pagamento: Pagamento
     ) -> Result<Pagamento, DomainError> {
+        let client = self.executor.client().await?;
         let _id = *pagamento.id() as i32;
         let _id_pedido = *pagamento.id_pedido() as i32;
-        let update_pagamento_row = self
-            .client
-            .query_one(
+        let update_pagamento_row = client
+            .query_opt(
                 UPDATE_PAGAMENTO,
                 &[
                     &_id,
@@ -110,19 +132,20 @@ impl PagamentoGateway for PostgresPagamentoRepository {
                     &pagamento.valor(),
                     &pagamento.metodo(),
                     &pagamento.referencia(),
+                    &self.loja(),
                 ],
             )
-            .await;
+            .await?;
         match update_pagamento_row {
-            Ok(row) => {
+            Some(row) => {
                 let updated_pagamento: Pagamento = Pagamento::from_row(&row);
                 Ok(updated_pagamento)
             }
-            Err(_) => Err(DomainError::Invalid("Pagamento".to_string())),
+            None => Err(DomainError::not_found("Pagamento", pagamento.id())),
         }
     }
 
-    // async fn atualiza_status(&mut self, id: usize, status: Status) -> Result<Pagamento, DomainError> {
+    // async fn atualiza_status(&self, id: usize, status: Status) -> Result<Pagamento, DomainError> {
     //     let _id = id as i32;
     //     let updated_pedido = self
     //         .client
@@ -139,3 +162,76 @@ impl PagamentoGateway for PostgresPagamentoRepository {
 
 
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::entities::loja::Loja;
+    use crate::entities::pedido::Status;
+    use crate::external::postgres::PgConnectionManager;
+    use crate::gateways::{
+        postgres_cliente_gateway::PostgresClienteRepository,
+        postgres_loja_gateway::PostgresLojaRepository,
+        postgres_pedido_gateway::PostgresPedidoRepository,
+        postgres_produto_gateway::PostgresProdutoRepository,
+    };
+    use crate::traits::loja_gateway::LojaGateway;
+
+    #[tokio::test]
+    #[ignore = "precisa de um postgres com as migrations aplicadas, ex.: o do docker-compose.dev.yml"]
+    async fn test_pagamento_de_outra_loja_nao_aparece() {
+        let pool = PgConnectionManager::new(std::env::var("DB_URL").expect("DB_URL"), 2).unwrap().pool;
+        let loja = PostgresLojaRepository::new(pool.clone())
+            .await
+            .create_loja(Loja::new(0, "Filial".to_string(), Utc::now(), Utc::now()))
+            .await
+            .unwrap();
+        let pedido_repository = Arc::new(
+            PostgresPedidoRepository::new(
+                pool.clone(),
+                Arc::new(PostgresClienteRepository::new(pool.clone()).await),
+                Arc::new(PostgresProdutoRepository::new(pool.clone()).await),
+            )
+            .await,
+        );
+        let pedido = pedido_repository
+            .com_loja(*loja.id())
+            .create_pedido(Pedido::new(
+                0,
+                None,
+                None,
+                None,
+                None,
+                "Mercado Pago".to_string(),
+                Status::Finalizado,
+                Utc::now(),
+                Utc::now(),
+            ))
+            .await
+            .unwrap();
+        let padrao = PostgresPagamentoRepository::new(pool, pedido_repository).await;
+        let filial = padrao.com_loja(*loja.id());
+        let pagamento = Pagamento::new(
+            0,
+            *pedido.id(),
+            "pendente".to_string(),
+            10.0,
+            "Mercado Pago".to_string(),
+            String::new(),
+            Utc::now(),
+        );
+
+        // O pedido é de outra loja, então nem o pagamento pode ser criado aqui
+        assert!(matches!(
+            padrao.create_pagamento(pagamento.clone()).await,
+            Err(DomainError::NotFound { .. })
+        ));
+        let pagamento = filial.create_pagamento(pagamento).await.unwrap();
+        assert_eq!(pagamento.loja_id(), *loja.id());
+
+        assert!(padrao.get_pagamento_by_id_pedido(*pedido.id()).await.is_err());
+        assert!(padrao.update_pagamento(pagamento.clone()).await.is_err());
+        assert_eq!(filial.get_pagamento_by_id_pedido(*pedido.id()).await.unwrap().id(), pagamento.id());
+    }
+}
@@ -1,34 +1,50 @@
 use bytes::BytesMut;
+use chrono::{DateTime, Utc};
 use postgres_from_row::FromRow;
+use std::collections::HashMap;
 use std::error::Error;
 use std::sync::Arc;
-use tokio::sync::Mutex;
 use tokio_postgres::types::{FromSql, ToSql, Type};
-use tokio_postgres::Client;
+use deadpool_postgres::Pool;
+use tracing::{info, instrument};
 
 use crate::base::domain_error::DomainError;
 use crate::entities::cliente::Cliente;
+use crate::entities::estacao::StatusDoTicket;
+use crate::entities::loja::LOJA_PADRAO;
 use crate::entities::pagamento::Pagamento;
 use crate::entities::pedido::{Pedido, Status};
+use crate::entities::preco_historico::PrecoHistorico;
 use crate::entities::produto::Produto;
+use crate::entities::tempo_de_preparo::{MudancaDeStatus, TempoDePreparo};
+use crate::gateways::postgres_cliente_gateway::PostgresClienteRepository;
+use crate::gateways::postgres_produto_gateway::PostgresProdutoRepository;
 use crate::traits::cliente_gateway::ClienteGateway;
 use crate::traits::pedido_gateway::PedidoGateway;
 use crate::traits::produto_gateway::ProdutoGateway;
 
-use crate::external::postgres::pedido::ProxyPedido;
-use crate::external::postgres::table::Table;
-
-const CREATE_PEDIDO: &str = "INSERT INTO pedido (cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, status, data_criacao, data_atualizacao) VALUES ($1, $2, $3, $4, $5, $6, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP) RETURNING id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao";
-const QUERY_PEDIDOS: &str = "SELECT id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao FROM pedido where  status  <> 'Finalizado' order by array_position(array['Pronto','EmPreparacao', 'Recebido'], CAST(status AS VARCHAR)), data_criacao asc";
-const QUERY_PEDIDO_BY_ID: &str = "SELECT id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao FROM pedido WHERE id = $1";
-const QUERY_PEDIDOS_NOVOS: &str = "SELECT id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao FROM pedido WHERE status IN ('Pendente', 'EmPreparacao')";
-const SET_PEDIDO_STATUS: &str = "UPDATE pedido SET status = $2, data_atualizacao = CURRENT_TIMESTAMP WHERE id = $1 RETURNING id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao";
-const SET_PEDIDO_CLIENTE: &str = "UPDATE pedido SET cliente_id = $2 WHERE id = $1 RETURNING id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao";
-const SET_PEDIDO_LANCHE: &str = "UPDATE pedido SET lanche_id = $2 WHERE id = $1 RETURNING id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao";
-const SET_PEDIDO_ACOMPANHAMENTO: &str = "UPDATE pedido SET acompanhamento_id = $2 WHERE id = $1 RETURNING id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao";
-const SET_PEDIDO_BEBIDA: &str = "UPDATE pedido SET bebida_id = $2 WHERE id = $1 RETURNING id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao";
-const SET_PEDIDO_PAGAMENTO: &str = "UPDATE pedido SET pagamento = $2 WHERE id = $1 RETURNING id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao";
-const CREATE_PAGAMENTO: &str = "INSERT INTO pagamento (id_pedido, estado, metodo, referencia, data_criacao) VALUES ($1, $2, $3, $4, CURRENT_TIMESTAMP)";
+use crate::external::postgres::pedido::{mudanca_de_status_from_row, status_do_ticket_from_row, ProxyPedido};
+use crate::external::postgres::PgExecutor;
+
+// A criação e as mudanças de status gravam também o histórico, na mesma instrução
+const CREATE_PEDIDO: &str = "WITH novo AS (INSERT INTO pedido (cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, status, loja_id, data_criacao, data_atualizacao) VALUES ($1, $2, $3, $4, $5, $6, $7, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP) RETURNING id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, status, data_criacao, data_atualizacao), historico AS (INSERT INTO pedido_status (pedido_id, status, data) SELECT id, status, data_criacao FROM novo) SELECT id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR) AS status, data_criacao, data_atualizacao FROM novo";
+const QUERY_PEDIDOS: &str = "SELECT id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao FROM pedido where loja_id = $1 AND status  <> 'Finalizado' ORDER BY id";
+const QUERY_PEDIDO_BY_ID: &str = "SELECT id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao FROM pedido WHERE id = $1 AND loja_id = $2";
+// Os status são os de `STATUS_NA_FILA_DA_COZINHA`
+const QUERY_PEDIDOS_NOVOS: &str = "SELECT id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao FROM pedido WHERE loja_id = $1 AND status IN ('Pago', 'EmPreparacao') ORDER BY id";
+const SET_PEDIDO_STATUS: &str = "WITH atualizado AS (UPDATE pedido SET status = $2, data_atualizacao = CURRENT_TIMESTAMP WHERE id = $1 AND loja_id = $3 RETURNING id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, status, data_criacao, data_atualizacao), historico AS (INSERT INTO pedido_status (pedido_id, status, data) SELECT id, status, data_atualizacao FROM atualizado) SELECT id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR) AS status, data_criacao, data_atualizacao FROM atualizado";
+const SET_PEDIDO_CLIENTE: &str = "UPDATE pedido SET cliente_id = $2 WHERE id = $1 AND loja_id = $3 RETURNING id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao";
+const SET_PEDIDO_LANCHE: &str = "UPDATE pedido SET lanche_id = $2 WHERE id = $1 AND loja_id = $3 RETURNING id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao";
+const SET_PEDIDO_ACOMPANHAMENTO: &str = "UPDATE pedido SET acompanhamento_id = $2 WHERE id = $1 AND loja_id = $3 RETURNING id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao";
+const SET_PEDIDO_BEBIDA: &str = "UPDATE pedido SET bebida_id = $2 WHERE id = $1 AND loja_id = $3 RETURNING id, cliente_id, lanche_id, acompanhamento_id, bebida_id, pagamento, CAST(status AS VARCHAR), data_criacao, data_atualizacao";
+const QUERY_HISTORICO_DE_STATUS: &str = "SELECT pedido_id, CAST(status AS VARCHAR) AS status, data FROM pedido_status WHERE pedido_id = ANY($1) ORDER BY data, id";
+// Um pedido conta uma vez para cada item; a entrada na fila é o pagamento ou, sem ele, a criação
+const QUERY_TEMPOS_DE_PREPARO: &str = "WITH tempos AS (SELECT p.lanche_id, p.acompanhamento_id, p.bebida_id, COALESCE(MIN(s.data) FILTER (WHERE s.status = 'Pago'), MIN(p.data_criacao)) AS entrada, MIN(s.data) FILTER (WHERE s.status = 'EmPreparacao') AS inicio, MIN(s.data) FILTER (WHERE s.status = 'Pronto') AS pronto FROM pedido p JOIN pedido_status s ON s.pedido_id = p.id WHERE p.loja_id = $1 GROUP BY p.id) SELECT produto_id, COUNT(*) AS pedidos, AVG(GREATEST(EXTRACT(EPOCH FROM inicio - entrada), 0))::FLOAT8 AS espera_media_segundos, AVG(GREATEST(EXTRACT(EPOCH FROM pronto - inicio), 0))::FLOAT8 AS preparo_medio_segundos FROM tempos, unnest(ARRAY[lanche_id, acompanhamento_id, bebida_id]) AS produto_id WHERE produto_id IS NOT NULL AND inicio IS NOT NULL AND pronto >= $2 GROUP BY produto_id ORDER BY produto_id";
+const QUERY_STATUS_DOS_TICKETS: &str = "SELECT pedido_id, estacao_id, status, data_atualizacao FROM pedido_estacao WHERE pedido_id = ANY($1)";
+// Trava o pedido até o fim da transação, para que duas estações terminando juntas não deixem de ver uma à outra
+const SET_STATUS_DO_TICKET: &str = "WITH bloqueado AS (SELECT id FROM pedido WHERE id = $1 AND loja_id = $4 FOR UPDATE) INSERT INTO pedido_estacao (pedido_id, estacao_id, status, data_atualizacao) SELECT id, $2, $3, CURRENT_TIMESTAMP FROM bloqueado ON CONFLICT (pedido_id, estacao_id) DO UPDATE SET status = EXCLUDED.status, data_atualizacao = EXCLUDED.data_atualizacao RETURNING pedido_id";
+// O pedido precisa ser da loja; sem linha de retorno o pagamento não é criado
+const CREATE_PAGAMENTO: &str = "INSERT INTO pagamento (id_pedido, estado, valor, metodo, referencia, loja_id, data_criacao) SELECT id, $2, $3, $4, $5, loja_id, CURRENT_TIMESTAMP FROM pedido WHERE id = $1 AND loja_id = $6 RETURNING id, id_pedido, estado, valor, metodo, referencia, data_criacao, loja_id";
 
 impl<'a> FromSql<'a> for Status {
     fn from_sql(
@@ -86,126 +102,280 @@ impl ToSql for Status {
     }
 }
 
+// Toda consulta filtra por `loja_id`; `com_loja` cria a cópia que acessa outra loja.
+// Os métodos devolvem a conexão antes de montar os pedidos, que usam outras do pool.
+// Clientes e produtos que também estão no postgres são guardados com o tipo concreto,
+// para que a cópia em transação os leia pela conexão e pelo snapshot dela
 pub struct PostgresPedidoRepository {
-    client: Client,
-    tables: Vec<Table>,
-    cliente_repository: Arc<Mutex<dyn ClienteGateway + Send + Sync>>,
-    produto_repository: Arc<Mutex<dyn ProdutoGateway + Send + Sync>>,
+    executor: PgExecutor,
+    cliente_repository: Arc<dyn ClienteGateway + Send + Sync>,
+    produto_repository: Arc<dyn ProdutoGateway + Send + Sync>,
+    clientes_no_postgres: Option<Arc<PostgresClienteRepository>>,
+    produtos_no_postgres: Option<Arc<PostgresProdutoRepository>>,
+    loja_id: usize,
 }
 
 impl PostgresPedidoRepository {
     pub async fn new(
-        client: Client,
-        tables: Vec<Table>,
-        cliente_repository: Arc<Mutex<dyn ClienteGateway + Send + Sync>>,
-        produto_repository: Arc<Mutex<dyn ProdutoGateway + Send + Sync>>,
+        pool: Pool,
+        cliente_repository: Arc<dyn ClienteGateway + Send + Sync>,
+        produto_repository: Arc<dyn ProdutoGateway + Send + Sync>,
     ) -> Self {
         let repo = PostgresPedidoRepository {
-            client,
-            tables,
+            executor: PgExecutor::Pool(pool),
             cliente_repository,
             produto_repository,
+            clientes_no_postgres: None,
+            produtos_no_postgres: None,
+            loja_id: LOJA_PADRAO,
         };
-        repo.check_for_tables().await;
         repo
     }
 
-    async fn check_for_tables(&self) {
-        for table in self.tables.iter() {
-            let query = table.get_create_if_not_exists_query();
-            self.client.execute(query.as_str(), &[]).await.unwrap();
-        }
+    pub fn com_clientes_no_postgres(mut self, clientes: PostgresClienteRepository) -> Self {
+        let clientes = Arc::new(clientes);
+        self.cliente_repository = clientes.clone();
+        self.clientes_no_postgres = Some(clientes);
+        self
     }
 
-    async fn pedido_from_proxy(&self, pedido_row: &tokio_postgres::Row) -> Pedido {
-        let _pedido: ProxyPedido = ProxyPedido::from_row(&pedido_row);
+    pub fn com_produtos_no_postgres(mut self, produtos: PostgresProdutoRepository) -> Self {
+        let produtos = Arc::new(produtos);
+        self.produto_repository = produtos.clone();
+        self.produtos_no_postgres = Some(produtos);
+        self
+    }
 
-        let cliente = if let Some(cliente_id) = _pedido.cliente_id() {
-            let cliente_repo = self.cliente_repository.lock().await;
-            cliente_repo.get_cliente_by_id(*cliente_id).await.ok()
-        } else {
-            None
+    pub fn em_transacao(&self, executor: PgExecutor) -> Self {
+        let repository = PostgresPedidoRepository {
+            executor: executor.clone(),
+            cliente_repository: self.cliente_repository.clone(),
+            produto_repository: self.produto_repository.clone(),
+            clientes_no_postgres: None,
+            produtos_no_postgres: None,
+            loja_id: self.loja_id,
         };
-
-        let lanche = if let Some(lanche_id) = _pedido.lanche_id() {
-            let produto_repo = self.produto_repository.lock().await;
-            produto_repo.get_produto_by_id(*lanche_id).await.ok()
-        } else {
-            None
+        let repository = match &self.clientes_no_postgres {
+            Some(clientes) => repository.com_clientes_no_postgres(clientes.em_transacao(executor.clone())),
+            None => repository,
         };
+        match &self.produtos_no_postgres {
+            Some(produtos) => repository.com_produtos_no_postgres(produtos.em_transacao(executor)),
+            None => repository,
+        }
+    }
 
-        let bebida = if let Some(bebida_id) = _pedido.bebida_id() {
-            let produto_repo = self.produto_repository.lock().await;
-            produto_repo.get_produto_by_id(*bebida_id).await.ok()
-        } else {
-            None
-        };
+    // Os produtos dos pedidos também são buscados só na loja
+    pub fn com_loja(&self, loja_id: usize) -> Self {
+        PostgresPedidoRepository {
+            executor: self.executor.clone(),
+            cliente_repository: self.cliente_repository.clone(),
+            produto_repository: self.produto_repository.na_loja(loja_id),
+            clientes_no_postgres: self.clientes_no_postgres.clone(),
+            produtos_no_postgres: self
+                .produtos_no_postgres
+                .as_ref()
+                .map(|produtos| Arc::new(produtos.com_loja(loja_id))),
+            loja_id,
+        }
+    }
 
-        let acompanhamento = if let Some(acompanhamento_id) = _pedido.acompanhamento_id() {
-            let produto_repo = self.produto_repository.lock().await;
-            produto_repo
-                .get_produto_by_id(*acompanhamento_id)
-                .await
-                .ok()
-        } else {
-            None
-        };
+    fn loja(&self) -> i32 {
+        self.loja_id as i32
+    }
+
+    async fn pedido_from_proxy(&self, pedido_row: &tokio_postgres::Row) -> Result<Pedido, DomainError> {
+        Ok(self
+            .pedidos_from_rows(std::slice::from_ref(pedido_row))
+            .await?
+            .remove(0))
+    }
 
-        Pedido::new(
-            *_pedido.id(),
-            cliente,
-            lanche,
-            acompanhamento,
-            bebida,
-            _pedido.pagamento().clone(),
-            _pedido.status().clone(),
-            _pedido.data_criacao().clone(),
-            _pedido.data_atualizacao().clone(),
+    async fn pedidos_from_rows(&self, pedido_rows: &[tokio_postgres::Row]) -> Result<Vec<Pedido>, DomainError> {
+        let proxies: Vec<ProxyPedido> = pedido_rows.iter().map(ProxyPedido::from_row).collect();
+        let mut pedidos = carregar_pedidos(
+            self.cliente_repository.as_ref(),
+            self.produto_repository.as_ref(),
+            proxies,
         )
+        .await?;
+        let ids: Vec<i32> = pedidos.iter().map(|pedido| *pedido.id() as i32).collect();
+        let client = self.executor.client().await?;
+        let mut historicos: HashMap<usize, Vec<MudancaDeStatus>> = HashMap::new();
+        for row in client.query(QUERY_HISTORICO_DE_STATUS, &[&ids]).await? {
+            let (pedido_id, mudanca) = mudanca_de_status_from_row(&row)?;
+            historicos.entry(pedido_id).or_default().push(mudanca);
+        }
+        let status_dos_tickets = client.query(QUERY_STATUS_DOS_TICKETS, &[&ids]).await?;
+        drop(client);
+        // As consultas já filtram pela loja, então todas as linhas são dela
+        for pedido in pedidos.iter_mut() {
+            pedido.set_loja_id(self.loja_id);
+            pedido.set_historico_de_status(historicos.remove(pedido.id()).unwrap_or_default());
+        }
+        for row in status_dos_tickets {
+            let (pedido_id, estacao_id, status, data) = status_do_ticket_from_row(&row)?;
+            // Estações que não têm mais itens do pedido ficam de fora
+            if let Some(pedido) = pedidos.iter_mut().find(|pedido| *pedido.id() == pedido_id) {
+                if let Err(err) = pedido.registrar_status_do_ticket(estacao_id, status, data) {
+                    info!(pedido_id, estacao_id, error = %err, "Ticket de estação sem itens do pedido ignorado");
+                }
+            }
+        }
+        Ok(pedidos)
     }
+}
+
+// Resolve clientes e produtos de todos os pedidos com uma busca em lote por gateway,
+// então o número de consultas não depende da quantidade de pedidos. Uma falha em
+// qualquer busca falha a carga toda, em vez de devolver pedidos sem os itens
+async fn carregar_pedidos(
+    cliente_repository: &(dyn ClienteGateway + Send + Sync),
+    produto_repository: &(dyn ProdutoGateway + Send + Sync),
+    proxies: Vec<ProxyPedido>,
+) -> Result<Vec<Pedido>, DomainError> {
+    let mut cliente_ids: Vec<usize> = proxies
+        .iter()
+        .filter_map(|pedido| pedido.cliente_id().copied())
+        .collect();
+    cliente_ids.sort_unstable();
+    cliente_ids.dedup();
+
+    let mut produto_ids: Vec<usize> = proxies
+        .iter()
+        .flat_map(|pedido| {
+            [
+                pedido.lanche_id(),
+                pedido.acompanhamento_id(),
+                pedido.bebida_id(),
+            ]
+        })
+        .flatten()
+        .copied()
+        .collect();
+    produto_ids.sort_unstable();
+    produto_ids.dedup();
+
+    let clientes: HashMap<usize, Cliente> = if cliente_ids.is_empty() {
+        HashMap::new()
+    } else {
+        cliente_repository
+            .get_clientes_by_ids(cliente_ids)
+            .await?
+            .into_iter()
+            .map(|cliente| (*cliente.id(), cliente))
+            .collect()
+    };
 
+    let (produtos, historico): (HashMap<usize, Produto>, Vec<PrecoHistorico>) = if produto_ids.is_empty() {
+        (HashMap::new(), Vec::new())
+    } else {
+        let historico = produto_repository
+            .get_historico_de_precos(produto_ids.clone())
+            .await?;
+        let produtos = produto_repository
+            .get_produtos_by_ids(produto_ids)
+            .await?
+            .into_iter()
+            .map(|produto| (*produto.id(), produto))
+            .collect();
+        (produtos, historico)
+    };
+
+    // Cada item sai com o preço de quando o pedido foi feito, não com o preço atual
+    let produto = |id: Option<&usize>, data: &DateTime<Utc>| {
+        id.and_then(|id| produtos.get(id).cloned())
+            .map(|produto| produto.no_preco_de(&historico, data))
+    };
+
+    Ok(proxies
+        .into_iter()
+        .map(|_pedido| {
+            Pedido::new(
+                *_pedido.id(),
+                _pedido.cliente_id().and_then(|id| clientes.get(id).cloned()),
+                produto(_pedido.lanche_id(), _pedido.data_criacao()),
+                produto(_pedido.acompanhamento_id(), _pedido.data_criacao()),
+                produto(_pedido.bebida_id(), _pedido.data_criacao()),
+                _pedido.pagamento().clone(),
+                _pedido.status().clone(),
+                *_pedido.data_criacao(),
+                *_pedido.data_atualizacao(),
+            )
+        })
+        .collect())
 }
 
 #[async_trait]
 impl PedidoGateway for PostgresPedidoRepository {
-    async fn lista_pedidos(&mut self) -> Result<Vec<Pedido>, DomainError> {
-        let pedidos = self.client.query(QUERY_PEDIDOS, &[]).await.unwrap();
-        let mut pedidos_vec = Vec::new();
-        for pedido in pedidos {
-            let _pedido = self.pedido_from_proxy(&pedido).await;
-            pedidos_vec.push(_pedido.clone());
-        }
-        Ok(pedidos_vec)
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn PedidoGateway + Sync + Send> {
+        Arc::new(self.com_loja(loja_id))
     }
 
-    async fn get_pedidos_novos(&self) -> Result<Vec<Pedido>, DomainError> {
-        let pedidos_rows = self.client.query(QUERY_PEDIDOS_NOVOS, &[]).await.unwrap();
-        let mut pedidos_vec = Vec::new();
+    #[instrument(level = "debug", skip(self))]
+    async fn lista_pedidos(&self) -> Result<Vec<Pedido>, DomainError> {
+        let client = self.executor.client().await?;
+        let pedidos = client.query(QUERY_PEDIDOS, &[&self.loja()]).await?;
+        drop(client);
+        self.pedidos_from_rows(&pedidos).await
+    }
 
-        for pedido_row in pedidos_rows {
-            let pedido = self.pedido_from_proxy(&pedido_row).await;
-            pedidos_vec.push(pedido);
-        }
+    #[instrument(level = "debug", skip(self))]
+    async fn get_tempos_de_preparo(&self, desde: DateTime<Utc>) -> Result<Vec<TempoDePreparo>, DomainError> {
+        let client = self.executor.client().await?;
+        let tempos = client.query(QUERY_TEMPOS_DE_PREPARO, &[&self.loja(), &desde]).await?;
+        Ok(tempos.iter().map(TempoDePreparo::from_row).collect())
+    }
 
-        Ok(pedidos_vec)
+    #[instrument(level = "debug", skip(self))]
+    async fn get_pedidos_novos(&self) -> Result<Vec<Pedido>, DomainError> {
+        let client = self.executor.client().await?;
+        let pedidos_rows = client.query(QUERY_PEDIDOS_NOVOS, &[&self.loja()]).await?;
+        drop(client);
+        self.pedidos_from_rows(&pedidos_rows).await
     }
 
-    async fn atualiza_status(&mut self, id: usize, status: Status) -> Result<Pedido, DomainError> {
+    #[instrument(level = "debug", skip(self, status))]
+    async fn atualiza_status(&self, id: usize, status: Status) -> Result<Pedido, DomainError> {
+        let client = self.executor.client().await?;
         let _id = id as i32;
-        let updated_pedido = self
-            .client
-            .query(SET_PEDIDO_STATUS, &[&_id, &status])
-            .await
-            .unwrap();
+        let updated_pedido = client
+            .query(SET_PEDIDO_STATUS, &[&_id, &status, &self.loja()])
+            .await?;
+        drop(client);
 
         let updated_pedido = updated_pedido.get(0);
         match updated_pedido {
-            Some(pedido) => Ok(self.pedido_from_proxy(&pedido).await),
-            None => Err(DomainError::NotFound),
+            Some(pedido) => self.pedido_from_proxy(pedido).await,
+            None => Err(DomainError::not_found("Pedido", id)),
         }
     }
 
-    async fn create_pedido(&mut self, pedido: Pedido) -> Result<Pedido, DomainError> {
+    #[instrument(level = "debug", skip(self))]
+    async fn atualiza_status_do_ticket(
+        &self,
+        pedido_id: usize,
+        estacao_id: usize,
+        status: StatusDoTicket,
+    ) -> Result<Pedido, DomainError> {
+        let client = self.executor.client().await?;
+        let atualizado = client
+            .query_opt(
+                SET_STATUS_DO_TICKET,
+                &[&(pedido_id as i32), &(estacao_id as i32), &status.to_string(), &self.loja()],
+            )
+            .await?;
+        // Devolve a conexão antes de recarregar o pedido, que usa outras do pool
+        drop(client);
+        match atualizado {
+            Some(_) => self.get_pedido_by_id(pedido_id).await,
+            None => Err(DomainError::not_found("Pedido", pedido_id)),
+        }
+    }
+
+    #[instrument(level = "debug", skip(self, pedido))]
+    async fn create_pedido(&self, pedido: Pedido) -> Result<Pedido, DomainError> {
+        let client = self.executor.client().await?;
         let cliente_id = pedido.cliente().map(|cliente| *cliente.id() as i32);
         let lanche_id = pedido.lanche().map(|lanche| *lanche.id() as i32);
         let acompanhamento_id = pedido
@@ -214,8 +384,7 @@ impl PedidoGateway for PostgresPedidoRepository {
         let bebida_id = pedido.bebida().map(|bebida| *bebida.id() as i32);
 
         let status = pedido.status();
-        let new_pedido_row = self
-            .client
+        let new_pedido_row = client
             .query_one(
                 CREATE_PEDIDO,
                 &[
@@ -225,147 +394,725 @@ impl PedidoGateway for PostgresPedidoRepository {
                     &bebida_id,
                     &pedido.pagamento(),
                     &status,
+                    &self.loja(),
                 ],
             )
             .await;
+        drop(client);
         match new_pedido_row {
             Ok(row) => {
-                let new_pedido = self.pedido_from_proxy(&row).await;
-                println!("Novo pedido cadastrado: {:?}", new_pedido);
+                let new_pedido = self.pedido_from_proxy(&row).await?;
+                info!(pedido_id = *new_pedido.id(), "Novo pedido cadastrado");
                 Ok(new_pedido)
             }
-            Err(_) => Err(DomainError::Invalid("Pedido".to_string())),
+            Err(err) => Err(err.into()),
         }
     }
 
+    #[instrument(level = "debug", skip(self))]
     async fn get_pedido_by_id(&self, pedido_id: usize) -> Result<Pedido, DomainError> {
+        let client = self.executor.client().await?;
         let pedido_id = pedido_id as i32;
-        let pedido_row_result = self
-            .client
-            .query_opt(QUERY_PEDIDO_BY_ID, &[&pedido_id])
+        let pedido_row_result = client
+            .query_opt(QUERY_PEDIDO_BY_ID, &[&pedido_id, &self.loja()])
             .await;
+        drop(client);
 
         match pedido_row_result {
-            Ok(Some(row)) => {
-                let pedido = self.pedido_from_proxy(&row).await;
-                Ok(pedido)
-            }
-            Ok(None) => Err(DomainError::NotFound),
-            Err(_) => Err(DomainError::Invalid("Pedido".to_string())),
+            Ok(Some(row)) => self.pedido_from_proxy(&row).await,
+            Ok(None) => Err(DomainError::not_found("Pedido", pedido_id)),
+            Err(err) => Err(err.into()),
         }
     }
 
+    #[instrument(level = "debug", skip(self, cliente))]
     async fn cadastrar_cliente(
-        &mut self,
+        &self,
         pedido_id: usize,
         cliente: Cliente,
     ) -> Result<Pedido, DomainError> {
+        let client = self.executor.client().await?;
         let _pedido_id: i32 = pedido_id as i32;
         let _cliente_id = *cliente.id() as i32;
 
-        let updated_pedido = self
-            .client
-            .query(SET_PEDIDO_CLIENTE, &[&_pedido_id, &_cliente_id])
-            .await
-            .unwrap();
+        let updated_pedido = client
+            .query(SET_PEDIDO_CLIENTE, &[&_pedido_id, &_cliente_id, &self.loja()])
+            .await?;
+        drop(client);
 
         let updated_pedido = updated_pedido.get(0);
         match updated_pedido {
-            Some(pedido) => Ok(self.pedido_from_proxy(&pedido).await),
-            None => Err(DomainError::NotFound),
+            Some(pedido) => self.pedido_from_proxy(pedido).await,
+            None => Err(DomainError::not_found("Pedido", pedido_id)),
         }
     }
 
+    #[instrument(level = "debug", skip(self, lanche))]
     async fn cadastrar_lanche(
-        &mut self,
+        &self,
         pedido_id: usize,
         lanche: Produto,
     ) -> Result<Pedido, DomainError> {
+        let client = self.executor.client().await?;
         let _pedido_id: i32 = pedido_id as i32;
         let _lanche_id = *lanche.id() as i32;
 
-        let updated_pedido = self
-            .client
-            .query(SET_PEDIDO_LANCHE, &[&_pedido_id, &_lanche_id])
-            .await
-            .unwrap();
+        let updated_pedido = client
+            .query(SET_PEDIDO_LANCHE, &[&_pedido_id, &_lanche_id, &self.loja()])
+            .await?;
+        drop(client);
 
         let updated_pedido = updated_pedido.get(0);
         match updated_pedido {
-            Some(pedido) => Ok(self.pedido_from_proxy(&pedido).await),
-            None => Err(DomainError::NotFound),
+            Some(pedido) => self.pedido_from_proxy(pedido).await,
+            None => Err(DomainError::not_found("Pedido", pedido_id)),
         }
     }
 
+    #[instrument(level = "debug", skip(self, acompanhamento))]
     async fn cadastrar_acompanhamento(
-        &mut self,
+        &self,
         pedido_id: usize,
         acompanhamento: Produto,
     ) -> Result<Pedido, DomainError> {
+        let client = self.executor.client().await?;
         let _pedido_id: i32 = pedido_id as i32;
         let _acompanhamento_id = *acompanhamento.id() as i32;
 
-        let updated_pedido = self
-            .client
+        let updated_pedido = client
             .query(
                 SET_PEDIDO_ACOMPANHAMENTO,
-                &[&_pedido_id, &_acompanhamento_id],
+                &[&_pedido_id, &_acompanhamento_id, &self.loja()],
             )
-            .await
-            .unwrap();
+            .await?;
+        drop(client);
 
         let updated_pedido = updated_pedido.get(0);
         match updated_pedido {
-            Some(pedido) => Ok(self.pedido_from_proxy(&pedido).await),
-            None => Err(DomainError::NotFound),
+            Some(pedido) => self.pedido_from_proxy(pedido).await,
+            None => Err(DomainError::not_found("Pedido", pedido_id)),
         }
     }
 
+    #[instrument(level = "debug", skip(self, bebida))]
     async fn cadastrar_bebida(
-        &mut self,
+        &self,
         pedido_id: usize,
         bebida: Produto,
     ) -> Result<Pedido, DomainError> {
+        let client = self.executor.client().await?;
         let _pedido_id: i32 = pedido_id as i32;
         let _bebida_id = *bebida.id() as i32;
 
-        let updated_pedido = self
-            .client
-            .query(SET_PEDIDO_BEBIDA, &[&_pedido_id, &_bebida_id])
-            .await
-            .unwrap();
+        let updated_pedido = client
+            .query(SET_PEDIDO_BEBIDA, &[&_pedido_id, &_bebida_id, &self.loja()])
+            .await?;
+        drop(client);
 
         let updated_pedido = updated_pedido.get(0);
         match updated_pedido {
-            Some(pedido) => Ok(self.pedido_from_proxy(&pedido).await),
-            None => Err(DomainError::NotFound),
+            Some(pedido) => self.pedido_from_proxy(pedido).await,
+            None => Err(DomainError::not_found("Pedido", pedido_id)),
         }
     }
 
+    #[instrument(level = "debug", skip(self, pagamento))]
     async fn cadastrar_pagamento(
-        &mut self,
+        &self,
         pagamento: Pagamento
     ) -> Result<Pagamento, DomainError> {
+        let client = self.executor.client().await?;
         let _id_pedido = *pagamento.id_pedido() as i32;
-        let new_pagamento_row = self
-            .client
-            .query_one(
+        let new_pagamento_row = client
+            .query_opt(
                 CREATE_PAGAMENTO,
                 &[
                     &_id_pedido,
                     &String::from("pendente"),
+                    &pagamento.valor(),
                     &pagamento.metodo(),
                     &pagamento.referencia(),
+                    &self.loja(),
                 ],
             )
-            .await;
+            .await?;
         match new_pagamento_row {
-            Ok(row) => {
+            Some(row) => {
                 let new_pagamento = Pagamento::from_row(&row);
-                println!("Novo pagamento cadastrado: {:?}", new_pagamento);
+                info!(pagamento_id = *new_pagamento.id(), "Novo pagamento cadastrado");
                 Ok(new_pagamento)
             }
-            Err(_) => Err(DomainError::Invalid("Pagamento".to_string())),
+            None => Err(DomainError::not_found("Pedido", _id_pedido)),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{cpf::Cpf, ingredientes::Ingredientes, produto::Categoria};
+    use crate::traits::{cliente_gateway::MockClienteGateway, produto_gateway::MockProdutoGateway};
+    use chrono::Duration;
+
+    fn produto(id: usize, categoria: Categoria) -> Produto {
+        Produto::new(
+            id,
+            format!("Produto {}", id),
+            "foto.png".to_string(),
+            "descricao".to_string(),
+            categoria,
+            10.0,
+            Ingredientes::new(vec![]).unwrap(),
+            Utc::now(),
+            Utc::now(),
+        )
+    }
+
+    fn proxies(quantidade: usize) -> Vec<ProxyPedido> {
+        (1..=quantidade)
+            .map(|id| {
+                ProxyPedido::new(
+                    id,
+                    Some(id % 3 + 1),
+                    Some(1),
+                    Some(2),
+                    Some(3),
+                    "Mercado Pago".to_string(),
+                    Status::Pendente,
+                    Utc::now(),
+                    Utc::now(),
+                )
+            })
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn test_carregar_pedidos_consultas_independem_da_quantidade() {
+        for quantidade in [1, 200] {
+            let mut mock_cliente_repository = MockClienteGateway::new();
+            let mut mock_produto_repository = MockProdutoGateway::new();
+
+            mock_cliente_repository.expect_get_cliente_by_id().never();
+            mock_cliente_repository
+                .expect_get_clientes_by_ids()
+                .times(1)
+                .returning(|ids| {
+                    Ok(ids
+                        .into_iter()
+                        .map(|id| {
+                            Cliente::new(
+                                id,
+                                "nome".to_string(),
+                                "email".to_string(),
+                                Cpf::new("000.000.000-00".to_string()).unwrap(),
+                                Utc::now(),
+                                Utc::now(),
+                            )
+                        })
+                        .collect())
+                });
+
+            mock_produto_repository.expect_get_produto_by_id().never();
+            mock_produto_repository
+                .expect_get_produtos_by_ids()
+                .times(1)
+                .returning(|ids| {
+                    assert_eq!(ids, vec![1, 2, 3]);
+                    Ok(vec![
+                        produto(1, Categoria::Lanche),
+                        produto(2, Categoria::Acompanhamento),
+                        produto(3, Categoria::Bebida),
+                    ])
+                });
+            mock_produto_repository
+                .expect_get_historico_de_precos()
+                .times(1)
+                .returning(|_| Ok(vec![]));
+
+            let pedidos = carregar_pedidos(
+                &mock_cliente_repository,
+                &mock_produto_repository,
+                proxies(quantidade),
+            )
+            .await
+            .unwrap();
+
+            assert_eq!(pedidos.len(), quantidade);
+            for pedido in pedidos.iter() {
+                assert_eq!(*pedido.cliente().unwrap().id(), pedido.id() % 3 + 1);
+                assert_eq!(*pedido.lanche().unwrap().id(), 1);
+                assert_eq!(*pedido.acompanhamento().unwrap().id(), 2);
+                assert_eq!(*pedido.bebida().unwrap().id(), 3);
+            }
         }
     }
+
+    #[tokio::test]
+    async fn test_carregar_pedidos_sem_relacionamentos_nao_consulta() {
+        let mut mock_cliente_repository = MockClienteGateway::new();
+        let mut mock_produto_repository = MockProdutoGateway::new();
+        mock_cliente_repository.expect_get_clientes_by_ids().never();
+        mock_produto_repository.expect_get_produtos_by_ids().never();
+        mock_produto_repository.expect_get_historico_de_precos().never();
+
+        let pedido = ProxyPedido::new(
+            1,
+            None,
+            None,
+            None,
+            None,
+            "Mercado Pago".to_string(),
+            Status::Pendente,
+            Utc::now(),
+            Utc::now(),
+        );
+        let pedidos = carregar_pedidos(
+            &mock_cliente_repository,
+            &mock_produto_repository,
+            vec![pedido],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(pedidos.len(), 1);
+        assert!(pedidos[0].cliente().is_none());
+        assert!(pedidos[0].lanche().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_carregar_pedidos_usa_o_preco_da_data_do_pedido() {
+        let mut mock_cliente_repository = MockClienteGateway::new();
+        let mut mock_produto_repository = MockProdutoGateway::new();
+        let data_do_pedido = Utc::now() - Duration::days(2);
+
+        mock_cliente_repository.expect_get_clientes_by_ids().never();
+        mock_produto_repository
+            .expect_get_produtos_by_ids()
+            .returning(|_| Ok(vec![produto(1, Categoria::Lanche)]));
+        mock_produto_repository
+            .expect_get_historico_de_precos()
+            .returning(move |_| {
+                Ok(vec![
+                    PrecoHistorico::new(1, 8.0, data_do_pedido - Duration::days(1)),
+                    PrecoHistorico::new(1, 10.0, data_do_pedido + Duration::days(1)),
+                ])
+            });
+
+        let pedido = ProxyPedido::new(
+            1,
+            None,
+            Some(1),
+            None,
+            None,
+            "Mercado Pago".to_string(),
+            Status::Finalizado,
+            data_do_pedido,
+            data_do_pedido,
+        );
+        let pedidos = carregar_pedidos(
+            &mock_cliente_repository,
+            &mock_produto_repository,
+            vec![pedido],
+        )
+        .await
+        .unwrap();
+
+        assert_eq!(pedidos[0].lanche().unwrap().preco(), 8.0);
+        assert_eq!(pedidos[0].valor(), 8.0);
+    }
+
+    #[tokio::test]
+    async fn test_carregar_pedidos_propaga_falha_dos_gateways() {
+        let mut mock_cliente_repository = MockClienteGateway::new();
+        let mut mock_produto_repository = MockProdutoGateway::new();
+        mock_cliente_repository
+            .expect_get_clientes_by_ids()
+            .returning(|_| Ok(vec![]));
+        mock_produto_repository
+            .expect_get_historico_de_precos()
+            .returning(|_| Ok(vec![]));
+        mock_produto_repository
+            .expect_get_produtos_by_ids()
+            .returning(|_| Err(DomainError::unavailable("Banco de dados indisponível")));
+
+        let resultado = carregar_pedidos(&mock_cliente_repository, &mock_produto_repository, proxies(1)).await;
+
+        assert!(matches!(resultado, Err(DomainError::Unavailable { .. })));
+    }
+
+    #[tokio::test]
+    #[ignore = "precisa de um postgres com as migrations aplicadas, ex.: o do docker-compose.dev.yml"]
+    async fn test_pedido_de_outra_loja_nao_aparece() {
+        use crate::entities::loja::Loja;
+        use crate::external::postgres::PgConnectionManager;
+        use crate::gateways::{
+            postgres_cliente_gateway::PostgresClienteRepository,
+            postgres_loja_gateway::PostgresLojaRepository,
+            postgres_produto_gateway::PostgresProdutoRepository,
+        };
+        use crate::traits::loja_gateway::LojaGateway;
+
+        let pool = PgConnectionManager::new(std::env::var("DB_URL").expect("DB_URL"), 2).unwrap().pool;
+        let loja = PostgresLojaRepository::new(pool.clone())
+            .await
+            .create_loja(Loja::new(0, "Filial".to_string(), Utc::now(), Utc::now()))
+            .await
+            .unwrap();
+        let padrao = PostgresPedidoRepository::new(
+            pool.clone(),
+            Arc::new(PostgresClienteRepository::new(pool.clone()).await),
+            Arc::new(PostgresProdutoRepository::new(pool).await),
+        )
+        .await;
+        let filial = padrao.com_loja(*loja.id());
+
+        let pedido = filial
+            .create_pedido(Pedido::new(
+                0,
+                None,
+                None,
+                None,
+                None,
+                "Mercado Pago".to_string(),
+                Status::Pendente,
+                Utc::now(),
+                Utc::now(),
+            ))
+            .await
+            .unwrap();
+        let id = *pedido.id();
+
+        assert!(matches!(padrao.get_pedido_by_id(id).await, Err(DomainError::NotFound { .. })));
+        assert!(padrao.lista_pedidos().await.unwrap().iter().all(|pedido| *pedido.id() != id));
+        assert!(padrao.get_pedidos_novos().await.unwrap().iter().all(|pedido| *pedido.id() != id));
+        assert!(padrao.atualiza_status(id, Status::Finalizado).await.is_err());
+
+        assert_eq!(filial.get_pedido_by_id(id).await.unwrap().loja_id(), *loja.id());
+        filial.atualiza_status(id, Status::Finalizado).await.unwrap();
+    }
+
+    #[tokio::test]
+    #[ignore = "precisa de um postgres com as migrations aplicadas, ex.: o do docker-compose.dev.yml"]
+    async fn test_historico_de_status_e_tempos_de_preparo() {
+        use crate::entities::loja::Loja;
+        use crate::external::postgres::PgConnectionManager;
+        use crate::gateways::{
+            postgres_cliente_gateway::PostgresClienteRepository,
+            postgres_loja_gateway::PostgresLojaRepository,
+            postgres_produto_gateway::PostgresProdutoRepository,
+        };
+        use crate::traits::loja_gateway::LojaGateway;
+
+        let pool = PgConnectionManager::new(std::env::var("DB_URL").expect("DB_URL"), 2).unwrap().pool;
+        let loja = PostgresLojaRepository::new(pool.clone())
+            .await
+            .create_loja(Loja::new(0, "Cozinha".to_string(), Utc::now(), Utc::now()))
+            .await
+            .unwrap();
+        let produtos = PostgresProdutoRepository::new(pool.clone()).await.com_loja(*loja.id());
+        let lanche = produtos.create_produto(produto(0, Categoria::Lanche)).await.unwrap();
+        let repository = PostgresPedidoRepository::new(
+            pool.clone(),
+            Arc::new(PostgresClienteRepository::new(pool).await),
+            Arc::new(produtos),
+        )
+        .await
+        .com_loja(*loja.id());
+        let inicio = Utc::now();
+
+        let pedido = repository
+            .create_pedido(Pedido::new(
+                0,
+                None,
+                Some(lanche.clone()),
+                None,
+                None,
+                "Mercado Pago".to_string(),
+                Status::Pendente,
+                Utc::now(),
+                Utc::now(),
+            ))
+            .await
+            .unwrap();
+        let id = *pedido.id();
+        repository.atualiza_status(id, Status::EmPreparacao).await.unwrap();
+        let pedido = repository.atualiza_status(id, Status::Pronto).await.unwrap();
+
+        let historico: Vec<Status> = pedido
+            .historico_de_status()
+            .iter()
+            .map(|mudanca| mudanca.status().clone())
+            .collect();
+        assert_eq!(historico, vec![Status::Pendente, Status::EmPreparacao, Status::Pronto]);
+        assert!(pedido.tempo_de_preparo().is_some());
+
+        let tempos = repository.get_tempos_de_preparo(inicio - Duration::minutes(1)).await.unwrap();
+        assert_eq!(tempos.len(), 1);
+        assert_eq!(tempos[0].produto_id(), lanche.id());
+        assert_eq!(tempos[0].pedidos(), 1);
+    }
+
+    #[tokio::test]
+    #[ignore = "precisa de um postgres com as migrations aplicadas, ex.: o do docker-compose.dev.yml"]
+    async fn test_fila_da_cozinha() {
+        use crate::entities::loja::Loja;
+        use crate::external::postgres::PgConnectionManager;
+        use crate::gateways::{
+            contrato_pedido_gateway,
+            postgres_cliente_gateway::PostgresClienteRepository,
+            postgres_loja_gateway::PostgresLojaRepository,
+            postgres_produto_gateway::PostgresProdutoRepository,
+        };
+        use crate::traits::loja_gateway::LojaGateway;
+
+        let pool = PgConnectionManager::new(std::env::var("DB_URL").expect("DB_URL"), 2).unwrap().pool;
+        let loja = PostgresLojaRepository::new(pool.clone())
+            .await
+            .create_loja(Loja::new(0, "Fila".to_string(), Utc::now(), Utc::now()))
+            .await
+            .unwrap();
+        let repository = PostgresPedidoRepository::new(
+            pool.clone(),
+            Arc::new(PostgresClienteRepository::new(pool.clone()).await),
+            Arc::new(PostgresProdutoRepository::new(pool).await),
+        )
+        .await
+        .com_loja(*loja.id());
+
+        contrato_pedido_gateway::verifica_fila_da_cozinha(&repository).await;
+    }
+
+    #[tokio::test]
+    #[ignore = "precisa de um postgres com as migrations aplicadas, ex.: o do docker-compose.dev.yml"]
+    async fn test_status_dos_tickets_por_estacao() {
+        use crate::entities::estacao::{Estacao, StatusDoTicket};
+        use crate::entities::loja::Loja;
+        use crate::external::postgres::PgConnectionManager;
+        use crate::gateways::{
+            postgres_cliente_gateway::PostgresClienteRepository,
+            postgres_estacao_gateway::PostgresEstacaoRepository,
+            postgres_loja_gateway::PostgresLojaRepository,
+            postgres_produto_gateway::PostgresProdutoRepository,
+        };
+        use crate::traits::{estacao_gateway::EstacaoGateway, loja_gateway::LojaGateway};
+
+        let pool = PgConnectionManager::new(std::env::var("DB_URL").expect("DB_URL"), 2).unwrap().pool;
+        let loja = PostgresLojaRepository::new(pool.clone())
+            .await
+            .create_loja(Loja::new(0, "Estações".to_string(), Utc::now(), Utc::now()))
+            .await
+            .unwrap();
+        let loja_id = *loja.id();
+        let estacoes = PostgresEstacaoRepository::new(pool.clone()).await.com_loja(loja_id);
+        let chapa = estacoes
+            .create_estacao(Estacao::new(0, "Chapa".to_string(), Utc::now(), Utc::now()))
+            .await
+            .unwrap();
+        let bebidas = estacoes
+            .create_estacao(Estacao::new(0, "Bebidas".to_string(), Utc::now(), Utc::now()))
+            .await
+            .unwrap();
+        let produtos = PostgresProdutoRepository::new(pool.clone()).await.com_loja(loja_id);
+        let mut lanche = produto(0, Categoria::Lanche);
+        lanche.set_estacao_id(Some(*chapa.id()));
+        let lanche = produtos.create_produto(lanche).await.unwrap();
+        let mut bebida = produto(0, Categoria::Bebida);
+        bebida.set_estacao_id(Some(*bebidas.id()));
+        let bebida = produtos.create_produto(bebida).await.unwrap();
+        let repository = PostgresPedidoRepository::new(
+            pool.clone(),
+            Arc::new(PostgresClienteRepository::new(pool.clone()).await),
+            Arc::new(produtos),
+        )
+        .await
+        .com_loja(loja_id);
+
+        let pedido = repository
+            .create_pedido(Pedido::new(
+                0,
+                None,
+                Some(lanche),
+                None,
+                Some(bebida),
+                "Mercado Pago".to_string(),
+                Status::Pendente,
+                Utc::now(),
+                Utc::now(),
+            ))
+            .await
+            .unwrap();
+        let id = *pedido.id();
+        assert_eq!(pedido.tickets().len(), 2);
+
+        repository.atualiza_status_do_ticket(id, *chapa.id(), StatusDoTicket::Pronto).await.unwrap();
+        let pedido = repository
+            .atualiza_status_do_ticket(id, *bebidas.id(), StatusDoTicket::EmPreparacao)
+            .await
+            .unwrap();
+        assert_eq!(*pedido.ticket_da_estacao(*chapa.id()).unwrap().status(), StatusDoTicket::Pronto);
+        assert_eq!(*pedido.ticket_da_estacao(*bebidas.id()).unwrap().status(), StatusDoTicket::EmPreparacao);
+        assert!(!pedido.pronto_em_todas_as_estacoes());
+
+        let outra_loja = repository.com_loja(1);
+        assert!(outra_loja.atualiza_status_do_ticket(id, *chapa.id(), StatusDoTicket::Recebido).await.is_err());
+    }
+
+    // Com uma única conexão no pool, um método que segurasse a sua enquanto monta
+    // os pedidos ficaria sem conexão para buscar produtos e histórico
+    #[tokio::test]
+    #[ignore = "precisa de um postgres com as migrations aplicadas, ex.: o do docker-compose.dev.yml"]
+    async fn test_pool_de_uma_conexao() {
+        use crate::entities::loja::Loja;
+        use crate::external::postgres::PgConnectionManager;
+        use crate::gateways::{
+            postgres_cliente_gateway::PostgresClienteRepository,
+            postgres_loja_gateway::PostgresLojaRepository,
+            postgres_produto_gateway::PostgresProdutoRepository,
+        };
+        use crate::traits::loja_gateway::LojaGateway;
+
+        let pool = PgConnectionManager::new(std::env::var("DB_URL").expect("DB_URL"), 1).unwrap().pool;
+        let loja = PostgresLojaRepository::new(pool.clone())
+            .await
+            .create_loja(Loja::new(0, "Uma conexão".to_string(), Utc::now(), Utc::now()))
+            .await
+            .unwrap();
+        let produtos = PostgresProdutoRepository::new(pool.clone()).await.com_loja(*loja.id());
+        let lanche = produtos.create_produto(produto(0, Categoria::Lanche)).await.unwrap();
+        let bebida = produtos.create_produto(produto(0, Categoria::Bebida)).await.unwrap();
+        let repository = PostgresPedidoRepository::new(
+            pool.clone(),
+            Arc::new(PostgresClienteRepository::new(pool).await),
+            Arc::new(produtos),
+        )
+        .await
+        .com_loja(*loja.id());
+
+        let pedido = repository
+            .create_pedido(Pedido::new(
+                0,
+                None,
+                Some(lanche.clone()),
+                None,
+                None,
+                "Mercado Pago".to_string(),
+                Status::Pendente,
+                Utc::now(),
+                Utc::now(),
+            ))
+            .await
+            .unwrap();
+        let id = *pedido.id();
+        assert_eq!(pedido.lanche().map(|lanche| *lanche.id()), Some(*lanche.id()));
+        assert_eq!(pedido.historico_de_status().len(), 1);
+
+        let pedido = repository.cadastrar_bebida(id, bebida.clone()).await.unwrap();
+        assert_eq!(pedido.bebida().map(|bebida| *bebida.id()), Some(*bebida.id()));
+        let pedido = repository.atualiza_status(id, Status::Pago).await.unwrap();
+        assert_eq!(pedido.historico_de_status().len(), 2);
+        assert!(repository.get_pedido_by_id(id).await.unwrap().lanche().is_some());
+        assert!(repository.lista_pedidos().await.unwrap()[0].bebida().is_some());
+        assert!(repository.get_pedidos_novos().await.unwrap()[0].lanche().is_some());
+    }
+
+    // A transação segura a única conexão do pool; clientes e produtos dos pedidos
+    // precisam ser lidos por ela, senão a carga espera o timeout do pool
+    #[tokio::test]
+    #[ignore = "precisa de um postgres com as migrations aplicadas, ex.: o do docker-compose.dev.yml"]
+    async fn test_transacao_com_pool_de_uma_conexao() {
+        use crate::entities::loja::Loja;
+        use crate::external::postgres::PgConnectionManager;
+        use crate::gateways::{
+            postgres_loja_gateway::PostgresLojaRepository,
+            postgres_pagamento_gateway::PostgresPagamentoRepository,
+            postgres_unit_of_work::PostgresUnitOfWork,
+        };
+        use crate::traits::{loja_gateway::LojaGateway, unit_of_work::UnitOfWork};
+
+        let pool = PgConnectionManager::new(std::env::var("DB_URL").expect("DB_URL"), 1).unwrap().pool;
+        let loja = PostgresLojaRepository::new(pool.clone())
+            .await
+            .create_loja(Loja::new(0, "Transação".to_string(), Utc::now(), Utc::now()))
+            .await
+            .unwrap();
+        let produtos = PostgresProdutoRepository::new(pool.clone()).await.com_loja(*loja.id());
+        let lanche = produtos.create_produto(produto(0, Categoria::Lanche)).await.unwrap();
+        let clientes = PostgresClienteRepository::new(pool.clone()).await;
+        let pedido_repository = Arc::new(
+            PostgresPedidoRepository::new(pool.clone(), Arc::new(MockClienteGateway::new()), Arc::new(MockProdutoGateway::new()))
+                .await
+                .com_clientes_no_postgres(clientes)
+                .com_produtos_no_postgres(produtos)
+                .com_loja(*loja.id()),
+        );
+        let pagamento_repository =
+            Arc::new(PostgresPagamentoRepository::new(pool.clone(), pedido_repository.clone()).await);
+        let unit_of_work = PostgresUnitOfWork::new(pool, pedido_repository.clone(), pagamento_repository);
+
+        let transacao = unit_of_work.begin().await.unwrap();
+        let pedido = transacao
+            .pedido_repository()
+            .create_pedido(Pedido::new(
+                0,
+                None,
+                Some(lanche.clone()),
+                None,
+                None,
+                "Mercado Pago".to_string(),
+                Status::Pendente,
+                Utc::now(),
+                Utc::now(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(pedido.lanche().map(|lanche| *lanche.id()), Some(*lanche.id()));
+        transacao.commit().await.unwrap();
+        drop(transacao);
+
+        assert!(pedido_repository.get_pedido_by_id(*pedido.id()).await.unwrap().lanche().is_some());
+    }
+
+    #[tokio::test]
+    #[ignore = "precisa de um postgres com as migrations aplicadas, ex.: o do docker-compose.dev.yml"]
+    async fn test_cadastrar_pagamento() {
+        use crate::external::postgres::PgConnectionManager;
+
+        let pool = PgConnectionManager::new(std::env::var("DB_URL").expect("DB_URL"), 2).unwrap().pool;
+        let repository = PostgresPedidoRepository::new(
+            pool.clone(),
+            Arc::new(PostgresClienteRepository::new(pool.clone()).await),
+            Arc::new(PostgresProdutoRepository::new(pool).await),
+        )
+        .await;
+        let pedido = repository
+            .create_pedido(Pedido::new(
+                0,
+                None,
+                None,
+                None,
+                None,
+                "Mercado Pago".to_string(),
+                Status::Pendente,
+                Utc::now(),
+                Utc::now(),
+            ))
+            .await
+            .unwrap();
+        let pagamento = Pagamento::new(
+            0,
+            *pedido.id(),
+            "pendente".to_string(),
+            25.0,
+            "Mercado Pago".to_string(),
+            "ref".to_string(),
+            Utc::now(),
+        );
+
+        let criado = repository.cadastrar_pagamento(pagamento.clone()).await.unwrap();
+        assert_eq!(*criado.id_pedido(), *pedido.id());
+        assert_eq!(criado.valor(), 25.0);
+
+        let outra_loja = repository.com_loja(999);
+        assert!(matches!(outra_loja.cadastrar_pagamento(pagamento).await, Err(DomainError::NotFound { .. })));
+    }
 }
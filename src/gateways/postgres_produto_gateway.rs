@@ -1,30 +1,42 @@
 use std::error::Error;
+use std::sync::Arc;
 use bytes::BytesMut;
 use postgres_from_row::FromRow;
-use tokio_postgres::Client;
-use tokio_postgres::types::{FromSql, ToSql, Type};
+use deadpool_postgres::Pool;
+use tokio_postgres::types::{FromSql, Json, ToSql, Type};
+use tracing::instrument;
 
 
 use crate::{
     base::domain_error::DomainError, 
     entities::produto::Categoria, 
     entities::produto::Produto,
+    entities::preco_historico::PrecoHistorico,
+    entities::loja::LOJA_PADRAO,
     traits::produto_gateway::ProdutoGateway,
 };
+use crate::external::postgres::PgExecutor;
 
-use crate::external::postgres::table::Table;
 
+// Toda consulta filtra por `loja_id`; `com_loja` cria a cópia que acessa outra loja
 pub struct PostgresProdutoRepository {
-    client: Client,
-    tables: Vec<Table>,
+    executor: PgExecutor,
+    loja_id: usize,
 }
 
-const CREATE_PRODUCT: &str = "INSERT INTO produto (nome, foto, descricao, categoria, preco, ingredientes, data_criacao, data_atualizacao) VALUES ($1, $2, $3, $4, $5, $6, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP) RETURNING id, nome, foto, descricao, CAST(categoria AS VARCHAR) AS categoria, preco, ingredientes, data_criacao, data_atualizacao";
-const QUERY_PRODUCT_BY_ID: &str = "SELECT id, nome, foto, descricao, CAST(categoria AS VARCHAR) AS categoria, preco, ingredientes, data_criacao, data_atualizacao FROM produto WHERE id = $1";
-const QUERY_PRODUCTS: &str = "SELECT id, nome, foto, descricao, CAST(categoria AS VARCHAR) AS categoria, preco, ingredientes, data_criacao, data_atualizacao FROM produto";
-const QUERY_PRODUCT_BY_CATEGORIA: &str = "SELECT id, nome, foto, descricao, CAST(categoria AS VARCHAR) AS categoria, preco, ingredientes, data_criacao, data_atualizacao FROM produto WHERE categoria = $1";
-const UPDATE_PRODUCT: &str = "UPDATE produto SET nome = $1, foto = $2, descricao = $3, categoria = $4, preco = $5, ingredientes = $6, data_atualizacao = CURRENT_TIMESTAMP WHERE id = $7 RETURNING id, nome, foto, descricao, CAST(categoria AS VARCHAR) AS categoria, preco, ingredientes, data_criacao, data_atualizacao";
-const DELETE_PRODUCT: &str = "DELETE FROM produto WHERE id = $1 RETURNING RETURNING id, nome, foto, descricao, CAST(categoria AS VARCHAR) AS categoria, preco, ingredientes, data_criacao, data_atualizacao";
+// O preço inicial já entra no histórico, na mesma instrução
+const CREATE_PRODUCT: &str = "WITH criado AS (INSERT INTO produto (nome, foto, descricao, categoria, preco, ingredientes, alergenos, informacao_nutricional, janelas_de_venda, loja_id, estacao_id, data_criacao, data_atualizacao) VALUES ($1, $2, $3, $4, $5, $6, $7, $8, $9, $10, $11, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP) RETURNING id, nome, foto, descricao, CAST(categoria AS VARCHAR) AS categoria, preco, ingredientes, data_criacao, data_atualizacao, versao, arquivado, data_arquivamento, alergenos, informacao_nutricional, janelas_de_venda, estacao_id, loja_id), preco AS (INSERT INTO produto_preco (produto_id, preco, vigente_desde) SELECT id, preco, data_criacao FROM criado) SELECT * FROM criado";
+const QUERY_PRODUCT_BY_ID: &str = "SELECT id, nome, foto, descricao, CAST(categoria AS VARCHAR) AS categoria, preco, ingredientes, data_criacao, data_atualizacao, versao, arquivado, data_arquivamento, alergenos, informacao_nutricional, janelas_de_venda, estacao_id, loja_id FROM produto WHERE id = $1 AND loja_id = $2";
+const QUERY_PRODUCTS_BY_IDS: &str = "SELECT id, nome, foto, descricao, CAST(categoria AS VARCHAR) AS categoria, preco, ingredientes, data_criacao, data_atualizacao, versao, arquivado, data_arquivamento, alergenos, informacao_nutricional, janelas_de_venda, estacao_id, loja_id FROM produto WHERE id = ANY($1) AND loja_id = $2";
+const QUERY_PRODUCTS: &str = "SELECT id, nome, foto, descricao, CAST(categoria AS VARCHAR) AS categoria, preco, ingredientes, data_criacao, data_atualizacao, versao, arquivado, data_arquivamento, alergenos, informacao_nutricional, janelas_de_venda, estacao_id, loja_id FROM produto WHERE loja_id = $1 AND NOT arquivado";
+const QUERY_PRODUCT_BY_CATEGORIA: &str = "SELECT id, nome, foto, descricao, CAST(categoria AS VARCHAR) AS categoria, preco, ingredientes, data_criacao, data_atualizacao, versao, arquivado, data_arquivamento, alergenos, informacao_nutricional, janelas_de_venda, estacao_id, loja_id FROM produto WHERE categoria = $1 AND loja_id = $2 AND NOT arquivado";
+// Só grava se a versão não mudou desde a leitura; sem linha de retorno, o produto sumiu ou foi alterado por outra requisição.
+// Mudanças de preço são registradas no histórico junto com a atualização
+const UPDATE_PRODUCT: &str = "WITH atualizado AS (UPDATE produto SET nome = $1, foto = $2, descricao = $3, categoria = $4, preco = $5, ingredientes = $6, alergenos = $9, informacao_nutricional = $10, janelas_de_venda = $11, estacao_id = $13, data_atualizacao = CURRENT_TIMESTAMP, versao = versao + 1 WHERE id = $7 AND versao = $8 AND loja_id = $12 RETURNING id, nome, foto, descricao, CAST(categoria AS VARCHAR) AS categoria, preco, ingredientes, data_criacao, data_atualizacao, versao, arquivado, data_arquivamento, alergenos, informacao_nutricional, janelas_de_venda, estacao_id, loja_id), preco AS (INSERT INTO produto_preco (produto_id, preco, vigente_desde) SELECT id, preco, data_atualizacao FROM atualizado WHERE preco IS DISTINCT FROM (SELECT ultimo.preco FROM produto_preco ultimo WHERE ultimo.produto_id = atualizado.id ORDER BY ultimo.vigente_desde DESC, ultimo.id DESC LIMIT 1)) SELECT * FROM atualizado";
+const EXISTS_PRODUCT: &str = "SELECT 1 FROM produto WHERE id = $1 AND loja_id = $2";
+const DELETE_PRODUCT: &str = "UPDATE produto SET arquivado = TRUE, data_arquivamento = CURRENT_TIMESTAMP, data_atualizacao = CURRENT_TIMESTAMP, versao = versao + 1 WHERE id = $1 AND loja_id = $2 AND NOT arquivado RETURNING id";
+const RESTORE_PRODUCT: &str = "UPDATE produto SET arquivado = FALSE, data_arquivamento = NULL, data_atualizacao = CURRENT_TIMESTAMP, versao = versao + 1 WHERE id = $1 AND loja_id = $2 AND arquivado RETURNING id, nome, foto, descricao, CAST(categoria AS VARCHAR) AS categoria, preco, ingredientes, data_criacao, data_atualizacao, versao, arquivado, data_arquivamento, alergenos, informacao_nutricional, janelas_de_venda, estacao_id, loja_id";
+const QUERY_PRICE_HISTORY: &str = "SELECT historico.produto_id, historico.preco, historico.vigente_desde FROM produto_preco historico JOIN produto ON produto.id = historico.produto_id WHERE historico.produto_id = ANY($1) AND produto.loja_id = $2 ORDER BY historico.produto_id, historico.vigente_desde";
 
 
 impl<'a> FromSql<'a> for Categoria {
@@ -79,24 +91,43 @@ impl ToSql for Categoria {
 
 
 impl PostgresProdutoRepository {
-    pub async fn new(client: Client, tables: Vec<Table>) -> Self {
-        let repo = PostgresProdutoRepository { client, tables };
-        repo.check_for_tables().await;
+    pub async fn new(pool: Pool) -> Self {
+        let repo = PostgresProdutoRepository {
+            executor: PgExecutor::Pool(pool),
+            loja_id: LOJA_PADRAO,
+        };
         repo
     }
 
-    async fn check_for_tables(&self) {
-        for table in self.tables.iter() {
-            let query = table.get_create_if_not_exists_query();
-            self.client.execute(query.as_str(), &[]).await.unwrap();
+    pub fn em_transacao(&self, executor: PgExecutor) -> Self {
+        PostgresProdutoRepository {
+            executor,
+            loja_id: self.loja_id,
         }
     }
+
+    pub fn com_loja(&self, loja_id: usize) -> Self {
+        PostgresProdutoRepository {
+            executor: self.executor.clone(),
+            loja_id,
+        }
+    }
+
+    fn loja(&self) -> i32 {
+        self.loja_id as i32
+    }
 }
 
 #[async_trait]
 impl ProdutoGateway for PostgresProdutoRepository {
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn ProdutoGateway + Sync + Send> {
+        Arc::new(self.com_loja(loja_id))
+    }
+
+    #[instrument(level = "debug", skip(self))]
     async fn get_produtos(&self) -> Result<Vec<Produto>, DomainError> {
-        let produtos = self.client.query(QUERY_PRODUCTS, &[]).await.unwrap();
+        let client = self.executor.client().await?;
+        let produtos = client.query(QUERY_PRODUCTS, &[&self.loja()]).await?;
         let mut produtos_vec = Vec::new();
         for produto in produtos {
             produtos_vec.push(Produto::from_row(&produto));
@@ -104,25 +135,39 @@ impl ProdutoGateway for PostgresProdutoRepository {
         Ok(produtos_vec)
     }
 
+    #[instrument(level = "debug", skip(self))]
     async fn get_produto_by_id(&self, id: usize) -> Result<Produto, DomainError> {
-        let id = id as i32;
-        let produto = self.client.query_one(QUERY_PRODUCT_BY_ID, &[&id]).await;
+        let client = self.executor.client().await?;
+        let produto = client.query_opt(QUERY_PRODUCT_BY_ID, &[&(id as i32), &self.loja()]).await?;
         match produto {
-            Ok(produto) => Ok(Produto::from_row(&produto)),
-            Err(_) => Err(DomainError::NotFound),
+            Some(produto) => Ok(Produto::from_row(&produto)),
+            None => Err(DomainError::not_found("Produto", id)),
+        }
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn get_produtos_by_ids(&self, ids: Vec<usize>) -> Result<Vec<Produto>, DomainError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
         }
+        let client = self.executor.client().await?;
+        let ids: Vec<i32> = ids.into_iter().map(|id| id as i32).collect();
+        let produtos = client
+            .query(QUERY_PRODUCTS_BY_IDS, &[&ids, &self.loja()])
+            .await?;
+        Ok(produtos.iter().map(Produto::from_row).collect())
     }
 
+    #[instrument(level = "debug", skip(self, categoria))]
     async fn get_produtos_by_categoria(
         &self,
         categoria: Categoria,
     ) -> Result<Vec<Produto>, DomainError> {
+        let client = self.executor.client().await?;
         let categoria = tokio_postgres::types::Json(categoria);
-        let lista_produtos = self
-            .client
-            .query(QUERY_PRODUCT_BY_CATEGORIA, &[&categoria])
-            .await
-            .unwrap();
+        let lista_produtos = client
+            .query(QUERY_PRODUCT_BY_CATEGORIA, &[&categoria, &self.loja()])
+            .await?;
         let mut produtos_vec = Vec::new();
         for produto in lista_produtos {
             produtos_vec.push(Produto::from_row(&produto));
@@ -130,11 +175,12 @@ impl ProdutoGateway for PostgresProdutoRepository {
         Ok(produtos_vec)
     }
 
-    async fn create_produto(&mut self, produto: Produto) -> Result<Produto, DomainError> {
+    #[instrument(level = "debug", skip(self, produto))]
+    async fn create_produto(&self, produto: Produto) -> Result<Produto, DomainError> {
+        let client = self.executor.client().await?;
         let ingredientes = produto.ingredientes();
         let ingredientes_vec: Vec<String> = ingredientes.to_vec_string();
-        let new_produto = self
-            .client
+        let new_produto = client
             .query(
                 CREATE_PRODUCT,
                 &[
@@ -143,25 +189,31 @@ impl ProdutoGateway for PostgresProdutoRepository {
                     &produto.descricao(),
                     &produto.categoria(),
                     &produto.preco(),
-                    &ingredientes_vec
+                    &ingredientes_vec,
+                    &Json(produto.alergenos_por_ingrediente()),
+                    &produto.informacao_nutricional().map(Json),
+                    &Json(produto.janelas_de_venda()),
+                    &self.loja(),
+                    &produto.estacao_id().map(|id| id as i32),
                 ],
             )
-            .await
-            .unwrap();
+            .await?;
         let new_produto = new_produto.get(0);
         match new_produto {
             Some(produto) => Ok(Produto::from_row(produto)),
-            None => Err(DomainError::Invalid("Produto".to_string())),
+            None => Err(DomainError::persistence("Produto não foi criado")),
         }
     }
 
-    async fn update_produto(&mut self, new_produto_data: Produto) -> Result<Produto, DomainError> {
+    #[instrument(level = "debug", skip(self, new_produto_data))]
+    async fn update_produto(&self, new_produto_data: Produto) -> Result<Produto, DomainError> {
+        let client = self.executor.client().await?;
         let id = new_produto_data.id().clone() as i32;
+        let versao = new_produto_data.versao() as i32;
         let ingredientes = new_produto_data.ingredientes();
         let ingredientes_vec: Vec<String> = ingredientes.to_vec_string();
 
-        let updated_produto = self
-            .client
+        let updated_produto = client
             .query(
                 UPDATE_PRODUCT,
                 &[
@@ -172,23 +224,107 @@ impl ProdutoGateway for PostgresProdutoRepository {
                     &new_produto_data.preco(),
                     &ingredientes_vec,
                     &id,
+                    &versao,
+                    &Json(new_produto_data.alergenos_por_ingrediente()),
+                    &new_produto_data.informacao_nutricional().map(Json),
+                    &Json(new_produto_data.janelas_de_venda()),
+                    &self.loja(),
+                    &new_produto_data.estacao_id().map(|id| id as i32),
                 ],
             )
-            .await
-            .unwrap();
-        let updated_produto = updated_produto.get(0);
-        match updated_produto {
+            .await?;
+        match updated_produto.get(0) {
             Some(produto) => Ok(Produto::from_row(produto)),
-            None => Err(DomainError::NotFound),
+            None if client.query_opt(EXISTS_PRODUCT, &[&id, &self.loja()]).await?.is_some() => {
+                Err(DomainError::conflict("Produto", "Produto foi alterado por outra requisição"))
+            }
+            None => Err(DomainError::not_found("Produto", id)),
         }
     }
 
-    async fn delete_produto(&mut self, id: usize) -> Result<(), DomainError> {
+    #[instrument(level = "debug", skip(self))]
+    async fn delete_produto(&self, id: usize) -> Result<(), DomainError> {
+        let client = self.executor.client().await?;
         let id = id as i32;
-        let deleted_produto = self.client.query_one(DELETE_PRODUCT, &[&id]).await;
-        match deleted_produto {
-            Ok(_) => Ok(()),
-            _ => Err(DomainError::NotFound),
+        // Sem linha de retorno o produto já estava arquivado ou não existe
+        match client.query_opt(DELETE_PRODUCT, &[&id, &self.loja()]).await? {
+            Some(_) => Ok(()),
+            None if client.query_opt(EXISTS_PRODUCT, &[&id, &self.loja()]).await?.is_some() => Ok(()),
+            None => Err(DomainError::not_found("Produto", id)),
         }
     }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn restaurar_produto(&self, id: usize) -> Result<Produto, DomainError> {
+        let client = self.executor.client().await?;
+        let restaurado = client.query_opt(RESTORE_PRODUCT, &[&(id as i32), &self.loja()]).await?;
+        drop(client);
+        match restaurado {
+            Some(produto) => Ok(Produto::from_row(&produto)),
+            None => self.get_produto_by_id(id).await,
+        }
+    }
+
+    #[instrument(level = "debug", skip(self))]
+    async fn get_historico_de_precos(&self, ids: Vec<usize>) -> Result<Vec<PrecoHistorico>, DomainError> {
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+        let client = self.executor.client().await?;
+        let ids: Vec<i32> = ids.into_iter().map(|id| id as i32).collect();
+        let precos = client.query(QUERY_PRICE_HISTORY, &[&ids, &self.loja()]).await?;
+        Ok(precos.iter().map(PrecoHistorico::from_row).collect())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use chrono::Utc;
+    use crate::entities::ingredientes::Ingredientes;
+    use crate::entities::loja::Loja;
+    use crate::external::postgres::PgConnectionManager;
+    use crate::gateways::postgres_loja_gateway::PostgresLojaRepository;
+    use crate::traits::loja_gateway::LojaGateway;
+
+    #[tokio::test]
+    #[ignore = "precisa de um postgres com as migrations aplicadas, ex.: o do docker-compose.dev.yml"]
+    async fn test_produto_de_outra_loja_nao_aparece() {
+        let pool = PgConnectionManager::new(std::env::var("DB_URL").expect("DB_URL"), 2).unwrap().pool;
+        let loja = PostgresLojaRepository::new(pool.clone())
+            .await
+            .create_loja(Loja::new(0, "Filial".to_string(), Utc::now(), Utc::now()))
+            .await
+            .unwrap();
+        let padrao = PostgresProdutoRepository::new(pool).await;
+        let filial = padrao.com_loja(*loja.id());
+
+        let produto = filial
+            .create_produto(Produto::new(
+                0,
+                "X-Filial".to_string(),
+                "foto.png".to_string(),
+                "descricao".to_string(),
+                Categoria::Lanche,
+                10.0,
+                Ingredientes::new(vec!["pão".to_string()]).unwrap(),
+                Utc::now(),
+                Utc::now(),
+            ))
+            .await
+            .unwrap();
+        let id = *produto.id();
+        assert_eq!(produto.loja_id(), *loja.id());
+
+        assert!(matches!(padrao.get_produto_by_id(id).await, Err(DomainError::NotFound { .. })));
+        assert!(padrao.get_produtos().await.unwrap().iter().all(|produto| *produto.id() != id));
+        assert!(padrao.get_produtos_by_ids(vec![id]).await.unwrap().is_empty());
+        assert!(padrao.update_produto(produto.clone()).await.is_err());
+        assert!(padrao.delete_produto(id).await.is_err());
+        assert!(padrao.get_historico_de_precos(vec![id]).await.unwrap().is_empty());
+
+        assert_eq!(filial.get_produtos().await.unwrap().len(), 1);
+        assert_eq!(filial.get_produto_by_id(id).await.unwrap().nome(), "X-Filial");
+        filial.delete_produto(id).await.unwrap();
+    }
 }
@@ -0,0 +1,111 @@
+use deadpool_postgres::{Object, Pool};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+use tracing::error;
+
+use crate::base::domain_error::DomainError;
+use crate::external::postgres::{get_client, PgExecutor};
+use crate::gateways::postgres_pagamento_gateway::PostgresPagamentoRepository;
+use crate::gateways::postgres_pedido_gateway::PostgresPedidoRepository;
+use crate::traits::pagamento_gateway::PagamentoGateway;
+use crate::traits::pedido_gateway::PedidoGateway;
+use crate::traits::unit_of_work::{Transacao, UnitOfWork};
+
+pub struct PostgresUnitOfWork {
+    pool: Pool,
+    pedido_repository: Arc<PostgresPedidoRepository>,
+    pagamento_repository: Arc<PostgresPagamentoRepository>,
+}
+
+impl PostgresUnitOfWork {
+    pub fn new(
+        pool: Pool,
+        pedido_repository: Arc<PostgresPedidoRepository>,
+        pagamento_repository: Arc<PostgresPagamentoRepository>,
+    ) -> Self {
+        PostgresUnitOfWork {
+            pool,
+            pedido_repository,
+            pagamento_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl UnitOfWork for PostgresUnitOfWork {
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn UnitOfWork + Sync + Send> {
+        Arc::new(PostgresUnitOfWork::new(
+            self.pool.clone(),
+            Arc::new(self.pedido_repository.com_loja(loja_id)),
+            Arc::new(self.pagamento_repository.com_loja(loja_id)),
+        ))
+    }
+
+    async fn begin(&self) -> Result<Box<dyn Transacao + Sync + Send>, DomainError> {
+        let conexao = Arc::new(get_client(&self.pool).await?);
+        conexao.batch_execute("BEGIN").await.map_err(|err| {
+            error!(error = %err, "Erro ao iniciar transação");
+            DomainError::persistence("Erro ao iniciar transação").com_causa(err)
+        })?;
+
+        let executor = PgExecutor::Transacao(conexao.clone());
+        Ok(Box::new(PostgresTransacao {
+            conexao,
+            pedido_repository: Arc::new(self.pedido_repository.em_transacao(executor.clone())),
+            pagamento_repository: Arc::new(self.pagamento_repository.em_transacao(executor)),
+            finalizada: AtomicBool::new(false),
+        }))
+    }
+}
+
+pub struct PostgresTransacao {
+    conexao: Arc<Object>,
+    pedido_repository: Arc<PostgresPedidoRepository>,
+    pagamento_repository: Arc<PostgresPagamentoRepository>,
+    finalizada: AtomicBool,
+}
+
+impl PostgresTransacao {
+    async fn finalizar(&self, comando: &str) -> Result<(), DomainError> {
+        if self.finalizada.swap(true, Ordering::SeqCst) {
+            return Err(DomainError::conflict("Transação", "Transação já finalizada"));
+        }
+        self.conexao.batch_execute(comando).await.map_err(|err| {
+            error!(comando, error = %err, "Erro ao finalizar transação");
+            DomainError::persistence("Erro ao finalizar transação").com_causa(err)
+        })
+    }
+}
+
+#[async_trait]
+impl Transacao for PostgresTransacao {
+    fn pedido_repository(&self) -> Arc<dyn PedidoGateway + Sync + Send> {
+        self.pedido_repository.clone()
+    }
+
+    fn pagamento_repository(&self) -> Arc<dyn PagamentoGateway + Sync + Send> {
+        self.pagamento_repository.clone()
+    }
+
+    async fn commit(&self) -> Result<(), DomainError> {
+        self.finalizar("COMMIT").await
+    }
+
+    async fn rollback(&self) -> Result<(), DomainError> {
+        self.finalizar("ROLLBACK").await
+    }
+}
+
+impl Drop for PostgresTransacao {
+    // Garante que a conexão não volte para o pool com uma transação aberta
+    fn drop(&mut self) {
+        if !self.finalizada.load(Ordering::SeqCst) {
+            let conexao = self.conexao.clone();
+            tokio::spawn(async move {
+                if let Err(err) = conexao.batch_execute("ROLLBACK").await {
+                    error!(error = %err, "Erro ao executar ROLLBACK");
+                }
+            });
+        }
+    }
+}
@@ -1,53 +1,61 @@
 use chrono::Utc;
 use postgres_from_row::FromRow;
-use tokio_postgres::Client;
+use deadpool_postgres::Pool;
+use std::sync::Arc;
+use tracing::{error, info, instrument};
 
 use crate::{
-    base::domain_error::DomainError, entities::cpf::Cpf, entities::usuario::Usuario,
-    traits::usuario_gateway::UsuarioGateway,
+    base::domain_error::DomainError, entities::cpf::Cpf, entities::loja::LOJA_PADRAO,
+    entities::usuario::Usuario, traits::usuario_gateway::UsuarioGateway,
 };
+use crate::external::postgres::get_client;
 
-use crate::external::postgres::table::Table;
+// Sem loja (`$n::INT IS NULL`) as consultas valem para todas as lojas
 pub struct PostgresUsuarioGateway {
-    client: Client,
-    tables: Vec<Table>,
+    pool: Pool,
+    loja_id: Option<usize>,
 }
 
-const CREATE_USUARIO: &str = "INSERT INTO usuario (nome, email, cpf, senha, tipo, status, data_criacao, data_atualizacao) VALUES ($1, $2, $3, $4, $5, $6, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP) RETURNING *";
-const UPDATE_USUARIO: &str = "UPDATE usuario SET nome = $1, email = $2, cpf = $3, senha = $4, tipo = $5, status = $6, data_atualizacao = CURRENT_TIMESTAMP WHERE id = $7 RETURNING *";
+const CREATE_USUARIO: &str = "INSERT INTO usuario (nome, email, cpf, senha, tipo, status, lojas, data_criacao, data_atualizacao) VALUES ($1, $2, $3, $4, $5, $6, $7, CURRENT_TIMESTAMP, CURRENT_TIMESTAMP) RETURNING *";
+// Sem lojas ($8 nulo) o usuário continua nas lojas em que já estava
+const UPDATE_USUARIO: &str = "UPDATE usuario SET nome = $1, email = $2, cpf = $3, senha = $4, tipo = $5, status = $6, lojas = COALESCE($8, lojas), data_atualizacao = CURRENT_TIMESTAMP WHERE id = $7 AND ($9::INT IS NULL OR $9 = ANY(lojas)) RETURNING *";
 const QUERY_USUARIO_BY_CPF: &str = "SELECT * FROM usuario WHERE cpf = $1";
-const QUERY_USUARIO_BY_ID: &str = "SELECT * FROM usuario WHERE id = $1";
-const QUERY_USUARIOS: &str = "SELECT * FROM usuario";
-const DELETE_USUARIO: &str = "DELETE FROM usuario WHERE cpf = $1 RETURNING *";
+const QUERY_USUARIO_BY_ID: &str = "SELECT * FROM usuario WHERE id = $1 AND ($2::INT IS NULL OR $2 = ANY(lojas))";
+const QUERY_USUARIOS: &str = "SELECT * FROM usuario WHERE $1::INT IS NULL OR $1 = ANY(lojas)";
+// Na loja, o usuário só deixa de trabalhar nela; é excluído quando não sobra nenhuma loja
+const REMOVE_USUARIO_DA_LOJA: &str = "UPDATE usuario SET lojas = array_remove(lojas, $2) WHERE cpf = $1 AND $2 = ANY(lojas) RETURNING *";
+const DELETE_USUARIO: &str = "DELETE FROM usuario WHERE cpf = $1 AND ($2::INT IS NULL OR cardinality(lojas) = 0) RETURNING *";
 
 impl PostgresUsuarioGateway {
-    pub async fn new(client: Client, tables: Vec<Table>) -> Self {
-        let mut repo = PostgresUsuarioGateway { client, tables };
-
-        repo.check_for_tables().await;
+    pub async fn new(pool: Pool) -> Self {
+        let repo = PostgresUsuarioGateway { pool, loja_id: None };
         repo.check_for_usuario_admin().await;
 
         repo
     }
 
-    async fn check_for_tables(&self) {
-        for table in self.tables.iter() {
-            let query = table.get_create_if_not_exists_query();
-            self.client.execute(query.as_str(), &[]).await.unwrap();
+    pub fn com_loja(&self, loja_id: usize) -> Self {
+        PostgresUsuarioGateway {
+            pool: self.pool.clone(),
+            loja_id: Some(loja_id),
         }
     }
 
-    async fn check_for_usuario_admin(&mut self) {
+    fn loja(&self) -> Option<i32> {
+        self.loja_id.map(|loja_id| loja_id as i32)
+    }
+
+    async fn check_for_usuario_admin(&self) {
         let admin_cpf = Cpf::new("000.000.000-00".to_string()).unwrap();
         let usuario_admin = self.get_usuario_by_cpf(admin_cpf).await;
         match usuario_admin {
             Ok(usuario) => {
-                println!("Usuário Admin encontrado: {:?}", usuario);
+                info!(usuario_id = *usuario.id(), "Usuário Admin encontrado");
             }
-            _ => {
-                println!("Usuário Admin não encontrado. Criando...");
+            Err(DomainError::NotFound { .. }) => {
+                info!("Usuário Admin não encontrado. Criando...");
                 let _id = 0;
-                let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+                let _now = Utc::now();
                 let usuario_admin = Usuario::new(
                     _id,
                     "Administrador".to_string(),
@@ -56,19 +64,28 @@ impl PostgresUsuarioGateway {
                     "melhor_projeto".to_string(),
                     "Admin".parse().unwrap(),
                     "Ativo".parse().unwrap(),
-                    _now.clone(),
+                    _now,
                     _now,
                 );
                 self.create_usuario(usuario_admin).await.unwrap();
             }
+            Err(err) => {
+                error!(error = %err, "Não foi possível verificar o usuário Admin");
+            }
         }
     }
 }
 
 #[async_trait]
 impl UsuarioGateway for PostgresUsuarioGateway {
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn UsuarioGateway + Sync + Send> {
+        Arc::new(self.com_loja(loja_id))
+    }
+
+    #[instrument(level = "debug", skip(self))]
     async fn get_usuarios(&self) -> Result<Vec<Usuario>, DomainError> {
-        let usuarios = self.client.query(QUERY_USUARIOS, &[]).await.unwrap();
+        let client = get_client(&self.pool).await?;
+        let usuarios = client.query(QUERY_USUARIOS, &[&self.loja()]).await?;
         let mut usuarios_vec = Vec::new();
         for usuario in usuarios {
             usuarios_vec.push(Usuario::from_row(&usuario));
@@ -76,26 +93,35 @@ impl UsuarioGateway for PostgresUsuarioGateway {
         Ok(usuarios_vec)
     }
 
+    #[instrument(level = "debug", skip(self))]
     async fn get_usuario_by_id(&self, id: usize) -> Result<Usuario, DomainError> {
-        let id = id as i32;
-        let usuario = self.client.query_one(QUERY_USUARIO_BY_ID, &[&id]).await;
+        let client = get_client(&self.pool).await?;
+        let usuario = client.query_opt(QUERY_USUARIO_BY_ID, &[&(id as i32), &self.loja()]).await?;
         match usuario {
-            Ok(usuario) => Ok(Usuario::from_row(&usuario)),
-            Err(_) => Err(DomainError::NotFound),
+            Some(usuario) => Ok(Usuario::from_row(&usuario)),
+            None => Err(DomainError::not_found("Usuário", id)),
         }
     }
 
+    #[instrument(level = "debug", skip(self, cpf))]
     async fn get_usuario_by_cpf(&self, cpf: Cpf) -> Result<Usuario, DomainError> {
-        let usuario = self.client.query_one(QUERY_USUARIO_BY_CPF, &[&cpf.0]).await;
+        let client = get_client(&self.pool).await?;
+        let usuario = client.query_opt(QUERY_USUARIO_BY_CPF, &[&cpf.0]).await?;
         match usuario {
-            Ok(usuario) => Ok(Usuario::from_row(&usuario)),
-            Err(_) => Err(DomainError::NotFound),
+            Some(usuario) => Ok(Usuario::from_row(&usuario)),
+            None => Err(DomainError::not_found("Usuário", cpf.0)),
         }
     }
 
-    async fn create_usuario(&mut self, usuario: Usuario) -> Result<Usuario, DomainError> {
-        let new_usuario = self
-            .client
+    #[instrument(level = "debug", skip(self, usuario))]
+    async fn create_usuario(&self, usuario: Usuario) -> Result<Usuario, DomainError> {
+        let client = get_client(&self.pool).await?;
+        let lojas: Vec<i32> = if usuario.lojas().is_empty() {
+            vec![self.loja_id.unwrap_or(LOJA_PADRAO) as i32]
+        } else {
+            usuario.lojas().iter().map(|loja| *loja as i32).collect()
+        };
+        let new_usuario = client
             .query(
                 CREATE_USUARIO,
                 &[
@@ -105,27 +131,34 @@ impl UsuarioGateway for PostgresUsuarioGateway {
                     &usuario.senha(),
                     &usuario.tipo().to_string(),
                     &usuario.status().to_string(),
+                    &lojas,
                 ],
             )
-            .await
-            .unwrap();
+            .await?;
         let new_usuario = new_usuario.get(0);
         match new_usuario {
             Some(usuario) => {
-                println!("Novo usuário cadastrado: {:?}", usuario);
-                Ok(Usuario::from_row(usuario))
+                let usuario = Usuario::from_row(usuario);
+                info!(usuario_id = *usuario.id(), "Novo usuário cadastrado");
+                Ok(usuario)
             }
-            None => Err(DomainError::Invalid("Usuário".to_string())),
+            None => Err(DomainError::persistence("Usuário não foi criado")),
         }
     }
 
+    #[instrument(level = "debug", skip(self, dados_usuario_atualizado))]
     async fn update_usuario(
-        &mut self,
+        &self,
         dados_usuario_atualizado: Usuario,
     ) -> Result<Usuario, DomainError> {
+        let client = get_client(&self.pool).await?;
         let id = dados_usuario_atualizado.id().clone() as i32;
-        let usuario_atualizado = self
-            .client
+        let lojas: Option<Vec<i32>> = if dados_usuario_atualizado.lojas().is_empty() {
+            None
+        } else {
+            Some(dados_usuario_atualizado.lojas().iter().map(|loja| *loja as i32).collect())
+        };
+        let usuario_atualizado = client
             .query(
                 UPDATE_USUARIO,
                 &[
@@ -136,22 +169,85 @@ impl UsuarioGateway for PostgresUsuarioGateway {
                     &dados_usuario_atualizado.tipo().to_string(),
                     &dados_usuario_atualizado.status().to_string(),
                     &id,
+                    &lojas,
+                    &self.loja(),
                 ],
             )
-            .await
-            .unwrap();
+            .await?;
         let usuario_atualizado = usuario_atualizado.get(0);
         match usuario_atualizado {
             Some(user) => Ok(Usuario::from_row(user)),
-            None => Err(DomainError::NotFound),
+            None => Err(DomainError::not_found("Usuário", id)),
         }
     }
 
-    async fn delete_usuario(&mut self, cpf: Cpf) -> Result<(), DomainError> {
-        let deleted_usuario = self.client.query_one(DELETE_USUARIO, &[&cpf.0]).await;
+    #[instrument(level = "debug", skip(self, cpf))]
+    async fn delete_usuario(&self, cpf: Cpf) -> Result<(), DomainError> {
+        let client = get_client(&self.pool).await?;
+        if let Some(loja) = self.loja() {
+            if client.query_opt(REMOVE_USUARIO_DA_LOJA, &[&cpf.0, &loja]).await?.is_none() {
+                return Err(DomainError::not_found("Usuário", cpf.0));
+            }
+            client.query_opt(DELETE_USUARIO, &[&cpf.0, &self.loja()]).await?;
+            return Ok(());
+        }
+        let deleted_usuario = client.query_opt(DELETE_USUARIO, &[&cpf.0, &self.loja()]).await?;
         match deleted_usuario {
-            Ok(_) => Ok(()),
-            _ => Err(DomainError::NotFound),
+            Some(_) => Ok(()),
+            None => Err(DomainError::not_found("Usuário", cpf.0)),
         }
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::loja::{Loja, LOJA_PADRAO};
+    use crate::entities::usuario::{Status, Tipo};
+    use crate::external::postgres::PgConnectionManager;
+    use crate::gateways::postgres_loja_gateway::PostgresLojaRepository;
+    use crate::traits::loja_gateway::LojaGateway;
+
+    #[tokio::test]
+    #[ignore = "precisa de um postgres com as migrations aplicadas, ex.: o do docker-compose.dev.yml"]
+    async fn test_usuario_de_outra_loja_nao_aparece() {
+        let pool = PgConnectionManager::new(std::env::var("DB_URL").expect("DB_URL"), 2).unwrap().pool;
+        let loja = PostgresLojaRepository::new(pool.clone())
+            .await
+            .create_loja(Loja::new(0, "Filial".to_string(), Utc::now(), Utc::now()))
+            .await
+            .unwrap();
+        let todas = PostgresUsuarioGateway::new(pool).await;
+        let padrao = todas.com_loja(LOJA_PADRAO);
+        let filial = todas.com_loja(*loja.id());
+        let cpf = Cpf::new("123.456.789-09".to_string()).unwrap();
+        // Sobra de uma execução que falhou no meio
+        let _ = todas.delete_usuario(cpf.clone()).await;
+
+        let usuario = filial
+            .create_usuario(Usuario::new(
+                0,
+                "Cozinheiro".to_string(),
+                "cozinha@fiap.com.br".to_string(),
+                cpf.clone(),
+                "panela".to_string(),
+                Tipo::Cozinha,
+                Status::Ativo,
+                Utc::now(),
+                Utc::now(),
+            ))
+            .await
+            .unwrap();
+        assert_eq!(usuario.lojas(), &vec![*loja.id()]);
+
+        assert!(padrao.get_usuario_by_id(*usuario.id()).await.is_err());
+        assert!(padrao.get_usuarios().await.unwrap().iter().all(|outro| outro.id() != usuario.id()));
+        assert!(padrao.update_usuario(usuario.clone()).await.is_err());
+        assert!(padrao.delete_usuario(cpf.clone()).await.is_err());
+        // O login procura o CPF em todas as lojas
+        assert_eq!(padrao.get_usuario_by_cpf(cpf.clone()).await.unwrap().id(), usuario.id());
+
+        filial.delete_usuario(cpf.clone()).await.unwrap();
+        assert!(todas.get_usuario_by_cpf(cpf).await.is_err());
+    }
+}
@@ -0,0 +1,136 @@
+use aws_config::meta::region::RegionProviderChain;
+use aws_sdk_s3::primitives::ByteStream;
+use aws_sdk_s3::Client;
+use tracing::info;
+
+use crate::base::domain_error::DomainError;
+use crate::traits::health_check_adapter::HealthCheckAdapter;
+use crate::traits::storage_gateway::{Arquivo, StorageGateway};
+
+pub struct S3StorageGateway {
+    client: Client,
+    bucket: String,
+}
+
+impl S3StorageGateway {
+    // `endpoint` aponta para um serviço compatível com S3, como o MinIO local;
+    // sem ele o client usa os endpoints da AWS
+    pub async fn new(bucket: String, endpoint: Option<String>) -> Self {
+        let region_provider = RegionProviderChain::default_provider().or_else("us-east-1");
+        let config = aws_config::from_env().region(region_provider).load().await;
+
+        let mut s3_config = aws_sdk_s3::config::Builder::from(&config);
+        if let Some(endpoint) = endpoint {
+            info!(endpoint = %endpoint, "Usando endpoint S3 customizado");
+            s3_config = s3_config.endpoint_url(endpoint).force_path_style(true);
+        }
+        S3StorageGateway {
+            client: Client::from_conf(s3_config.build()),
+            bucket,
+        }
+    }
+}
+
+#[async_trait]
+impl StorageGateway for S3StorageGateway {
+    async fn salvar(&self, chave: &str, arquivo: Arquivo) -> Result<(), DomainError> {
+        self.client
+            .put_object()
+            .bucket(&self.bucket)
+            .key(chave)
+            .content_type(arquivo.content_type)
+            .body(ByteStream::from(arquivo.conteudo))
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(|err| DomainError::external_service("S3", "Erro ao gravar arquivo").com_causa(err))
+    }
+
+    async fn buscar(&self, chave: &str) -> Result<Arquivo, DomainError> {
+        let resposta = self
+            .client
+            .get_object()
+            .bucket(&self.bucket)
+            .key(chave)
+            .send()
+            .await
+            .map_err(|err| match err.as_service_error() {
+                Some(erro) if erro.is_no_such_key() => DomainError::not_found("Arquivo", chave),
+                _ => DomainError::external_service("S3", "Erro ao buscar arquivo").com_causa(err),
+            })?;
+        let content_type = resposta
+            .content_type()
+            .unwrap_or("application/octet-stream")
+            .to_string();
+        let conteudo = resposta
+            .body
+            .collect()
+            .await
+            .map_err(|err| DomainError::external_service("S3", "Erro ao ler arquivo").com_causa(err))?;
+        Ok(Arquivo {
+            conteudo: conteudo.into_bytes().to_vec(),
+            content_type,
+        })
+    }
+
+    async fn remover(&self, chave: &str) -> Result<(), DomainError> {
+        self.client
+            .delete_object()
+            .bucket(&self.bucket)
+            .key(chave)
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(|err| DomainError::external_service("S3", "Erro ao remover arquivo").com_causa(err))
+    }
+}
+
+#[async_trait]
+impl HealthCheckAdapter for S3StorageGateway {
+    fn nome(&self) -> String {
+        "s3".to_string()
+    }
+
+    // Sem o storage só as fotos ficam indisponíveis
+    fn critico(&self) -> bool {
+        false
+    }
+
+    async fn verificar(&self) -> Result<(), String> {
+        self.client
+            .head_bucket()
+            .bucket(&self.bucket)
+            .send()
+            .await
+            .map(|_| ())
+            .map_err(|err| err.to_string())
+    }
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    // S3_ENDPOINT=http://localhost:9100 S3_BUCKET=fotos cargo test s3_storage -- --ignored
+    #[tokio::test]
+    #[ignore = "precisa de um serviço compatível com S3, ex.: o MinIO do docker-compose.dev.yml"]
+    async fn test_salvar_buscar_e_remover() {
+        let bucket = std::env::var("S3_BUCKET").expect("S3_BUCKET");
+        let storage = S3StorageGateway::new(bucket, std::env::var("S3_ENDPOINT").ok()).await;
+        let chave = format!("testes/{}.png", uuid::Uuid::new_v4());
+        let arquivo = Arquivo {
+            conteudo: vec![1, 2, 3],
+            content_type: "image/png".to_string(),
+        };
+
+        storage.salvar(&chave, arquivo.clone()).await.unwrap();
+        assert_eq!(storage.buscar(&chave).await.unwrap(), arquivo);
+
+        storage.remover(&chave).await.unwrap();
+        assert!(matches!(
+            storage.buscar(&chave).await,
+            Err(DomainError::NotFound { .. })
+        ));
+    }
+}
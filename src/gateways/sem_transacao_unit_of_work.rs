@@ -0,0 +1,68 @@
+use std::sync::Arc;
+use tracing::warn;
+
+use crate::base::domain_error::DomainError;
+use crate::traits::pagamento_gateway::PagamentoGateway;
+use crate::traits::pedido_gateway::PedidoGateway;
+use crate::traits::unit_of_work::{Transacao, UnitOfWork};
+
+// Para repositórios sem suporte a transação: as escritas são aplicadas na hora
+// e o rollback não consegue desfazê-las
+pub struct SemTransacaoUnitOfWork {
+    pedido_repository: Arc<dyn PedidoGateway + Sync + Send>,
+    pagamento_repository: Arc<dyn PagamentoGateway + Sync + Send>,
+}
+
+impl SemTransacaoUnitOfWork {
+    pub fn new(
+        pedido_repository: Arc<dyn PedidoGateway + Sync + Send>,
+        pagamento_repository: Arc<dyn PagamentoGateway + Sync + Send>,
+    ) -> Self {
+        SemTransacaoUnitOfWork {
+            pedido_repository,
+            pagamento_repository,
+        }
+    }
+}
+
+#[async_trait]
+impl UnitOfWork for SemTransacaoUnitOfWork {
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn UnitOfWork + Sync + Send> {
+        Arc::new(SemTransacaoUnitOfWork::new(
+            self.pedido_repository.na_loja(loja_id),
+            self.pagamento_repository.na_loja(loja_id),
+        ))
+    }
+
+    async fn begin(&self) -> Result<Box<dyn Transacao + Sync + Send>, DomainError> {
+        Ok(Box::new(SemTransacao {
+            pedido_repository: self.pedido_repository.clone(),
+            pagamento_repository: self.pagamento_repository.clone(),
+        }))
+    }
+}
+
+pub struct SemTransacao {
+    pedido_repository: Arc<dyn PedidoGateway + Sync + Send>,
+    pagamento_repository: Arc<dyn PagamentoGateway + Sync + Send>,
+}
+
+#[async_trait]
+impl Transacao for SemTransacao {
+    fn pedido_repository(&self) -> Arc<dyn PedidoGateway + Sync + Send> {
+        self.pedido_repository.clone()
+    }
+
+    fn pagamento_repository(&self) -> Arc<dyn PagamentoGateway + Sync + Send> {
+        self.pagamento_repository.clone()
+    }
+
+    async fn commit(&self) -> Result<(), DomainError> {
+        Ok(())
+    }
+
+    async fn rollback(&self) -> Result<(), DomainError> {
+        warn!("Rollback sem transação: as escritas já aplicadas não foram desfeitas");
+        Ok(())
+    }
+}
@@ -4,10 +4,10 @@
 
 mod adapters;
 pub mod api;
-mod base;
+pub mod base;
 mod controllers;
-mod entities;
+pub mod entities;
 mod external;
 mod gateways;
-mod traits;
+pub mod traits;
 mod use_cases;
\ No newline at end of file
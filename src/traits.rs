@@ -5,4 +5,9 @@ pub mod pedido_gateway;
 pub mod produto_gateway;
 pub mod pagamento_gateway;
 pub mod pagamento_adapter;
-pub mod pagamento_webhook_adapter;
\ No newline at end of file
+pub mod pagamento_webhook_adapter;
+pub mod unit_of_work;
+pub mod health_check_adapter;
+pub mod storage_gateway;
+pub mod loja_gateway;
+pub mod estacao_gateway;
@@ -5,9 +5,24 @@ use crate::{
     entities::usuario::{Tipo, Usuario},
 };
 
+// O que um token válido garante sobre quem fez a requisição
+#[derive(Clone, Debug, PartialEq)]
+pub struct DadosDoToken {
+    pub usuario_id: String,
+    pub tipo: Tipo,
+    // Lojas em que o usuário pode operar
+    pub lojas: Vec<usize>,
+}
+
+impl DadosDoToken {
+    pub fn acessa_loja(&self, loja_id: usize) -> bool {
+        self.lojas.contains(&loja_id)
+    }
+}
+
 #[automock]
 #[async_trait]
 pub trait AuthenticationAdapter{
     async fn get_token(&self, user: Usuario) -> Result<String, DomainError>;
-    async fn validate_token(&self, token: String, role: Option<Tipo>) -> Result<String, DomainError>;
+    async fn validate_token(&self, token: String, role: Option<Tipo>) -> Result<DadosDoToken, DomainError>;
 }
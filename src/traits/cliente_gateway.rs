@@ -15,7 +15,10 @@ pub trait ClienteGateway {
 
     async fn get_cliente_by_id(&self, id: usize) -> Result<Cliente, DomainError>;
 
-    async fn create_cliente(&mut self, cliente: Cliente) -> Result<Cliente, DomainError>;
+    // Ids inexistentes são ignorados; a ordem do retorno não é garantida
+    async fn get_clientes_by_ids(&self, ids: Vec<usize>) -> Result<Vec<Cliente>, DomainError>;
 
-    async fn delete_cliente(&mut self, cpf: Cpf) -> Result<(), DomainError>;
+    async fn create_cliente(&self, cliente: Cliente) -> Result<Cliente, DomainError>;
+
+    async fn delete_cliente(&self, cpf: Cpf) -> Result<(), DomainError>;
 }
@@ -0,0 +1,20 @@
+use mockall::*;
+use std::sync::Arc;
+
+use crate::base::domain_error::DomainError;
+use crate::entities::estacao::Estacao;
+
+#[automock]
+#[async_trait]
+pub trait EstacaoGateway {
+    // Cópia que só enxerga e grava estações da loja; estações criadas por ela pertencem à loja
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn EstacaoGateway + Sync + Send>;
+
+    async fn get_estacoes(&self) -> Result<Vec<Estacao>, DomainError>;
+
+    async fn get_estacao_by_id(&self, id: usize) -> Result<Estacao, DomainError>;
+
+    async fn create_estacao(&self, estacao: Estacao) -> Result<Estacao, DomainError>;
+
+    async fn update_estacao(&self, estacao: Estacao) -> Result<Estacao, DomainError>;
+}
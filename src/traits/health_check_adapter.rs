@@ -0,0 +1,13 @@
+use mockall::*;
+
+// Uma dependência externa verificada pelo endpoint de readiness
+#[automock]
+#[async_trait]
+pub trait HealthCheckAdapter {
+    fn nome(&self) -> String;
+
+    // Dependências críticas derrubam a readiness; as demais só marcam o serviço como degradado
+    fn critico(&self) -> bool;
+
+    async fn verificar(&self) -> Result<(), String>;
+}
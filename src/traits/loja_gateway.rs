@@ -0,0 +1,16 @@
+use mockall::*;
+
+use crate::base::domain_error::DomainError;
+use crate::entities::loja::Loja;
+
+#[automock]
+#[async_trait]
+pub trait LojaGateway {
+    async fn get_lojas(&self) -> Result<Vec<Loja>, DomainError>;
+
+    async fn get_loja_by_id(&self, id: usize) -> Result<Loja, DomainError>;
+
+    async fn create_loja(&self, loja: Loja) -> Result<Loja, DomainError>;
+
+    async fn update_loja(&self, loja: Loja) -> Result<Loja, DomainError>;
+}
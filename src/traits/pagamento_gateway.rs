@@ -1,4 +1,5 @@
 use mockall::*;
+use std::sync::Arc;
 
 use crate::base::domain_error::DomainError;
 use crate::entities::pagamento::Pagamento;
@@ -6,13 +7,16 @@ use crate::entities::pagamento::Pagamento;
 #[automock]
 #[async_trait]
 pub trait PagamentoGateway {
-    async fn create_pagamento(&mut self, pagamento: Pagamento) -> Result<Pagamento, DomainError>;
+    // Cópia que só enxerga e grava pagamentos da loja
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn PagamentoGateway + Sync + Send>;
 
-    async fn get_pagamento_by_id_pedido(&mut self, id_pagamento: usize) -> Result<Pagamento, DomainError>;
+    async fn create_pagamento(&self, pagamento: Pagamento) -> Result<Pagamento, DomainError>;
 
-    async fn update_pagamento(&mut self, pagamento: Pagamento) -> Result<Pagamento, DomainError>;
+    async fn get_pagamento_by_id_pedido(&self, id_pagamento: usize) -> Result<Pagamento, DomainError>;
+
+    async fn update_pagamento(&self, pagamento: Pagamento) -> Result<Pagamento, DomainError>;
 
     // async fn get_pagamento_by_id(&self, id: usize) -> Result<Pagamento, DomainError>;
 
-    // async fn update_pagamento(&mut self, pagamento: Pagamento) -> Result<Pagamento, DomainError>;
+    // async fn update_pagamento(&self, pagamento: Pagamento) -> Result<Pagamento, DomainError>;
 }
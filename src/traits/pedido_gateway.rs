@@ -1,11 +1,15 @@
+use chrono::{DateTime, Utc};
 use mockall::*;
+use std::sync::Arc;
 
 use crate::base::domain_error::DomainError;
 use crate::entities::{
     cliente::Cliente,
+    estacao::StatusDoTicket,
     pedido::{Pedido, Status},
     produto::Produto,
     pagamento::Pagamento,
+    tempo_de_preparo::TempoDePreparo,
 };
 use std::fmt;
 use std::str::FromStr;
@@ -45,49 +49,93 @@ impl fmt::Display for Status {
     }
 }
 
+impl FromStr for StatusDoTicket {
+    type Err = ();
+
+    fn from_str(input: &str) -> Result<StatusDoTicket, Self::Err> {
+        match input {
+            "Recebido" => Ok(StatusDoTicket::Recebido),
+            "EmPreparacao" => Ok(StatusDoTicket::EmPreparacao),
+            "Pronto" => Ok(StatusDoTicket::Pronto),
+            _ => Err(()),
+        }
+    }
+}
+
+impl fmt::Display for StatusDoTicket {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{}",
+            match self {
+                StatusDoTicket::Recebido => "Recebido",
+                StatusDoTicket::EmPreparacao => "EmPreparacao",
+                StatusDoTicket::Pronto => "Pronto",
+            }
+        )
+    }
+}
+
 #[automock]
 #[async_trait]
 pub trait PedidoGateway {
-    async fn create_pedido(&mut self, pedido: Pedido) -> Result<Pedido, DomainError>;
+    // Cópia que só enxerga e grava pedidos da loja; pedidos criados por ela pertencem à loja
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn PedidoGateway + Sync + Send>;
 
-    async fn lista_pedidos(&mut self) -> Result<Vec<Pedido>, DomainError>;
+    async fn create_pedido(&self, pedido: Pedido) -> Result<Pedido, DomainError>;
 
+    // Pedidos da loja que não foram finalizados; a ordem de atendimento é da `PoliticaDaFila`
+    async fn lista_pedidos(&self) -> Result<Vec<Pedido>, DomainError>;
+
+    // Pedidos da loja com status em `STATUS_NA_FILA_DA_COZINHA`, sem ordem definida
     async fn get_pedidos_novos(&self) -> Result<Vec<Pedido>, DomainError>;
 
+    // Médias por produto dos pedidos da loja que ficaram prontos a partir de `desde`
+    async fn get_tempos_de_preparo(&self, desde: DateTime<Utc>) -> Result<Vec<TempoDePreparo>, DomainError>;
+
     async fn get_pedido_by_id(&self, pedido_id: usize) -> Result<Pedido, DomainError>;
 
     async fn cadastrar_cliente(
-        &mut self,
+        &self,
         pedido_id: usize,
         cliente: Cliente,
     ) -> Result<Pedido, DomainError>;
 
     async fn cadastrar_lanche(
-        &mut self,
+        &self,
         pedido_id: usize,
         lanche: Produto,
     ) -> Result<Pedido, DomainError>;
 
     async fn cadastrar_acompanhamento(
-        &mut self,
+        &self,
         pedido_id: usize,
         acompanhamento: Produto,
     ) -> Result<Pedido, DomainError>;
 
     async fn cadastrar_bebida(
-        &mut self,
+        &self,
         pedido_id: usize,
         bebida: Produto,
     ) -> Result<Pedido, DomainError>;
 
     async fn cadastrar_pagamento(
-        &mut self,
+        &self,
         pagamento: Pagamento,
     ) -> Result<Pagamento, DomainError>;
 
     async fn atualiza_status(
-        &mut self,
+        &self,
         pedido_id: usize,
         status: Status,
     ) -> Result<Pedido, DomainError>;
+
+    // Bloqueia o pedido até o fim da transação, então o pedido devolvido já enxerga os
+    // tickets gravados por outras estações
+    async fn atualiza_status_do_ticket(
+        &self,
+        pedido_id: usize,
+        estacao_id: usize,
+        status: StatusDoTicket,
+    ) -> Result<Pedido, DomainError>;
 }
@@ -1,6 +1,8 @@
 use mockall::*;
+use std::sync::Arc;
 
 use crate::base::domain_error::DomainError;
+use crate::entities::preco_historico::PrecoHistorico;
 use crate::entities::produto::{Produto, Categoria};
 use std::fmt;
 use std::str::FromStr;
@@ -37,15 +39,30 @@ impl fmt::Display for Categoria {
 #[automock]
 #[async_trait]
 pub trait ProdutoGateway {
+  // Cópia que só enxerga e grava produtos da loja; produtos criados por ela pertencem à loja
+  fn na_loja(&self, loja_id: usize) -> Arc<dyn ProdutoGateway + Sync + Send>;
+
+  // As listagens trazem só o cardápio; buscas por id também encontram produtos arquivados
   async fn get_produtos(&self) -> Result<Vec<Produto>, DomainError>;
 
   async fn get_produto_by_id(&self, id: usize) -> Result<Produto, DomainError>;
 
+  // Ids inexistentes são ignorados; a ordem do retorno não é garantida
+  async fn get_produtos_by_ids(&self, ids: Vec<usize>) -> Result<Vec<Produto>, DomainError>;
+
   async fn get_produtos_by_categoria(&self, categoria: Categoria) -> Result<Vec<Produto>, DomainError>;
 
-  async fn create_produto(&mut self, produto: Produto) -> Result<Produto, DomainError>;
+  async fn create_produto(&self, produto: Produto) -> Result<Produto, DomainError>;
+
+  // Só grava se a versão armazenada for igual a `new_produto_data.versao()`, senão devolve Conflict;
+  // o produto retornado já vem com a versão seguinte
+  async fn update_produto(&self, new_produto_data: Produto) -> Result<Produto, DomainError>;
+
+  // Exclusão lógica: o produto é arquivado e continua referenciado pelos pedidos antigos
+  async fn delete_produto(&self, id: usize) -> Result<(), DomainError>;
 
-  async fn update_produto(&mut self, new_produto_data: Produto) -> Result<Produto, DomainError>;
+  async fn restaurar_produto(&self, id: usize) -> Result<Produto, DomainError>;
 
-  async fn delete_produto(&mut self, id: usize) -> Result<(), DomainError>;
+  // Um registro por alteração de preço, inclusive o preço inicial
+  async fn get_historico_de_precos(&self, ids: Vec<usize>) -> Result<Vec<PrecoHistorico>, DomainError>;
 }
\ No newline at end of file
@@ -0,0 +1,24 @@
+use mockall::*;
+
+use crate::base::domain_error::DomainError;
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct Arquivo {
+    pub conteudo: Vec<u8>,
+    pub content_type: String,
+}
+
+// Armazenamento de arquivos binários, como as fotos dos produtos. A chave é um
+// caminho relativo, ex.: "produtos/1/3fa2c1.png"
+#[automock]
+#[async_trait]
+pub trait StorageGateway {
+    // Sobrescreve o arquivo se a chave já existir
+    async fn salvar(&self, chave: &str, arquivo: Arquivo) -> Result<(), DomainError>;
+
+    // NotFound quando a chave não existe
+    async fn buscar(&self, chave: &str) -> Result<Arquivo, DomainError>;
+
+    // Remover uma chave inexistente não é erro
+    async fn remover(&self, chave: &str) -> Result<(), DomainError>;
+}
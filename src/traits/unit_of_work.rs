@@ -0,0 +1,27 @@
+use mockall::*;
+use std::sync::Arc;
+
+use crate::base::domain_error::DomainError;
+use crate::traits::{pagamento_gateway::PagamentoGateway, pedido_gateway::PedidoGateway};
+
+// Repositórios que enxergam e escrevem dentro de uma mesma transação
+#[automock]
+#[async_trait]
+pub trait Transacao {
+    fn pedido_repository(&self) -> Arc<dyn PedidoGateway + Sync + Send>;
+
+    fn pagamento_repository(&self) -> Arc<dyn PagamentoGateway + Sync + Send>;
+
+    async fn commit(&self) -> Result<(), DomainError>;
+
+    async fn rollback(&self) -> Result<(), DomainError>;
+}
+
+#[automock]
+#[async_trait]
+pub trait UnitOfWork {
+    // Transações cujos repositórios só enxergam a loja
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn UnitOfWork + Sync + Send>;
+
+    async fn begin(&self) -> Result<Box<dyn Transacao + Sync + Send>, DomainError>;
+}
@@ -1,4 +1,5 @@
 use mockall::*;
+use std::sync::Arc;
 
 use crate::base::domain_error::DomainError;
 use crate::entities::{
@@ -36,18 +37,25 @@ impl fmt::Display for Status {
 #[automock]
 #[async_trait]
 pub trait UsuarioGateway {
+    // Cópia que só enxerga os usuários que trabalham na loja. Sem ela a busca é em todas
+    // as lojas, o que só o login deve fazer
+    fn na_loja(&self, loja_id: usize) -> Arc<dyn UsuarioGateway + Sync + Send>;
+
     async fn get_usuarios(&self) -> Result<Vec<Usuario>, DomainError>;
 
     async fn get_usuario_by_id(&self, id: usize) -> Result<Usuario, DomainError>;
 
+    // Busca sempre em todas as lojas: o CPF é único e o login não sabe a loja
     async fn get_usuario_by_cpf(&self, cpf: Cpf) -> Result<Usuario, DomainError>;
 
-    async fn create_usuario(&mut self, user: Usuario) -> Result<Usuario, DomainError>;
+    // Usuário sem lojas é vinculado à loja do gateway
+    async fn create_usuario(&self, user: Usuario) -> Result<Usuario, DomainError>;
 
+    // Lista de lojas vazia mantém as lojas atuais
     async fn update_usuario(
-        &mut self,
+        &self,
         dados_usuario_atualizado: Usuario,
     ) -> Result<Usuario, DomainError>;
 
-    async fn delete_usuario(&mut self, cpf: Cpf) -> Result<(), DomainError>;
+    async fn delete_usuario(&self, cpf: Cpf) -> Result<(), DomainError>;
 }
@@ -3,3 +3,7 @@ pub mod gerenciamento_de_clientes_use_case;
 pub mod preparacao_e_entrega_use_case;
 pub mod pedidos_e_pagamentos_use_case;
 pub mod gerenciamento_de_produtos_use_case;
+pub mod verificacao_de_saude_use_case;
+pub mod fotos_de_produtos_use_case;
+pub mod gerenciamento_de_lojas_use_case;
+pub mod gerenciamento_de_estacoes_use_case;
@@ -0,0 +1,234 @@
+use std::io::Cursor;
+use std::sync::Arc;
+
+use image::{DynamicImage, ImageFormat};
+use sha2::{Digest, Sha256};
+use tracing::warn;
+
+use crate::base::domain_error::DomainError;
+use crate::entities::produto::Produto;
+use crate::traits::produto_gateway::ProdutoGateway;
+use crate::traits::storage_gateway::{Arquivo, StorageGateway};
+
+pub const TAMANHO_MAXIMO_FOTO: usize = 5 * 1024 * 1024;
+const LADO_DA_MINIATURA: u32 = 256;
+
+#[derive(Clone)]
+pub struct FotoDeProdutoUseCase {
+    produto_repository: Arc<dyn ProdutoGateway + Sync + Send>,
+    storage: Arc<dyn StorageGateway + Sync + Send>,
+}
+
+fn formato_aceito(content_type: &str) -> Result<(ImageFormat, &'static str), DomainError> {
+    match content_type {
+        "image/png" => Ok((ImageFormat::Png, "png")),
+        "image/jpeg" | "image/jpg" => Ok((ImageFormat::Jpeg, "jpg")),
+        _ => Err(DomainError::validation("foto", "Formato não suportado, envie PNG ou JPEG")),
+    }
+}
+
+// A chave muda a cada foto nova, então um cache da foto anterior nunca é servido no lugar dela
+fn chave_da_foto(produto_id: usize, conteudo: &[u8], extensao: &str) -> String {
+    let hash: String = Sha256::digest(conteudo)
+        .iter()
+        .take(8)
+        .map(|byte| format!("{:02x}", byte))
+        .collect();
+    format!("produtos/{}/{}.{}", produto_id, hash, extensao)
+}
+
+pub fn chave_da_miniatura(chave: &str) -> String {
+    match chave.rsplit_once('.') {
+        Some((nome, extensao)) => format!("{}_miniatura.{}", nome, extensao),
+        None => format!("{}_miniatura", chave),
+    }
+}
+
+fn gerar_miniatura(imagem: &DynamicImage, formato: ImageFormat) -> Result<Vec<u8>, DomainError> {
+    let mut miniatura = Vec::new();
+    imagem
+        .thumbnail(LADO_DA_MINIATURA, LADO_DA_MINIATURA)
+        .write_to(&mut Cursor::new(&mut miniatura), formato)
+        .map_err(|err| DomainError::validation("foto", "Não foi possível gerar a miniatura").com_causa(err))?;
+    Ok(miniatura)
+}
+
+impl FotoDeProdutoUseCase {
+    pub fn new(
+        produto_repository: Arc<dyn ProdutoGateway + Sync + Send>,
+        storage: Arc<dyn StorageGateway + Sync + Send>,
+    ) -> Self {
+        FotoDeProdutoUseCase {
+            produto_repository,
+            storage,
+        }
+    }
+
+    // Grava a foto e a miniatura e aponta `Produto::foto` para a chave da foto
+    pub async fn enviar_foto(
+        &self,
+        id: usize,
+        conteudo: Vec<u8>,
+        content_type: &str,
+    ) -> Result<Produto, DomainError> {
+        let (formato, extensao) = formato_aceito(content_type)?;
+        if conteudo.is_empty() {
+            return Err(DomainError::empty("foto"));
+        }
+        if conteudo.len() > TAMANHO_MAXIMO_FOTO {
+            return Err(DomainError::validation(
+                "foto",
+                format!("Imagem maior que {} MB", TAMANHO_MAXIMO_FOTO / 1024 / 1024),
+            ));
+        }
+        // Decodificar confirma que o conteúdo é mesmo do formato declarado
+        let imagem = image::load_from_memory_with_format(&conteudo, formato)
+            .map_err(|err| DomainError::validation("foto", "Arquivo não é uma imagem válida").com_causa(err))?;
+        let miniatura = gerar_miniatura(&imagem, formato)?;
+
+        let mut produto = self.produto_repository.get_produto_by_id(id).await?;
+        let foto_anterior = produto.foto().clone();
+        let chave = chave_da_foto(id, &conteudo, extensao);
+        let content_type = formato.to_mime_type().to_string();
+        self.storage
+            .salvar(&chave, Arquivo { conteudo, content_type: content_type.clone() })
+            .await?;
+        self.storage
+            .salvar(&chave_da_miniatura(&chave), Arquivo { conteudo: miniatura, content_type })
+            .await?;
+
+        produto.set_foto(chave.clone());
+        let produto = match self.produto_repository.update_produto(produto).await {
+            Ok(produto) => produto,
+            Err(err) => {
+                self.remover_foto(&chave).await;
+                return Err(err);
+            }
+        };
+
+        // Fotos que não vieram de um envio (ex.: "hamburguer.jpg" dos dados iniciais) não são removidas
+        if foto_anterior != chave && foto_anterior.starts_with(&format!("produtos/{}/", id)) {
+            self.remover_foto(&foto_anterior).await;
+        }
+        Ok(produto)
+    }
+
+    // Devolve a chave junto com o arquivo, para ser usada como ETag
+    pub async fn buscar_foto(&self, id: usize, miniatura: bool) -> Result<(String, Arquivo), DomainError> {
+        let produto = self.produto_repository.get_produto_by_id(id).await?;
+        let chave = if miniatura {
+            chave_da_miniatura(produto.foto())
+        } else {
+            produto.foto().clone()
+        };
+        match self.storage.buscar(&chave).await {
+            Ok(arquivo) => Ok((chave, arquivo)),
+            Err(DomainError::NotFound { .. }) => Err(DomainError::not_found("Foto do produto", id)),
+            Err(err) => Err(err),
+        }
+    }
+
+    // Arquivo que sobra no storage não quebra nada, então a falha só é registrada
+    async fn remover_foto(&self, chave: &str) {
+        for chave in [chave.to_string(), chave_da_miniatura(chave)] {
+            if let Err(err) = self.storage.remover(&chave).await {
+                warn!(chave = %chave, error = %err, "Não foi possível remover a foto");
+            }
+        }
+    }
+}
+
+// Unit Tests
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::{ingredientes::Ingredientes, produto::Categoria};
+    use crate::traits::produto_gateway::MockProdutoGateway;
+    use crate::traits::storage_gateway::MockStorageGateway;
+    use chrono::Utc;
+    use image::RgbImage;
+
+    fn png(largura: u32, altura: u32) -> Vec<u8> {
+        let mut conteudo = Vec::new();
+        DynamicImage::ImageRgb8(RgbImage::new(largura, altura))
+            .write_to(&mut Cursor::new(&mut conteudo), ImageFormat::Png)
+            .unwrap();
+        conteudo
+    }
+
+    fn produto(foto: &str) -> Produto {
+        Produto::new(
+            1,
+            "nome".to_string(),
+            foto.to_string(),
+            "descricao".to_string(),
+            Categoria::Lanche,
+            10.0,
+            Ingredientes::new(vec![]).unwrap(),
+            Utc::now(),
+            Utc::now(),
+        )
+    }
+
+    #[test]
+    fn test_chave_da_miniatura() {
+        assert_eq!(chave_da_miniatura("produtos/1/abc.png"), "produtos/1/abc_miniatura.png");
+    }
+
+    #[tokio::test]
+    async fn test_enviar_foto_grava_foto_e_miniatura() {
+        let mut mock_produto = MockProdutoGateway::new();
+        let mut mock_storage = MockStorageGateway::new();
+
+        mock_produto
+            .expect_get_produto_by_id()
+            .returning(|_| Ok(produto("produtos/1/antiga.png")));
+        mock_produto
+            .expect_update_produto()
+            .times(1)
+            .returning(Ok);
+        mock_storage
+            .expect_salvar()
+            .withf(|chave, arquivo| !chave.ends_with("_miniatura.png") && arquivo.content_type == "image/png")
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock_storage
+            .expect_salvar()
+            .withf(|chave, arquivo| {
+                let miniatura = image::load_from_memory(&arquivo.conteudo).unwrap();
+                chave.ends_with("_miniatura.png") && miniatura.width() == LADO_DA_MINIATURA
+            })
+            .times(1)
+            .returning(|_, _| Ok(()));
+        mock_storage
+            .expect_remover()
+            .withf(|chave| chave.starts_with("produtos/1/antiga"))
+            .times(2)
+            .returning(|_| Ok(()));
+
+        let use_case = FotoDeProdutoUseCase::new(Arc::new(mock_produto), Arc::new(mock_storage));
+        let produto = use_case.enviar_foto(1, png(600, 300), "image/png").await.unwrap();
+
+        assert!(produto.foto().starts_with("produtos/1/"));
+        assert!(produto.foto().ends_with(".png"));
+    }
+
+    #[tokio::test]
+    async fn test_enviar_foto_recusa_formato_e_conteudo() {
+        let mut mock_produto = MockProdutoGateway::new();
+        let mut mock_storage = MockStorageGateway::new();
+        mock_produto.expect_get_produto_by_id().never();
+        mock_storage.expect_salvar().never();
+        let use_case = FotoDeProdutoUseCase::new(Arc::new(mock_produto), Arc::new(mock_storage));
+
+        let result = use_case.enviar_foto(1, png(10, 10), "image/gif").await;
+        assert!(matches!(result, Err(DomainError::Validation { .. })));
+
+        // Declarado como JPEG, mas o conteúdo é PNG
+        let result = use_case.enviar_foto(1, png(10, 10), "image/jpeg").await;
+        assert!(matches!(result, Err(DomainError::Validation { .. })));
+
+        let result = use_case.enviar_foto(1, vec![0; TAMANHO_MAXIMO_FOTO + 1], "image/png").await;
+        assert!(matches!(result, Err(DomainError::Validation { .. })));
+    }
+}
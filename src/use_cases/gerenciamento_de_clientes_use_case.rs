@@ -1,5 +1,4 @@
 use chrono::Utc;
-use tokio::sync::Mutex;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use std::sync::Arc;
@@ -20,39 +19,36 @@ pub struct CreateClienteInput {
 
 #[derive(Clone)]
 pub struct ClienteUseCase {
-    cliente_repository: Arc<Mutex<dyn ClienteGateway + Sync + Send>>,
+    cliente_repository: Arc<dyn ClienteGateway + Sync + Send>,
 }
 
 impl ClienteUseCase {
-    pub fn new(cliente_repository: Arc<Mutex<dyn ClienteGateway + Sync + Send>>) -> Self {
+    pub fn new(cliente_repository: Arc<dyn ClienteGateway + Sync + Send>) -> Self {
         ClienteUseCase { cliente_repository }
     }
 
     pub async fn get_clientes(&self) -> Result<Vec<Cliente>, DomainError> {
-        let cliente_repository = self.cliente_repository.lock().await;
-        cliente_repository.get_clientes().await
+        self.cliente_repository.get_clientes().await
     }
 
     pub async fn get_cliente_by_cpf(&self, cpf: Cpf) -> Result<Cliente, DomainError> {
-        let cliente_repository = self.cliente_repository.lock().await;
-        cliente_repository.get_cliente_by_cpf(cpf).await
+        self.cliente_repository.get_cliente_by_cpf(cpf).await
     }
 
     pub async fn create_cliente(
         &self,
         cliente: CreateClienteInput,
     ) -> Result<Cliente, DomainError> {
-        let mut cliente_repository = self.cliente_repository.lock().await;
         let _id = 0;
         let cpf = Cpf::new(cliente.cpf.clone())?;
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
-        let cliente = cliente_repository
+        let _now = Utc::now();
+        let cliente = self.cliente_repository
             .create_cliente(Cliente::new(
                 _id,
                 cliente.nome,
                 cliente.email,
                 cpf,
-                _now.clone(),
+                _now,
                 _now,
             ))
             .await?;
@@ -61,8 +57,7 @@ impl ClienteUseCase {
     }
 
     pub async fn delete_cliente(&self, cpf: Cpf) -> Result<(), DomainError> {
-        let mut cliente_repository = self.cliente_repository.lock().await;
-        cliente_repository.delete_cliente(cpf).await?;
+        self.cliente_repository.delete_cliente(cpf).await?;
         Ok(())
     }
 }
@@ -76,8 +71,7 @@ mod tests {
     use mockall::predicate::*;
     use crate::entities::cliente::Cliente;
     use crate::traits::cliente_gateway::MockClienteGateway;
-    use tokio::sync::Mutex;
-    use std::sync::Arc;
+        use std::sync::Arc;
     use tokio;
 
     #[tokio::test]
@@ -89,8 +83,8 @@ mod tests {
             "nome".to_string(),
             "email".to_string(),
             Cpf::new("000.000.000-00".to_string()).unwrap(),
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_cliente = returned_cliente.clone();
@@ -99,7 +93,7 @@ mod tests {
             .times(1)
             .returning(move || Ok(vec![returned_cliente.clone()]));
 
-        let use_case = ClienteUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = ClienteUseCase::new(Arc::new(mock));
         let result = use_case.get_clientes().await;
         assert_eq!(result.unwrap()[0].id(), expected_cliente.id());
     }
@@ -113,8 +107,8 @@ mod tests {
             "nome".to_string(),
             "email".to_string(),
             Cpf::new("000.000.000-00".to_string()).unwrap(),
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_cliente = returned_cliente.clone();
@@ -124,7 +118,7 @@ mod tests {
             .with(eq(Cpf::new("000.000.000-00".to_string()).unwrap()))
             .returning(move |_| Ok(returned_cliente.clone()));
 
-        let use_case = ClienteUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = ClienteUseCase::new(Arc::new(mock));
         let result = use_case.get_cliente_by_cpf(Cpf::new("000.000.000-00".to_string()).unwrap()).await;
         assert_eq!(result.unwrap().id(), expected_cliente.id());
     }
@@ -138,8 +132,8 @@ mod tests {
             "nome".to_string(),
             "email".to_string(),
             Cpf::new("000.000.000-00".to_string()).unwrap(),
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_cliente = returned_cliente.clone();
@@ -148,7 +142,7 @@ mod tests {
             .times(1)
             .returning(move |_| Ok(returned_cliente.clone()));
 
-        let use_case = ClienteUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = ClienteUseCase::new(Arc::new(mock));
         let result = use_case.create_cliente(CreateClienteInput {
             nome: "nome".to_string(),
             email: "email".to_string(),
@@ -167,7 +161,7 @@ mod tests {
             .with(eq(Cpf::new("000.000.000-00".to_string()).unwrap()))
             .returning(move |_| Ok(()));
 
-        let use_case = ClienteUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = ClienteUseCase::new(Arc::new(mock));
         let result = use_case.delete_cliente(Cpf::new("000.000.000-00".to_string()).unwrap()).await;
         assert_eq!(result.unwrap(), ());
     }
@@ -0,0 +1,85 @@
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::base::domain_error::DomainError;
+use crate::entities::estacao::Estacao;
+use crate::traits::estacao_gateway::EstacaoGateway;
+
+#[derive(Clone, Debug, Deserialize, JsonSchema)]
+pub struct CreateEstacaoInput {
+    nome: String,
+}
+
+#[derive(Clone)]
+pub struct EstacaoUseCase {
+    estacao_repository: Arc<dyn EstacaoGateway + Sync + Send>,
+}
+
+impl EstacaoUseCase {
+    pub fn new(estacao_repository: Arc<dyn EstacaoGateway + Sync + Send>) -> Self {
+        EstacaoUseCase { estacao_repository }
+    }
+
+    pub async fn get_estacoes(&self) -> Result<Vec<Estacao>, DomainError> {
+        self.estacao_repository.get_estacoes().await
+    }
+
+    pub async fn get_estacao_by_id(&self, id: usize) -> Result<Estacao, DomainError> {
+        self.estacao_repository.get_estacao_by_id(id).await
+    }
+
+    pub async fn create_estacao(&self, estacao: CreateEstacaoInput) -> Result<Estacao, DomainError> {
+        let _now = Utc::now();
+        let estacao = Estacao::new(0, estacao.nome, _now, _now);
+        estacao.validate_entity()?;
+        self.estacao_repository.create_estacao(estacao).await
+    }
+
+    pub async fn update_estacao(&self, id: usize, estacao: CreateEstacaoInput) -> Result<Estacao, DomainError> {
+        let mut estacao_atual = self.estacao_repository.get_estacao_by_id(id).await?;
+        estacao_atual.set_nome(estacao.nome)?;
+        estacao_atual.set_data_atualizacao(Utc::now());
+        self.estacao_repository.update_estacao(estacao_atual).await
+    }
+}
+
+unsafe impl Send for EstacaoUseCase {}
+unsafe impl Sync for EstacaoUseCase {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::estacao_gateway::MockEstacaoGateway;
+    use tokio;
+
+    #[tokio::test]
+    async fn test_create_estacao_sem_nome() {
+        let mut mock = MockEstacaoGateway::new();
+        mock.expect_create_estacao().times(0);
+
+        let use_case = EstacaoUseCase::new(Arc::new(mock));
+        let result = use_case.create_estacao(CreateEstacaoInput { nome: "".to_string() }).await;
+        assert!(matches!(result, Err(DomainError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_update_estacao() {
+        let mut mock = MockEstacaoGateway::new();
+        mock.expect_get_estacao_by_id()
+            .times(1)
+            .returning(|id| Ok(Estacao::new(id, "Chapa".to_string(), Utc::now(), Utc::now())));
+        mock.expect_update_estacao()
+            .withf(|estacao| estacao.nome() == "Fritadeira")
+            .times(1)
+            .returning(Ok);
+
+        let use_case = EstacaoUseCase::new(Arc::new(mock));
+        let estacao = use_case
+            .update_estacao(1, CreateEstacaoInput { nome: "Fritadeira".to_string() })
+            .await
+            .unwrap();
+        assert_eq!(*estacao.id(), 1);
+    }
+}
@@ -0,0 +1,153 @@
+use chrono::Utc;
+use schemars::JsonSchema;
+use serde::Deserialize;
+use std::sync::Arc;
+
+use crate::base::domain_error::DomainError;
+use crate::entities::loja::Loja;
+use crate::traits::loja_gateway::LojaGateway;
+use crate::traits::usuario_gateway::UsuarioGateway;
+
+#[derive(Clone, Debug, Deserialize, JsonSchema)]
+pub struct CreateLojaInput {
+    nome: String,
+}
+
+#[derive(Clone)]
+pub struct LojaUseCase {
+    loja_repository: Arc<dyn LojaGateway + Sync + Send>,
+    usuario_repository: Arc<dyn UsuarioGateway + Sync + Send>,
+    lojas_permitidas: Vec<usize>,
+}
+
+impl LojaUseCase {
+    // `usuario_repository` não deve estar restrito a uma loja: a loja nova ainda não tem usuários
+    pub fn new(
+        loja_repository: Arc<dyn LojaGateway + Sync + Send>,
+        usuario_repository: Arc<dyn UsuarioGateway + Sync + Send>,
+        lojas_permitidas: Vec<usize>,
+    ) -> Self {
+        LojaUseCase {
+            loja_repository,
+            usuario_repository,
+            lojas_permitidas,
+        }
+    }
+
+    fn verifica_acesso(&self, id: usize) -> Result<(), DomainError> {
+        if self.lojas_permitidas.contains(&id) {
+            Ok(())
+        } else {
+            Err(DomainError::forbidden("Usuário sem acesso a esta loja"))
+        }
+    }
+
+    pub async fn get_lojas(&self) -> Result<Vec<Loja>, DomainError> {
+        let lojas = self.loja_repository.get_lojas().await?;
+        Ok(lojas
+            .into_iter()
+            .filter(|loja| self.lojas_permitidas.contains(loja.id()))
+            .collect())
+    }
+
+    pub async fn get_loja_by_id(&self, id: usize) -> Result<Loja, DomainError> {
+        self.verifica_acesso(id)?;
+        self.loja_repository.get_loja_by_id(id).await
+    }
+
+    // Quem cria a loja passa a gerenciá-la, mas só enxerga a loja num token novo
+    pub async fn create_loja(&self, usuario_id: usize, loja: CreateLojaInput) -> Result<Loja, DomainError> {
+        let _now = Utc::now();
+        let loja = Loja::new(0, loja.nome, _now, _now);
+        loja.validate_entity()?;
+
+        let mut usuario = self.usuario_repository.get_usuario_by_id(usuario_id).await?;
+        let loja = self.loja_repository.create_loja(loja).await?;
+        let mut lojas = usuario.lojas().clone();
+        lojas.push(*loja.id());
+        usuario.set_lojas(lojas);
+        self.usuario_repository.update_usuario(usuario).await?;
+        Ok(loja)
+    }
+
+    pub async fn update_loja(&self, id: usize, loja: CreateLojaInput) -> Result<Loja, DomainError> {
+        self.verifica_acesso(id)?;
+        let mut loja_atual = self.loja_repository.get_loja_by_id(id).await?;
+        loja_atual.set_nome(loja.nome)?;
+        self.loja_repository.update_loja(loja_atual).await
+    }
+}
+
+unsafe impl Send for LojaUseCase {}
+unsafe impl Sync for LojaUseCase {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::entities::cpf::Cpf;
+    use crate::entities::usuario::{Status, Tipo, Usuario};
+    use crate::traits::loja_gateway::MockLojaGateway;
+    use crate::traits::usuario_gateway::MockUsuarioGateway;
+    use tokio;
+
+    fn loja(id: usize) -> Loja {
+        Loja::new(id, format!("Loja {}", id), Utc::now(), Utc::now())
+    }
+
+    #[tokio::test]
+    async fn test_get_lojas_so_as_permitidas() {
+        let mut mock = MockLojaGateway::new();
+        mock.expect_get_lojas()
+            .times(1)
+            .returning(|| Ok(vec![loja(1), loja(2), loja(3)]));
+
+        let use_case = LojaUseCase::new(Arc::new(mock), Arc::new(MockUsuarioGateway::new()), vec![1, 3]);
+        let lojas = use_case.get_lojas().await.unwrap();
+        assert_eq!(lojas.iter().map(|loja| *loja.id()).collect::<Vec<_>>(), vec![1, 3]);
+    }
+
+    #[tokio::test]
+    async fn test_update_loja_sem_acesso() {
+        let mut mock = MockLojaGateway::new();
+        mock.expect_update_loja().times(0);
+
+        let use_case = LojaUseCase::new(Arc::new(mock), Arc::new(MockUsuarioGateway::new()), vec![1]);
+        let result = use_case.update_loja(2, CreateLojaInput { nome: "Nova".to_string() }).await;
+        assert!(matches!(result, Err(DomainError::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_create_loja_vincula_quem_criou() {
+        let mut loja_mock = MockLojaGateway::new();
+        loja_mock.expect_create_loja()
+            .times(1)
+            .returning(|_| Ok(loja(2)));
+
+        let mut usuario_mock = MockUsuarioGateway::new();
+        usuario_mock.expect_get_usuario_by_id()
+            .times(1)
+            .returning(|id| {
+                let mut usuario = Usuario::new(
+                    id,
+                    "nome".to_string(),
+                    "email".to_string(),
+                    Cpf::new("000.000.000-00".to_string()).unwrap(),
+                    "senha".to_string(),
+                    Tipo::Admin,
+                    Status::Ativo,
+                    Utc::now(),
+                    Utc::now(),
+                );
+                usuario.set_lojas(vec![1]);
+                Ok(usuario)
+            });
+        usuario_mock.expect_update_usuario()
+            .withf(|usuario| usuario.lojas() == &vec![1, 2])
+            .times(1)
+            .returning(Ok);
+
+        let use_case = LojaUseCase::new(Arc::new(loja_mock), Arc::new(usuario_mock), vec![1]);
+        let loja = use_case.create_loja(1, CreateLojaInput { nome: "Loja 2".to_string() }).await;
+        assert_eq!(*loja.unwrap().id(), 2);
+    }
+}
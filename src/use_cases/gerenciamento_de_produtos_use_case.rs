@@ -1,15 +1,21 @@
-use chrono::Utc;
+use chrono::{NaiveDateTime, Utc};
+use chrono_tz::Tz;
 
-use tokio::sync::Mutex;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use std::sync::Arc;
 
 use crate::base::domain_error::DomainError;
 use crate::entities::{
+    alergeno::{Alergeno, AlergenosPorIngrediente},
+    cardapio::{Cardapio, ORDEM_DAS_CATEGORIAS},
+    informacao_nutricional::InformacaoNutricional,
     ingredientes::Ingredientes,
+    janela_de_venda::{JanelaDeVenda, FUSO_HORARIO_PADRAO},
+    preco_historico::PrecoHistorico,
     produto::{Categoria, Produto},
 };
+use crate::traits::estacao_gateway::EstacaoGateway;
 use crate::traits::produto_gateway::ProdutoGateway;
 
 #[derive(Clone, Debug, Deserialize, JsonSchema)]
@@ -20,6 +26,14 @@ pub struct CreateProdutoInput {
     categoria: Categoria,
     preco: f64,
     ingredientes: Ingredientes,
+    #[serde(default)]
+    alergenos_por_ingrediente: AlergenosPorIngrediente,
+    #[serde(default)]
+    informacao_nutricional: Option<InformacaoNutricional>,
+    #[serde(default)]
+    janelas_de_venda: Vec<JanelaDeVenda>,
+    #[serde(default)]
+    estacao_id: Option<usize>,
 }
 
 impl CreateProdutoInput {
@@ -38,95 +52,240 @@ impl CreateProdutoInput {
             categoria,
             preco,
             ingredientes,
+            alergenos_por_ingrediente: AlergenosPorIngrediente::new(),
+            informacao_nutricional: None,
+            janelas_de_venda: Vec::new(),
+            estacao_id: None,
         }
     }
 }
 
-#[derive(Clone, Debug, Deserialize, JsonSchema)]
+// Campos ausentes ficam como estão no produto; nos opcionais do produto, `null` apaga o valor
+#[derive(Clone, Debug, Default, Deserialize, JsonSchema)]
 pub struct UpdateProdutoInput {
     nome: Option<String>,
     foto: Option<String>,
     descricao: Option<String>,
     categoria: Option<Categoria>,
-    preco: Option<f32>,
+    preco: Option<f64>,
     ingredientes: Option<Ingredientes>,
+    alergenos_por_ingrediente: Option<AlergenosPorIngrediente>,
+    #[serde(default, deserialize_with = "serde_with::rust::double_option::deserialize")]
+    informacao_nutricional: Option<Option<InformacaoNutricional>>,
+    janelas_de_venda: Option<Vec<JanelaDeVenda>>,
+    #[serde(default, deserialize_with = "serde_with::rust::double_option::deserialize")]
+    estacao_id: Option<Option<usize>>,
 }
 
 #[derive(Clone)]
 pub struct ProdutoUseCase {
-    produto_repository: Arc<Mutex<dyn ProdutoGateway + Sync + Send>>,
+    produto_repository: Arc<dyn ProdutoGateway + Sync + Send>,
+    fuso_horario: Tz,
+    estacao_repository: Option<Arc<dyn EstacaoGateway + Sync + Send>>,
 }
 
 impl ProdutoUseCase {
-    pub fn new(produto_repository: Arc<Mutex<dyn ProdutoGateway + Sync + Send>>) -> Self {
-        ProdutoUseCase { produto_repository }
+    pub fn new(produto_repository: Arc<dyn ProdutoGateway + Sync + Send>) -> Self {
+        ProdutoUseCase {
+            produto_repository,
+            fuso_horario: FUSO_HORARIO_PADRAO,
+            estacao_repository: None,
+        }
+    }
+
+    // Com as estações da loja, um `estacao_id` de estação inexistente é rejeitado na gravação
+    pub fn com_estacoes(mut self, estacao_repository: Arc<dyn EstacaoGateway + Sync + Send>) -> Self {
+        self.estacao_repository = Some(estacao_repository);
+        self
+    }
+
+    async fn valida_estacao(&self, estacao_id: Option<usize>) -> Result<(), DomainError> {
+        let (Some(estacao_id), Some(estacao_repository)) = (estacao_id, &self.estacao_repository) else {
+            return Ok(());
+        };
+        match estacao_repository.get_estacao_by_id(estacao_id).await {
+            Err(DomainError::NotFound { .. }) => {
+                Err(DomainError::validation("estacao_id", "Estação não encontrada"))
+            }
+            resultado => resultado.map(|_| ()),
+        }
+    }
+
+    pub fn com_fuso_horario(mut self, fuso_horario: Tz) -> Self {
+        self.fuso_horario = fuso_horario;
+        self
+    }
+
+    fn agora_na_loja(&self) -> NaiveDateTime {
+        Utc::now().with_timezone(&self.fuso_horario).naive_local()
     }
 
     pub async fn get_produtos(&self) -> Result<Vec<Produto>, DomainError> {
-        let produto_repository = self.produto_repository.lock().await;
-        produto_repository.get_produtos().await
+        self.produto_repository.get_produtos().await
     }
 
     pub async fn get_produto_by_id(&self, id: usize) -> Result<Produto, DomainError> {
-        let produto_repository = self.produto_repository.lock().await;
-        produto_repository.get_produto_by_id(id).await
+        self.produto_repository.get_produto_by_id(id).await
+    }
+
+    // Só entram os produtos à venda agora, sem nenhum dos alérgenos em `sem_alergenos`
+    pub async fn get_cardapio(&self, sem_alergenos: &[Alergeno]) -> Result<Cardapio, DomainError> {
+        let agora = self.agora_na_loja();
+        let mut produtos_por_categoria = Vec::new();
+        for categoria in ORDEM_DAS_CATEGORIAS {
+            let produtos = self
+                .produto_repository
+                .get_produtos_by_categoria(categoria.clone())
+                .await?
+                .into_iter()
+                .filter(|produto| produto.disponivel_em(&agora))
+                .filter(|produto| !sem_alergenos.iter().any(|alergeno| produto.alergenos().contains(alergeno)))
+                .collect();
+            produtos_por_categoria.push((categoria, produtos));
+        }
+        Ok(Cardapio::new(produtos_por_categoria))
+    }
+
+    // Como o produto fica sem os ingredientes removidos pelo cliente, com os alérgenos recalculados
+    pub async fn personalizar_produto(
+        &self,
+        id: usize,
+        sem_ingredientes: Vec<String>,
+    ) -> Result<Produto, DomainError> {
+        let produto = self.produto_repository.get_produto_by_id(id).await?;
+        if produto.arquivado() || !produto.disponivel_em(&self.agora_na_loja()) {
+            return Err(DomainError::not_found("Produto", id));
+        }
+        produto.sem_ingredientes(&sem_ingredientes)
     }
 
     pub async fn create_produto(
         &self,
         produto: CreateProdutoInput,
     ) -> Result<Produto, DomainError> {
-        let mut produto_repository = self.produto_repository.lock().await;
-
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
-
-        let produto = produto_repository
-            .create_produto(Produto::new(
-                0,
-                produto.nome,
-                produto.foto,
-                produto.descricao,
-                produto.categoria,
-                produto.preco,
-                produto.ingredientes.clone(),
-                _now.clone(),
-                _now,
-            ))
+        let _now = Utc::now();
+
+        let mut novo_produto = Produto::new(
+            0,
+            produto.nome,
+            produto.foto,
+            produto.descricao,
+            produto.categoria,
+            produto.preco,
+            produto.ingredientes.clone(),
+            _now,
+            _now,
+        );
+        novo_produto.set_alergenos_por_ingrediente(produto.alergenos_por_ingrediente)?;
+        novo_produto.set_informacao_nutricional(produto.informacao_nutricional)?;
+        novo_produto.set_janelas_de_venda(produto.janelas_de_venda)?;
+        self.valida_estacao(produto.estacao_id).await?;
+        novo_produto.set_estacao_id(produto.estacao_id);
+
+        let produto = self.produto_repository
+            .create_produto(novo_produto)
             .await?;
 
         Ok(produto.clone())
     }
 
+    // `versao_esperada` vem do If-Match; sem ela vale a versão lida aqui, o que ainda
+    // impede sobrescrever uma alteração feita entre a leitura e a gravação
     pub async fn update_produto(
         &self,
         id: usize,
         fields_to_update: CreateProdutoInput,
+        versao_esperada: Option<usize>,
     ) -> Result<Produto, DomainError> {
-        let mut produto_repository = self.produto_repository.lock().await;
-
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
-        let produto_atualizado = produto_repository
-            .update_produto(Produto::new(
-                id,
-                fields_to_update.nome,
-                fields_to_update.foto,
-                fields_to_update.descricao,
-                fields_to_update.categoria,
-                fields_to_update.preco,
-                fields_to_update.ingredientes,
-                _now.clone(),
-                _now,
-            ))
-            .await?;
+        let mut produto = self.produto_para_atualizar(id, versao_esperada).await?;
+        produto.set_nome(fields_to_update.nome)?;
+        produto.set_foto(fields_to_update.foto);
+        produto.set_descricao(fields_to_update.descricao)?;
+        produto.set_categoria(fields_to_update.categoria);
+        produto.set_preco(fields_to_update.preco)?;
+        produto.set_ingredientes(fields_to_update.ingredientes);
+        produto.set_alergenos_por_ingrediente(fields_to_update.alergenos_por_ingrediente)?;
+        produto.set_informacao_nutricional(fields_to_update.informacao_nutricional)?;
+        produto.set_janelas_de_venda(fields_to_update.janelas_de_venda)?;
+        self.valida_estacao(fields_to_update.estacao_id).await?;
+        produto.set_estacao_id(fields_to_update.estacao_id);
+        produto.validate_entity()?;
+
+        self.produto_repository.update_produto(produto).await
+    }
+
+    pub async fn patch_produto(
+        &self,
+        id: usize,
+        fields_to_update: UpdateProdutoInput,
+        versao_esperada: Option<usize>,
+    ) -> Result<Produto, DomainError> {
+        let mut produto = self.produto_para_atualizar(id, versao_esperada).await?;
+        if let Some(nome) = fields_to_update.nome {
+            produto.set_nome(nome)?;
+        }
+        if let Some(foto) = fields_to_update.foto {
+            produto.set_foto(foto);
+        }
+        if let Some(descricao) = fields_to_update.descricao {
+            produto.set_descricao(descricao)?;
+        }
+        if let Some(categoria) = fields_to_update.categoria {
+            produto.set_categoria(categoria);
+        }
+        if let Some(preco) = fields_to_update.preco {
+            produto.set_preco(preco)?;
+        }
+        if let Some(ingredientes) = fields_to_update.ingredientes {
+            produto.set_ingredientes(ingredientes);
+        }
+        if let Some(alergenos_por_ingrediente) = fields_to_update.alergenos_por_ingrediente {
+            produto.set_alergenos_por_ingrediente(alergenos_por_ingrediente)?;
+        }
+        if let Some(informacao_nutricional) = fields_to_update.informacao_nutricional {
+            produto.set_informacao_nutricional(informacao_nutricional)?;
+        }
+        if let Some(janelas_de_venda) = fields_to_update.janelas_de_venda {
+            produto.set_janelas_de_venda(janelas_de_venda)?;
+        }
+        if let Some(estacao_id) = fields_to_update.estacao_id {
+            self.valida_estacao(estacao_id).await?;
+            produto.set_estacao_id(estacao_id);
+        }
+        produto.validate_entity()?;
 
-        Ok(produto_atualizado.clone())
+        self.produto_repository.update_produto(produto).await
+    }
+
+    async fn produto_para_atualizar(
+        &self,
+        id: usize,
+        versao_esperada: Option<usize>,
+    ) -> Result<Produto, DomainError> {
+        let produto = self.produto_repository.get_produto_by_id(id).await?;
+        match versao_esperada {
+            Some(versao) if versao != produto.versao() => Err(DomainError::conflict(
+                "Produto",
+                "Produto foi alterado por outra requisição",
+            )),
+            _ => Ok(produto),
+        }
     }
 
     pub async fn delete_produto(&self, id: usize) -> Result<(), DomainError> {
-        let mut produto_repository = self.produto_repository.lock().await;
-        produto_repository.delete_produto(id).await?;
+        self.produto_repository.delete_produto(id).await?;
         Ok(())
     }
+
+    pub async fn restaurar_produto(&self, id: usize) -> Result<Produto, DomainError> {
+        self.produto_repository.restaurar_produto(id).await
+    }
+
+    pub async fn get_historico_de_precos(&self, id: usize) -> Result<Vec<PrecoHistorico>, DomainError> {
+        // Garante o 404 para produto inexistente em vez de uma lista vazia
+        self.produto_repository.get_produto_by_id(id).await?;
+        self.produto_repository.get_historico_de_precos(vec![id]).await
+    }
 }
 
 unsafe impl Send for ProdutoUseCase {}
@@ -136,6 +295,7 @@ unsafe impl Sync for ProdutoUseCase {}
 mod tests {
     use super::*;
     use crate::entities::{ingredientes::Ingredientes, produto::Produto};
+    use crate::traits::estacao_gateway::MockEstacaoGateway;
     use crate::traits::produto_gateway::MockProdutoGateway;
     use mockall::predicate::*;
     use tokio;
@@ -153,8 +313,8 @@ mod tests {
             10.0,
             Ingredientes::new(vec!["ingrediente1".to_string(), "ingrediente2".to_string()])
                 .unwrap(),
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_produto = returned_produto.clone();
@@ -163,11 +323,41 @@ mod tests {
             .times(1)
             .returning(move || Ok(vec![returned_produto.clone()]));
 
-        let use_case = ProdutoUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = ProdutoUseCase::new(Arc::new(mock));
         let result = use_case.get_produtos().await;
         assert_eq!(result.unwrap()[0].id(), expected_produto.id());
     }
 
+    #[tokio::test]
+    async fn test_get_cardapio_consulta_cada_categoria() {
+        let mut mock = MockProdutoGateway::new();
+
+        mock.expect_get_produtos_by_categoria()
+            .times(4)
+            .returning(|categoria| {
+                if categoria != Categoria::Bebida {
+                    return Ok(vec![]);
+                }
+                Ok(vec![Produto::new(
+                    2,
+                    "Refrigerante".to_string(),
+                    "foto".to_string(),
+                    "descricao".to_string(),
+                    Categoria::Bebida,
+                    5.0,
+                    Ingredientes::new(vec![]).unwrap(),
+                    Utc::now(),
+                    Utc::now(),
+                )])
+            });
+
+        let use_case = ProdutoUseCase::new(Arc::new(mock));
+        let cardapio = use_case.get_cardapio(&[]).await.unwrap();
+        assert_eq!(cardapio.secoes().len(), 1);
+        assert_eq!(cardapio.secoes()[0].categoria(), &Categoria::Bebida);
+        assert_eq!(*cardapio.secoes()[0].produtos()[0].id(), 2);
+    }
+
     #[tokio::test]
     async fn test_get_produto_by_id() {
         let mut mock = MockProdutoGateway::new();
@@ -181,8 +371,8 @@ mod tests {
             10.0,
             Ingredientes::new(vec!["ingrediente1".to_string(), "ingrediente2".to_string()])
                 .unwrap(),
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_produto = returned_produto.clone();
@@ -192,7 +382,7 @@ mod tests {
             .with(eq(1))
             .returning(move |_| Ok(returned_produto.clone()));
 
-        let use_case = ProdutoUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = ProdutoUseCase::new(Arc::new(mock));
         let result = use_case.get_produto_by_id(1).await;
         assert_eq!(result.unwrap().id(), expected_produto.id());
     }
@@ -210,8 +400,8 @@ mod tests {
             10.0,
             Ingredientes::new(vec!["ingrediente1".to_string(), "ingrediente2".to_string()])
                 .unwrap(),
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_produto = returned_produto.clone();
@@ -220,7 +410,7 @@ mod tests {
             .times(1)
             .returning(move |_| Ok(returned_produto.clone()));
 
-        let use_case = ProdutoUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = ProdutoUseCase::new(Arc::new(mock));
         let result = use_case
             .create_produto(CreateProdutoInput::new(
                 "nome".to_string(),
@@ -248,17 +438,21 @@ mod tests {
             10.0,
             Ingredientes::new(vec!["ingrediente1".to_string(), "ingrediente2".to_string()])
                 .unwrap(),
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_produto = returned_produto.clone();
 
+        mock.expect_get_produto_by_id()
+            .times(1)
+            .with(eq(1))
+            .returning(move |_| Ok(produto_salvo()));
         mock.expect_update_produto()
             .times(1)
             .returning(move |_| Ok(returned_produto.clone()));
 
-        let use_case = ProdutoUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = ProdutoUseCase::new(Arc::new(mock));
         let result = use_case
             .update_produto(
                 1,
@@ -271,11 +465,125 @@ mod tests {
                     Ingredientes::new(vec!["ingrediente1".to_string(), "ingrediente2".to_string()])
                         .unwrap(),
                 ),
+                None,
             )
             .await;
         assert_eq!(result.unwrap().id(), expected_produto.id());
     }
 
+    fn produto_salvo() -> Produto {
+        let mut produto = Produto::new(
+            1,
+            "X-Salada".to_string(),
+            "x_salada.jpg".to_string(),
+            "Lanche com salada".to_string(),
+            Categoria::Lanche,
+            15.0,
+            Ingredientes::new(vec!["pão".to_string(), "alface".to_string()]).unwrap(),
+            Utc::now(),
+            Utc::now(),
+        );
+        produto.set_versao(3);
+        produto
+    }
+
+    #[tokio::test]
+    async fn test_patch_produto_altera_so_os_campos_informados() {
+        let mut mock = MockProdutoGateway::new();
+        mock.expect_get_produto_by_id()
+            .times(1)
+            .returning(|_| Ok(produto_salvo()));
+        mock.expect_update_produto()
+            .times(1)
+            .withf(|produto| {
+                produto.preco() == 17.5 && produto.nome() == "X-Salada" && produto.versao() == 3
+            })
+            .returning(Ok);
+
+        let use_case = ProdutoUseCase::new(Arc::new(mock));
+        let input = UpdateProdutoInput {
+            preco: Some(17.5),
+            ..Default::default()
+        };
+        let result = use_case.patch_produto(1, input, Some(3)).await;
+
+        assert_eq!(result.unwrap().preco(), 17.5);
+    }
+
+    #[tokio::test]
+    async fn test_patch_produto_valida_a_entidade() {
+        let mut mock = MockProdutoGateway::new();
+        mock.expect_get_produto_by_id()
+            .returning(|_| Ok(produto_salvo()));
+        mock.expect_update_produto().times(0);
+
+        let use_case = ProdutoUseCase::new(Arc::new(mock));
+        let input = UpdateProdutoInput {
+            preco: Some(-1.0),
+            ..Default::default()
+        };
+        let result = use_case.patch_produto(1, input, None).await;
+
+        assert!(matches!(result, Err(DomainError::Validation { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_patch_produto_com_estacao_inexistente() {
+        let mut mock = MockProdutoGateway::new();
+        mock.expect_get_produto_by_id()
+            .returning(|_| Ok(produto_salvo()));
+        mock.expect_update_produto().times(0);
+        let mut estacoes = MockEstacaoGateway::new();
+        estacoes.expect_get_estacao_by_id()
+            .with(eq(9))
+            .times(1)
+            .returning(|id| Err(DomainError::not_found("Estacao", id)));
+
+        let use_case = ProdutoUseCase::new(Arc::new(mock)).com_estacoes(Arc::new(estacoes));
+        let input = UpdateProdutoInput {
+            estacao_id: Some(Some(9)),
+            ..Default::default()
+        };
+        let result = use_case.patch_produto(1, input, None).await;
+
+        assert!(matches!(result, Err(DomainError::Validation { ref campo, .. }) if campo == "estacao_id"));
+    }
+
+    #[tokio::test]
+    async fn test_patch_produto_com_null_apaga_a_estacao() {
+        let mut mock = MockProdutoGateway::new();
+        mock.expect_get_produto_by_id().times(2).returning(|_| {
+            let mut produto = produto_salvo();
+            produto.set_estacao_id(Some(2));
+            Ok(produto)
+        });
+        mock.expect_update_produto().times(2).returning(Ok);
+
+        let use_case = ProdutoUseCase::new(Arc::new(mock));
+        let ausente: UpdateProdutoInput = serde_json::from_str(r#"{ "preco": 17.5 }"#).unwrap();
+        let produto = use_case.patch_produto(1, ausente, None).await.unwrap();
+        assert_eq!(produto.estacao_id(), Some(2));
+
+        let nulo: UpdateProdutoInput = serde_json::from_str(r#"{ "estacao_id": null }"#).unwrap();
+        let produto = use_case.patch_produto(1, nulo, None).await.unwrap();
+        assert_eq!(produto.estacao_id(), None);
+    }
+
+    #[tokio::test]
+    async fn test_patch_produto_com_versao_desatualizada() {
+        let mut mock = MockProdutoGateway::new();
+        mock.expect_get_produto_by_id()
+            .returning(|_| Ok(produto_salvo()));
+        mock.expect_update_produto().times(0);
+
+        let use_case = ProdutoUseCase::new(Arc::new(mock));
+        let result = use_case
+            .patch_produto(1, UpdateProdutoInput::default(), Some(2))
+            .await;
+
+        assert!(matches!(result, Err(DomainError::Conflict { .. })));
+    }
+
     #[tokio::test]
     async fn test_delete_produto() {
         let mut mock = MockProdutoGateway::new();
@@ -284,8 +592,22 @@ mod tests {
             .times(1)
             .returning(move |_| Ok(()));
 
-        let use_case = ProdutoUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = ProdutoUseCase::new(Arc::new(mock));
         let result = use_case.delete_produto(1).await;
         assert_eq!(result.unwrap(), ());
     }
+
+    #[tokio::test]
+    async fn test_get_historico_de_precos_produto_inexistente() {
+        let mut mock = MockProdutoGateway::new();
+
+        mock.expect_get_produto_by_id()
+            .times(1)
+            .returning(|id| Err(DomainError::not_found("Produto", id)));
+        mock.expect_get_historico_de_precos().never();
+
+        let use_case = ProdutoUseCase::new(Arc::new(mock));
+        let result = use_case.get_historico_de_precos(9).await;
+        assert!(matches!(result, Err(DomainError::NotFound { .. })));
+    }
 }
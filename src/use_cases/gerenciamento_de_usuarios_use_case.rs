@@ -4,7 +4,6 @@ use chrono::Utc;
 
 use schemars::JsonSchema;
 use serde::Deserialize;
-use tokio::sync::Mutex;
 
 use crate::base::domain_error::DomainError;
 use crate::entities::cpf::Cpf;
@@ -19,57 +18,76 @@ pub struct CreateUsuarioInput {
     cpf: String,
     tipo: String,
     status: String,
+    // Vazio no cadastro vincula à loja atual; na atualização mantém as lojas de antes
+    #[serde(default)]
+    lojas: Vec<usize>,
 }
 
 #[derive(Clone)]
 pub struct UsuarioUseCase {
-    usuario_repository: Arc<Mutex<dyn UsuarioGateway + Sync + Send>>,
+    usuario_repository: Arc<dyn UsuarioGateway + Sync + Send>,
+    lojas_permitidas: Option<Vec<usize>>,
 }
 
 impl UsuarioUseCase {
-    pub fn new(usuario_repository: Arc<Mutex<dyn UsuarioGateway + Sync + Send>>) -> Self {
-        UsuarioUseCase { usuario_repository }
+    pub fn new(usuario_repository: Arc<dyn UsuarioGateway + Sync + Send>) -> Self {
+        UsuarioUseCase {
+            usuario_repository,
+            lojas_permitidas: None,
+        }
+    }
+
+    // Lojas que quem faz a operação gerencia; sem isso qualquer loja é aceita
+    pub fn com_lojas_permitidas(mut self, lojas_permitidas: Vec<usize>) -> Self {
+        self.lojas_permitidas = Some(lojas_permitidas);
+        self
+    }
+
+    fn verifica_lojas(&self, lojas: &[usize]) -> Result<(), DomainError> {
+        match &self.lojas_permitidas {
+            Some(permitidas) if lojas.iter().any(|loja| !permitidas.contains(loja)) => Err(
+                DomainError::forbidden("Usuário sem permissão para dar acesso a essa loja"),
+            ),
+            _ => Ok(()),
+        }
     }
 
     pub async fn get_usuarios(&self) -> Result<Vec<Usuario>, DomainError> {
-        let usuario_repository = self.usuario_repository.lock().await;
-        usuario_repository.get_usuarios().await
+        self.usuario_repository.get_usuarios().await
     }
 
     pub async fn get_usuario_by_id(&self, id: usize) -> Result<Usuario, DomainError> {
-        let usuario_repository = self.usuario_repository.lock().await;
-        usuario_repository.get_usuario_by_id(id).await
+        self.usuario_repository.get_usuario_by_id(id).await
     }
 
     pub async fn get_usuario_by_cpf(&self, cpf: Cpf) -> Result<Usuario, DomainError> {
-        let usuario_repository = self.usuario_repository.lock().await;
-        usuario_repository.get_usuario_by_cpf(cpf).await
+        self.usuario_repository.get_usuario_by_cpf(cpf).await
     }
 
     pub async fn create_usuario(
         &self,
         usuario: CreateUsuarioInput,
     ) -> Result<Usuario, DomainError> {
-        let mut usuario_repository = self.usuario_repository.lock().await;
         let _id = 0;
         let valid_cpf = Cpf::new(usuario.cpf.clone())?;
         let valid_tipo: Tipo = usuario.tipo.parse().unwrap();
         let valid_status: Status = usuario.status.parse().unwrap();
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
-
-        let usuario = usuario_repository
-            .create_usuario(Usuario::new(
-                _id,
-                usuario.nome,
-                usuario.email,
-                valid_cpf,
-                usuario.senha,
-                valid_tipo,
-                valid_status,
-                _now.clone(),
-                _now,
-            ))
-            .await?;
+        let _now = Utc::now();
+        self.verifica_lojas(&usuario.lojas)?;
+
+        let mut novo_usuario = Usuario::new(
+            _id,
+            usuario.nome,
+            usuario.email,
+            valid_cpf,
+            usuario.senha,
+            valid_tipo,
+            valid_status,
+            _now,
+            _now,
+        );
+        novo_usuario.set_lojas(usuario.lojas);
+        let usuario = self.usuario_repository.create_usuario(novo_usuario).await?;
 
         Ok(usuario.clone())
     }
@@ -79,33 +97,38 @@ impl UsuarioUseCase {
         id: usize,
         usuario: CreateUsuarioInput,
     ) -> Result<Usuario, DomainError> {
-        let mut usuario_repository = self.usuario_repository.lock().await;
-
         let valid_cpf = Cpf::new(usuario.cpf.clone())?;
         let valid_tipo: Tipo = usuario.tipo.parse().unwrap();
         let valid_status: Status = usuario.status.parse().unwrap();
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
-
-        let usuario = usuario_repository
-            .update_usuario(Usuario::new(
-                id,
-                usuario.nome,
-                usuario.email,
-                valid_cpf,
-                usuario.senha,
-                valid_tipo,
-                valid_status,
-                _now.clone(),
-                _now,
-            ))
-            .await?;
+        let _now = Utc::now();
+        self.verifica_lojas(&usuario.lojas)?;
+
+        // Quem edita só mexe nas lojas que gerencia; as outras lojas do usuário continuam
+        let mut lojas = usuario.lojas;
+        if let (Some(permitidas), false) = (&self.lojas_permitidas, lojas.is_empty()) {
+            let atual = self.usuario_repository.get_usuario_by_id(id).await?;
+            lojas.extend(atual.lojas().iter().filter(|loja| !permitidas.contains(loja)));
+        }
+
+        let mut usuario_atualizado = Usuario::new(
+            id,
+            usuario.nome,
+            usuario.email,
+            valid_cpf,
+            usuario.senha,
+            valid_tipo,
+            valid_status,
+            _now,
+            _now,
+        );
+        usuario_atualizado.set_lojas(lojas);
+        let usuario = self.usuario_repository.update_usuario(usuario_atualizado).await?;
 
         Ok(usuario.clone())
     }
 
     pub async fn delete_usuario(&self, cpf: Cpf) -> Result<(), DomainError> {
-        let mut usuario_repository = self.usuario_repository.lock().await;
-        usuario_repository.delete_usuario(cpf).await?;
+        self.usuario_repository.delete_usuario(cpf).await?;
         Ok(())
     }
 }
@@ -131,8 +154,8 @@ mod tests {
             "senha".to_string(),
             Tipo::Cozinha,
             Status::Ativo,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_usuario = returned_usuario.clone();
@@ -141,7 +164,7 @@ mod tests {
             .times(1)
             .returning(move || Ok(vec![returned_usuario.clone()]));
 
-        let use_case = UsuarioUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = UsuarioUseCase::new(Arc::new(mock));
         let result = use_case.get_usuarios().await;
         assert_eq!(result.unwrap()[0].id(), expected_usuario.id());
     }
@@ -158,8 +181,8 @@ mod tests {
             "senha".to_string(),
             Tipo::Admin,
             Status::Ativo,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_usuario = returned_usuario.clone();
@@ -168,7 +191,7 @@ mod tests {
             .times(1)
             .returning(move |_| Ok(returned_usuario.clone()));
 
-        let use_case = UsuarioUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = UsuarioUseCase::new(Arc::new(mock));
         let result = use_case.get_usuario_by_id(1).await;
         assert_eq!(result.unwrap().id(), expected_usuario.id());
     }
@@ -185,8 +208,8 @@ mod tests {
             "senha".to_string(),
             Tipo::Admin,
             Status::Ativo,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_usuario = returned_usuario.clone();
@@ -195,7 +218,7 @@ mod tests {
             .times(1)
             .returning(move |_| Ok(returned_usuario.clone()));
 
-        let use_case = UsuarioUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = UsuarioUseCase::new(Arc::new(mock));
         let result = use_case
             .get_usuario_by_cpf(Cpf::new("000.000.000-00".to_string()).unwrap())
             .await;
@@ -214,8 +237,8 @@ mod tests {
             "senha".to_string(),
             Tipo::Admin,
             Status::Ativo,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_usuario = returned_usuario.clone();
@@ -224,7 +247,7 @@ mod tests {
             .times(1)
             .returning(move |_| Ok(returned_usuario.clone()));
 
-        let use_case = UsuarioUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = UsuarioUseCase::new(Arc::new(mock));
         let result = use_case
             .create_usuario(CreateUsuarioInput {
                 nome: "nome".to_string(),
@@ -233,6 +256,7 @@ mod tests {
                 cpf: "000.000.000-00".to_string(),
                 tipo: "Admin".to_string(),
                 status: "Ativo".to_string(),
+                lojas: vec![],
             })
             .await;
         assert_eq!(result.unwrap().id(), expected_usuario.id());
@@ -250,8 +274,8 @@ mod tests {
             "senha".to_string(),
             Tipo::Admin,
             Status::Ativo,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_usuario = returned_usuario.clone();
@@ -260,7 +284,7 @@ mod tests {
             .times(1)
             .returning(move |_| Ok(returned_usuario.clone()));
 
-        let use_case = UsuarioUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = UsuarioUseCase::new(Arc::new(mock));
         let result = use_case
             .update_usuario(
                 1,
@@ -271,6 +295,7 @@ mod tests {
                     cpf: "000.000.000-00".to_string(),
                     tipo: "Cozinha".to_string(),
                     status: "Ativo".to_string(),
+                    lojas: vec![],
                 },
             )
             .await;
@@ -285,10 +310,72 @@ mod tests {
             .times(1)
             .returning(move |_| Ok(()));
 
-        let use_case = UsuarioUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = UsuarioUseCase::new(Arc::new(mock));
         let result = use_case
             .delete_usuario(Cpf::new("000.000.000-00".to_string()).unwrap())
             .await;
         assert!(result.is_ok());
     }
+
+    #[tokio::test]
+    async fn test_create_usuario_em_loja_nao_gerenciada() {
+        let mut mock = MockUsuarioGateway::new();
+        mock.expect_create_usuario().times(0);
+
+        let use_case = UsuarioUseCase::new(Arc::new(mock)).com_lojas_permitidas(vec![1]);
+        let result = use_case
+            .create_usuario(CreateUsuarioInput {
+                nome: "nome".to_string(),
+                email: "email".to_string(),
+                senha: "senha".to_string(),
+                cpf: "000.000.000-00".to_string(),
+                tipo: "Cozinha".to_string(),
+                status: "Ativo".to_string(),
+                lojas: vec![1, 2],
+            })
+            .await;
+        assert!(matches!(result, Err(DomainError::Forbidden(_))));
+    }
+
+    #[tokio::test]
+    async fn test_update_usuario_mantem_lojas_nao_gerenciadas() {
+        let mut mock = MockUsuarioGateway::new();
+
+        let mut usuario_atual = Usuario::new(
+            1,
+            "nome".to_string(),
+            "email".to_string(),
+            Cpf::new("000.000.000-00".to_string()).unwrap(),
+            "senha".to_string(),
+            Tipo::Cozinha,
+            Status::Ativo,
+            Utc::now(),
+            Utc::now(),
+        );
+        usuario_atual.set_lojas(vec![1, 3]);
+
+        mock.expect_get_usuario_by_id()
+            .times(1)
+            .returning(move |_| Ok(usuario_atual.clone()));
+        mock.expect_update_usuario()
+            .times(1)
+            .returning(Ok);
+
+        let use_case = UsuarioUseCase::new(Arc::new(mock)).com_lojas_permitidas(vec![1, 2]);
+        let result = use_case
+            .update_usuario(
+                1,
+                CreateUsuarioInput {
+                    nome: "nome".to_string(),
+                    email: "email".to_string(),
+                    senha: "senha".to_string(),
+                    cpf: "000.000.000-00".to_string(),
+                    tipo: "Cozinha".to_string(),
+                    status: "Ativo".to_string(),
+                    lojas: vec![2],
+                },
+            )
+            .await;
+        assert_eq!(result.unwrap().lojas(), &vec![2, 3]);
+    }
 }
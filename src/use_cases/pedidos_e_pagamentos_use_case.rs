@@ -1,6 +1,8 @@
 use crate::base::domain_error::DomainError;
+use crate::external::metrics;
 use crate::entities::pagamento;
 use crate::entities::{
+    janela_de_venda::FUSO_HORARIO_PADRAO,
     pagamento::Pagamento,
     pedido::{Pedido, Status},
     produto::{Categoria, Produto},
@@ -8,17 +10,19 @@ use crate::entities::{
 use crate::traits::{
     cliente_gateway::ClienteGateway, pagamento_gateway::PagamentoGateway,
     pedido_gateway::PedidoGateway, produto_gateway::ProdutoGateway,
+    unit_of_work::{Transacao, UnitOfWork},
 };
 
-use crate::adapters::mercadopago_pagamento_webhook_adapter::MercadoPagoPagamentoWebhookAdapter;
 use crate::traits::pagamento_webhook_adapter::PagamentoWebhookAdapter;
 use chrono::Utc;
+use chrono_tz::Tz;
 use rocket::serde::json::Value;
 use schemars::JsonSchema;
 use serde::Deserialize;
 use std::collections::HashMap;
 use std::sync::Arc;
-use tokio::sync::Mutex;
+use std::time::Instant;
+use tracing::{error, info, instrument, warn};
 
 #[derive(Clone, Debug, Deserialize, JsonSchema)]
 pub struct CreatePedidoInput {
@@ -30,20 +34,23 @@ pub struct CreatePedidoInput {
 
 #[derive(Clone)]
 pub struct PedidosEPagamentosUseCase {
-    pedido_repository: Arc<Mutex<dyn PedidoGateway + Sync + Send>>,
-    cliente_repository: Arc<Mutex<dyn ClienteGateway + Sync + Send>>,
-    produto_repository: Arc<Mutex<dyn ProdutoGateway + Sync + Send>>,
-    pagamento_repository: Arc<Mutex<dyn PagamentoGateway + Sync + Send>>,
+    pedido_repository: Arc<dyn PedidoGateway + Sync + Send>,
+    cliente_repository: Arc<dyn ClienteGateway + Sync + Send>,
+    produto_repository: Arc<dyn ProdutoGateway + Sync + Send>,
+    pagamento_repository: Arc<dyn PagamentoGateway + Sync + Send>,
     metodos_pagamento: HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>>,
+    unit_of_work: Arc<dyn UnitOfWork + Sync + Send>,
+    fuso_horario: Tz,
 }
 
 impl PedidosEPagamentosUseCase {
     pub fn new(
-        pedido_repository: Arc<Mutex<dyn PedidoGateway + Sync + Send>>,
-        cliente_repository: Arc<Mutex<dyn ClienteGateway + Sync + Send>>,
-        produto_repository: Arc<Mutex<dyn ProdutoGateway + Sync + Send>>,
-        pagamento_repository: Arc<Mutex<dyn PagamentoGateway + Sync + Send>>,
+        pedido_repository: Arc<dyn PedidoGateway + Sync + Send>,
+        cliente_repository: Arc<dyn ClienteGateway + Sync + Send>,
+        produto_repository: Arc<dyn ProdutoGateway + Sync + Send>,
+        pagamento_repository: Arc<dyn PagamentoGateway + Sync + Send>,
         metodos_pagamento: HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>>,
+        unit_of_work: Arc<dyn UnitOfWork + Sync + Send>,
     ) -> Self {
         PedidosEPagamentosUseCase {
             pedido_repository,
@@ -51,52 +58,56 @@ impl PedidosEPagamentosUseCase {
             produto_repository,
             pagamento_repository,
             metodos_pagamento,
+            unit_of_work,
+            fuso_horario: FUSO_HORARIO_PADRAO,
         }
     }
 
+    pub fn com_fuso_horario(mut self, fuso_horario: Tz) -> Self {
+        self.fuso_horario = fuso_horario;
+        self
+    }
+
+    #[instrument(skip(self), err(level = "warn", Debug))]
     pub async fn lista_pedidos(&self) -> Result<Vec<Pedido>, DomainError> {
-        let mut pedido_repository = self.pedido_repository.lock().await;
-        pedido_repository.lista_pedidos().await
+        self.pedido_repository.lista_pedidos().await
     }
 
+    #[instrument(skip(self), err(level = "warn", Debug))]
     pub async fn seleciona_pedido_por_id(&self, id: usize) -> Result<Pedido, DomainError> {
-        let pedido_repository = self.pedido_repository.lock().await;
-        pedido_repository.get_pedido_by_id(id).await
+        self.pedido_repository.get_pedido_by_id(id).await
     }
 
+    #[instrument(skip(self), err(level = "warn", Debug))]
     pub async fn novo_pedido(
         &self,
         pedido_input: CreatePedidoInput,
     ) -> Result<Pedido, DomainError> {
         let cliente = if let Some(cliente_id) = pedido_input.cliente_id {
-            let cliente_repo = self.cliente_repository.lock().await;
-            Some(cliente_repo.get_cliente_by_id(cliente_id).await?)
+            Some(self.cliente_repository.get_cliente_by_id(cliente_id).await?)
         } else {
             None
         };
 
         let lanche = if let Some(lanche_id) = pedido_input.lanche_id {
-            let produto_repo = self.produto_repository.lock().await;
-            Some(produto_repo.get_produto_by_id(lanche_id).await?)
+            Some(self.produto_do_cardapio("lanche_id", lanche_id).await?)
         } else {
             None
         };
 
         let bebida = if let Some(bebida_id) = pedido_input.bebida_id {
-            let produto_repo = self.produto_repository.lock().await;
-            Some(produto_repo.get_produto_by_id(bebida_id).await?)
+            Some(self.produto_do_cardapio("bebida_id", bebida_id).await?)
         } else {
             None
         };
 
         let acompanhamento = if let Some(acompanhamento_id) = pedido_input.acompanhamento_id {
-            let produto_repo = self.produto_repository.lock().await;
-            Some(produto_repo.get_produto_by_id(acompanhamento_id).await?)
+            Some(self.produto_do_cardapio("acompanhamento_id", acompanhamento_id).await?)
         } else {
             None
         };
 
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+        let _now = Utc::now();
         let pedido = Pedido::new(
             0,
             cliente,
@@ -105,107 +116,128 @@ impl PedidosEPagamentosUseCase {
             bebida,
             String::from("Mercado Pago"),
             Status::Pendente,
-            _now.clone(),
-            _now.clone(),
+            _now,
+            _now,
         );
 
-        let mut pedido_repository = self.pedido_repository.lock().await;
-        let novo_pedido = pedido_repository.create_pedido(pedido).await;
-
-        match novo_pedido {
-            Ok(pedido) => {
-                drop(pedido_repository);
-                self.criar_pagamento_do_pedido(pedido.id().clone()).await?;
-                Ok(pedido)
-            }
-            Err(err) => Err(err),
+        // Pedido e pagamento pendente são gravados juntos; o webhook só é registrado
+        // depois do commit, sem transação aberta esperando o serviço de pagamento.
+        // Se o registro falha o pedido é cancelado, então não fica pedido pendente sem pagamento
+        let transacao = self.unit_of_work.begin().await?;
+        let resultado = async {
+            let pedido = transacao.pedido_repository().create_pedido(pedido).await?;
+            let pagamento = self.criar_pagamento(transacao.as_ref(), &pedido).await?;
+            Ok((pedido, pagamento))
         }
+        .await;
+        let (pedido, pagamento) = finalizar_transacao(transacao, resultado).await?;
+        metrics::registrar_pedido_criado(pedido.status());
+        self.registrar_webhook(pagamento, true).await?;
+        Ok(pedido)
     }
 
+    #[instrument(skip(self), err(level = "warn", Debug))]
     pub async fn adicionar_cliente(
         &self,
         pedido_id: usize,
         cliente_id: usize,
     ) -> Result<Pedido, DomainError> {
-        let cliente_repo = self.cliente_repository.lock().await;
-        let cliente = cliente_repo.get_cliente_by_id(cliente_id).await?;
-        drop(cliente_repo);
-        let mut pedido_repository = self.pedido_repository.lock().await;
-        pedido_repository
+        let cliente = self.cliente_repository.get_cliente_by_id(cliente_id).await?;
+        self.pedido_repository
             .cadastrar_cliente(pedido_id, cliente)
             .await
     }
 
+    #[instrument(skip(self), err(level = "warn", Debug))]
     pub async fn adicionar_lanche_com_personalizacao(
         &self,
         pedido_id: usize,
         lanche_id: usize,
     ) -> Result<Pedido, DomainError> {
-        let produto_repository = self.produto_repository.lock().await;
-        let lanche = produto_repository.get_produto_by_id(lanche_id).await?;
-        drop(produto_repository);
+        let lanche = self.produto_do_cardapio("produto_id", lanche_id).await?;
         if lanche.categoria().clone() != Categoria::Lanche {
-            Err(DomainError::Invalid("Produto não é um lanche".to_string()))?;
+            Err(DomainError::validation("produto_id", "Produto não é um lanche"))?;
         }
-        let mut pedido_repository = self.pedido_repository.lock().await;
-        pedido_repository.cadastrar_lanche(pedido_id, lanche).await
+        self.pedido_repository.cadastrar_lanche(pedido_id, lanche).await
     }
 
+    #[instrument(skip(self), err(level = "warn", Debug))]
     pub async fn adicionar_acompanhamento(
         &self,
         pedido_id: usize,
         acompanhamento_id: usize,
     ) -> Result<Pedido, DomainError> {
-        let produto_repository = self.produto_repository.lock().await;
-        let acompanhamento = produto_repository
-            .get_produto_by_id(acompanhamento_id)
+        let acompanhamento = self
+            .produto_do_cardapio("produto_id", acompanhamento_id)
             .await?;
-        drop(produto_repository);
         if acompanhamento.categoria().clone() != Categoria::Acompanhamento {
-            Err(DomainError::Invalid(
-                "Produto não é um acompanhamento".to_string(),
-            ))?;
+            Err(DomainError::validation("produto_id", "Produto não é um acompanhamento"))?;
         }
-        let mut pedido_repository = self.pedido_repository.lock().await;
-        pedido_repository
+        self.pedido_repository
             .cadastrar_acompanhamento(pedido_id, acompanhamento)
             .await
     }
 
+    #[instrument(skip(self), err(level = "warn", Debug))]
     pub async fn adicionar_bebida(
         &self,
         pedido_id: usize,
         bebida_id: usize,
     ) -> Result<Pedido, DomainError> {
-        let produto_repository = self.produto_repository.lock().await;
-        let bebida = produto_repository.get_produto_by_id(bebida_id).await?;
-        drop(produto_repository);
+        let bebida = self.produto_do_cardapio("produto_id", bebida_id).await?;
         if bebida.categoria().clone() != Categoria::Bebida {
-            Err(DomainError::Invalid("Produto não é uma bebida".to_string()))?;
+            Err(DomainError::validation("produto_id", "Produto não é uma bebida"))?;
         }
-        let mut pedido_repository = self.pedido_repository.lock().await;
-        pedido_repository.cadastrar_bebida(pedido_id, bebida).await
+        self.pedido_repository.cadastrar_bebida(pedido_id, bebida).await
     }
 
+    #[instrument(skip(self), err(level = "warn", Debug))]
     pub async fn get_pagamento_by_pedido_id(
         &self,
         pedido_id: usize,
     ) -> Result<Pagamento, DomainError> {
-        let mut pagamento_repository = self.pagamento_repository.lock().await;
-        pagamento_repository
+        self.pagamento_repository
             .get_pagamento_by_id_pedido(pedido_id)
             .await
     }
 
+    #[instrument(skip(self), err(level = "warn", Debug))]
     pub async fn criar_pagamento_do_pedido(
         &self,
         pedido_id: usize,
     ) -> Result<Pagamento, DomainError> {
-        let pedido_repository = self.pedido_repository.lock().await;
-        let mut pagamento_repository = self.pagamento_repository.lock().await;
+        let transacao = self.unit_of_work.begin().await?;
+        let resultado = async {
+            let pedido = transacao.pedido_repository().get_pedido_by_id(pedido_id).await?;
+            self.criar_pagamento(transacao.as_ref(), &pedido).await
+        }
+        .await;
+        let pagamento = finalizar_transacao(transacao, resultado).await?;
+        self.registrar_webhook(pagamento, false).await
+    }
 
-        let pedido: Pedido = pedido_repository.get_pedido_by_id(pedido_id).await?;
-        let _now = Utc::now().format("%Y-%m-%d %H:%M:%S%.3f%z").to_string();
+    // Produtos arquivados continuam nos pedidos antigos, mas não entram em pedidos novos
+    async fn produto_do_cardapio(&self, campo: &str, id: usize) -> Result<Produto, DomainError> {
+        let produto = self.produto_repository.get_produto_by_id(id).await?;
+        if produto.arquivado() {
+            return Err(DomainError::validation(campo, "Produto não está mais no cardápio"));
+        }
+        if !produto.disponivel_em(&Utc::now().with_timezone(&self.fuso_horario).naive_local()) {
+            return Err(DomainError::validation(campo, "Produto fora do horário de venda"));
+        }
+        Ok(produto)
+    }
+
+    async fn criar_pagamento(
+        &self,
+        transacao: &(dyn Transacao + Sync + Send),
+        pedido: &Pedido,
+    ) -> Result<Pagamento, DomainError> {
+        if !self.metodos_pagamento.contains_key(pedido.pagamento().as_str()) {
+            warn!(metodo = %pedido.pagamento(), "Método de pagamento inválido");
+            return Err(DomainError::validation("pagamento", "Método de pagamento não suportado"));
+        }
+        let _now = Utc::now();
         let pagamento = Pagamento::new(
             0,
             pedido.id().clone(),
@@ -213,82 +245,130 @@ impl PedidosEPagamentosUseCase {
             pedido.valor(),
             pedido.pagamento().clone(),
             String::from(""),
-            _now.clone(),
+            _now,
         );
-        let mut pagamento = pagamento_repository.create_pagamento(pagamento).await?;
-
-        // set webhook
-        let metodo_pagamento = self.metodos_pagamento.get(pagamento.metodo().as_str());
+        transacao.pagamento_repository().create_pagamento(pagamento).await
+    }
 
-        match metodo_pagamento.cloned() {
-            Some(metodo_pagamento) => {
-                match metodo_pagamento
-                    .set_webhook_pagamento(pagamento.clone())
-                    .await
-                {
-                    Ok(pagamento_updated) => {
-                        println!("Webhook set successfully");
-                        let updated_pagamento = pagamento_repository
-                            .update_pagamento(pagamento_updated.clone())
-                            .await?;
-                        Ok(updated_pagamento)
-                    }
-                    Err(err) => {
-                        eprintln!("Failed to set webhook");
-                        pagamento.set_estado(String::from("cancelado"));
-                        pagamento_repository
-                            .update_pagamento(pagamento.clone())
-                            .await?;
-                        Err(DomainError::Invalid("Internal server error".to_string()))
-                    }
+    // Chamado fora de transação; se o serviço de pagamento falhar o pagamento fica com
+    // estado "falha" e um novo pode ser criado em POST /pedidos/<id>/pagamento, a menos
+    // que o pedido seja cancelado junto
+    async fn registrar_webhook(&self, pagamento: Pagamento, cancelar_pedido_na_falha: bool) -> Result<Pagamento, DomainError> {
+        let metodo_pagamento = self
+            .metodos_pagamento
+            .get(pagamento.metodo().as_str())
+            .cloned()
+            .ok_or_else(|| DomainError::validation("pagamento", "Método de pagamento não suportado"))?;
+
+        match metodo_pagamento.set_webhook_pagamento(pagamento.clone()).await {
+            Ok(pagamento_updated) => {
+                info!(pedido_id = *pagamento.id_pedido(), "Webhook de pagamento registrado");
+                self.pagamento_repository.update_pagamento(pagamento_updated).await
+            }
+            Err(err) => {
+                error!(pedido_id = *pagamento.id_pedido(), error = ?err, "Falha ao registrar webhook de pagamento");
+                metrics::registrar_pagamento(pagamento.metodo(), "falha");
+                if let Err(update_err) = self.registrar_falha_do_webhook(pagamento, cancelar_pedido_na_falha).await {
+                    error!(error = ?update_err, "Erro ao marcar pagamento com falha");
                 }
+                Err(err)
             }
-            None => {
-                println!("Metodo de pagamento invalido");
-                Err(DomainError::Invalid("Internal server error".to_string()))
+        }
+    }
+
+    async fn registrar_falha_do_webhook(
+        &self,
+        mut pagamento: Pagamento,
+        cancelar_pedido: bool,
+    ) -> Result<(), DomainError> {
+        pagamento.set_estado(String::from("falha"))?;
+        let transacao = self.unit_of_work.begin().await?;
+        let resultado = async {
+            if cancelar_pedido {
+                let pedido_id = *pagamento.id_pedido();
+                transacao.pedido_repository().atualiza_status(pedido_id, Status::Cancelado).await?;
+                info!(pedido_id, "Pedido cancelado por falha no registro do pagamento");
             }
+            transacao.pagamento_repository().update_pagamento(pagamento).await?;
+            Ok(())
         }
+        .await;
+        finalizar_transacao(transacao, resultado).await
     }
 
+    #[instrument(skip(self, data_pagamento), err(level = "warn", Debug))]
     pub async fn webhook_pagamento(
         &self,
         pedido_id: usize,
         data_pagamento: Value,
     ) -> Result<Pagamento, DomainError> {
-        let mut pedido_repository = self.pedido_repository.lock().await;
-        let mut pagamento_repository = self.pagamento_repository.lock().await;
+        let transacao = self.unit_of_work.begin().await?;
+        let resultado = self
+            .processar_webhook_pagamento(transacao.as_ref(), pedido_id, data_pagamento)
+            .await;
+        finalizar_transacao(transacao, resultado).await
+    }
+
+    async fn processar_webhook_pagamento(
+        &self,
+        transacao: &(dyn Transacao + Sync + Send),
+        pedido_id: usize,
+        data_pagamento: Value,
+    ) -> Result<Pagamento, DomainError> {
+        let inicio = Instant::now();
+        let pedido_repository = transacao.pedido_repository();
+        let pagamento_repository = transacao.pagamento_repository();
         let pedido: Pedido = pedido_repository.get_pedido_by_id(pedido_id).await?;
         let mut pagamento: Pagamento = pagamento_repository
             .get_pagamento_by_id_pedido(pedido_id)
             .await?;
-        println!("Pagamento:");
-        println!("{}", pedido.pagamento().as_str());
-        match pedido.pagamento().as_str() {
-            "Mercado Pago" => {
-                let mercado_pago_adapter: Arc<dyn PagamentoWebhookAdapter + Sync + Send> =
-                    Arc::new(MercadoPagoPagamentoWebhookAdapter::new());
-
-                pagamento = mercado_pago_adapter.processa_webhook(data_pagamento, pagamento);
+        match self.metodos_pagamento.get(pedido.pagamento().as_str()) {
+            Some(metodo_pagamento) => {
+                pagamento = metodo_pagamento.processa_webhook(data_pagamento, pagamento);
                 pagamento_repository
                     .update_pagamento(pagamento.clone())
                     .await?;
-                println!("Status do pagamento:");
-                println!("{}", *pagamento.estado());
+                info!(pedido_id, estado = %pagamento.estado(), "Webhook de pagamento processado");
+                let resultado = if *pagamento.estado() == "aprovado" {
+                    "aprovado"
+                } else {
+                    "recusado"
+                };
+                metrics::registrar_pagamento(pedido.pagamento(), resultado);
                 if *pagamento.estado() == String::from("aprovado")
                     && *pedido.status() == Status::Pendente
                 {
-                    println!("Atualizando pedido para pago");
+                    info!(pedido_id, "Atualizando pedido para pago");
                     pedido_repository
                         .atualiza_status(*pedido.id(), Status::Pago)
                         .await?;
                 }
-                println!("Atualização de pagamento realizada com sucesso");
+                metrics::registrar_webhook_pagamento(pedido.pagamento(), inicio.elapsed());
                 Ok(pagamento)
             }
-            _ => {
-                println!("Metodo de pagamento invalido");
-                Err(DomainError::Invalid("Internal server error".to_string()))
+            None => {
+                warn!(metodo = %pedido.pagamento(), "Método de pagamento inválido");
+                Err(DomainError::validation("pagamento", "Método de pagamento não suportado"))
+            }
+        }
+    }
+}
+
+// Commit no sucesso e rollback no erro, devolvendo o resultado da operação
+pub(crate) async fn finalizar_transacao<T>(
+    transacao: Box<dyn Transacao + Sync + Send>,
+    resultado: Result<T, DomainError>,
+) -> Result<T, DomainError> {
+    match resultado {
+        Ok(valor) => {
+            transacao.commit().await?;
+            Ok(valor)
+        }
+        Err(err) => {
+            if let Err(rollback_err) = transacao.rollback().await {
+                error!(error = ?rollback_err, "Erro ao desfazer transação");
             }
+            Err(err)
         }
     }
 }
@@ -300,15 +380,21 @@ unsafe impl Sync for PedidosEPagamentosUseCase {}
 mod tests {
     use super::*;
     use crate::entities::{cliente::Cliente, cpf::Cpf, ingredientes::Ingredientes, pedido::Pedido};
+    use mockall::Sequence;
     use crate::traits::{
         cliente_gateway::MockClienteGateway, pagamento_gateway::MockPagamentoGateway,
         pedido_gateway::MockPedidoGateway, produto_gateway::MockProdutoGateway,
-        pagamento_webhook_adapter::MockPagamentoWebhookAdapter
+        pagamento_webhook_adapter::MockPagamentoWebhookAdapter,
+        unit_of_work::{MockTransacao, MockUnitOfWork},
+    };
+    use crate::gateways::{
+        in_memory_pagamento_gateway::InMemoryPagamentoRepository,
+        in_memory_pedido_gateway::InMemoryPedidoRepository,
+        in_memory_unit_of_work::InMemoryUnitOfWork,
     };
     use std::sync::Arc;
     use tokio;
-    use tokio::sync::Mutex;
-
+    
     #[tokio::test]
     async fn test_lista_pedidos() {
         let mut mock = MockPedidoGateway::new();
@@ -321,8 +407,8 @@ mod tests {
             None,
             "id_pagamento".to_string(),
             Status::Pendente,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_pedido = returned_pedido.clone();
@@ -332,11 +418,12 @@ mod tests {
             .returning(move || Ok(vec![returned_pedido.clone()]));
 
         let use_case = PedidosEPagamentosUseCase::new(
-            Arc::new(Mutex::new(mock)),
-            Arc::new(Mutex::new(MockClienteGateway::new())),
-            Arc::new(Mutex::new(MockProdutoGateway::new())),
-            Arc::new(Mutex::new(MockPagamentoGateway::new())),
+            Arc::new(mock),
+            Arc::new(MockClienteGateway::new()),
+            Arc::new(MockProdutoGateway::new()),
+            Arc::new(MockPagamentoGateway::new()),
             HashMap::new(),
+            Arc::new(MockUnitOfWork::new()),
         );
         let result = use_case.lista_pedidos().await;
         assert_eq!(result.unwrap()[0].id(), expected_pedido.id());
@@ -354,8 +441,8 @@ mod tests {
             None,
             "id_pagamento".to_string(),
             Status::Pendente,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_pedido = returned_pedido.clone();
@@ -365,11 +452,12 @@ mod tests {
             .returning(move |_| Ok(returned_pedido.clone()));
 
         let use_case = PedidosEPagamentosUseCase::new(
-            Arc::new(Mutex::new(mock)),
-            Arc::new(Mutex::new(MockClienteGateway::new())),
-            Arc::new(Mutex::new(MockProdutoGateway::new())),
-            Arc::new(Mutex::new(MockPagamentoGateway::new())),
+            Arc::new(mock),
+            Arc::new(MockClienteGateway::new()),
+            Arc::new(MockProdutoGateway::new()),
+            Arc::new(MockPagamentoGateway::new()),
             HashMap::new(),
+            Arc::new(MockUnitOfWork::new()),
         );
         let result = use_case.seleciona_pedido_por_id(1).await;
         assert_eq!(result.unwrap().id(), expected_pedido.id());
@@ -380,15 +468,17 @@ mod tests {
         let mut mock_pedido_repository = MockPedidoGateway::new();
         let mut mock_cliente_repository = MockClienteGateway::new();
         let mut mock_pagamento_repository = MockPagamentoGateway::new();
+        let mut mock_pagamento_repository_fora_da_transacao = MockPagamentoGateway::new();
         let mut mock_mercado_pago_webhook_adapter = MockPagamentoWebhookAdapter::new();
+        let mut sequencia = Sequence::new();
 
         let returned_cliente = Cliente::new(
             1,
             "nome".to_string(),
             "email".to_string(),
             Cpf::new("000.000.000-00".to_string()).unwrap(),
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let returned_pagamento = Pagamento::new(
@@ -398,7 +488,7 @@ mod tests {
             10.0,
             "Mercado Pago".to_string(),
             "id_pagamento".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
         );
 
         let updated_pagamento = returned_pagamento.clone();
@@ -411,26 +501,19 @@ mod tests {
             None,
             None,
             None,
-            "id_pagamento".to_string(),
+            "Mercado Pago".to_string(),
             Status::Pendente,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_pedido = returned_pedido.clone();
 
-        let created_pedido = returned_pedido.clone();
-
         mock_pedido_repository
             .expect_create_pedido()
             .times(1)
             .returning(move |_| Ok(returned_pedido.clone()));
 
-        mock_pedido_repository
-            .expect_get_pedido_by_id()
-            .times(1)
-            .returning(move |_| Ok(created_pedido.clone()));
-
         mock_cliente_repository
             .expect_get_cliente_by_id()
             .times(1)
@@ -441,25 +524,53 @@ mod tests {
             .times(1)
             .returning(move |_| Ok(returned_pagamento.clone()));
 
-        mock_pagamento_repository
-            .expect_update_pagamento()
+        mock_pagamento_repository.expect_update_pagamento().never();
+
+        let pedido_repository: Arc<dyn PedidoGateway + Sync + Send> = Arc::new(mock_pedido_repository);
+        let pagamento_repository: Arc<dyn PagamentoGateway + Sync + Send> = Arc::new(mock_pagamento_repository);
+
+        let mut mock_transacao = MockTransacao::new();
+        mock_transacao
+            .expect_pedido_repository()
+            .returning(move || pedido_repository.clone());
+        mock_transacao
+            .expect_pagamento_repository()
+            .returning(move || pagamento_repository.clone());
+        mock_transacao
+            .expect_commit()
             .times(1)
-            .returning(move |_| Ok(updated_pagamento.clone()));
+            .in_sequence(&mut sequencia)
+            .returning(|| Ok(()));
+        mock_transacao.expect_rollback().never();
 
+        // O serviço de pagamento só é chamado depois do commit, e o resultado é gravado fora da transação
         mock_mercado_pago_webhook_adapter
             .expect_set_webhook_pagamento()
             .times(1)
+            .in_sequence(&mut sequencia)
             .returning(move |_| Ok(payed_pagamento.clone()));
+        mock_pagamento_repository_fora_da_transacao
+            .expect_update_pagamento()
+            .times(1)
+            .in_sequence(&mut sequencia)
+            .returning(move |_| Ok(updated_pagamento.clone()));
 
         let mut metodos_pagamento: HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>> = HashMap::new();
             metodos_pagamento.insert("Mercado Pago".to_string(), Arc::new(mock_mercado_pago_webhook_adapter));
 
+        let mut mock_unit_of_work = MockUnitOfWork::new();
+        mock_unit_of_work
+            .expect_begin()
+            .times(1)
+            .return_once(move || Ok(Box::new(mock_transacao)));
+
         let use_case = PedidosEPagamentosUseCase::new(
-            Arc::new(Mutex::new(mock_pedido_repository)),
-            Arc::new(Mutex::new(mock_cliente_repository)),
-            Arc::new(Mutex::new(MockProdutoGateway::new())),
-            Arc::new(Mutex::new(mock_pagamento_repository)),
+            Arc::new(MockPedidoGateway::new()),
+            Arc::new(mock_cliente_repository),
+            Arc::new(MockProdutoGateway::new()),
+            Arc::new(mock_pagamento_repository_fora_da_transacao),
             metodos_pagamento,
+            Arc::new(mock_unit_of_work),
         );
         let result = use_case
             .novo_pedido(CreatePedidoInput {
@@ -493,8 +604,8 @@ mod tests {
             Categoria::Lanche,
             10.0,
             ingredientes,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let returned_pedido = Pedido::new(
@@ -505,8 +616,8 @@ mod tests {
             None,
             "id_pagamento".to_string(),
             Status::Pendente,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_pedido = returned_pedido.clone();
@@ -522,11 +633,12 @@ mod tests {
             .returning(move |_, _| Ok(returned_pedido.clone()));
 
         let use_case = PedidosEPagamentosUseCase::new(
-            Arc::new(Mutex::new(mock_pedido_repository)),
-            Arc::new(Mutex::new(MockClienteGateway::new())),
-            Arc::new(Mutex::new(mock_produto_repository)),
-            Arc::new(Mutex::new(MockPagamentoGateway::new())),
+            Arc::new(mock_pedido_repository),
+            Arc::new(MockClienteGateway::new()),
+            Arc::new(mock_produto_repository),
+            Arc::new(MockPagamentoGateway::new()),
             HashMap::new(),
+            Arc::new(MockUnitOfWork::new()),
         );
         let result = use_case.adicionar_lanche_com_personalizacao(1, 1).await;
         assert_eq!(result.unwrap().id(), expected_pedido.id());
@@ -548,8 +660,8 @@ mod tests {
             Categoria::Acompanhamento,
             10.0,
             ingredientes,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let returned_pedido = Pedido::new(
@@ -560,8 +672,8 @@ mod tests {
             None,
             "id_pagamento".to_string(),
             Status::Pendente,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_pedido = returned_pedido.clone();
@@ -577,11 +689,12 @@ mod tests {
             .returning(move |_, _| Ok(returned_pedido.clone()));
 
         let use_case = PedidosEPagamentosUseCase::new(
-            Arc::new(Mutex::new(mock_pedido_repository)),
-            Arc::new(Mutex::new(MockClienteGateway::new())),
-            Arc::new(Mutex::new(mock_produto_repository)),
-            Arc::new(Mutex::new(MockPagamentoGateway::new())),
+            Arc::new(mock_pedido_repository),
+            Arc::new(MockClienteGateway::new()),
+            Arc::new(mock_produto_repository),
+            Arc::new(MockPagamentoGateway::new()),
             HashMap::new(),
+            Arc::new(MockUnitOfWork::new()),
         );
 
         let result = use_case.adicionar_acompanhamento(1, 1).await;
@@ -604,8 +717,8 @@ mod tests {
             Categoria::Bebida,
             10.0,
             ingredientes,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let returned_pedido = Pedido::new(
@@ -616,8 +729,8 @@ mod tests {
             None,
             "id_pagamento".to_string(),
             Status::Pendente,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string(),
+            Utc::now(),
+            Utc::now(),
         );
 
         let expected_pedido = returned_pedido.clone();
@@ -633,14 +746,151 @@ mod tests {
             .returning(move |_, _| Ok(returned_pedido.clone()));
 
         let use_case = PedidosEPagamentosUseCase::new(
-            Arc::new(Mutex::new(mock_pedido_repository)),
-            Arc::new(Mutex::new(MockClienteGateway::new())),
-            Arc::new(Mutex::new(mock_produto_repository)),
-            Arc::new(Mutex::new(MockPagamentoGateway::new())),
+            Arc::new(mock_pedido_repository),
+            Arc::new(MockClienteGateway::new()),
+            Arc::new(mock_produto_repository),
+            Arc::new(MockPagamentoGateway::new()),
             HashMap::new(),
+            Arc::new(MockUnitOfWork::new()),
         );
 
         let result = use_case.adicionar_bebida(1, 1).await;
         assert_eq!(result.unwrap().id(), expected_pedido.id());
     }
+
+    fn use_case_em_memoria(
+        webhook_adapter: MockPagamentoWebhookAdapter,
+    ) -> (
+        PedidosEPagamentosUseCase,
+        Arc<InMemoryPedidoRepository>,
+        Arc<InMemoryPagamentoRepository>,
+    ) {
+        let pedido_repository = Arc::new(InMemoryPedidoRepository::new());
+        let pagamento_repository = Arc::new(InMemoryPagamentoRepository::new());
+        let unit_of_work = InMemoryUnitOfWork::new(
+            pedido_repository.clone(),
+            pagamento_repository.clone(),
+        );
+
+        let mut metodos_pagamento: HashMap<String, Arc<dyn PagamentoWebhookAdapter + Sync + Send>> = HashMap::new();
+        metodos_pagamento.insert("Mercado Pago".to_string(), Arc::new(webhook_adapter));
+
+        let use_case = PedidosEPagamentosUseCase::new(
+            pedido_repository.clone(),
+            Arc::new(MockClienteGateway::new()),
+            Arc::new(MockProdutoGateway::new()),
+            pagamento_repository.clone(),
+            metodos_pagamento,
+            Arc::new(unit_of_work),
+        );
+        (use_case, pedido_repository, pagamento_repository)
+    }
+
+    fn pedido_input_vazio() -> CreatePedidoInput {
+        CreatePedidoInput {
+            cliente_id: None,
+            lanche_id: None,
+            acompanhamento_id: None,
+            bebida_id: None,
+        }
+    }
+
+    #[tokio::test]
+    async fn test_novo_pedido_commit_grava_pedido_e_pagamento() {
+        let mut webhook_adapter = MockPagamentoWebhookAdapter::new();
+        webhook_adapter
+            .expect_set_webhook_pagamento()
+            .times(1)
+            .returning(Ok);
+
+        let (use_case, pedido_repository, pagamento_repository) = use_case_em_memoria(webhook_adapter);
+        let pedidos_antes = pedido_repository.lista_pedidos().await.unwrap().len();
+
+        let pedido = use_case.novo_pedido(pedido_input_vazio()).await.unwrap();
+
+        assert_eq!(pedido_repository.lista_pedidos().await.unwrap().len(), pedidos_antes + 1);
+        let pagamento = pagamento_repository
+            .get_pagamento_by_id_pedido(*pedido.id())
+            .await
+            .unwrap();
+        assert_eq!(pagamento.estado(), "pendente");
+    }
+
+    #[tokio::test]
+    async fn test_novo_pedido_cancela_pedido_quando_webhook_falha() {
+        let mut webhook_adapter = MockPagamentoWebhookAdapter::new();
+        webhook_adapter
+            .expect_set_webhook_pagamento()
+            .times(1)
+            .returning(|_| Err(DomainError::external_service("Mercado Pago", "webhook")));
+
+        let (use_case, pedido_repository, pagamento_repository) = use_case_em_memoria(webhook_adapter);
+        let pedidos_antes = pedido_repository.lista_pedidos().await.unwrap();
+
+        let result = use_case.novo_pedido(pedido_input_vazio()).await;
+
+        assert!(matches!(result, Err(DomainError::ExternalService { .. })));
+        let pedidos_depois = pedido_repository.lista_pedidos().await.unwrap();
+        assert_eq!(pedidos_depois.len(), pedidos_antes.len() + 1);
+        let novo = pedidos_depois.iter().max_by_key(|pedido| *pedido.id()).unwrap();
+        assert_eq!(*novo.status(), Status::Cancelado);
+        let pagamento = pagamento_repository
+            .get_pagamento_by_id_pedido(*novo.id())
+            .await
+            .unwrap();
+        assert_eq!(pagamento.estado(), "falha");
+    }
+
+    #[tokio::test]
+    async fn test_novo_pagamento_com_falha_no_webhook_mantem_o_pedido() {
+        let mut webhook_adapter = MockPagamentoWebhookAdapter::new();
+        webhook_adapter
+            .expect_set_webhook_pagamento()
+            .times(1)
+            .returning(|_| Err(DomainError::external_service("Mercado Pago", "webhook")));
+
+        let (use_case, pedido_repository, pagamento_repository) = use_case_em_memoria(webhook_adapter);
+        let pedido = pedido_repository
+            .create_pedido(Pedido::new(
+                0,
+                None,
+                None,
+                None,
+                None,
+                "Mercado Pago".to_string(),
+                Status::Pendente,
+                Utc::now(),
+                Utc::now(),
+            ))
+            .await
+            .unwrap();
+
+        let result = use_case.criar_pagamento_do_pedido(*pedido.id()).await;
+
+        assert!(matches!(result, Err(DomainError::ExternalService { .. })));
+        let pedido = pedido_repository.get_pedido_by_id(*pedido.id()).await.unwrap();
+        assert_eq!(*pedido.status(), Status::Pendente);
+        let pagamento = pagamento_repository
+            .get_pagamento_by_id_pedido(*pedido.id())
+            .await
+            .unwrap();
+        assert_eq!(pagamento.estado(), "falha");
+    }
+
+    #[tokio::test]
+    async fn test_metodo_de_pagamento_nao_suportado_nao_grava_pedido() {
+        let mut webhook_adapter = MockPagamentoWebhookAdapter::new();
+        webhook_adapter.expect_set_webhook_pagamento().never();
+        let (use_case, pedido_repository, _) = use_case_em_memoria(webhook_adapter);
+        let use_case = PedidosEPagamentosUseCase {
+            metodos_pagamento: HashMap::new(),
+            ..use_case
+        };
+        let pedidos_antes = pedido_repository.lista_pedidos().await.unwrap().len();
+
+        let result = use_case.novo_pedido(pedido_input_vazio()).await;
+
+        assert!(matches!(result, Err(DomainError::Validation { .. })));
+        assert_eq!(pedido_repository.lista_pedidos().await.unwrap().len(), pedidos_antes);
+    }
 }
@@ -1,30 +1,173 @@
+use std::collections::HashMap;
 use std::sync::Arc;
 
-use tokio::sync::Mutex;
+use chrono::{DateTime, Duration, Utc};
 
 use crate::base::domain_error::DomainError;
+use crate::external::metrics;
+use crate::entities::estacao::{StatusDoTicket, TicketDeEstacao};
+use crate::entities::fila_da_cozinha::{na_fila_da_cozinha, PoliticaDaFila};
 use crate::entities::pedido::{Pedido, Status};
+use crate::entities::tempo_de_preparo::{
+    TempoDePreparo, DIAS_TEMPOS_DE_PREPARO_PADRAO, SLA_COZINHA_PADRAO_MINUTOS,
+};
 
+use crate::traits::estacao_gateway::EstacaoGateway;
 use crate::traits::pedido_gateway::PedidoGateway;
+use crate::traits::unit_of_work::{Transacao, UnitOfWork};
+use crate::use_cases::pedidos_e_pagamentos_use_case::finalizar_transacao;
 
 #[derive(Clone)]
 pub struct PreparacaoeEntregaUseCase {
-    pedido_repository: Arc<Mutex<dyn PedidoGateway + Sync + Send>>,
+    pedido_repository: Arc<dyn PedidoGateway + Sync + Send>,
+    estacao_repository: Arc<dyn EstacaoGateway + Sync + Send>,
+    unit_of_work: Arc<dyn UnitOfWork + Sync + Send>,
+    sla: Duration,
+    politica_da_fila: PoliticaDaFila,
 }
 
 impl PreparacaoeEntregaUseCase {
-    pub fn new(pedido_repository: Arc<Mutex<dyn PedidoGateway + Sync + Send>>) -> Self {
-        PreparacaoeEntregaUseCase { pedido_repository }
+    pub fn new(
+        pedido_repository: Arc<dyn PedidoGateway + Sync + Send>,
+        estacao_repository: Arc<dyn EstacaoGateway + Sync + Send>,
+        unit_of_work: Arc<dyn UnitOfWork + Sync + Send>,
+    ) -> Self {
+        PreparacaoeEntregaUseCase {
+            pedido_repository,
+            estacao_repository,
+            unit_of_work,
+            sla: Duration::minutes(SLA_COZINHA_PADRAO_MINUTOS as i64),
+            politica_da_fila: PoliticaDaFila::default(),
+        }
+    }
+
+    pub fn com_politica_da_fila(mut self, politica_da_fila: PoliticaDaFila) -> Self {
+        self.politica_da_fila = politica_da_fila;
+        self
+    }
+
+    pub fn com_sla(mut self, sla: std::time::Duration) -> Self {
+        self.sla = Duration::from_std(sla).unwrap_or(self.sla);
+        self
     }
 
     pub async fn get_pedidos_novos(&self) -> Result<Vec<Pedido>, DomainError> {
-        let pedido_repository = self.pedido_repository.lock().await;
-        pedido_repository.get_pedidos_novos().await
+        let mut pedidos = self.pedido_repository.get_pedidos_novos().await?;
+        self.organizar_fila(&mut pedidos).await?;
+        let atrasados = pedidos.iter().filter(|pedido| pedido.atrasado()).count();
+        metrics::registrar_fila_cozinha(pedidos.len(), atrasados);
+        Ok(pedidos)
+    }
+
+    // Previsão e atraso primeiro, porque a política pode priorizar os atrasados
+    pub async fn organizar_fila(&self, pedidos: &mut [Pedido]) -> Result<(), DomainError> {
+        self.com_previsao(pedidos).await?;
+        self.politica_da_fila.ordenar(pedidos);
+        Ok(())
+    }
+
+    // Preenche previsão de pronto e atraso dos pedidos que ainda estão na cozinha
+    pub async fn com_previsao(&self, pedidos: &mut [Pedido]) -> Result<(), DomainError> {
+        if pedidos.iter().all(Pedido::saiu_da_cozinha) {
+            return Ok(());
+        }
+        let medias: HashMap<usize, Duration> = self
+            .get_tempos_de_preparo(DIAS_TEMPOS_DE_PREPARO_PADRAO)
+            .await?
+            .iter()
+            .map(|tempo| (*tempo.produto_id(), tempo.preparo_medio()))
+            .collect();
+        let agora = Utc::now();
+        for pedido in pedidos.iter_mut().filter(|pedido| !pedido.saiu_da_cozinha()) {
+            let previsao = self.previsao_de_pronto(pedido, &medias, agora);
+            pedido.set_previsao_de_pronto(Some(previsao));
+            pedido.set_atrasado(agora - *pedido.entrada_na_fila() > self.sla);
+        }
+        Ok(())
+    }
+
+    // O item mais demorado define o pedido; itens sem histórico usam o SLA
+    fn previsao_de_pronto(
+        &self,
+        pedido: &Pedido,
+        medias: &HashMap<usize, Duration>,
+        agora: DateTime<Utc>,
+    ) -> DateTime<Utc> {
+        let preparo = [pedido.lanche(), pedido.acompanhamento(), pedido.bebida()]
+            .into_iter()
+            .flatten()
+            .map(|produto| medias.get(produto.id()).copied().unwrap_or(self.sla))
+            .max()
+            .unwrap_or(self.sla);
+        let inicio = pedido.data_do_status(&Status::EmPreparacao).copied().unwrap_or(agora);
+        inicio + preparo
+    }
+
+    pub async fn get_tempos_de_preparo(&self, dias: i64) -> Result<Vec<TempoDePreparo>, DomainError> {
+        if dias <= 0 {
+            return Err(DomainError::validation("dias", "deve ser maior que zero"));
+        }
+        self.pedido_repository
+            .get_tempos_de_preparo(Utc::now() - Duration::days(dias))
+            .await
     }
 
     pub async fn atualiza_status(&self,  id: usize, status : Status) -> Result<Pedido, DomainError> {
-        let mut pedido_repository = self.pedido_repository.lock().await;
-        pedido_repository.atualiza_status(id, status).await
+        self.pedido_repository.atualiza_status(id, status).await
+    }
+
+    // Tickets da estação que ainda não ficaram prontos, na ordem da fila da cozinha. Estação
+    // inexistente ou de outra loja é NotFound em vez de uma fila vazia
+    pub async fn get_fila_da_estacao(&self, estacao_id: usize) -> Result<Vec<TicketDeEstacao>, DomainError> {
+        self.estacao_repository.get_estacao_by_id(estacao_id).await?;
+        let mut pedidos = self.pedido_repository.get_pedidos_novos().await?;
+        self.organizar_fila(&mut pedidos).await?;
+        Ok(pedidos
+            .iter()
+            .filter_map(|pedido| pedido.ticket_da_estacao(estacao_id))
+            .filter(|ticket| *ticket.status() != StatusDoTicket::Pronto)
+            .cloned()
+            .collect())
+    }
+
+    // O primeiro ticket em preparo coloca o pedido em preparação e o último pronto libera o
+    // pedido; tudo na mesma transação para duas estações não perderem a transição do pedido
+    pub async fn atualiza_status_do_ticket(
+        &self,
+        estacao_id: usize,
+        pedido_id: usize,
+        status: StatusDoTicket,
+    ) -> Result<Pedido, DomainError> {
+        let transacao = self.unit_of_work.begin().await?;
+        let resultado = Self::registrar_ticket(transacao.as_ref(), estacao_id, pedido_id, status).await;
+        finalizar_transacao(transacao, resultado).await
+    }
+
+    async fn registrar_ticket(
+        transacao: &(dyn Transacao + Sync + Send),
+        estacao_id: usize,
+        pedido_id: usize,
+        status: StatusDoTicket,
+    ) -> Result<Pedido, DomainError> {
+        let pedido_repository = transacao.pedido_repository();
+        let pedido = pedido_repository.get_pedido_by_id(pedido_id).await?;
+        if !na_fila_da_cozinha(pedido.status()) {
+            return Err(DomainError::conflict("Pedido", "O pedido não está na fila da cozinha"));
+        }
+        if pedido.ticket_da_estacao(estacao_id).is_none() {
+            return Err(DomainError::not_found("Ticket", format!("{}/{}", pedido_id, estacao_id)));
+        }
+
+        let mut pedido = pedido_repository
+            .atualiza_status_do_ticket(pedido_id, estacao_id, status.clone())
+            .await?;
+        if status == StatusDoTicket::EmPreparacao && *pedido.status() == Status::Pago {
+            pedido = pedido_repository.atualiza_status(pedido_id, Status::EmPreparacao).await?;
+        }
+        if pedido.pronto_em_todas_as_estacoes() {
+            pedido = pedido_repository.atualiza_status(pedido_id, Status::Pronto).await?;
+        }
+        Ok(pedido)
     }
 }
 
@@ -35,9 +178,14 @@ unsafe impl Sync for PreparacaoeEntregaUseCase {}
 mod tests {
     use super::*;
     use tokio;
+    use chrono::Utc;
     use crate::entities::pedido::Pedido;
+    use crate::entities::ingredientes::Ingredientes;
+    use crate::entities::produto::{Categoria, Produto};
+    use crate::entities::estacao::Estacao;
+    use crate::traits::estacao_gateway::MockEstacaoGateway;
     use crate::traits::pedido_gateway::MockPedidoGateway;
-    use tokio::sync::Mutex;
+    use crate::traits::unit_of_work::{MockTransacao, MockUnitOfWork};
     use std::sync::Arc;
     use mockall::predicate::*;
 
@@ -53,8 +201,8 @@ mod tests {
             None,
             "id_pagamento".to_string(),
             Status::Pendente,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string()
+            Utc::now(),
+            Utc::now()
         );
 
         let expected_pedido = returned_pedido.clone();
@@ -62,8 +210,11 @@ mod tests {
         mock.expect_get_pedidos_novos()
             .times(1)
             .returning(move || Ok(vec![returned_pedido.clone()]));
+        mock.expect_get_tempos_de_preparo()
+            .times(1)
+            .returning(|_| Ok(vec![]));
 
-        let use_case = PreparacaoeEntregaUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = PreparacaoeEntregaUseCase::new(Arc::new(mock), Arc::new(MockEstacaoGateway::new()), Arc::new(MockUnitOfWork::new()));
         let result = use_case.get_pedidos_novos().await;
         assert_eq!(result.unwrap()[0].id(), expected_pedido.id());
     }
@@ -80,8 +231,8 @@ mod tests {
             None,
             "id_pagamento".to_string(),
             Status::EmPreparacao,
-            "2021-10-10".to_string(),
-            "2021-10-10".to_string()
+            Utc::now(),
+            Utc::now()
         );
 
         mock.expect_atualiza_status()
@@ -89,8 +240,229 @@ mod tests {
             .with(eq(1), eq(Status::EmPreparacao))
             .returning(move |_, _| Ok(returned_pedido.clone()));
 
-        let use_case = PreparacaoeEntregaUseCase::new(Arc::new(Mutex::new(mock)));
+        let use_case = PreparacaoeEntregaUseCase::new(Arc::new(mock), Arc::new(MockEstacaoGateway::new()), Arc::new(MockUnitOfWork::new()));
         let result = use_case.atualiza_status(1, Status::EmPreparacao).await;
         assert_eq!(result.unwrap().status().to_owned(), Status::EmPreparacao);
     }
+
+    #[tokio::test]
+    async fn test_get_pedidos_novos_previsao_e_atraso() {
+        let mut mock = MockPedidoGateway::new();
+        let lanche = Produto::new(
+            1,
+            "X-Bacon".to_string(),
+            "foto.png".to_string(),
+            "Lanche de bacon".to_string(),
+            Categoria::Lanche,
+            10.0,
+            Ingredientes::new(vec!["Pão".to_string()]).unwrap(),
+            Utc::now(),
+            Utc::now(),
+        );
+        let mut atrasado = Pedido::new(
+            1,
+            None,
+            Some(lanche.clone()),
+            None,
+            None,
+            "id_pagamento".to_string(),
+            Status::Pendente,
+            Utc::now() - Duration::minutes(40),
+            Utc::now() - Duration::minutes(40),
+        );
+        atrasado.registrar_status(Status::Pago, Utc::now() - Duration::minutes(30));
+        atrasado.registrar_status(Status::EmPreparacao, Utc::now() - Duration::minutes(25));
+        let mut no_prazo = Pedido::new(
+            2,
+            None,
+            None,
+            None,
+            None,
+            "id_pagamento".to_string(),
+            Status::Pendente,
+            Utc::now(),
+            Utc::now(),
+        );
+        no_prazo.registrar_status(Status::Pago, Utc::now());
+        let inicio_do_atrasado = *atrasado.data_do_status(&Status::EmPreparacao).unwrap();
+
+        mock.expect_get_pedidos_novos()
+            .times(1)
+            .returning(move || Ok(vec![atrasado.clone(), no_prazo.clone()]));
+        mock.expect_get_tempos_de_preparo()
+            .times(1)
+            .returning(|_| Ok(vec![TempoDePreparo::new(1, 3, 60.0, 300.0)]));
+
+        let use_case = PreparacaoeEntregaUseCase::new(Arc::new(mock), Arc::new(MockEstacaoGateway::new()), Arc::new(MockUnitOfWork::new())).com_sla(std::time::Duration::from_secs(15 * 60));
+        let pedidos = use_case.get_pedidos_novos().await.unwrap();
+
+        assert!(pedidos[0].atrasado());
+        assert_eq!(pedidos[0].previsao_de_pronto(), Some(&(inicio_do_atrasado + Duration::minutes(5))));
+        assert!(!pedidos[1].atrasado());
+        let previsao = *pedidos[1].previsao_de_pronto().unwrap();
+        assert!(previsao > Utc::now() + Duration::minutes(14));
+    }
+
+    fn produto_da_estacao(id: usize, categoria: Categoria, estacao_id: usize) -> Produto {
+        let mut produto = Produto::new(
+            id,
+            format!("Produto {}", id),
+            "foto.png".to_string(),
+            "descricao".to_string(),
+            categoria,
+            10.0,
+            Ingredientes::new(vec!["Pão".to_string()]).unwrap(),
+            Utc::now(),
+            Utc::now(),
+        );
+        produto.set_estacao_id(Some(estacao_id));
+        produto
+    }
+
+    // Lanche na estação 1 e bebida na estação 2
+    fn pedido_com_duas_estacoes(id: usize, status: Status) -> Pedido {
+        let mut pedido = Pedido::new(
+            id,
+            None,
+            Some(produto_da_estacao(1, Categoria::Lanche, 1)),
+            None,
+            Some(produto_da_estacao(2, Categoria::Bebida, 2)),
+            "id_pagamento".to_string(),
+            Status::Pendente,
+            Utc::now(),
+            Utc::now(),
+        );
+        if status != Status::Pendente {
+            pedido.registrar_status(status, Utc::now());
+        }
+        pedido
+    }
+
+    fn unit_of_work_com(pedido_repository: MockPedidoGateway, commit: bool) -> MockUnitOfWork {
+        let pedido_repository: Arc<dyn PedidoGateway + Sync + Send> = Arc::new(pedido_repository);
+        let mut mock_transacao = MockTransacao::new();
+        mock_transacao
+            .expect_pedido_repository()
+            .returning(move || pedido_repository.clone());
+        mock_transacao.expect_commit().times(commit as usize).returning(|| Ok(()));
+        mock_transacao.expect_rollback().times(!commit as usize).returning(|| Ok(()));
+
+        let mut mock_unit_of_work = MockUnitOfWork::new();
+        mock_unit_of_work
+            .expect_begin()
+            .times(1)
+            .return_once(move || Ok(Box::new(mock_transacao)));
+        mock_unit_of_work
+    }
+
+    #[tokio::test]
+    async fn test_get_fila_da_estacao_sem_tickets_prontos() {
+        let mut mock = MockPedidoGateway::new();
+        let mut pronto_na_estacao = pedido_com_duas_estacoes(1, Status::Pago);
+        pronto_na_estacao.registrar_status_do_ticket(1, StatusDoTicket::Pronto, Utc::now()).unwrap();
+        let na_fila = pedido_com_duas_estacoes(2, Status::Pago);
+
+        mock.expect_get_pedidos_novos()
+            .times(1)
+            .returning(move || Ok(vec![pronto_na_estacao.clone(), na_fila.clone()]));
+        mock.expect_get_tempos_de_preparo()
+            .times(1)
+            .returning(|_| Ok(vec![]));
+        let mut estacoes = MockEstacaoGateway::new();
+        estacoes
+            .expect_get_estacao_by_id()
+            .with(eq(1))
+            .times(1)
+            .returning(|id| Ok(Estacao::new(id, "Chapa".to_string(), Utc::now(), Utc::now())));
+
+        let use_case = PreparacaoeEntregaUseCase::new(Arc::new(mock), Arc::new(estacoes), Arc::new(MockUnitOfWork::new()));
+        let fila = use_case.get_fila_da_estacao(1).await.unwrap();
+        assert_eq!(fila.len(), 1);
+        assert_eq!(*fila[0].pedido_id(), 2);
+        assert_eq!(fila[0].itens().len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_fila_de_estacao_inexistente_ou_de_outra_loja() {
+        let mut mock = MockPedidoGateway::new();
+        mock.expect_get_pedidos_novos().never();
+        let mut estacoes = MockEstacaoGateway::new();
+        estacoes
+            .expect_get_estacao_by_id()
+            .times(1)
+            .returning(|id| Err(DomainError::not_found("Estação", id)));
+
+        let use_case = PreparacaoeEntregaUseCase::new(Arc::new(mock), Arc::new(estacoes), Arc::new(MockUnitOfWork::new()));
+        let result = use_case.get_fila_da_estacao(99).await;
+        assert!(matches!(result, Err(DomainError::NotFound { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_primeiro_ticket_em_preparo_coloca_pedido_em_preparacao() {
+        let mut mock = MockPedidoGateway::new();
+        mock.expect_get_pedido_by_id()
+            .times(1)
+            .returning(|_| Ok(pedido_com_duas_estacoes(1, Status::Pago)));
+        mock.expect_atualiza_status_do_ticket()
+            .with(eq(1), eq(1), eq(StatusDoTicket::EmPreparacao))
+            .times(1)
+            .returning(|_, estacao_id, status| {
+                let mut pedido = pedido_com_duas_estacoes(1, Status::Pago);
+                pedido.registrar_status_do_ticket(estacao_id, status, Utc::now())?;
+                Ok(pedido)
+            });
+        mock.expect_atualiza_status()
+            .with(eq(1), eq(Status::EmPreparacao))
+            .times(1)
+            .returning(|_, status| Ok(pedido_com_duas_estacoes(1, status)));
+
+        let use_case = PreparacaoeEntregaUseCase::new(Arc::new(MockPedidoGateway::new()), Arc::new(MockEstacaoGateway::new()), Arc::new(unit_of_work_com(mock, true)));
+        let pedido = use_case.atualiza_status_do_ticket(1, 1, StatusDoTicket::EmPreparacao).await.unwrap();
+        assert_eq!(*pedido.status(), Status::EmPreparacao);
+    }
+
+    #[tokio::test]
+    async fn test_ultimo_ticket_pronto_libera_o_pedido() {
+        let mut mock = MockPedidoGateway::new();
+        mock.expect_get_pedido_by_id()
+            .times(1)
+            .returning(|_| Ok(pedido_com_duas_estacoes(1, Status::EmPreparacao)));
+        mock.expect_atualiza_status_do_ticket()
+            .times(1)
+            .returning(|_, _, _| {
+                let mut pedido = pedido_com_duas_estacoes(1, Status::EmPreparacao);
+                pedido.registrar_status_do_ticket(1, StatusDoTicket::Pronto, Utc::now())?;
+                pedido.registrar_status_do_ticket(2, StatusDoTicket::Pronto, Utc::now())?;
+                Ok(pedido)
+            });
+        mock.expect_atualiza_status()
+            .with(eq(1), eq(Status::Pronto))
+            .times(1)
+            .returning(|_, status| Ok(pedido_com_duas_estacoes(1, status)));
+
+        let use_case = PreparacaoeEntregaUseCase::new(Arc::new(MockPedidoGateway::new()), Arc::new(MockEstacaoGateway::new()), Arc::new(unit_of_work_com(mock, true)));
+        let pedido = use_case.atualiza_status_do_ticket(2, 1, StatusDoTicket::Pronto).await.unwrap();
+        assert_eq!(*pedido.status(), Status::Pronto);
+    }
+
+    #[tokio::test]
+    async fn test_ticket_de_pedido_fora_da_cozinha() {
+        let mut mock = MockPedidoGateway::new();
+        mock.expect_get_pedido_by_id()
+            .times(1)
+            .returning(|_| Ok(pedido_com_duas_estacoes(1, Status::Pendente)));
+        mock.expect_atualiza_status_do_ticket().never();
+
+        let use_case = PreparacaoeEntregaUseCase::new(Arc::new(MockPedidoGateway::new()), Arc::new(MockEstacaoGateway::new()), Arc::new(unit_of_work_com(mock, false)));
+        let result = use_case.atualiza_status_do_ticket(1, 1, StatusDoTicket::Pronto).await;
+        assert!(matches!(result, Err(DomainError::Conflict { .. })));
+    }
+
+    #[tokio::test]
+    async fn test_get_tempos_de_preparo_dias_invalidos() {
+        let mock = MockPedidoGateway::new();
+        let use_case = PreparacaoeEntregaUseCase::new(Arc::new(mock), Arc::new(MockEstacaoGateway::new()), Arc::new(MockUnitOfWork::new()));
+        let result = use_case.get_tempos_de_preparo(0).await;
+        assert!(matches!(result, Err(DomainError::Validation { .. })));
+    }
 }
@@ -0,0 +1,144 @@
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use rocket::futures::future::join_all;
+use schemars::JsonSchema;
+use serde::Serialize;
+
+use crate::traits::health_check_adapter::HealthCheckAdapter;
+
+const TIMEOUT_VERIFICACAO: Duration = Duration::from_secs(3);
+
+#[derive(Clone, Debug, PartialEq, Serialize, JsonSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SituacaoSaude {
+    Ok,
+    Degradado,
+    Indisponivel,
+}
+
+#[derive(Clone, Debug, Serialize, JsonSchema)]
+pub struct SaudeDependencia {
+    pub nome: String,
+    pub situacao: SituacaoSaude,
+    pub critico: bool,
+    pub latencia_ms: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub erro: Option<String>,
+}
+
+#[derive(Clone, Debug, Serialize, JsonSchema)]
+pub struct RelatorioSaude {
+    pub situacao: SituacaoSaude,
+    pub dependencias: Vec<SaudeDependencia>,
+}
+
+#[derive(Clone)]
+pub struct VerificacaoDeSaudeUseCase {
+    dependencias: Vec<Arc<dyn HealthCheckAdapter + Sync + Send>>,
+}
+
+impl VerificacaoDeSaudeUseCase {
+    pub fn new(dependencias: Vec<Arc<dyn HealthCheckAdapter + Sync + Send>>) -> Self {
+        VerificacaoDeSaudeUseCase { dependencias }
+    }
+
+    // Verifica todas as dependências em paralelo; uma dependência que não responde
+    // dentro do timeout conta como indisponível
+    pub async fn prontidao(&self) -> RelatorioSaude {
+        let verificacoes = self.dependencias.iter().map(|dependencia| async move {
+            let inicio = Instant::now();
+            let resultado =
+                match tokio::time::timeout(TIMEOUT_VERIFICACAO, dependencia.verificar()).await {
+                    Ok(resultado) => resultado,
+                    Err(_) => Err("Tempo esgotado".to_string()),
+                };
+            SaudeDependencia {
+                nome: dependencia.nome(),
+                situacao: if resultado.is_ok() {
+                    SituacaoSaude::Ok
+                } else {
+                    SituacaoSaude::Indisponivel
+                },
+                critico: dependencia.critico(),
+                latencia_ms: inicio.elapsed().as_millis() as u64,
+                erro: resultado.err(),
+            }
+        });
+        let dependencias = join_all(verificacoes).await;
+
+        let falhas: Vec<&SaudeDependencia> = dependencias
+            .iter()
+            .filter(|dependencia| dependencia.situacao != SituacaoSaude::Ok)
+            .collect();
+        let situacao = if falhas.iter().any(|dependencia| dependencia.critico) {
+            SituacaoSaude::Indisponivel
+        } else if !falhas.is_empty() {
+            SituacaoSaude::Degradado
+        } else {
+            SituacaoSaude::Ok
+        };
+
+        RelatorioSaude {
+            situacao,
+            dependencias,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::traits::health_check_adapter::MockHealthCheckAdapter;
+
+    fn dependencia(
+        nome: &'static str,
+        critico: bool,
+        resultado: Result<(), String>,
+    ) -> Arc<dyn HealthCheckAdapter + Sync + Send> {
+        let mut mock = MockHealthCheckAdapter::new();
+        mock.expect_nome().returning(move || nome.to_string());
+        mock.expect_critico().returning(move || critico);
+        mock.expect_verificar()
+            .times(1)
+            .returning(move || resultado.clone());
+        Arc::new(mock)
+    }
+
+    #[tokio::test]
+    async fn test_prontidao_ok() {
+        let use_case = VerificacaoDeSaudeUseCase::new(vec![
+            dependencia("postgres", true, Ok(())),
+            dependencia("pagamentos", false, Ok(())),
+        ]);
+        let relatorio = use_case.prontidao().await;
+        assert_eq!(relatorio.situacao, SituacaoSaude::Ok);
+        assert_eq!(relatorio.dependencias.len(), 2);
+        assert!(relatorio.dependencias.iter().all(|d| d.erro.is_none()));
+    }
+
+    #[tokio::test]
+    async fn test_prontidao_degradada_quando_dependencia_nao_critica_falha() {
+        let use_case = VerificacaoDeSaudeUseCase::new(vec![
+            dependencia("postgres", true, Ok(())),
+            dependencia("pagamentos", false, Err("connection refused".to_string())),
+        ]);
+        let relatorio = use_case.prontidao().await;
+        assert_eq!(relatorio.situacao, SituacaoSaude::Degradado);
+        assert_eq!(
+            relatorio.dependencias[1].erro.as_deref(),
+            Some("connection refused")
+        );
+    }
+
+    #[tokio::test]
+    async fn test_prontidao_indisponivel_quando_dependencia_critica_falha() {
+        let use_case = VerificacaoDeSaudeUseCase::new(vec![
+            dependencia("postgres", true, Err("timeout".to_string())),
+            dependencia("pagamentos", false, Ok(())),
+        ]);
+        let relatorio = use_case.prontidao().await;
+        assert_eq!(relatorio.situacao, SituacaoSaude::Indisponivel);
+        assert_eq!(relatorio.dependencias[0].situacao, SituacaoSaude::Indisponivel);
+    }
+}
@@ -0,0 +1,78 @@
+mod common;
+
+use rocket::http::Status;
+use serde_json::json;
+
+use common::{client, delete, get, login, post, token_admin, ADMIN_CPF};
+
+#[rocket::async_test]
+async fn test_login_admin_retorna_token_e_usuario() {
+    let client = client().await;
+
+    let (status, body) = login(&client, ADMIN_CPF, "melhor_projeto").await;
+
+    assert_eq!(status, Status::Ok);
+    assert!(body["token"].as_str().is_some_and(|token| !token.is_empty()));
+    assert_eq!(body["usuario"]["cpf"], ADMIN_CPF);
+}
+
+#[rocket::async_test]
+async fn test_login_com_senha_errada_falha() {
+    let client = client().await;
+
+    let (status, body) = login(&client, ADMIN_CPF, "senha_errada").await;
+
+    assert_eq!(status, Status::Unauthorized);
+    assert!(body.get("token").is_none());
+}
+
+#[rocket::async_test]
+async fn test_rota_protegida_sem_token_e_recusada() {
+    let client = client().await;
+
+    let response = client.get("/pedidos").dispatch().await;
+
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[rocket::async_test]
+async fn test_rota_protegida_com_token_invalido_e_recusada() {
+    let client = client().await;
+
+    let (status, _) = get(&client, "/pedidos", "token-invalido").await;
+
+    assert_eq!(status, Status::Unauthorized);
+}
+
+#[rocket::async_test]
+async fn test_usuario_cozinha_nao_acessa_rotas_de_admin() {
+    let client = client().await;
+    let admin = token_admin(&client).await;
+    let (status, _) = post(
+        &client,
+        "/usuarios",
+        &admin,
+        json!({
+            "nome": "Cozinheiro",
+            "email": "cozinha@fastfood.com.br",
+            "senha": "senha_da_cozinha",
+            "cpf": "529.982.247-25",
+            "tipo": "Cozinha",
+            "status": "Ativo"
+        }),
+    )
+    .await;
+    assert_eq!(status, Status::Ok);
+
+    let (status, body) = login(&client, "529.982.247-25", "senha_da_cozinha").await;
+    assert_eq!(status, Status::Ok);
+    let cozinha = body["token"].as_str().unwrap().to_string();
+
+    let (status, _) = get(&client, "/pedidos", &cozinha).await;
+    assert_eq!(status, Status::Ok);
+    let (status, body) = get(&client, "/usuarios", &cozinha).await;
+    assert_eq!(status, Status::Forbidden);
+    assert_eq!(body["codigo"], "proibido");
+    let (status, _) = delete(&client, "/produtos/1", &cozinha).await;
+    assert_eq!(status, Status::Forbidden);
+}
@@ -0,0 +1,192 @@
+mod common;
+
+use chrono::{Datelike, Utc, Weekday};
+use rocket::http::{Header, Status};
+use rocket::local::asynchronous::Client;
+use serde_json::{json, Value};
+
+use common::{client, delete, patch, post, put, token_admin};
+
+fn dia_da_semana(dia: Weekday) -> &'static str {
+    match dia {
+        Weekday::Sun => "Domingo",
+        Weekday::Mon => "Segunda",
+        Weekday::Tue => "Terca",
+        Weekday::Wed => "Quarta",
+        Weekday::Thu => "Quinta",
+        Weekday::Fri => "Sexta",
+        Weekday::Sat => "Sabado",
+    }
+}
+
+async fn ids_no_cardapio(client: &Client) -> Vec<u64> {
+    let response = client.get("/cardapio").dispatch().await;
+    let body: Value = response.into_json().await.unwrap();
+    body["secoes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .flat_map(|secao| secao["produtos"].as_array().unwrap().clone())
+        .map(|produto| produto["id"].as_u64().unwrap())
+        .collect()
+}
+
+#[rocket::async_test]
+async fn test_cardapio_e_publico_e_agrupado_por_categoria() {
+    let client = client().await;
+
+    let response = client.get("/cardapio").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    assert!(response.headers().get_one("ETag").is_some());
+    let body: Value = response.into_json().await.unwrap();
+
+    let secoes = body["secoes"].as_array().unwrap();
+    let categorias: Vec<&str> = secoes.iter().map(|secao| secao["categoria"].as_str().unwrap()).collect();
+    assert_eq!(categorias, vec!["Lanche", "Acompanhamento", "Bebida", "Sobremesa"]);
+    let ordens: Vec<u64> = secoes.iter().map(|secao| secao["ordem"].as_u64().unwrap()).collect();
+    assert_eq!(ordens, vec![1, 2, 3, 4]);
+    let total: usize = secoes.iter().map(|secao| secao["produtos"].as_array().unwrap().len()).sum();
+    assert_eq!(total, 6);
+}
+
+#[rocket::async_test]
+async fn test_cardapio_responde_304_enquanto_nao_muda() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let response = client.get("/cardapio").dispatch().await;
+    let etag = response.headers().get_one("ETag").unwrap().to_string();
+
+    let response = client
+        .get("/cardapio")
+        .header(Header::new("If-None-Match", etag.clone()))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::NotModified);
+
+    let (status, _) = delete(&client, "/produtos/1", &token).await;
+    assert_eq!(status, Status::Ok);
+
+    let response = client
+        .get("/cardapio")
+        .header(Header::new("If-None-Match", etag.clone()))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_ne!(response.headers().get_one("ETag").unwrap(), etag);
+    assert!(!ids_no_cardapio(&client).await.contains(&1));
+}
+
+#[rocket::async_test]
+async fn test_produto_novo_entra_no_fim_da_categoria() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let (status, criado) = post(
+        &client,
+        "/produtos",
+        &token,
+        json!({
+            "nome": "Milkshake",
+            "foto": "milkshake.jpg",
+            "descricao": "Milkshake de chocolate",
+            "categoria": "Sobremesa",
+            "preco": 14.5,
+            "ingredientes": ["leite", "sorvete", "chocolate"]
+        }),
+    )
+    .await;
+    assert_eq!(status, Status::Ok);
+
+    let response = client.get("/cardapio").dispatch().await;
+    let body: Value = response.into_json().await.unwrap();
+    let sobremesas = body["secoes"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|secao| secao["categoria"] == "Sobremesa")
+        .unwrap()["produtos"]
+        .as_array()
+        .unwrap()
+        .clone();
+    assert_eq!(sobremesas.last().unwrap()["id"], criado["id"]);
+}
+
+#[rocket::async_test]
+async fn test_cardapio_sem_alergeno_omite_os_produtos_que_o_contem() {
+    let client = client().await;
+
+    let response = client.get("/cardapio?sem_alergeno=Lactose").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    let body: Value = response.into_json().await.unwrap();
+
+    let secoes = body["secoes"].as_array().unwrap();
+    let categorias: Vec<&str> = secoes.iter().map(|secao| secao["categoria"].as_str().unwrap()).collect();
+    assert_eq!(categorias, vec!["Acompanhamento", "Bebida", "Sobremesa"]);
+    assert_eq!(secoes[0]["ordem"], 1);
+}
+
+#[rocket::async_test]
+async fn test_personalizacao_recalcula_os_alergenos() {
+    let client = client().await;
+
+    let response = client.get("/cardapio/1").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    let produto: Value = response.into_json().await.unwrap();
+    assert_eq!(produto["alergenos"], json!(["Lactose"]));
+
+    let response = client
+        .get("/cardapio/1?sem_ingrediente=queijo%20cheddar")
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let produto: Value = response.into_json().await.unwrap();
+    assert_eq!(produto["alergenos"], json!([]));
+    assert_eq!(produto["ingredientes"], json!(["carne bovina", "alface"]));
+
+    let response = client.get("/cardapio/1?sem_ingrediente=picles").dispatch().await;
+    assert_eq!(response.status(), Status::BadRequest);
+    let erro: Value = response.into_json().await.unwrap();
+    assert_eq!(erro["detalhes"][0]["campo"], "sem_ingrediente");
+}
+
+#[rocket::async_test]
+async fn test_produto_fora_da_janela_de_venda_some_do_cardapio_e_dos_pedidos() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+    // O harness usa o fuso padrão da loja
+    let hoje = Utc::now().with_timezone(&chrono_tz::America::Sao_Paulo).weekday();
+
+    let so_amanha = json!({ "janelas_de_venda": [{ "dias": [dia_da_semana(hoje.succ())] }] });
+    let (status, produto) = patch(&client, "/produtos/2", &token, None, so_amanha).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(produto["janelas_de_venda"][0]["dias"][0], dia_da_semana(hoje.succ()));
+    assert!(!ids_no_cardapio(&client).await.contains(&2));
+    assert_eq!(client.get("/cardapio/2").dispatch().await.status(), Status::NotFound);
+
+    let (status, erro) = post(&client, "/pedidos", &token, json!({ "lanche_id": 2 })).await;
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(erro["detalhes"][0]["campo"], "lanche_id");
+    let (_, pedido) = post(&client, "/pedidos", &token, json!({ "lanche_id": 1 })).await;
+    let uri = format!("/pedidos/{}/produto/Lanche/2", pedido["id"]);
+    let (status, _) = put(&client, &uri, &token, None).await;
+    assert_eq!(status, Status::BadRequest);
+
+    let hoje_o_dia_todo = json!({ "janelas_de_venda": [{ "dias": [dia_da_semana(hoje)], "inicio": "00:00" }] });
+    let (status, _) = patch(&client, "/produtos/2", &token, None, hoje_o_dia_todo).await;
+    assert_eq!(status, Status::Ok);
+    assert!(ids_no_cardapio(&client).await.contains(&2));
+    let (status, _) = put(&client, &uri, &token, None).await;
+    assert_eq!(status, Status::Ok);
+}
+
+#[rocket::async_test]
+async fn test_janela_de_venda_invalida_e_recusada() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let virando_a_noite = json!({ "janelas_de_venda": [{ "inicio": "22:00", "fim": "02:00" }] });
+    let (status, erro) = patch(&client, "/produtos/2", &token, None, virando_a_noite).await;
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(erro["detalhes"][0]["campo"], "janelas_de_venda");
+}
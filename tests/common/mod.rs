@@ -0,0 +1,105 @@
+// Harness compartilhado pelos testes de integração: sobe a aplicação inteira com
+// os gateways em memória e pagamentos simulados, sem banco nem serviços externos
+#![allow(dead_code)]
+
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::asynchronous::Client;
+use serde_json::{json, Value};
+
+use tech_challenge::api::config::{Backend, Config, Env};
+use tech_challenge::api::dependencies::AppDependencies;
+use tech_challenge::api::server;
+
+pub const ADMIN_CPF: &str = "000.000.000-00";
+pub const ADMIN_SENHA: &str = "melhor_projeto";
+
+pub async fn dependencias() -> AppDependencies {
+    let config = Config {
+        env: Env::Test,
+        backend: Some(Backend::Memory),
+        mock_pagamentos_url: None,
+        ..Config::default()
+    };
+    AppDependencies::from_config(&config)
+        .await
+        .expect("dependências em memória")
+}
+
+pub async fn client() -> Client {
+    client_com(dependencias().await).await
+}
+
+pub async fn client_com(dependencias: AppDependencies) -> Client {
+    Client::tracked(server::build_rocket(dependencias))
+        .await
+        .expect("instância do rocket válida")
+}
+
+pub fn bearer(token: &str) -> Header<'static> {
+    Header::new("Authorization", format!("Bearer {}", token))
+}
+
+pub async fn login(client: &Client, cpf: &str, senha: &str) -> (Status, Value) {
+    let response = client
+        .post("/auth/login")
+        .header(ContentType::JSON)
+        .body(json!({ "cpf": cpf, "senha": senha }).to_string())
+        .dispatch()
+        .await;
+    let status = response.status();
+    (status, response.into_json().await.unwrap_or(Value::Null))
+}
+
+pub async fn token_admin(client: &Client) -> String {
+    let (status, body) = login(client, ADMIN_CPF, ADMIN_SENHA).await;
+    assert_eq!(status, Status::Ok);
+    body["token"].as_str().expect("token no login").to_string()
+}
+
+pub async fn get(client: &Client, uri: &str, token: &str) -> (Status, Value) {
+    let response = client.get(uri.to_string()).header(bearer(token)).dispatch().await;
+    let status = response.status();
+    (status, response.into_json().await.unwrap_or(Value::Null))
+}
+
+pub async fn post(client: &Client, uri: &str, token: &str, body: Value) -> (Status, Value) {
+    let response = client
+        .post(uri.to_string())
+        .header(ContentType::JSON)
+        .header(bearer(token))
+        .body(body.to_string())
+        .dispatch()
+        .await;
+    let status = response.status();
+    (status, response.into_json().await.unwrap_or(Value::Null))
+}
+
+pub async fn put(client: &Client, uri: &str, token: &str, body: Option<Value>) -> (Status, Value) {
+    let mut request = client.put(uri.to_string()).header(bearer(token));
+    if let Some(body) = body {
+        request = request.header(ContentType::JSON).body(body.to_string());
+    }
+    let response = request.dispatch().await;
+    let status = response.status();
+    (status, response.into_json().await.unwrap_or(Value::Null))
+}
+
+pub async fn patch(client: &Client, uri: &str, token: &str, if_match: Option<&str>, body: Value) -> (Status, Value) {
+    let mut request = client
+        .patch(uri.to_string())
+        .header(ContentType::JSON)
+        .header(bearer(token))
+        .body(body.to_string());
+    if let Some(etag) = if_match {
+        request = request.header(Header::new("If-Match", etag.to_string()));
+    }
+    let response = request.dispatch().await;
+    let status = response.status();
+    (status, response.into_json().await.unwrap_or(Value::Null))
+}
+
+pub async fn delete(client: &Client, uri: &str, token: &str) -> (Status, Value) {
+    let response = client.delete(uri.to_string()).header(bearer(token)).dispatch().await;
+    let status = response.status();
+    (status, response.into_json().await.unwrap_or(Value::Null))
+}
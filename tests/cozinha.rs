@@ -0,0 +1,134 @@
+mod common;
+
+use rocket::http::Status;
+use rocket::local::asynchronous::Client;
+use serde_json::{json, Value};
+
+use common::{client, get, patch, post, put, token_admin};
+
+async fn nova_estacao(client: &Client, token: &str, nome: &str) -> u64 {
+    let (status, estacao) = post(client, "/cozinha/estacoes", token, json!({ "nome": nome })).await;
+    assert_eq!(status, Status::Ok);
+    estacao["id"].as_u64().unwrap()
+}
+
+async fn pedido_pago(client: &Client, token: &str) -> u64 {
+    let combo = json!({ "cliente_id": 1, "lanche_id": 2, "acompanhamento_id": 5, "bebida_id": 4 });
+    let (_, pedido) = post(client, "/pedidos", token, combo).await;
+    let id = pedido["id"].as_u64().unwrap();
+    put(client, &format!("/pedidos/{}/status/Pago", id), token, None).await;
+    id
+}
+
+fn pedidos_na_fila(fila: &Value) -> Vec<u64> {
+    fila.as_array()
+        .unwrap()
+        .iter()
+        .map(|ticket| ticket["pedido_id"].as_u64().unwrap())
+        .collect()
+}
+
+// Lanche na chapa, acompanhamento na fritadeira e bebida nas bebidas
+async fn cozinha_com_tres_estacoes(client: &Client, token: &str) -> [u64; 3] {
+    let estacoes = [
+        nova_estacao(client, token, "Chapa").await,
+        nova_estacao(client, token, "Fritadeira").await,
+        nova_estacao(client, token, "Bebidas").await,
+    ];
+    for (produto_id, estacao_id) in [2, 5, 4].into_iter().zip(estacoes) {
+        let uri = format!("/produtos/{}", produto_id);
+        let (status, _) = patch(client, &uri, token, None, json!({ "estacao_id": estacao_id })).await;
+        assert_eq!(status, Status::Ok);
+    }
+    estacoes
+}
+
+#[rocket::async_test]
+async fn test_pedido_vira_um_ticket_por_estacao() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+    let [chapa, fritadeira, _] = cozinha_com_tres_estacoes(&client, &token).await;
+    let pedido_id = pedido_pago(&client, &token).await;
+
+    let (status, fila) = get(&client, &format!("/cozinha/estacoes/{}/fila", chapa), &token).await;
+    assert_eq!(status, Status::Ok);
+    assert!(pedidos_na_fila(&fila).contains(&pedido_id));
+    let ticket = fila.as_array().unwrap().iter().find(|ticket| ticket["pedido_id"] == pedido_id).unwrap();
+    assert_eq!(ticket["status"], "Recebido");
+    assert_eq!(ticket["itens"].as_array().unwrap().len(), 1);
+    assert_eq!(ticket["itens"][0]["produto_id"], 2);
+
+    let (_, pedido) = get(&client, &format!("/pedidos/{}", pedido_id), &token).await;
+    assert_eq!(pedido["tickets"].as_array().unwrap().len(), 3);
+
+    let uri = format!("/cozinha/estacoes/{}/pedidos/{}/status/EmPreparacao", fritadeira, pedido_id);
+    let (status, pedido) = put(&client, &uri, &token, None).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(pedido["status"], "EmPreparacao");
+}
+
+#[rocket::async_test]
+async fn test_pedido_fica_pronto_quando_todas_as_estacoes_terminam() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+    let estacoes = cozinha_com_tres_estacoes(&client, &token).await;
+    let pedido_id = pedido_pago(&client, &token).await;
+
+    for (i, estacao_id) in estacoes.iter().enumerate() {
+        let uri = format!("/cozinha/estacoes/{}/pedidos/{}/status/Pronto", estacao_id, pedido_id);
+        let (status, pedido) = put(&client, &uri, &token, None).await;
+        assert_eq!(status, Status::Ok);
+        let esperado = if i == estacoes.len() - 1 { "Pronto" } else { "Pago" };
+        assert_eq!(pedido["status"], esperado);
+
+        let (_, fila) = get(&client, &format!("/cozinha/estacoes/{}/fila", estacao_id), &token).await;
+        assert!(!pedidos_na_fila(&fila).contains(&pedido_id));
+    }
+}
+
+#[rocket::async_test]
+async fn test_ticket_de_estacao_sem_itens_do_pedido() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+    let pedido_id = pedido_pago(&client, &token).await;
+    let estacao_id = nova_estacao(&client, &token, "Sobremesas").await;
+
+    let uri = format!("/cozinha/estacoes/{}/pedidos/{}/status/Pronto", estacao_id, pedido_id);
+    let (status, _) = put(&client, &uri, &token, None).await;
+    assert_eq!(status, Status::NotFound);
+
+    let uri = format!("/cozinha/estacoes/{}/pedidos/{}/status/Voando", estacao_id, pedido_id);
+    let (status, _) = put(&client, &uri, &token, None).await;
+    assert_eq!(status, Status::BadRequest);
+}
+
+#[rocket::async_test]
+async fn test_produto_sai_da_estacao_com_null() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+    let estacao_id = nova_estacao(&client, &token, "Chapa").await;
+
+    let (status, produto) = patch(&client, "/produtos/2", &token, None, json!({ "estacao_id": estacao_id })).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(produto["estacao_id"], estacao_id);
+
+    let (status, produto) = patch(&client, "/produtos/2", &token, None, json!({ "nome": "X-Tudo" })).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(produto["estacao_id"], estacao_id);
+
+    let (status, produto) = patch(&client, "/produtos/2", &token, None, json!({ "estacao_id": null })).await;
+    assert_eq!(status, Status::Ok);
+    assert!(produto["estacao_id"].is_null());
+}
+
+#[rocket::async_test]
+async fn test_fila_de_estacao_inexistente() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let (status, _) = get(&client, "/cozinha/estacoes/999/fila", &token).await;
+    assert_eq!(status, Status::NotFound);
+
+    let (status, _) = patch(&client, "/produtos/2", &token, None, json!({ "estacao_id": 999 })).await;
+    assert_eq!(status, Status::BadRequest);
+}
@@ -0,0 +1,81 @@
+mod common;
+
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::Arc;
+
+use rocket::async_trait;
+use rocket::http::Status;
+use serde_json::{json, Value};
+
+use tech_challenge::base::domain_error::DomainError;
+use tech_challenge::entities::pagamento::Pagamento;
+use tech_challenge::traits::health_check_adapter::HealthCheckAdapter;
+use tech_challenge::traits::pagamento_webhook_adapter::PagamentoWebhookAdapter;
+
+use common::{client_com, dependencias, get, post, token_admin};
+
+#[derive(Default)]
+struct PagamentoExterno {
+    chamadas: AtomicUsize,
+}
+
+#[async_trait]
+impl PagamentoWebhookAdapter for PagamentoExterno {
+    fn processa_webhook(&self, _data: Value, pagamento: Pagamento) -> Pagamento {
+        pagamento
+    }
+
+    async fn set_webhook_pagamento(&self, mut pagamento: Pagamento) -> Result<Pagamento, DomainError> {
+        let chamada = self.chamadas.fetch_add(1, Ordering::SeqCst) + 1;
+        pagamento.set_referencia(format!("externo-{}", chamada));
+        Ok(pagamento)
+    }
+}
+
+struct DependenciaFora;
+
+#[async_trait]
+impl HealthCheckAdapter for DependenciaFora {
+    fn nome(&self) -> String {
+        "fila".to_string()
+    }
+
+    fn critico(&self) -> bool {
+        true
+    }
+
+    async fn verificar(&self) -> Result<(), String> {
+        Err("sem conexão".to_string())
+    }
+}
+
+#[rocket::async_test]
+async fn test_adapter_de_pagamento_fornecido_pelo_usuario() {
+    let adapter = Arc::new(PagamentoExterno::default());
+    let mut dependencias = dependencias().await;
+    dependencias
+        .metodos_pagamento
+        .insert("Mercado Pago".to_string(), adapter.clone());
+    let client = client_com(dependencias).await;
+    let token = token_admin(&client).await;
+
+    let (status, pedido) = post(&client, "/pedidos", &token, json!({ "lanche_id": 1 })).await;
+    assert_eq!(status, Status::Ok);
+
+    let (_, pagamento) = get(&client, &format!("/pedidos/{}/pagamento", pedido["id"]), &token).await;
+    assert_eq!(pagamento["referencia"], "externo-1");
+    assert_eq!(adapter.chamadas.load(Ordering::SeqCst), 1);
+}
+
+#[rocket::async_test]
+async fn test_health_check_fornecido_pelo_usuario() {
+    let mut dependencias = dependencias().await;
+    dependencias.health_checks.push(Arc::new(DependenciaFora));
+    let client = client_com(dependencias).await;
+
+    let response = client.get("/health/ready").dispatch().await;
+
+    assert_eq!(response.status(), Status::ServiceUnavailable);
+    let relatorio: Value = response.into_json().await.unwrap();
+    assert_eq!(relatorio["dependencias"][0]["nome"], "fila");
+}
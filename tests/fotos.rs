@@ -0,0 +1,112 @@
+mod common;
+
+use std::io::Cursor;
+
+use image::{DynamicImage, ImageFormat, RgbImage};
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::asynchronous::{Client, LocalResponse};
+use serde_json::Value;
+
+use common::{bearer, client, token_admin};
+
+const BOUNDARY: &str = "----fronteira-do-teste";
+
+fn png(largura: u32, altura: u32) -> Vec<u8> {
+    let mut conteudo = Vec::new();
+    DynamicImage::ImageRgb8(RgbImage::new(largura, altura))
+        .write_to(&mut Cursor::new(&mut conteudo), ImageFormat::Png)
+        .unwrap();
+    conteudo
+}
+
+fn multipart(content_type: &str, conteudo: &[u8]) -> Vec<u8> {
+    let mut corpo = format!(
+        "--{}\r\nContent-Disposition: form-data; name=\"foto\"; filename=\"foto\"\r\nContent-Type: {}\r\n\r\n",
+        BOUNDARY, content_type
+    )
+    .into_bytes();
+    corpo.extend_from_slice(conteudo);
+    corpo.extend_from_slice(format!("\r\n--{}--\r\n", BOUNDARY).as_bytes());
+    corpo
+}
+
+async fn enviar<'c>(client: &'c Client, token: &str, content_type: &str, conteudo: &[u8]) -> LocalResponse<'c> {
+    client
+        .post("/produtos/1/foto")
+        .header(ContentType::new("multipart", "form-data").with_params(("boundary", BOUNDARY)))
+        .header(bearer(token))
+        .body(multipart(content_type, conteudo))
+        .dispatch()
+        .await
+}
+
+#[rocket::async_test]
+async fn test_envia_e_serve_a_foto() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+    let foto = png(600, 300);
+
+    let response = enviar(&client, &token, "image/png", &foto).await;
+    assert_eq!(response.status(), Status::Ok);
+    let produto: Value = response.into_json().await.unwrap();
+    assert!(produto["foto"].as_str().unwrap().starts_with("produtos/1/"));
+
+    // Servida sem autenticação, para uso direto no cardápio
+    let response = client.get("/produtos/1/foto").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.content_type(), Some(ContentType::PNG));
+    assert!(response.headers().get_one("Cache-Control").unwrap().contains("max-age"));
+    let etag = response.headers().get_one("ETag").unwrap().to_string();
+    assert_eq!(response.into_bytes().await.unwrap(), foto);
+
+    let response = client
+        .get("/produtos/1/foto")
+        .header(Header::new("If-None-Match", etag))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::NotModified);
+
+    let response = client.get("/produtos/1/foto?miniatura=true").dispatch().await;
+    assert_eq!(response.status(), Status::Ok);
+    let miniatura = image::load_from_memory(&response.into_bytes().await.unwrap()).unwrap();
+    assert_eq!((miniatura.width(), miniatura.height()), (256, 128));
+}
+
+#[rocket::async_test]
+async fn test_recusa_arquivo_que_nao_e_imagem() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let response = enviar(&client, &token, "text/plain", b"nao sou uma imagem").await;
+    assert_eq!(response.status(), Status::BadRequest);
+    let erro: Value = response.into_json().await.unwrap();
+    assert_eq!(erro["detalhes"][0]["campo"], "foto");
+
+    let response = enviar(&client, &token, "image/png", b"nao sou uma imagem").await;
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[rocket::async_test]
+async fn test_envio_sem_token_nao_grava_a_foto() {
+    let client = client().await;
+
+    let response = client
+        .post("/produtos/1/foto")
+        .header(ContentType::new("multipart", "form-data").with_params(("boundary", BOUNDARY)))
+        .body(multipart("image/png", &png(10, 10)))
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::BadRequest);
+    let response = client.get("/produtos/1/foto").dispatch().await;
+    assert_eq!(response.status(), Status::NotFound);
+}
+
+#[rocket::async_test]
+async fn test_produto_sem_foto_enviada_retorna_404() {
+    let client = client().await;
+
+    let response = client.get("/produtos/2/foto").dispatch().await;
+
+    assert_eq!(response.status(), Status::NotFound);
+}
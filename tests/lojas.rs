@@ -0,0 +1,206 @@
+mod common;
+
+use rocket::http::{ContentType, Header, Status};
+use rocket::local::asynchronous::Client;
+use serde_json::{json, Value};
+
+use common::{bearer, client, get, login, post, token_admin};
+
+fn x_loja(loja: u64) -> Header<'static> {
+    Header::new("X-Loja", loja.to_string())
+}
+
+async fn get_na_loja(client: &Client, uri: &str, token: &str, loja: u64) -> (Status, Value) {
+    let response = client
+        .get(uri.to_string())
+        .header(bearer(token))
+        .header(x_loja(loja))
+        .dispatch()
+        .await;
+    let status = response.status();
+    (status, response.into_json().await.unwrap_or(Value::Null))
+}
+
+async fn post_na_loja(client: &Client, uri: &str, token: &str, loja: u64, body: Value) -> (Status, Value) {
+    let response = client
+        .post(uri.to_string())
+        .header(ContentType::JSON)
+        .header(bearer(token))
+        .header(x_loja(loja))
+        .body(body.to_string())
+        .dispatch()
+        .await;
+    let status = response.status();
+    (status, response.into_json().await.unwrap_or(Value::Null))
+}
+
+// Cria a loja 2 e devolve um token do admin que já a inclui
+async fn admin_com_segunda_loja(client: &Client) -> (String, u64) {
+    let token = token_admin(client).await;
+    let (status, loja) = post(client, "/lojas", &token, json!({ "nome": "Filial" })).await;
+    assert_eq!(status, Status::Ok);
+    (token_admin(client).await, loja["id"].as_u64().unwrap())
+}
+
+fn lanche(nome: &str) -> Value {
+    json!({
+        "nome": nome,
+        "foto": "lanche.jpg",
+        "descricao": "Lanche da casa",
+        "categoria": "Lanche",
+        "preco": 25.0,
+        "ingredientes": ["pão", "carne"]
+    })
+}
+
+#[rocket::async_test]
+async fn test_loja_criada_entra_no_token_depois_do_login() {
+    let client = client().await;
+    let token_antigo = token_admin(&client).await;
+
+    let (token, loja_id) = admin_com_segunda_loja(&client).await;
+
+    let (_, lojas) = get(&client, "/lojas", &token_antigo).await;
+    assert_eq!(lojas.as_array().unwrap().len(), 1);
+    let (status, lojas) = get(&client, "/lojas", &token).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(lojas.as_array().unwrap().len(), 2);
+
+    // O token antigo ainda não dá acesso à loja nova
+    let (status, _) = get_na_loja(&client, "/produtos", &token_antigo, loja_id).await;
+    assert_eq!(status, Status::Forbidden);
+}
+
+#[rocket::async_test]
+async fn test_produtos_ficam_isolados_por_loja() {
+    let client = client().await;
+    let (token, loja_id) = admin_com_segunda_loja(&client).await;
+
+    let (status, produto) = post_na_loja(&client, "/produtos", &token, loja_id, lanche("X-Filial")).await;
+    assert_eq!(status, Status::Ok);
+    let uri = format!("/produtos/{}", produto["id"]);
+
+    let (_, da_filial) = get_na_loja(&client, "/produtos", &token, loja_id).await;
+    assert_eq!(da_filial.as_array().unwrap().len(), 1);
+    let (_, da_padrao) = get(&client, "/produtos", &token).await;
+    assert_eq!(da_padrao.as_array().unwrap().len(), 6);
+
+    let (status, _) = get(&client, &uri, &token).await;
+    assert_eq!(status, Status::NotFound);
+    let (status, _) = get_na_loja(&client, &uri, &token, loja_id).await;
+    assert_eq!(status, Status::Ok);
+}
+
+#[rocket::async_test]
+async fn test_pedido_usa_so_produtos_da_loja_e_webhook_informa_a_loja() {
+    let client = client().await;
+    let (token, loja_id) = admin_com_segunda_loja(&client).await;
+
+    // Produto 2 é o cheeseburguer da loja padrão
+    let (status, _) = post_na_loja(&client, "/pedidos", &token, loja_id, json!({ "lanche_id": 2 })).await;
+    assert_eq!(status, Status::NotFound);
+
+    let (_, produto) = post_na_loja(&client, "/produtos", &token, loja_id, lanche("X-Filial")).await;
+    let (status, pedido) =
+        post_na_loja(&client, "/pedidos", &token, loja_id, json!({ "lanche_id": produto["id"] })).await;
+    assert_eq!(status, Status::Ok);
+    let id = pedido["id"].as_u64().unwrap();
+
+    let (_, da_padrao) = get(&client, "/pedidos", &token).await;
+    assert!(da_padrao.as_array().unwrap().iter().all(|pedido| pedido["id"] != id));
+
+    let webhook = json!({ "payment_status": "success", "payment_code": "pag-123" }).to_string();
+    let response = client
+        .post(format!("/pedidos/{}/webhook/pagamento", id))
+        .header(ContentType::JSON)
+        .body(webhook.clone())
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::NotFound);
+    let response = client
+        .post(format!("/pedidos/{}/webhook/pagamento?loja={}", id, loja_id))
+        .header(ContentType::JSON)
+        .body(webhook)
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+}
+
+#[rocket::async_test]
+async fn test_cozinha_nao_acessa_loja_fora_do_token() {
+    let client = client().await;
+    let (token, loja_id) = admin_com_segunda_loja(&client).await;
+    let cozinha = json!({
+        "nome": "Cozinheiro",
+        "email": "cozinha@fiap.com.br",
+        "senha": "panela",
+        "cpf": "123.456.789-09",
+        "tipo": "Cozinha",
+        "status": "Ativo"
+    });
+    let (status, usuario) = post(&client, "/usuarios", &token, cozinha).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(usuario["lojas"], json!([1]));
+
+    let (status, body) = login(&client, "123.456.789-09", "panela").await;
+    assert_eq!(status, Status::Ok);
+    let token_cozinha = body["token"].as_str().unwrap();
+
+    let (status, _) = get(&client, "/pedidos", token_cozinha).await;
+    assert_eq!(status, Status::Ok);
+    let (status, _) = get_na_loja(&client, "/pedidos", token_cozinha, loja_id).await;
+    assert_eq!(status, Status::Forbidden);
+    // Usuários da loja padrão não aparecem na lista da filial
+    let (_, usuarios) = get_na_loja(&client, "/usuarios", &token, loja_id).await;
+    assert!(usuarios.as_array().unwrap().iter().all(|usuario| usuario["cpf"] != "123.456.789-09"));
+}
+
+#[rocket::async_test]
+async fn test_admin_nao_da_acesso_a_loja_que_nao_gerencia() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+    let cozinha = json!({
+        "nome": "Cozinheiro",
+        "email": "cozinha@fiap.com.br",
+        "senha": "panela",
+        "cpf": "123.456.789-09",
+        "tipo": "Cozinha",
+        "status": "Ativo",
+        "lojas": [1, 2]
+    });
+
+    let (status, _) = post(&client, "/usuarios", &token, cozinha).await;
+
+    assert_eq!(status, Status::Forbidden);
+}
+
+#[rocket::async_test]
+async fn test_loja_inexistente_ou_invalida_retorna_400() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let (status, _) = get_na_loja(&client, "/produtos", &token, 99).await;
+    assert_eq!(status, Status::BadRequest);
+    let response = client
+        .get("/produtos")
+        .header(bearer(&token))
+        .header(Header::new("X-Loja", "filial"))
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::BadRequest);
+}
+
+#[rocket::async_test]
+async fn test_cardapio_publico_por_loja() {
+    let client = client().await;
+    let (token, loja_id) = admin_com_segunda_loja(&client).await;
+    post_na_loja(&client, "/produtos", &token, loja_id, lanche("X-Filial")).await;
+
+    let response = client.get(format!("/cardapio?loja={}", loja_id)).dispatch().await;
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one("Vary"), Some("X-Loja"));
+    let cardapio: Value = response.into_json().await.unwrap();
+    assert!(cardapio.to_string().contains("X-Filial"));
+    assert!(!cardapio.to_string().contains("Cheeseburguer"));
+}
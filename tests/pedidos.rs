@@ -0,0 +1,233 @@
+mod common;
+
+use rocket::http::{ContentType, Status};
+use serde_json::{json, Value};
+
+use std::time::Duration;
+
+use common::{client, client_com, dependencias, get, post, put, token_admin};
+
+fn combo_completo() -> Value {
+    json!({
+        "cliente_id": 1,
+        "lanche_id": 2,
+        "acompanhamento_id": 5,
+        "bebida_id": 4
+    })
+}
+
+#[rocket::async_test]
+async fn test_novo_pedido_cria_pagamento_pendente() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let (status, pedido) = post(&client, "/pedidos", &token, combo_completo()).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(pedido["status"], "Pendente");
+    assert_eq!(pedido["cliente"]["id"], 1);
+    assert_eq!(pedido["lanche"]["nome"], "Cheeseburguer");
+
+    let uri = format!("/pedidos/{}/pagamento", pedido["id"]);
+    let (status, pagamento) = get(&client, &uri, &token).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(pagamento["estado"], "pendente");
+    assert_eq!(pagamento["id_pedido"], pedido["id"]);
+    assert!(pagamento["referencia"].as_str().unwrap().starts_with("simulado-"));
+}
+
+#[rocket::async_test]
+async fn test_novo_pedido_com_produto_inexistente_nao_grava_nada() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+    let (_, antes) = get(&client, "/pedidos", &token).await;
+
+    let (status, _) = post(&client, "/pedidos", &token, json!({ "lanche_id": 999 })).await;
+
+    assert_eq!(status, Status::NotFound);
+    let (_, depois) = get(&client, "/pedidos", &token).await;
+    assert_eq!(antes.as_array().unwrap().len(), depois.as_array().unwrap().len());
+}
+
+#[rocket::async_test]
+async fn test_webhook_aprova_pagamento_e_marca_pedido_como_pago() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+    let (_, pedido) = post(&client, "/pedidos", &token, combo_completo()).await;
+    let id = pedido["id"].as_u64().unwrap();
+
+    // O webhook é chamado pelo provedor de pagamento, sem autenticação
+    let response = client
+        .post(format!("/pedidos/{}/webhook/pagamento", id))
+        .header(ContentType::JSON)
+        .body(json!({ "payment_status": "success", "payment_code": "pag-123" }).to_string())
+        .dispatch()
+        .await;
+    assert_eq!(response.status(), Status::Ok);
+    let pagamento: Value = response.into_json().await.unwrap();
+    assert_eq!(pagamento["estado"], "aprovado");
+    assert_eq!(pagamento["referencia"], "pag-123");
+
+    let (_, pedido) = get(&client, &format!("/pedidos/{}", id), &token).await;
+    assert_eq!(pedido["status"], "Pago");
+}
+
+#[rocket::async_test]
+async fn test_progressao_de_status_do_pedido() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+    let (_, pedido) = post(&client, "/pedidos", &token, combo_completo()).await;
+    let id = pedido["id"].as_u64().unwrap();
+
+    // Sem pagamento o pedido ainda não chegou à cozinha
+    let (_, novos) = get(&client, "/pedidos/novos", &token).await;
+    assert!(novos.as_array().unwrap().iter().all(|pedido| pedido["id"] != id));
+
+    put(&client, &format!("/pedidos/{}/status/Pago", id), &token, None).await;
+    let (_, novos) = get(&client, "/pedidos/novos", &token).await;
+    assert!(novos.as_array().unwrap().iter().any(|pedido| pedido["id"] == id));
+
+    for status in ["EmPreparacao", "Pronto", "Finalizado"] {
+        let (resposta, pedido) = put(&client, &format!("/pedidos/{}/status/{}", id, status), &token, None).await;
+        assert_eq!(resposta, Status::Ok);
+        assert_eq!(pedido["status"], status);
+    }
+
+    let (_, novos) = get(&client, "/pedidos/novos", &token).await;
+    assert!(novos.as_array().unwrap().iter().all(|pedido| pedido["id"] != id));
+}
+
+#[rocket::async_test]
+async fn test_tempos_de_preparo_do_pedido() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+    let (_, pedido) = post(&client, "/pedidos", &token, combo_completo()).await;
+    let id = pedido["id"].as_u64().unwrap();
+    assert!(pedido["previsao_de_pronto"].is_string());
+
+    for status in ["EmPreparacao", "Pronto"] {
+        put(&client, &format!("/pedidos/{}/status/{}", id, status), &token, None).await;
+    }
+
+    let (_, pedido) = get(&client, &format!("/pedidos/{}", id), &token).await;
+    let historico: Vec<&str> = pedido["historico_de_status"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|mudanca| mudanca["status"].as_str().unwrap())
+        .collect();
+    assert_eq!(historico, ["Pendente", "EmPreparacao", "Pronto"]);
+    assert!(pedido["tempo_de_espera_segundos"].is_i64());
+    assert!(pedido["tempo_de_preparo_segundos"].is_i64());
+    assert!(pedido["previsao_de_pronto"].is_null());
+
+    let (status, tempos) = get(&client, "/pedidos/tempos-de-preparo", &token).await;
+    assert_eq!(status, Status::Ok);
+    let lanche = tempos.as_array().unwrap().iter().find(|tempo| tempo["produto_id"] == 2).unwrap();
+    assert!(lanche["pedidos"].as_u64().unwrap() >= 1);
+
+    let (status, _) = get(&client, "/pedidos/tempos-de-preparo?dias=0", &token).await;
+    assert_eq!(status, Status::BadRequest);
+}
+
+#[rocket::async_test]
+async fn test_pedido_acima_do_sla_aparece_atrasado() {
+    let mut dependencias = dependencias().await;
+    dependencias.sla_cozinha = Duration::ZERO;
+    let client = client_com(dependencias).await;
+    let token = token_admin(&client).await;
+    let (_, pedido) = post(&client, "/pedidos", &token, combo_completo()).await;
+    let id = pedido["id"].as_u64().unwrap();
+    put(&client, &format!("/pedidos/{}/status/Pago", id), &token, None).await;
+
+    let (_, novos) = get(&client, "/pedidos/novos", &token).await;
+    let pedido = novos.as_array().unwrap().iter().find(|pedido| pedido["id"] == id).unwrap();
+
+    assert_eq!(pedido["atrasado"], true);
+}
+
+#[rocket::async_test]
+async fn test_fila_da_cozinha_por_status_e_chegada() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+    let mut ids = Vec::new();
+    for _ in 0..3 {
+        let (_, pedido) = post(&client, "/pedidos", &token, combo_completo()).await;
+        let id = pedido["id"].as_u64().unwrap();
+        put(&client, &format!("/pedidos/{}/status/Pago", id), &token, None).await;
+        ids.push(id);
+    }
+    put(&client, &format!("/pedidos/{}/status/EmPreparacao", ids[2]), &token, None).await;
+
+    let (_, novos) = get(&client, "/pedidos/novos", &token).await;
+    let fila: Vec<u64> = novos
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|pedido| pedido["id"].as_u64().unwrap())
+        .collect();
+
+    assert_eq!(fila, vec![ids[2], ids[0], ids[1]]);
+}
+
+#[rocket::async_test]
+async fn test_status_invalido_e_recusado() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let (status, _) = put(&client, "/pedidos/1/status/Voando", &token, None).await;
+
+    assert_eq!(status, Status::BadRequest);
+}
+
+#[rocket::async_test]
+async fn test_busca_pedido_por_id() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let (status, pedido) = get(&client, "/pedidos/1", &token).await;
+
+    assert_eq!(status, Status::Ok);
+    assert_eq!(pedido["id"], 1);
+    assert_eq!(pedido["cliente"]["nome"], "Fulano da Silva");
+}
+
+#[rocket::async_test]
+async fn test_pedido_inexistente_retorna_404() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let (status, body) = get(&client, "/pedidos/999", &token).await;
+
+    assert_eq!(status, Status::NotFound);
+    assert_eq!(body["status"], 404);
+}
+
+#[rocket::async_test]
+async fn test_produto_de_outra_categoria_aponta_o_campo() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    // Produto 4 é um refrigerante
+    let (status, body) = put(&client, "/pedidos/1/produto/Lanche/4", &token, None).await;
+
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body["codigo"], "invalido");
+    assert_eq!(body["detalhes"][0]["campo"], "produto_id");
+}
+
+#[rocket::async_test]
+async fn test_documentacao_openapi_dos_pedidos() {
+    let client = client().await;
+
+    let response = client.get("/pedidos/openapi.json").dispatch().await;
+
+    assert_eq!(response.status(), Status::Ok);
+    let documento: Value = response.into_json().await.unwrap();
+    let parametros = documento["paths"]["/{id}"]["get"]["parameters"].as_array().unwrap();
+    // O controller só expõe a loja; o resto do que ele injeta não é entrada da requisição
+    assert_eq!(parametros.len(), 2);
+    assert!(parametros.iter().any(|parametro| parametro["name"] == "id"));
+    assert!(parametros
+        .iter()
+        .any(|parametro| parametro["name"] == "X-Loja" && parametro["in"] == "header"));
+}
@@ -0,0 +1,268 @@
+mod common;
+
+use rocket::http::{ContentType, Header, Status};
+use serde_json::{json, Value};
+
+use common::{bearer, client, delete, get, patch, post, put, token_admin};
+
+fn produto_input(nome: &str, preco: f64) -> Value {
+    json!({
+        "nome": nome,
+        "foto": "milkshake.jpg",
+        "descricao": "Milkshake de chocolate",
+        "categoria": "Sobremesa",
+        "preco": preco,
+        "ingredientes": ["leite", "sorvete", "chocolate"]
+    })
+}
+
+#[rocket::async_test]
+async fn test_lista_produtos_de_demonstracao() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let (status, body) = get(&client, "/produtos", &token).await;
+
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body.as_array().unwrap().len(), 6);
+}
+
+#[rocket::async_test]
+async fn test_crud_de_produto() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let (status, criado) = post(&client, "/produtos", &token, produto_input("Milkshake", 14.5)).await;
+    assert_eq!(status, Status::Ok);
+    let id = criado["id"].as_u64().unwrap();
+    assert_eq!(criado["nome"], "Milkshake");
+
+    let uri = format!("/produtos/{}", id);
+    let (status, encontrado) = get(&client, &uri, &token).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(encontrado["categoria"], "Sobremesa");
+
+    let (status, atualizado) = put(&client, &uri, &token, Some(produto_input("Milkshake Grande", 18.0))).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(atualizado["nome"], "Milkshake Grande");
+    let (_, encontrado) = get(&client, &uri, &token).await;
+    assert_eq!(encontrado["preco"], 18.0);
+
+    let (status, _) = delete(&client, &uri, &token).await;
+    assert_eq!(status, Status::Ok);
+    let (status, arquivado) = get(&client, &uri, &token).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(arquivado["arquivado"], true);
+}
+
+#[rocket::async_test]
+async fn test_produto_inexistente_retorna_404() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let (status, body) = get(&client, "/produtos/999", &token).await;
+
+    assert_eq!(status, Status::NotFound);
+    assert_eq!(body["status"], 404);
+    assert_eq!(body["codigo"], "nao_encontrado");
+    assert_eq!(body["msg"], "Produto 999 não encontrado");
+}
+
+#[rocket::async_test]
+async fn test_corpo_com_tipo_errado_aponta_o_campo() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+    let mut input = produto_input("Milkshake", 14.5);
+    input["preco"] = json!("quatorze");
+
+    let (status, body) = post(&client, "/produtos", &token, input).await;
+
+    assert_eq!(status, Status::UnprocessableEntity);
+    assert_eq!(body["codigo"], "entrada_invalida");
+    assert_eq!(body["status"], 422);
+    assert_eq!(body["detalhes"][0]["campo"], "preco");
+}
+
+#[rocket::async_test]
+async fn test_json_malformado_retorna_400() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let response = client
+        .post("/produtos")
+        .header(ContentType::JSON)
+        .header(bearer(&token))
+        .body("{\"nome\": ")
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::BadRequest);
+    let body: Value = response.into_json().await.unwrap();
+    assert_eq!(body["codigo"], "requisicao_invalida");
+    assert_eq!(body["status"], 400);
+}
+
+#[rocket::async_test]
+async fn test_documentacao_openapi_lista_erros() {
+    let client = client().await;
+
+    let response = client.get("/produtos/openapi.json").dispatch().await;
+
+    let documento: Value = response.into_json().await.unwrap();
+    let respostas = &documento["paths"]["/"]["post"]["responses"];
+    assert!(respostas["422"].is_object());
+    assert!(respostas["404"].is_object());
+    assert!(documento["components"]["schemas"]["ErrorResponse"].is_object());
+}
+
+#[rocket::async_test]
+async fn test_patch_altera_so_os_campos_enviados() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let response = client.get("/produtos/6").header(bearer(&token)).dispatch().await;
+    let etag = response.headers().get_one("ETag").unwrap().to_string();
+    assert_eq!(etag, "\"1\"");
+
+    let (status, body) = patch(&client, "/produtos/6", &token, Some(&etag), json!({ "preco": 8.5 })).await;
+
+    assert_eq!(status, Status::Ok);
+    assert_eq!(body["preco"], 8.5);
+    assert_eq!(body["nome"], "Sorvete");
+    assert_eq!(body["versao"], 2);
+}
+
+#[rocket::async_test]
+async fn test_patch_com_etag_antigo_falha() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+    let (status, _) = patch(&client, "/produtos/1", &token, Some("\"1\""), json!({ "preco": 11.5 })).await;
+    assert_eq!(status, Status::Ok);
+
+    // Outro admin ainda com a versão 1
+    let (status, body) = patch(&client, "/produtos/1", &token, Some("\"1\""), json!({ "nome": "X-Burguer" })).await;
+
+    assert_eq!(status, Status::PreconditionFailed);
+    assert_eq!(body["codigo"], "versao_desatualizada");
+    let (_, atual) = get(&client, "/produtos/1", &token).await;
+    assert_eq!(atual["nome"], "Hamburguer");
+    assert_eq!(atual["preco"], 11.5);
+}
+
+#[rocket::async_test]
+async fn test_patch_valida_o_produto() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let (status, body) = patch(&client, "/produtos/1", &token, None, json!({ "preco": -3.0 })).await;
+
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body["detalhes"][0]["campo"], "preco");
+}
+
+#[rocket::async_test]
+async fn test_put_devolve_nova_etag() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let response = client
+        .put("/produtos/2")
+        .header(ContentType::JSON)
+        .header(bearer(&token))
+        .header(Header::new("If-Match", "\"1\""))
+        .body(produto_input("Cheeseburguer Duplo", 16.0).to_string())
+        .dispatch()
+        .await;
+
+    assert_eq!(response.status(), Status::Ok);
+    assert_eq!(response.headers().get_one("ETag"), Some("\"2\""));
+}
+
+#[rocket::async_test]
+async fn test_produto_arquivado_sai_do_cardapio_e_pode_ser_restaurado() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+    let ids = |produtos: &Value| -> Vec<u64> {
+        produtos.as_array().unwrap().iter().map(|produto| produto["id"].as_u64().unwrap()).collect()
+    };
+
+    let (status, _) = delete(&client, "/produtos/4", &token).await;
+    assert_eq!(status, Status::Ok);
+
+    let (_, produtos) = get(&client, "/produtos", &token).await;
+    assert!(!ids(&produtos).contains(&4));
+    let (status, pedido) = post(&client, "/pedidos", &token, json!({ "bebida_id": 4 })).await;
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(pedido["detalhes"][0]["campo"], "bebida_id");
+
+    let (status, restaurado) = post(&client, "/produtos/4/restaurar", &token, json!({})).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(restaurado["arquivado"], false);
+    let (_, produtos) = get(&client, "/produtos", &token).await;
+    assert!(ids(&produtos).contains(&4));
+}
+
+#[rocket::async_test]
+async fn test_pedido_antigo_mantem_o_produto_arquivado() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let (_, pedido) = post(&client, "/pedidos", &token, json!({ "lanche_id": 3 })).await;
+    delete(&client, "/produtos/3", &token).await;
+
+    let (status, pedido) = get(&client, &format!("/pedidos/{}", pedido["id"]), &token).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(pedido["lanche"]["id"], 3);
+}
+
+#[rocket::async_test]
+async fn test_historico_de_precos() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    patch(&client, "/produtos/5", &token, None, json!({ "preco": 6.5 })).await;
+    patch(&client, "/produtos/5", &token, None, json!({ "nome": "Batata Frita Média" })).await;
+
+    let (status, precos) = get(&client, "/produtos/5/precos", &token).await;
+    assert_eq!(status, Status::Ok);
+    let precos: Vec<f64> = precos.as_array().unwrap().iter().map(|preco| preco["preco"].as_f64().unwrap()).collect();
+    assert_eq!(precos, vec![5.99, 6.5]);
+}
+
+#[rocket::async_test]
+async fn test_produto_com_alergenos_e_informacao_nutricional() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let mut input = produto_input("Milkshake", 14.5);
+    input["alergenos_por_ingrediente"] = json!({ "leite": ["Lactose"], "chocolate": ["Lactose", "Soja"] });
+    input["informacao_nutricional"] = json!({ "calorias": 480.0, "proteinas": 9.5 });
+    let (status, criado) = post(&client, "/produtos", &token, input).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(criado["alergenos"], json!(["Lactose", "Soja"]));
+    assert_eq!(criado["informacao_nutricional"]["calorias"], 480.0);
+
+    let uri = format!("/produtos/{}", criado["id"]);
+    let (status, atualizado) = patch(&client, &uri, &token, None, json!({ "ingredientes": ["leite", "sorvete"] })).await;
+    assert_eq!(status, Status::Ok);
+    assert_eq!(atualizado["alergenos"], json!(["Lactose"]));
+    assert_eq!(atualizado["alergenos_por_ingrediente"], json!({ "leite": ["Lactose"] }));
+}
+
+#[rocket::async_test]
+async fn test_alergeno_de_ingrediente_desconhecido_e_recusado() {
+    let client = client().await;
+    let token = token_admin(&client).await;
+
+    let mut input = produto_input("Milkshake", 14.5);
+    input["alergenos_por_ingrediente"] = json!({ "amendoim": ["Amendoim"] });
+    let (status, body) = post(&client, "/produtos", &token, input).await;
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body["detalhes"][0]["campo"], "alergenos_por_ingrediente");
+
+    let mut input = produto_input("Milkshake", 14.5);
+    input["informacao_nutricional"] = json!({ "calorias": -10.0 });
+    let (status, body) = post(&client, "/produtos", &token, input).await;
+    assert_eq!(status, Status::BadRequest);
+    assert_eq!(body["detalhes"][0]["campo"], "informacao_nutricional.calorias");
+}